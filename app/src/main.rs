@@ -1,17 +1,159 @@
-﻿use yew::prelude::*;
+use std::collections::BTreeMap;
+
+use plug_kit::manifest::{last_deploys, PortalEntry, PortalIndex};
+use plug_kit::{GitHubClient, GitHubConfig, GlooTransport, WorkflowRun};
+use wasm_bindgen_futures::spawn_local;
+use web_sys::HtmlInputElement;
+use yew::prelude::*;
+
+/// Written by `tools/plug-index`; rerun it after adding or editing a `plug.toml`.
+const INDEX_JSON: &str = include_str!("../plugs.json");
+
+const USER_AGENT: &str = "webhtml5-portal";
+
+fn github() -> GitHubClient<GlooTransport> {
+    // no token: public repo, anonymous reads
+    GitHubClient::new("", GitHubConfig::default().with_user_agent(USER_AGENT))
+}
+
+fn day(s: &Option<String>) -> String {
+    s.as_deref()
+        .and_then(|s| s.get(..10))
+        .unwrap_or_default()
+        .to_string()
+}
+
+/// `(class, text)` for a plug's newest deploy from main.
+fn deploy_status(run: Option<&WorkflowRun>, loaded: bool) -> (&'static str, String) {
+    let Some(run) = run else {
+        let text = if loaded { "No recent deploy" } else { "…" };
+        return ("", text.to_string());
+    };
+    match (run.status.as_deref(), run.conclusion.as_deref()) {
+        (Some("completed"), Some("success")) => {
+            ("deploy-ok", format!("✅ Deployed {}", day(&run.created_at)))
+        }
+        (Some("completed"), _) => (
+            "deploy-fail",
+            format!("❌ Last deploy failed {}", day(&run.created_at)),
+        ),
+        _ => ("deploy-run", "⏳ Deploying now".to_string()),
+    }
+}
 
 #[function_component(App)]
 fn app() -> Html {
+    let index = use_memo((), |_| {
+        serde_json::from_str::<PortalIndex>(INDEX_JSON).unwrap_or_default()
+    });
+    let query = use_state(String::new);
+    let tag = use_state(|| None::<String>);
+
+    // newest deploy per plug; the index itself is static
+    let deploys = use_state(BTreeMap::<String, WorkflowRun>::new);
+    let deploys_loaded = use_state(|| false);
+    let deploys_err = use_state(String::new);
+
+    {
+        let deploys = deploys.clone();
+        let deploys_loaded = deploys_loaded.clone();
+        let deploys_err = deploys_err.clone();
+        use_effect_with((), move |_| {
+            spawn_local(async move {
+                let gh = github();
+                let branch = gh.config().branch.clone();
+                match gh.list_dispatch_runs(&branch, 100).await {
+                    Ok(runs) => deploys.set(last_deploys(&runs)),
+                    Err(e) => deploys_err.set(format!("Deploy status unavailable: {}", e)),
+                }
+                deploys_loaded.set(true);
+            });
+        });
+    }
+
+    let on_query = {
+        let query = query.clone();
+        Callback::from(move |e: InputEvent| {
+            let v = e.target_unchecked_into::<HtmlInputElement>().value();
+            query.set(v);
+        })
+    };
+
+    let on_tag = {
+        let tag = tag.clone();
+        Callback::from(move |t: Option<String>| {
+            // tapping the active chip clears it
+            if *tag == t {
+                tag.set(None);
+            } else {
+                tag.set(t);
+            }
+        })
+    };
+
+    let shown: Vec<&PortalEntry> = index.search(&query, tag.as_deref());
+
+    let chip = |t: Option<String>, label: String| {
+        let class = if *tag == t { "chip on" } else { "chip" };
+        let onclick = on_tag.reform(move |_: MouseEvent| t.clone());
+        html! { <button class={class} {onclick}>{ label }</button> }
+    };
+
+    let card = |p: &PortalEntry| {
+        let (status_class, status) = deploy_status(deploys.get(&p.name), *deploys_loaded);
+        html! {
+          <section class="card plug">
+            <a class="thumb" href={p.url.clone()} aria-hidden="true" tabindex="-1">
+              { match (&p.thumbnail, &p.icon) {
+                  (Some(src), _) => html! { <img src={src.clone()} alt="" loading="lazy" /> },
+                  (None, Some(icon)) => html! { { icon.clone() } },
+                  (None, None) => html! { { "🔌" } },
+              } }
+            </a>
+            <div class="card-h">
+              <h2 class="h2"><a href={p.url.clone()}>{ &p.title }</a></h2>
+            </div>
+            <div class="card-b">
+              if !p.description.is_empty() {
+                <p class="plug-desc">{ &p.description }</p>
+              }
+              <div class="chips" style="margin-top:0;">
+                { for p.tags.iter().map(|t| chip(Some(t.clone()), format!("#{}", t))) }
+              </div>
+              <div class="plug-foot">
+                <span class={status_class}>{ status }</span>
+                <span class="mono">{ &p.name }</span>
+              </div>
+            </div>
+          </section>
+        }
+    };
+
     html! {
-        <main style="font-family: system-ui, -apple-system, Segoe UI, Roboto, Arial, sans-serif; padding: 24px;">
-            <h1>{"Who Is Your Crew?"}</h1>
-            <p>{"Trunk + Yew build is working. Next: plug in the real crew selector UI."}</p>
-            <ul>
-                <li>{"✅ Trunk builds to dist/"}</li>
-                <li>{"✅ Public URL set to /who-is-your-crew/"}</li>
-                <li>{"✅ Hostek IIS web.config copied on deploy"}</li>
-            </ul>
-        </main>
+      <div class="wrap">
+        <div class="badge">{ "MikeGyver Studio • webhtml5" }</div>
+        <h1 class="h1">{ "Plugs" }</h1>
+        <p class="sub">{ format!("{} Rust + Yew apps, each deployed to its own folder.", index.plugs.len()) }</p>
+
+        <input class="input search" type="search" value={(*query).clone()} oninput={on_query} placeholder="Search by name, description or tag…" />
+
+        <div class="chips">
+          { chip(None, format!("All ({})", index.plugs.len())) }
+          { for index.tags().into_iter().map(|(t, n)| chip(Some(t.clone()), format!("{} ({})", t, n))) }
+        </div>
+
+        if !deploys_err.is_empty() {
+          <p class="sub">{ (*deploys_err).clone() }</p>
+        }
+
+        if shown.is_empty() {
+          <p class="sub" style="margin-top:16px;">{ "No plugs match." }</p>
+        } else {
+          <div class="plugs">
+            { for shown.into_iter().map(card) }
+          </div>
+        }
+      </div>
     }
 }
 
@@ -0,0 +1,36 @@
+use base64::Engine;
+
+pub fn b64_encode(s: &str) -> String {
+    base64::engine::general_purpose::STANDARD.encode(s.as_bytes())
+}
+
+/// GitHub wraps base64 content at 60 columns, so strip newlines before decoding.
+pub fn b64_decode(s: &str) -> Result<String, String> {
+    let cleaned = s.replace('\n', "");
+    let bytes = base64::engine::general_purpose::STANDARD
+        .decode(cleaned.as_bytes())
+        .map_err(|e| format!("base64 decode failed: {}", e))?;
+    String::from_utf8(bytes).map_err(|e| format!("utf8 decode failed: {}", e))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn round_trips_wrapped_content() {
+        let text = "fn main() {\n    println!(\"hi 🚀\");\n}\n".repeat(4);
+        let enc = b64_encode(&text);
+        let wrapped: String = enc
+            .as_bytes()
+            .chunks(60)
+            .map(|c| format!("{}\n", std::str::from_utf8(c).unwrap()))
+            .collect();
+        assert_eq!(b64_decode(&wrapped).unwrap(), text);
+    }
+
+    #[test]
+    fn rejects_garbage() {
+        assert!(b64_decode("!!!").is_err());
+    }
+}
@@ -0,0 +1,305 @@
+//! Pull rustc diagnostics out of a `trunk build` log so a failed deploy can be
+//! read — and jumped to in the editor — on the phone.
+//!
+//! Both the human format and `--message-format=json` lines are understood;
+//! a log can mix the two.
+
+use serde_json::Value;
+
+use crate::logs::strip_timestamp;
+
+/// Longest block kept per diagnostic; the rest is usually long `help:` notes.
+const MAX_BLOCK_LINES: usize = 40;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Severity {
+    Error,
+    Warning,
+}
+
+impl Severity {
+    pub fn label(self) -> &'static str {
+        match self {
+            Severity::Error => "error",
+            Severity::Warning => "warning",
+        }
+    }
+}
+
+/// One rustc error or warning.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Diagnostic {
+    pub severity: Severity,
+    /// `E0425`, or `None` for syntax errors, lints and the like.
+    pub code: Option<String>,
+    pub message: String,
+    /// As rustc printed it: usually relative to the crate (`src/main.rs`).
+    pub file: Option<String>,
+    /// 1-based.
+    pub line: Option<u32>,
+    /// 1-based.
+    pub column: Option<u32>,
+    /// Header plus source snippet, timestamps removed.
+    pub text: String,
+}
+
+impl Diagnostic {
+    /// The file relative to the plug folder (`plugs/<name>`), whether rustc
+    /// printed it crate-relative, repo-relative or absolute on the runner.
+    pub fn path_in(&self, app_dir: &str) -> Option<String> {
+        let file = self.file.as_deref()?;
+        let marker = format!("{}/", app_dir.trim_end_matches('/'));
+        if let Some(i) = file.rfind(&marker) {
+            return Some(file[i + marker.len()..].to_string());
+        }
+        (!file.starts_with('/')).then(|| file.trim_start_matches("./").to_string())
+    }
+
+    fn same_as(&self, other: &Diagnostic) -> bool {
+        self.severity == other.severity
+            && self.file == other.file
+            && self.line == other.line
+            && self.column == other.column
+            && self.message == other.message
+    }
+}
+
+/// `(severity, code, message)` if `line` starts a diagnostic. The summary
+/// lines cargo prints after the real ones are skipped.
+fn header(line: &str) -> Option<(Severity, Option<String>, String)> {
+    let (severity, rest) = if let Some(rest) = line.strip_prefix("error") {
+        (Severity::Error, rest)
+    } else {
+        (Severity::Warning, line.strip_prefix("warning")?)
+    };
+
+    let (code, message) = match rest.strip_prefix('[') {
+        Some(coded) => {
+            let (code, message) = coded.split_once("]:")?;
+            (Some(code.to_string()), message)
+        }
+        None => (None, rest.strip_prefix(':')?),
+    };
+    let message = message.trim();
+
+    let summary = message.starts_with("could not compile")
+        || message.starts_with("aborting due to")
+        || message.starts_with("build failed")
+        || message.starts_with("Recipe `")
+        || message.starts_with("Process completed")
+        || (message.starts_with('`') && message.contains(" generated "));
+    (!summary).then(|| (severity, code, message.to_string()))
+}
+
+/// `src/main.rs:12:20` → `(file, line, column)`.
+fn split_location(loc: &str) -> (String, Option<u32>, Option<u32>) {
+    let mut parts = loc.rsplitn(3, ':');
+    let last = parts.next().unwrap_or_default();
+    let mid = parts.next();
+    let file = parts.next();
+    match (file, mid) {
+        (Some(file), Some(mid)) => (file.to_string(), mid.parse().ok(), last.parse().ok()),
+        _ => (loc.to_string(), None, None),
+    }
+}
+
+/// A `{"reason":"compiler-message",…}` line from `--message-format=json`.
+fn from_json(line: &str) -> Option<Diagnostic> {
+    if !line.starts_with('{') || !line.contains("\"compiler-message\"") {
+        return None;
+    }
+    let json: Value = serde_json::from_str(line).ok()?;
+    let msg = json.get("message")?;
+    let severity = match msg.get("level")?.as_str()? {
+        "error" => Severity::Error,
+        "warning" => Severity::Warning,
+        _ => return None,
+    };
+    let message = msg.get("message")?.as_str()?.to_string();
+    // Same summary-line filter as the human format.
+    header(&format!("{}: {}", severity.label(), message))?;
+
+    let spans = msg.get("spans").and_then(Value::as_array);
+    let primary = spans.and_then(|s| {
+        s.iter()
+            .find(|sp| sp.get("is_primary").and_then(Value::as_bool) == Some(true))
+    });
+    let field = |name: &str| {
+        primary
+            .and_then(|sp| sp.get(name))
+            .and_then(Value::as_u64)
+            .map(|n| n as u32)
+    };
+
+    Some(Diagnostic {
+        severity,
+        code: msg
+            .get("code")
+            .and_then(|c| c.get("code"))
+            .and_then(Value::as_str)
+            .map(str::to_string),
+        file: primary
+            .and_then(|sp| sp.get("file_name"))
+            .and_then(Value::as_str)
+            .map(str::to_string),
+        line: field("line_start"),
+        column: field("column_start"),
+        text: msg
+            .get("rendered")
+            .and_then(Value::as_str)
+            .map(|r| r.trim_end().to_string())
+            .unwrap_or_else(|| format!("{}: {}", severity.label(), message)),
+        message,
+    })
+}
+
+/// Every error and warning in `log`, in order of appearance, duplicates dropped.
+/// Warnings without a source location (cargo chatter) are skipped.
+pub fn parse_diagnostics(log: &str) -> Vec<Diagnostic> {
+    fn finish(d: Option<(Diagnostic, usize)>, out: &mut Vec<Diagnostic>) {
+        let Some((d, _)) = d else { return };
+        let chatter = d.severity == Severity::Warning && d.file.is_none();
+        if !chatter && !out.iter().any(|o| o.same_as(&d)) {
+            out.push(d);
+        }
+    }
+
+    let mut out: Vec<Diagnostic> = Vec::new();
+    let mut current: Option<(Diagnostic, usize)> = None;
+
+    for raw in log.lines() {
+        let line = strip_timestamp(raw).trim_end();
+
+        if let Some(d) = from_json(line) {
+            finish(current.take(), &mut out);
+            finish(Some((d, 0)), &mut out);
+            continue;
+        }
+
+        if let Some((severity, code, message)) = header(line) {
+            finish(current.take(), &mut out);
+            current = Some((
+                Diagnostic {
+                    severity,
+                    code,
+                    message,
+                    file: None,
+                    line: None,
+                    column: None,
+                    text: line.to_string(),
+                },
+                1,
+            ));
+            continue;
+        }
+
+        let Some((d, lines)) = current.as_mut() else {
+            continue;
+        };
+        // rustc separates diagnostics with a blank line.
+        if line.is_empty() {
+            finish(current.take(), &mut out);
+            continue;
+        }
+        if d.file.is_none() {
+            if let Some(loc) = line.trim_start().strip_prefix("--> ") {
+                let (file, l, c) = split_location(loc.trim());
+                d.file = Some(file);
+                d.line = l;
+                d.column = c;
+            }
+        }
+        if *lines < MAX_BLOCK_LINES {
+            d.text.push('\n');
+            d.text.push_str(line);
+            *lines += 1;
+        }
+    }
+
+    finish(current, &mut out);
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const LOG: &str = "\
+2024-05-01T12:00:00.0000000Z    Compiling demo v0.1.0 (/home/runner/work/plugs/demo)
+2024-05-01T12:00:01.0000000Z error[E0425]: cannot find value `countr` in this scope
+2024-05-01T12:00:01.0000000Z   --> src/main.rs:12:20
+2024-05-01T12:00:01.0000000Z    |
+2024-05-01T12:00:01.0000000Z 12 |     let n = countr + 1;
+2024-05-01T12:00:01.0000000Z    |             ^^^^^^ help: a local variable with a similar name exists: `counter`
+2024-05-01T12:00:01.0000000Z
+2024-05-01T12:00:01.0000000Z warning: unused import: `std::fmt`
+2024-05-01T12:00:01.0000000Z  --> src/main.rs:1:5
+2024-05-01T12:00:01.0000000Z
+2024-05-01T12:00:01.0000000Z error: expected `;`, found `}`
+2024-05-01T12:00:01.0000000Z   --> src/main.rs:30:6
+2024-05-01T12:00:01.0000000Z
+2024-05-01T12:00:01.0000000Z warning: `demo` (bin \"demo\") generated 1 warning
+2024-05-01T12:00:01.0000000Z error: could not compile `demo` (bin \"demo\") due to 2 previous errors
+";
+
+    #[test]
+    fn parses_human_format() {
+        let diags = parse_diagnostics(LOG);
+        assert_eq!(diags.len(), 3);
+
+        assert_eq!(diags[0].severity, Severity::Error);
+        assert_eq!(diags[0].code.as_deref(), Some("E0425"));
+        assert_eq!(diags[0].message, "cannot find value `countr` in this scope");
+        assert_eq!(diags[0].file.as_deref(), Some("src/main.rs"));
+        assert_eq!((diags[0].line, diags[0].column), (Some(12), Some(20)));
+        assert!(diags[0].text.contains("help: a local variable"));
+        assert!(!diags[0].text.contains("2024-05-01"));
+        assert!(!diags[0].text.contains("unused import"));
+
+        assert_eq!(diags[1].severity, Severity::Warning);
+        assert_eq!((diags[1].line, diags[1].column), (Some(1), Some(5)));
+
+        assert_eq!(diags[2].code, None);
+        assert_eq!(diags[2].line, Some(30));
+    }
+
+    #[test]
+    fn parses_json_messages() {
+        let log = r#"{"reason":"compiler-artifact","target":{"name":"serde"}}
+2024-05-01T12:00:01.0000000Z {"reason":"compiler-message","message":{"message":"mismatched types","code":{"code":"E0308"},"level":"error","spans":[{"file_name":"src/other.rs","line_start":2,"column_start":1,"is_primary":false},{"file_name":"src/main.rs","line_start":7,"column_start":13,"is_primary":true}],"rendered":"error[E0308]: mismatched types\n --> src/main.rs:7:13\n"}}
+{"reason":"compiler-message","message":{"message":"aborting due to 1 previous error","code":null,"level":"error","spans":[],"rendered":"error: aborting due to 1 previous error\n"}}
+"#;
+        let diags = parse_diagnostics(log);
+        assert_eq!(diags.len(), 1);
+        assert_eq!(diags[0].code.as_deref(), Some("E0308"));
+        assert_eq!(diags[0].file.as_deref(), Some("src/main.rs"));
+        assert_eq!((diags[0].line, diags[0].column), (Some(7), Some(13)));
+        assert_eq!(
+            diags[0].text,
+            "error[E0308]: mismatched types\n --> src/main.rs:7:13"
+        );
+    }
+
+    #[test]
+    fn drops_repeated_diagnostics() {
+        let twice = format!("{}\n{}", LOG, LOG);
+        assert_eq!(parse_diagnostics(&twice).len(), 3);
+    }
+
+    #[test]
+    fn maps_paths_into_plug_folder() {
+        let mut d = parse_diagnostics(LOG).remove(0);
+        assert_eq!(d.path_in("plugs/demo").as_deref(), Some("src/main.rs"));
+
+        d.file = Some("/home/runner/work/repo/repo/plugs/demo/src/app.rs".into());
+        assert_eq!(d.path_in("plugs/demo").as_deref(), Some("src/app.rs"));
+
+        d.file = Some("/home/runner/.cargo/registry/src/yew/lib.rs".into());
+        assert_eq!(d.path_in("plugs/demo"), None);
+    }
+
+    #[test]
+    fn clean_log_has_no_diagnostics() {
+        assert!(parse_diagnostics("Finished release [optimized]\n").is_empty());
+    }
+}
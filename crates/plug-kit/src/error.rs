@@ -0,0 +1,79 @@
+use std::fmt;
+
+/// Every failure a `GitHubClient` call can produce.
+#[derive(Debug, Clone, PartialEq)]
+pub enum GitHubError {
+    /// The request never produced an HTTP response (offline, CORS, bad URL…).
+    Transport(String),
+    /// 404 for the given repo path / endpoint.
+    NotFound(String),
+    /// The file exists and the caller asked not to overwrite it.
+    AlreadyExists(String),
+    /// 409: the sha we sent no longer matches (someone pushed in between).
+    Conflict(String),
+    /// A leftover feature branch still has commits that are not on `base`,
+    /// so it can be neither reused nor reset.
+    UnmergedBranch { branch: String, base: String },
+    /// Primary or secondary rate limit hit and we did not wait it out.
+    RateLimited {
+        retry_after_secs: Option<u64>,
+        reset_epoch: Option<u64>,
+    },
+    /// Any other non-success status.
+    Api {
+        context: String,
+        status: u16,
+        body: String,
+    },
+    /// The response body was not what we expected.
+    Decode(String),
+}
+
+impl GitHubError {
+    pub fn is_not_found(&self) -> bool {
+        matches!(self, GitHubError::NotFound(_))
+    }
+
+    pub fn is_conflict(&self) -> bool {
+        matches!(self, GitHubError::Conflict(_))
+    }
+}
+
+impl fmt::Display for GitHubError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            GitHubError::Transport(e) => write!(f, "Network error: {}", e),
+            GitHubError::NotFound(p) => write!(f, "Not found: {}", p),
+            GitHubError::AlreadyExists(p) => {
+                write!(f, "File already exists (overwrite disabled): {}", p)
+            }
+            GitHubError::Conflict(p) => write!(f, "Conflict (sha changed on GitHub): {}", p),
+            GitHubError::UnmergedBranch { branch, base } => write!(
+                f,
+                "Branch {} has commits that are not on {}; merge or delete it first",
+                branch, base
+            ),
+            GitHubError::RateLimited {
+                retry_after_secs,
+                reset_epoch,
+            } => {
+                write!(f, "GitHub rate limit hit")?;
+                if let Some(s) = retry_after_secs {
+                    write!(f, " — retry in {}s", s)?;
+                }
+                if let Some(r) = reset_epoch {
+                    write!(f, " — resets at epoch {}", r)?;
+                }
+                Ok(())
+            }
+            GitHubError::Api {
+                context,
+                status,
+                body,
+            } => write!(f, "{} failed: {} {}", context, status, body),
+            GitHubError::Decode(e) => write!(f, "Unexpected response: {}", e),
+        }
+    }
+}
+
+impl std::error::Error for GitHubError {}
@@ -0,0 +1,345 @@
+//! Git Data API (blobs → tree → commit → ref) so several files land in one
+//! commit. Nothing is visible on the branch until the final ref update, so a
+//! failure part-way leaves it untouched (at worst a few unreferenced objects).
+
+use serde::{Deserialize, Serialize};
+
+use crate::codec::b64_encode;
+use crate::error::GitHubError;
+use crate::github::{expect_ok, parse_json, GitHubClient};
+use crate::transport::{Method, Transport};
+
+/// One path in a multi-file commit. `content: None` deletes the path.
+#[derive(Debug, Clone, PartialEq)]
+pub struct FileChange {
+    pub path: String,
+    pub content: Option<String>,
+}
+
+impl FileChange {
+    pub fn write(path: &str, content: &str) -> Self {
+        Self {
+            path: path.to_string(),
+            content: Some(content.to_string()),
+        }
+    }
+
+    pub fn delete(path: &str) -> Self {
+        Self {
+            path: path.to_string(),
+            content: None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct CommitResult {
+    pub commit_sha: String,
+    pub tree_sha: String,
+}
+
+#[derive(Deserialize)]
+struct RefResp {
+    object: ShaOnly,
+}
+
+#[derive(Deserialize)]
+struct CommitResp {
+    tree: ShaOnly,
+}
+
+#[derive(Deserialize)]
+struct ShaOnly {
+    sha: String,
+}
+
+#[derive(Serialize)]
+struct BlobBody {
+    content: String,
+    encoding: &'static str,
+}
+
+#[derive(Serialize)]
+struct TreeBody<'a> {
+    base_tree: &'a str,
+    tree: Vec<TreeEntry<'a>>,
+}
+
+#[derive(Serialize)]
+struct TreeEntry<'a> {
+    path: &'a str,
+    mode: &'static str,
+    #[serde(rename = "type")]
+    kind: &'static str,
+    /// `null` removes the path from the base tree.
+    sha: Option<&'a str>,
+}
+
+#[derive(Serialize)]
+struct NewCommitBody<'a> {
+    message: &'a str,
+    tree: &'a str,
+    parents: Vec<&'a str>,
+}
+
+#[derive(Serialize)]
+struct UpdateRefBody<'a> {
+    sha: &'a str,
+    force: bool,
+}
+
+impl<T: Transport> GitHubClient<T> {
+    /// Commit sha the branch currently points at.
+    pub async fn get_branch_head(&self, branch: &str) -> Result<String, GitHubError> {
+        let url = self.repo_url(&format!("git/ref/heads/{}", branch));
+        let resp = self.request(Method::Get, &url, None).await?;
+        let resp = expect_ok(resp, &format!("heads/{}", branch), "GET ref")?;
+        let json: RefResp = parse_json(&resp)?;
+        Ok(json.object.sha)
+    }
+
+    /// Tree sha of a commit.
+    pub async fn get_commit_tree(&self, commit_sha: &str) -> Result<String, GitHubError> {
+        let url = self.repo_url(&format!("git/commits/{}", commit_sha));
+        let resp = self.request(Method::Get, &url, None).await?;
+        let resp = expect_ok(resp, commit_sha, "GET commit")?;
+        let json: CommitResp = parse_json(&resp)?;
+        Ok(json.tree.sha)
+    }
+
+    pub async fn create_blob(&self, content: &str) -> Result<String, GitHubError> {
+        let url = self.repo_url("git/blobs");
+        let body = BlobBody {
+            content: b64_encode(content),
+            encoding: "base64",
+        };
+        let resp = self.request_json(Method::Post, &url, &body).await?;
+        let resp = expect_ok(resp, "blob", "Create blob")?;
+        let json: ShaOnly = parse_json(&resp)?;
+        Ok(json.sha)
+    }
+
+    /// `entries` are `(path, blob sha)`; a `None` sha deletes the path.
+    pub async fn create_tree(
+        &self,
+        base_tree: &str,
+        entries: &[(String, Option<String>)],
+    ) -> Result<String, GitHubError> {
+        let url = self.repo_url("git/trees");
+        let body = TreeBody {
+            base_tree,
+            tree: entries
+                .iter()
+                .map(|(path, sha)| TreeEntry {
+                    path,
+                    mode: "100644",
+                    kind: "blob",
+                    sha: sha.as_deref(),
+                })
+                .collect(),
+        };
+        let resp = self.request_json(Method::Post, &url, &body).await?;
+        let resp = expect_ok(resp, "tree", "Create tree")?;
+        let json: ShaOnly = parse_json(&resp)?;
+        Ok(json.sha)
+    }
+
+    pub async fn create_commit(
+        &self,
+        message: &str,
+        tree: &str,
+        parent: &str,
+    ) -> Result<String, GitHubError> {
+        let url = self.repo_url("git/commits");
+        let body = NewCommitBody {
+            message,
+            tree,
+            parents: vec![parent],
+        };
+        let resp = self.request_json(Method::Post, &url, &body).await?;
+        let resp = expect_ok(resp, "commit", "Create commit")?;
+        let json: ShaOnly = parse_json(&resp)?;
+        Ok(json.sha)
+    }
+
+    /// Fast-forward `branch` to `commit_sha`. A non-fast-forward (someone
+    /// pushed meanwhile) comes back from GitHub as a 422 saying so and maps to
+    /// `Conflict`; any other 422 is an ordinary API error.
+    pub async fn update_branch(&self, branch: &str, commit_sha: &str) -> Result<(), GitHubError> {
+        let url = self.repo_url(&format!("git/refs/heads/{}", branch));
+        let body = UpdateRefBody {
+            sha: commit_sha,
+            force: false,
+        };
+        let resp = self.request_json(Method::Patch, &url, &body).await?;
+        if resp.status == 422 && resp.text().contains("not a fast forward") {
+            return Err(GitHubError::Conflict(format!("heads/{}", branch)));
+        }
+        expect_ok(resp, &format!("heads/{}", branch), "Update ref").map(|_| ())
+    }
+
+    /// Move `branch` to `sha` even if that drops commits from it.
+    pub async fn reset_branch(&self, branch: &str, sha: &str) -> Result<(), GitHubError> {
+        let url = self.repo_url(&format!("git/refs/heads/{}", branch));
+        let body = UpdateRefBody { sha, force: true };
+        let resp = self.request_json(Method::Patch, &url, &body).await?;
+        expect_ok(resp, &format!("heads/{}", branch), "Reset ref").map(|_| ())
+    }
+
+    /// Land every change on the configured branch as a single commit.
+    pub async fn commit_files(
+        &self,
+        message: &str,
+        changes: &[FileChange],
+    ) -> Result<CommitResult, GitHubError> {
+        let branch = self.config().branch.clone();
+        self.commit_files_to(&branch, message, changes).await
+    }
+
+    /// Land every change on `branch` as a single commit.
+    /// If the branch moves under us, rebuild on the new head and retry.
+    pub async fn commit_files_to(
+        &self,
+        branch: &str,
+        message: &str,
+        changes: &[FileChange],
+    ) -> Result<CommitResult, GitHubError> {
+        // Blobs don't depend on the parent, so they survive a retry.
+        let mut entries = Vec::with_capacity(changes.len());
+        for change in changes {
+            let sha = match &change.content {
+                Some(content) => Some(self.create_blob(content).await?),
+                None => None,
+            };
+            entries.push((change.path.clone(), sha));
+        }
+
+        let mut attempt = 0;
+        loop {
+            let head = self.get_branch_head(branch).await?;
+            let base_tree = self.get_commit_tree(&head).await?;
+            let tree_sha = self.create_tree(&base_tree, &entries).await?;
+            let commit_sha = self.create_commit(message, &tree_sha, &head).await?;
+
+            match self.update_branch(branch, &commit_sha).await {
+                Ok(()) => {
+                    return Ok(CommitResult {
+                        commit_sha,
+                        tree_sha,
+                    })
+                }
+                Err(GitHubError::Conflict(_)) if attempt < self.config().conflict_retries => {
+                    attempt += 1;
+                }
+                Err(e) => return Err(e),
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mock::MockTransport;
+    use crate::GitHubConfig;
+    use futures::executor::block_on;
+
+    fn client(mock: MockTransport) -> GitHubClient<MockTransport> {
+        GitHubClient::with_transport("tok", GitHubConfig::default(), mock)
+    }
+
+    const NOT_FF: &str = r#"{"message":"Update is not a fast forward"}"#;
+
+    fn happy_tail(mock: MockTransport, head: &str) -> MockTransport {
+        mock.reply(200, &format!(r#"{{"object":{{"sha":"{}"}}}}"#, head))
+            .reply(200, r#"{"sha":"c0","tree":{"sha":"base"}}"#)
+            .reply(201, r#"{"sha":"t1"}"#)
+            .reply(201, r#"{"sha":"c1"}"#)
+    }
+
+    #[test]
+    fn commits_all_files_in_one_commit() {
+        let mock = MockTransport::new()
+            .reply(201, r#"{"sha":"b1"}"#)
+            .reply(201, r#"{"sha":"b2"}"#);
+        let mock = happy_tail(mock, "c0").reply(200, "{}");
+        let gh = client(mock);
+
+        let changes = [
+            FileChange::write("plugs/x/index.html", "<html>"),
+            FileChange::write("plugs/x/src/main.rs", "fn main() {}"),
+            FileChange::delete("plugs/x/old.txt"),
+        ];
+        let res = block_on(gh.commit_files("Add plug scaffold: x", &changes)).unwrap();
+        assert_eq!(res.commit_sha, "c1");
+
+        let t = gh.transport();
+        assert_eq!(t.request_count(), 7);
+
+        let tree = t.request_json(4);
+        assert_eq!(tree["base_tree"], "base");
+        assert_eq!(tree["tree"][1]["sha"], "b2");
+        assert!(tree["tree"][2]["sha"].is_null());
+
+        let commit = t.request_json(5);
+        assert_eq!(commit["parents"][0], "c0");
+        assert_eq!(commit["message"], "Add plug scaffold: x");
+
+        let update = t.request(6);
+        assert_eq!(update.method, Method::Patch);
+        assert!(update.url.ends_with("git/refs/heads/main"));
+        assert_eq!(t.request_json(6)["force"], false);
+    }
+
+    #[test]
+    fn failure_before_ref_update_leaves_branch_alone() {
+        let gh = client(
+            MockTransport::new()
+                .reply(201, r#"{"sha":"b1"}"#)
+                .reply(500, "boom"),
+        );
+        let changes = [FileChange::write("a", "1"), FileChange::write("b", "2")];
+        let err = block_on(gh.commit_files("m", &changes)).unwrap_err();
+        assert!(matches!(err, GitHubError::Api { status: 500, .. }));
+        assert!(gh
+            .transport()
+            .requests
+            .borrow()
+            .iter()
+            .all(|r| r.method != Method::Patch));
+    }
+
+    #[test]
+    fn rebuilds_on_new_head_when_branch_moved() {
+        let mock = MockTransport::new().reply(201, r#"{"sha":"b1"}"#);
+        let mock = happy_tail(mock, "c0").reply(422, NOT_FF);
+        let mock = happy_tail(mock, "c9").reply(200, "{}");
+        let gh = client(mock);
+
+        block_on(gh.commit_files("m", &[FileChange::write("a", "1")])).unwrap();
+        let t = gh.transport();
+        // one blob, two full rounds of ref/commit/tree/commit/update
+        assert_eq!(t.request_count(), 11);
+        assert_eq!(t.request_json(9)["parents"][0], "c9");
+    }
+
+    #[test]
+    fn non_fast_forward_after_retries_is_conflict() {
+        let mock = MockTransport::new().reply(201, r#"{"sha":"b1"}"#);
+        let mock = happy_tail(mock, "c0").reply(422, NOT_FF);
+        let mock = happy_tail(mock, "c0").reply(422, NOT_FF);
+        let gh = client(mock);
+        let err = block_on(gh.commit_files("m", &[FileChange::write("a", "1")])).unwrap_err();
+        assert!(err.is_conflict());
+    }
+
+    #[test]
+    fn other_ref_update_rejections_are_not_retried() {
+        let mock = MockTransport::new().reply(201, r#"{"sha":"b1"}"#);
+        let mock = happy_tail(mock, "c0").reply(422, r#"{"message":"Object does not exist"}"#);
+        let gh = client(mock);
+        let err = block_on(gh.commit_files("m", &[FileChange::write("a", "1")])).unwrap_err();
+        assert!(matches!(err, GitHubError::Api { status: 422, .. }));
+        assert_eq!(gh.transport().request_count(), 6);
+    }
+}
@@ -0,0 +1,589 @@
+use serde::{Deserialize, Serialize};
+
+use crate::codec::{b64_decode, b64_encode};
+use crate::error::GitHubError;
+use crate::transport::{HttpRequest, HttpResponse, Method, Transport};
+
+#[cfg(feature = "gloo")]
+use crate::transport::GlooTransport;
+
+/// Where the client points and how patient it is.
+#[derive(Debug, Clone, PartialEq)]
+pub struct GitHubConfig {
+    pub owner: String,
+    pub repo: String,
+    pub branch: String,
+    /// File name under `.github/workflows`.
+    pub workflow_file: String,
+    pub user_agent: String,
+    pub api_base: String,
+    /// Extra attempts after a 409 sha mismatch in `upsert_file`.
+    pub conflict_retries: u32,
+    /// Extra attempts after a rate-limit response we are willing to wait out.
+    pub rate_limit_retries: u32,
+    /// Longest `retry-after` we will sleep through before giving up.
+    pub max_rate_limit_wait_secs: u64,
+    /// Pause between polls while looking for or following a workflow run.
+    pub run_poll_secs: u64,
+    /// Polls for the run a dispatch started before giving up on it.
+    pub run_lookup_attempts: u32,
+    /// Polls `follow_run` makes before returning an unfinished run.
+    pub run_max_polls: u32,
+}
+
+impl Default for GitHubConfig {
+    fn default() -> Self {
+        Self {
+            owner: "ekim5sg".into(),
+            repo: "webhtml5-plug-deployer".into(),
+            branch: "main".into(),
+            workflow_file: "deploy-hostek-plug.yml".into(),
+            user_agent: "webhtml5-plug-kit".into(),
+            api_base: "https://api.github.com".into(),
+            conflict_retries: 1,
+            rate_limit_retries: 1,
+            max_rate_limit_wait_secs: 10,
+            run_poll_secs: 5,
+            run_lookup_attempts: 6,
+            run_max_polls: 240,
+        }
+    }
+}
+
+impl GitHubConfig {
+    pub fn with_user_agent(mut self, user_agent: &str) -> Self {
+        self.user_agent = user_agent.to_string();
+        self
+    }
+
+    pub fn with_branch(mut self, branch: &str) -> Self {
+        self.branch = branch.to_string();
+        self
+    }
+}
+
+/// A decoded file from the Contents API.
+#[derive(Debug, Clone, PartialEq)]
+pub struct RepoFile {
+    pub path: String,
+    pub sha: String,
+    pub content: String,
+}
+
+#[derive(Deserialize, Debug, Clone, PartialEq)]
+pub struct WorkflowRun {
+    pub id: u64,
+    pub html_url: String,
+    pub name: Option<String>,
+    /// The workflow's `run-name`; deploys read `Deploy <plug>`.
+    pub display_title: Option<String>,
+    pub status: Option<String>,
+    pub conclusion: Option<String>,
+    pub created_at: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct RunsResp {
+    workflow_runs: Vec<WorkflowRun>,
+}
+
+#[derive(Deserialize)]
+struct ContentGetResp {
+    sha: String,
+    content: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct PutContentResp {
+    content: PutContentSha,
+}
+
+#[derive(Deserialize)]
+struct PutContentSha {
+    sha: String,
+}
+
+#[derive(Serialize)]
+struct PutContentBody<'a> {
+    message: &'a str,
+    content: String, // base64
+    branch: &'a str,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    sha: Option<String>,
+}
+
+#[derive(Serialize)]
+struct DispatchBody<'a> {
+    #[serde(rename = "ref")]
+    git_ref: &'a str,
+    inputs: DispatchInputs<'a>,
+}
+
+#[derive(Serialize)]
+struct DispatchInputs<'a> {
+    plug_name: &'a str,
+    app_dir: &'a str,
+    clean_remote: &'a str,
+}
+
+pub struct GitHubClient<T> {
+    token: String,
+    config: GitHubConfig,
+    transport: T,
+}
+
+#[cfg(feature = "gloo")]
+impl GitHubClient<GlooTransport> {
+    pub fn new(token: &str, config: GitHubConfig) -> Self {
+        Self::with_transport(token, config, GlooTransport)
+    }
+}
+
+impl<T: Transport> GitHubClient<T> {
+    pub fn with_transport(token: &str, config: GitHubConfig, transport: T) -> Self {
+        Self {
+            token: token.trim().to_string(),
+            config,
+            transport,
+        }
+    }
+
+    pub fn config(&self) -> &GitHubConfig {
+        &self.config
+    }
+
+    pub fn transport(&self) -> &T {
+        &self.transport
+    }
+
+    pub(crate) fn repo_url(&self, tail: &str) -> String {
+        format!(
+            "{}/repos/{}/{}/{}",
+            self.config.api_base.trim_end_matches('/'),
+            self.config.owner,
+            self.config.repo,
+            tail
+        )
+    }
+
+    /// Send one authenticated request, waiting out short rate limits.
+    /// Non-2xx statuses other than rate limits are returned to the caller as-is.
+    pub(crate) async fn request(
+        &self,
+        method: Method,
+        url: &str,
+        body: Option<String>,
+    ) -> Result<HttpResponse, GitHubError> {
+        let mut attempt = 0;
+        loop {
+            let mut headers = vec![
+                ("Accept".into(), "application/vnd.github+json".into()),
+                ("X-GitHub-Api-Version".into(), "2022-11-28".into()),
+                ("User-Agent".into(), self.config.user_agent.clone()),
+            ];
+            // no token: anonymous read of public data (lower rate limit)
+            if !self.token.is_empty() {
+                headers.insert(
+                    0,
+                    ("Authorization".into(), format!("Bearer {}", self.token)),
+                );
+            }
+            let req = HttpRequest {
+                method,
+                url: url.to_string(),
+                headers,
+                body: body.clone(),
+            };
+
+            let resp = self
+                .transport
+                .send(req)
+                .await
+                .map_err(GitHubError::Transport)?;
+
+            let Some((retry_after_secs, reset_epoch)) = rate_limit_info(&resp) else {
+                return Ok(resp);
+            };
+
+            match retry_after_secs {
+                Some(wait)
+                    if attempt < self.config.rate_limit_retries
+                        && wait <= self.config.max_rate_limit_wait_secs =>
+                {
+                    attempt += 1;
+                    self.transport.sleep(wait).await;
+                }
+                _ => {
+                    return Err(GitHubError::RateLimited {
+                        retry_after_secs,
+                        reset_epoch,
+                    })
+                }
+            }
+        }
+    }
+
+    pub(crate) async fn request_json<B: Serialize>(
+        &self,
+        method: Method,
+        url: &str,
+        body: &B,
+    ) -> Result<HttpResponse, GitHubError> {
+        let body = serde_json::to_string(body).map_err(|e| GitHubError::Decode(e.to_string()))?;
+        self.request(method, url, Some(body)).await
+    }
+
+    /// Fetch and decode a file on the configured branch.
+    pub async fn get_file(&self, path: &str) -> Result<RepoFile, GitHubError> {
+        let url = self.repo_url(&format!("contents/{}?ref={}", path, self.config.branch));
+        let resp = self.request(Method::Get, &url, None).await?;
+        let resp = expect_ok(resp, path, &format!("GET {}", path))?;
+
+        let json: ContentGetResp = parse_json(&resp)?;
+        let content = b64_decode(&json.content.unwrap_or_default()).map_err(GitHubError::Decode)?;
+        Ok(RepoFile {
+            path: path.to_string(),
+            sha: json.sha,
+            content,
+        })
+    }
+
+    /// Current blob sha of a file, or `None` if it doesn't exist yet.
+    pub async fn get_sha(&self, path: &str) -> Result<Option<String>, GitHubError> {
+        let url = self.repo_url(&format!("contents/{}?ref={}", path, self.config.branch));
+        let resp = self.request(Method::Get, &url, None).await?;
+        if resp.status == 404 {
+            return Ok(None);
+        }
+        let resp = expect_ok(resp, path, &format!("GET {}", path))?;
+        let json: ContentGetResp = parse_json(&resp)?;
+        Ok(Some(json.sha))
+    }
+
+    /// Single PUT with the given sha (`None` = create). Returns the new blob sha.
+    pub async fn put_file(
+        &self,
+        path: &str,
+        message: &str,
+        content: &str,
+        sha: Option<String>,
+    ) -> Result<String, GitHubError> {
+        let url = self.repo_url(&format!("contents/{}", path));
+        let body = PutContentBody {
+            message,
+            content: b64_encode(content),
+            branch: &self.config.branch,
+            sha,
+        };
+
+        let resp = self.request_json(Method::Put, &url, &body).await?;
+        let resp = expect_ok(resp, path, &format!("PUT {}", path))?;
+        let json: PutContentResp = parse_json(&resp)?;
+        Ok(json.content.sha)
+    }
+
+    /// Create or overwrite a file, refetching the sha and retrying on 409.
+    pub async fn upsert_file(
+        &self,
+        path: &str,
+        message: &str,
+        content: &str,
+        overwrite: bool,
+    ) -> Result<String, GitHubError> {
+        let mut attempt = 0;
+        loop {
+            let sha = match self.get_sha(path).await? {
+                Some(_) if !overwrite => return Err(GitHubError::AlreadyExists(path.to_string())),
+                other => other,
+            };
+
+            match self.put_file(path, message, content, sha).await {
+                Err(GitHubError::Conflict(_)) if attempt < self.config.conflict_retries => {
+                    attempt += 1;
+                }
+                other => return other,
+            }
+        }
+    }
+
+    /// Trigger the deploy workflow for `plugs/<plug_name>` on the configured branch.
+    pub async fn dispatch(&self, plug_name: &str, app_dir: &str) -> Result<(), GitHubError> {
+        self.dispatch_ref(&self.config.branch, plug_name, app_dir)
+            .await
+    }
+
+    /// Trigger the deploy workflow against any branch/tag. `plug_name` is the
+    /// Hostek folder the build is published to.
+    pub async fn dispatch_ref(
+        &self,
+        git_ref: &str,
+        plug_name: &str,
+        app_dir: &str,
+    ) -> Result<(), GitHubError> {
+        let url = self.repo_url(&format!(
+            "actions/workflows/{}/dispatches",
+            self.config.workflow_file
+        ));
+        let body = DispatchBody {
+            git_ref,
+            inputs: DispatchInputs {
+                plug_name,
+                app_dir,
+                clean_remote: "false",
+            },
+        };
+
+        let resp = self.request_json(Method::Post, &url, &body).await?;
+        expect_ok(resp, &self.config.workflow_file, "Dispatch").map(|_| ())
+    }
+
+    /// Most recent runs of the deploy workflow.
+    pub async fn list_runs(&self, per_page: u32) -> Result<Vec<WorkflowRun>, GitHubError> {
+        let url = self.repo_url(&format!(
+            "actions/workflows/{}/runs?per_page={}",
+            self.config.workflow_file, per_page
+        ));
+        let resp = self.request(Method::Get, &url, None).await?;
+        let resp = expect_ok(resp, &self.config.workflow_file, "Fetch runs")?;
+        let json: RunsResp = parse_json(&resp)?;
+        Ok(json.workflow_runs)
+    }
+}
+
+/// `(retry_after_secs, reset_epoch)` when the response is a rate-limit rejection.
+fn rate_limit_info(resp: &HttpResponse) -> Option<(Option<u64>, Option<u64>)> {
+    let retry_after = resp
+        .header("retry-after")
+        .and_then(|v| v.trim().parse().ok());
+    let remaining_zero = resp.header("x-ratelimit-remaining").map(str::trim) == Some("0");
+    let reset = resp
+        .header("x-ratelimit-reset")
+        .and_then(|v| v.trim().parse().ok());
+
+    let limited =
+        resp.status == 429 || (resp.status == 403 && (remaining_zero || retry_after.is_some()));
+    limited.then_some((retry_after, reset))
+}
+
+pub(crate) fn expect_ok(
+    resp: HttpResponse,
+    subject: &str,
+    context: &str,
+) -> Result<HttpResponse, GitHubError> {
+    match resp.status {
+        s if (200..300).contains(&s) => Ok(resp),
+        404 => Err(GitHubError::NotFound(subject.to_string())),
+        409 => Err(GitHubError::Conflict(subject.to_string())),
+        status => Err(GitHubError::Api {
+            context: context.to_string(),
+            status,
+            body: resp.text(),
+        }),
+    }
+}
+
+pub(crate) fn parse_json<D: serde::de::DeserializeOwned>(
+    resp: &HttpResponse,
+) -> Result<D, GitHubError> {
+    serde_json::from_slice(&resp.body).map_err(|e| GitHubError::Decode(e.to_string()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mock::MockTransport;
+    use futures::executor::block_on;
+
+    fn client(mock: MockTransport) -> GitHubClient<MockTransport> {
+        GitHubClient::with_transport("tok", GitHubConfig::default(), mock)
+    }
+
+    const PUT_OK: &str = r#"{"content":{"sha":"new"},"commit":{"sha":"c1"}}"#;
+
+    #[test]
+    fn get_sha_sends_auth_and_maps_404_to_none() {
+        let gh = client(MockTransport::new().reply(404, "{}"));
+        assert_eq!(block_on(gh.get_sha("plugs/x/index.html")), Ok(None));
+
+        let req = gh.transport().request(0);
+        assert_eq!(req.method, Method::Get);
+        assert_eq!(
+            req.url,
+            "https://api.github.com/repos/ekim5sg/webhtml5-plug-deployer/contents/plugs/x/index.html?ref=main"
+        );
+        assert!(req
+            .headers
+            .contains(&("Authorization".into(), "Bearer tok".into())));
+    }
+
+    #[test]
+    fn empty_token_reads_anonymously() {
+        let gh = GitHubClient::with_transport(
+            "",
+            GitHubConfig::default(),
+            MockTransport::new().reply(404, "{}"),
+        );
+        block_on(gh.get_sha("plug.toml")).unwrap();
+        assert!(gh
+            .transport()
+            .request(0)
+            .headers
+            .iter()
+            .all(|(k, _)| k != "Authorization"));
+    }
+
+    #[test]
+    fn get_file_decodes_content() {
+        let body = format!(r#"{{"sha":"abc","content":"{}\n"}}"#, b64_encode("hello"));
+        let gh = client(MockTransport::new().reply(200, &body));
+        let file = block_on(gh.get_file("a.txt")).unwrap();
+        assert_eq!(file.sha, "abc");
+        assert_eq!(file.content, "hello");
+    }
+
+    #[test]
+    fn get_file_missing_is_not_found() {
+        let gh = client(MockTransport::new().reply(404, "{}"));
+        let err = block_on(gh.get_file("nope.rs")).unwrap_err();
+        assert!(err.is_not_found());
+        assert_eq!(err.to_string(), "Not found: nope.rs");
+    }
+
+    #[test]
+    fn put_uses_configured_branch() {
+        let cfg = GitHubConfig::default().with_branch("plug/demo");
+        let gh = GitHubClient::with_transport("tok", cfg, MockTransport::new().reply(201, PUT_OK));
+        let sha = block_on(gh.put_file("a.txt", "msg", "hi", Some("old".into()))).unwrap();
+        assert_eq!(sha, "new");
+
+        let body = gh.transport().request_json(0);
+        assert_eq!(body["branch"], "plug/demo");
+        assert_eq!(body["sha"], "old");
+        assert_eq!(body["content"], b64_encode("hi"));
+    }
+
+    #[test]
+    fn upsert_retries_once_on_conflict() {
+        let gh = client(
+            MockTransport::new()
+                .reply(200, r#"{"sha":"s1"}"#)
+                .reply(409, "sha mismatch")
+                .reply(200, r#"{"sha":"s2"}"#)
+                .reply(200, PUT_OK),
+        );
+        assert_eq!(
+            block_on(gh.upsert_file("a.txt", "m", "c", true)),
+            Ok("new".into())
+        );
+        assert_eq!(gh.transport().request_count(), 4);
+        assert_eq!(gh.transport().request_json(3)["sha"], "s2");
+    }
+
+    #[test]
+    fn upsert_gives_up_after_retries() {
+        let gh = client(
+            MockTransport::new()
+                .reply(200, r#"{"sha":"s1"}"#)
+                .reply(409, "")
+                .reply(200, r#"{"sha":"s2"}"#)
+                .reply(409, ""),
+        );
+        let err = block_on(gh.upsert_file("a.txt", "m", "c", true)).unwrap_err();
+        assert!(err.is_conflict());
+    }
+
+    #[test]
+    fn upsert_respects_overwrite_flag() {
+        let gh = client(MockTransport::new().reply(200, r#"{"sha":"s1"}"#));
+        let err = block_on(gh.upsert_file("a.txt", "m", "c", false)).unwrap_err();
+        assert_eq!(err, GitHubError::AlreadyExists("a.txt".into()));
+        assert_eq!(gh.transport().request_count(), 1);
+    }
+
+    #[test]
+    fn dispatch_uses_config() {
+        let cfg = GitHubConfig {
+            owner: "me".into(),
+            repo: "r".into(),
+            workflow_file: "wf.yml".into(),
+            ..GitHubConfig::default()
+        };
+        let gh = GitHubClient::with_transport("tok", cfg, MockTransport::new().reply(204, ""));
+        block_on(gh.dispatch("demo", "plugs/demo")).unwrap();
+
+        let req = gh.transport().request(0);
+        assert_eq!(req.method, Method::Post);
+        assert_eq!(
+            req.url,
+            "https://api.github.com/repos/me/r/actions/workflows/wf.yml/dispatches"
+        );
+        let body = gh.transport().request_json(0);
+        assert_eq!(body["ref"], "main");
+        assert_eq!(body["inputs"]["app_dir"], "plugs/demo");
+        assert_eq!(body["inputs"]["clean_remote"], "false");
+    }
+
+    #[test]
+    fn dispatch_error_keeps_status_and_body() {
+        let gh = client(MockTransport::new().reply(422, "bad inputs"));
+        let err = block_on(gh.dispatch("demo", "plugs/demo")).unwrap_err();
+        assert_eq!(err.to_string(), "Dispatch failed: 422 bad inputs");
+    }
+
+    #[test]
+    fn waits_out_short_secondary_rate_limit() {
+        let gh = client(
+            MockTransport::new()
+                .reply_with_headers(403, &[("Retry-After", "3")], "slow down")
+                .reply(200, r#"{"workflow_runs":[]}"#),
+        );
+        assert_eq!(block_on(gh.list_runs(5)), Ok(vec![]));
+        assert_eq!(*gh.transport().sleeps.borrow(), vec![3]);
+    }
+
+    #[test]
+    fn long_rate_limit_is_reported() {
+        let gh = client(MockTransport::new().reply_with_headers(
+            403,
+            &[
+                ("x-ratelimit-remaining", "0"),
+                ("x-ratelimit-reset", "1700000000"),
+            ],
+            "",
+        ));
+        let err = block_on(gh.list_runs(5)).unwrap_err();
+        assert_eq!(
+            err,
+            GitHubError::RateLimited {
+                retry_after_secs: None,
+                reset_epoch: Some(1_700_000_000)
+            }
+        );
+        assert!(gh.transport().sleeps.borrow().is_empty());
+    }
+
+    #[test]
+    fn plain_403_is_not_a_rate_limit() {
+        let gh = client(MockTransport::new().reply(403, "bad credentials"));
+        let err = block_on(gh.list_runs(5)).unwrap_err();
+        assert!(matches!(err, GitHubError::Api { status: 403, .. }));
+    }
+
+    #[test]
+    fn transport_failure_surfaces() {
+        let gh = client(MockTransport::new().fail("offline"));
+        let err = block_on(gh.get_sha("a")).unwrap_err();
+        assert_eq!(err, GitHubError::Transport("offline".into()));
+    }
+
+    #[test]
+    fn list_runs_parses() {
+        let body = r#"{"total_count":1,"workflow_runs":[{"id":7,"html_url":"u","name":"Build","status":"completed","conclusion":"success","created_at":"2024-01-01T00:00:00Z"}]}"#;
+        let gh = client(MockTransport::new().reply(200, body));
+        let runs = block_on(gh.list_runs(10)).unwrap();
+        assert_eq!(runs[0].id, 7);
+        assert_eq!(runs[0].conclusion.as_deref(), Some("success"));
+        assert!(gh.transport().request(0).url.ends_with("runs?per_page=10"));
+    }
+}
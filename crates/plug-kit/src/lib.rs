@@ -0,0 +1,38 @@
+//! Shared building blocks for webhtml5 plugs that talk to GitHub.
+//!
+//! `plug-creator` and `rust-iphone-compiler` both commit files into this repo and
+//! dispatch the Hostek deploy workflow. This crate holds the one copy of that
+//! client so retry/rate-limit behavior stays the same everywhere.
+
+pub mod codec;
+pub mod diagnostics;
+pub mod error;
+pub mod git_data;
+pub mod github;
+pub mod logs;
+pub mod manifest;
+pub mod merge;
+pub mod plug;
+pub mod project;
+pub mod pulls;
+pub mod runs;
+pub mod templates;
+pub mod transport;
+
+#[cfg(test)]
+mod mock;
+
+pub use diagnostics::{Diagnostic, Severity};
+pub use error::GitHubError;
+pub use git_data::{CommitResult, FileChange};
+pub use github::{GitHubClient, GitHubConfig, RepoFile, WorkflowRun};
+pub use manifest::{PlugManifest, PortalEntry, PortalIndex};
+pub use merge::{Conflict, Merge, Resolution};
+pub use project::{Project, TreeFile, TreeRow};
+pub use pulls::{Delivery, DeliveryMode, PullRequest};
+pub use runs::{JobStep, RunLogs, RunProgress, WorkflowJob};
+pub use templates::{Template, TemplateVars};
+pub use transport::{HttpRequest, HttpResponse, Method, Transport};
+
+#[cfg(feature = "gloo")]
+pub use transport::GlooTransport;
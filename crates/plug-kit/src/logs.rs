@@ -0,0 +1,209 @@
+//! Workflow log archives. `GET /actions/runs/{id}/logs` redirects to a zip with
+//! one `<n>_<job>.txt` per job plus a `<job>/` folder holding each step's log.
+//! Only stored and deflated entries are handled, which is all GitHub emits.
+
+use miniz_oxide::inflate::decompress_to_vec;
+
+/// One text file out of a log archive.
+#[derive(Debug, Clone, PartialEq)]
+pub struct LogFile {
+    pub name: String,
+    pub text: String,
+}
+
+impl LogFile {
+    /// Whole-job logs sit at the archive root; step logs are in per-job folders.
+    pub fn is_job_log(&self) -> bool {
+        !self.name.contains('/')
+    }
+
+    /// `2_build and deploy.txt` → `build and deploy`.
+    pub fn job_name(&self) -> &str {
+        let name = self.name.strip_suffix(".txt").unwrap_or(&self.name);
+        match name.split_once('_') {
+            Some((n, job)) if !n.is_empty() && n.bytes().all(|b| b.is_ascii_digit()) => job,
+            _ => name,
+        }
+    }
+
+    /// The log without the timestamp on every line.
+    pub fn plain_text(&self) -> String {
+        let lines: Vec<&str> = self.text.lines().map(strip_timestamp).collect();
+        lines.join("\n")
+    }
+}
+
+const EOCD_SIG: u32 = 0x0605_4b50;
+const CENTRAL_SIG: u32 = 0x0201_4b50;
+const LOCAL_SIG: u32 = 0x0403_4b50;
+
+fn u16_at(b: &[u8], at: usize) -> Result<usize, String> {
+    b.get(at..at + 2)
+        .map(|s| u16::from_le_bytes([s[0], s[1]]) as usize)
+        .ok_or_else(|| "zip truncated".to_string())
+}
+
+fn u32_at(b: &[u8], at: usize) -> Result<u32, String> {
+    b.get(at..at + 4)
+        .map(|s| u32::from_le_bytes([s[0], s[1], s[2], s[3]]))
+        .ok_or_else(|| "zip truncated".to_string())
+}
+
+/// Read every file in a log archive, in central-directory order.
+pub fn unzip_logs(bytes: &[u8]) -> Result<Vec<LogFile>, String> {
+    if bytes.len() < 22 {
+        return Err("not a zip archive".into());
+    }
+    // The end-of-central-directory record is followed by an optional comment.
+    let eocd = (0..=bytes.len() - 22)
+        .rev()
+        .find(|&i| u32_at(bytes, i) == Ok(EOCD_SIG))
+        .ok_or("not a zip archive")?;
+
+    let count = u16_at(bytes, eocd + 10)?;
+    let mut at = u32_at(bytes, eocd + 16)? as usize;
+    let mut files = Vec::with_capacity(count);
+
+    for _ in 0..count {
+        if u32_at(bytes, at)? != CENTRAL_SIG {
+            return Err("bad zip central directory".into());
+        }
+        let method = u16_at(bytes, at + 10)?;
+        let packed_len = u32_at(bytes, at + 20)? as usize;
+        let name_len = u16_at(bytes, at + 28)?;
+        let extra_len = u16_at(bytes, at + 30)?;
+        let comment_len = u16_at(bytes, at + 32)?;
+        let local = u32_at(bytes, at + 42)? as usize;
+        let name = bytes
+            .get(at + 46..at + 46 + name_len)
+            .ok_or("zip truncated")?;
+        let name = String::from_utf8_lossy(name).into_owned();
+        at += 46 + name_len + extra_len + comment_len;
+
+        if name.ends_with('/') {
+            continue;
+        }
+
+        if u32_at(bytes, local)? != LOCAL_SIG {
+            return Err(format!("bad zip entry header: {}", name));
+        }
+        let start = local + 30 + u16_at(bytes, local + 26)? + u16_at(bytes, local + 28)?;
+        let packed = bytes
+            .get(start..start + packed_len)
+            .ok_or("zip truncated")?;
+
+        let data = match method {
+            0 => packed.to_vec(),
+            8 => decompress_to_vec(packed)
+                .map_err(|e| format!("inflate failed for {}: {:?}", name, e))?,
+            m => return Err(format!("unsupported zip method {} for {}", m, name)),
+        };
+
+        files.push(LogFile {
+            name,
+            text: String::from_utf8_lossy(&data).into_owned(),
+        });
+    }
+
+    Ok(files)
+}
+
+/// Drop the `2024-05-01T12:34:56.1234567Z ` prefix Actions puts on every line.
+pub fn strip_timestamp(line: &str) -> &str {
+    let line = line.trim_start_matches('\u{feff}');
+    let (ts, rest) = line.split_once(' ').unwrap_or((line, ""));
+    let is_ts = ts.len() >= 20
+        && ts.ends_with('Z')
+        && ts.as_bytes()[4] == b'-'
+        && ts.as_bytes()[10] == b'T';
+    if is_ts {
+        rest
+    } else {
+        line
+    }
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use super::*;
+    use miniz_oxide::deflate::compress_to_vec;
+
+    /// Build a zip the way GitHub does: deflated entries, central directory last.
+    pub(crate) fn zip(files: &[(&str, &str)]) -> Vec<u8> {
+        let mut out = Vec::new();
+        let mut central = Vec::new();
+        for (name, text) in files {
+            let packed = compress_to_vec(text.as_bytes(), 6);
+            let offset = out.len() as u32;
+
+            out.extend_from_slice(&LOCAL_SIG.to_le_bytes());
+            out.extend_from_slice(&[20, 0, 0, 0, 8, 0, 0, 0, 0, 0, 0, 0, 0, 0]);
+            out.extend_from_slice(&(packed.len() as u32).to_le_bytes());
+            out.extend_from_slice(&(text.len() as u32).to_le_bytes());
+            out.extend_from_slice(&(name.len() as u16).to_le_bytes());
+            out.extend_from_slice(&0u16.to_le_bytes());
+            out.extend_from_slice(name.as_bytes());
+            out.extend_from_slice(&packed);
+
+            central.extend_from_slice(&CENTRAL_SIG.to_le_bytes());
+            central.extend_from_slice(&[20, 0, 20, 0, 0, 0, 8, 0, 0, 0, 0, 0, 0, 0, 0, 0]);
+            central.extend_from_slice(&(packed.len() as u32).to_le_bytes());
+            central.extend_from_slice(&(text.len() as u32).to_le_bytes());
+            central.extend_from_slice(&(name.len() as u16).to_le_bytes());
+            central.extend_from_slice(&[0; 12]);
+            central.extend_from_slice(&offset.to_le_bytes());
+            central.extend_from_slice(name.as_bytes());
+        }
+
+        let central_at = out.len() as u32;
+        out.extend_from_slice(&central);
+        out.extend_from_slice(&EOCD_SIG.to_le_bytes());
+        out.extend_from_slice(&[0; 4]);
+        out.extend_from_slice(&(files.len() as u16).to_le_bytes());
+        out.extend_from_slice(&(files.len() as u16).to_le_bytes());
+        out.extend_from_slice(&(central.len() as u32).to_le_bytes());
+        out.extend_from_slice(&central_at.to_le_bytes());
+        out.extend_from_slice(&0u16.to_le_bytes());
+        out
+    }
+
+    #[test]
+    fn reads_deflated_entries() {
+        let bytes = zip(&[
+            ("0_build.txt", "line one\nline two\n"),
+            ("build/3_Build.txt", "step log"),
+        ]);
+        let files = unzip_logs(&bytes).unwrap();
+        assert_eq!(files.len(), 2);
+        assert_eq!(files[0].text, "line one\nline two\n");
+        assert!(files[0].is_job_log());
+        assert!(!files[1].is_job_log());
+        assert_eq!(files[0].job_name(), "build");
+    }
+
+    #[test]
+    fn job_logs_lose_their_timestamps() {
+        let f = LogFile {
+            name: "12_build and deploy.txt".into(),
+            text: "2024-05-01T12:34:56.1234567Z one\n2024-05-01T12:34:57.1234567Z two\n".into(),
+        };
+        assert_eq!(f.job_name(), "build and deploy");
+        assert_eq!(f.plain_text(), "one\ntwo");
+    }
+
+    #[test]
+    fn rejects_non_zip() {
+        assert!(unzip_logs(b"<html>expired link</html> padding padding").is_err());
+    }
+
+    #[test]
+    fn strips_actions_timestamps() {
+        assert_eq!(
+            strip_timestamp("2024-05-01T12:34:56.1234567Z error[E0425]: x"),
+            "error[E0425]: x"
+        );
+        assert_eq!(strip_timestamp("2024-05-01T12:34:56.1234567Z"), "");
+        assert_eq!(strip_timestamp("\u{feff}plain line"), "plain line");
+        assert_eq!(strip_timestamp("cargo build"), "cargo build");
+    }
+}
@@ -0,0 +1,413 @@
+//! `plugs/<name>/plug.toml`: what a plug is, for the portal and the tools.
+//!
+//! ```toml
+//! title = "Steady Sip"
+//! description = "Hydration reminders that keep pace with your day."
+//! tags = ["health", "timer"]
+//! icon = "💧"
+//! thumbnail = "thumbnail.png"   # inside the plug folder
+//! owner = "MikeGyver Studio"
+//! # public_url defaults to https://www.webhtml5.info/<name>/
+//! ```
+//!
+//! Parsing the TOML is left to the native tools; this module holds the shape,
+//! the rules and the JSON index the portal is built from.
+
+use std::collections::BTreeMap;
+
+use serde::{Deserialize, Serialize};
+
+use crate::github::WorkflowRun;
+use crate::plug;
+
+pub const MANIFEST_FILE: &str = "plug.toml";
+
+/// Longest description the portal cards show in full.
+const MAX_DESCRIPTION: usize = 280;
+
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq, Default)]
+#[serde(deny_unknown_fields)]
+pub struct PlugManifest {
+    pub title: String,
+    #[serde(default)]
+    pub description: String,
+    #[serde(default)]
+    pub tags: Vec<String>,
+    /// An emoji shown when there is no thumbnail.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub icon: Option<String>,
+    /// Image path relative to the plug folder.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub thumbnail: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub owner: Option<String>,
+    /// Only for plugs that live somewhere other than their Hostek folder.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub public_url: Option<String>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum IssueSeverity {
+    /// Fails the index build.
+    Error,
+    Warning,
+}
+
+impl IssueSeverity {
+    pub fn label(self) -> &'static str {
+        match self {
+            IssueSeverity::Error => "error",
+            IssueSeverity::Warning => "warning",
+        }
+    }
+}
+
+/// One thing wrong with a manifest.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ManifestIssue {
+    pub severity: IssueSeverity,
+    pub message: String,
+}
+
+impl ManifestIssue {
+    fn error(message: String) -> Self {
+        ManifestIssue {
+            severity: IssueSeverity::Error,
+            message,
+        }
+    }
+
+    fn warning(message: String) -> Self {
+        ManifestIssue {
+            severity: IssueSeverity::Warning,
+            message,
+        }
+    }
+}
+
+/// Tags are lowercase words joined by hyphens, like plug names.
+fn valid_tag(tag: &str) -> bool {
+    !tag.is_empty()
+        && !tag.starts_with('-')
+        && !tag.ends_with('-')
+        && tag
+            .chars()
+            .all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '-')
+}
+
+fn toml_str(s: &str) -> String {
+    // TOML basic strings use the same escapes as JSON
+    serde_json::to_string(s).unwrap_or_else(|_| "\"\"".to_string())
+}
+
+impl PlugManifest {
+    /// A manifest for a freshly scaffolded plug.
+    pub fn starter(title: &str, description: &str) -> Self {
+        PlugManifest {
+            title: title.trim().to_string(),
+            description: description.trim().to_string(),
+            ..PlugManifest::default()
+        }
+    }
+
+    pub fn url(&self, plug_name: &str) -> String {
+        self.public_url
+            .clone()
+            .unwrap_or_else(|| plug::public_url(plug_name))
+    }
+
+    /// Problems with this manifest for the plug in `plugs/<plug_name>`.
+    /// `has_file` says whether a path inside the plug folder exists.
+    pub fn validate(&self, plug_name: &str, has_file: impl Fn(&str) -> bool) -> Vec<ManifestIssue> {
+        let mut out = Vec::new();
+
+        if self.title.trim().is_empty() {
+            out.push(ManifestIssue::error("title is empty".into()));
+        }
+        if self.description.trim().is_empty() {
+            out.push(ManifestIssue::warning(
+                "description is empty; the portal card will be bare".into(),
+            ));
+        } else if self.description.chars().count() > MAX_DESCRIPTION {
+            out.push(ManifestIssue::warning(format!(
+                "description is over {} characters and will be cut off",
+                MAX_DESCRIPTION
+            )));
+        }
+
+        if self.tags.is_empty() {
+            out.push(ManifestIssue::warning(
+                "no tags; the plug only shows up under All".into(),
+            ));
+        }
+        for tag in &self.tags {
+            if !valid_tag(tag) {
+                out.push(ManifestIssue::error(format!(
+                    "tag {:?} must be lowercase letters, numbers and hyphens",
+                    tag
+                )));
+            }
+        }
+        let mut seen = self.tags.clone();
+        seen.sort();
+        seen.dedup();
+        if seen.len() != self.tags.len() {
+            out.push(ManifestIssue::warning("tags repeat".into()));
+        }
+
+        if let Some(icon) = &self.icon {
+            if icon.trim().is_empty() || icon.chars().count() > 8 {
+                out.push(ManifestIssue::error("icon should be a single emoji".into()));
+            }
+        }
+        if let Some(thumb) = &self.thumbnail {
+            let lower = thumb.to_ascii_lowercase();
+            let image = [".png", ".jpg", ".jpeg", ".webp", ".svg", ".gif"]
+                .iter()
+                .any(|ext| lower.ends_with(ext));
+            if !image {
+                out.push(ManifestIssue::error(format!(
+                    "thumbnail {} is not an image (png, jpg, webp, svg, gif)",
+                    thumb
+                )));
+            } else if thumb.contains("..") || thumb.starts_with('/') || !has_file(thumb) {
+                out.push(ManifestIssue::error(format!(
+                    "thumbnail {} is not in {}",
+                    thumb,
+                    plug::app_dir(plug_name)
+                )));
+            }
+        }
+        if let Some(url) = &self.public_url {
+            if !url.starts_with("https://") {
+                out.push(ManifestIssue::error(format!(
+                    "public_url {} must start with https://",
+                    url
+                )));
+            }
+        }
+
+        out
+    }
+
+    /// The manifest as `plug.toml` text, fields in the documented order.
+    pub fn to_toml(&self) -> String {
+        let mut out = format!(
+            "title = {}\ndescription = {}\ntags = [{}]\n",
+            toml_str(&self.title),
+            toml_str(&self.description),
+            self.tags
+                .iter()
+                .map(|t| toml_str(t))
+                .collect::<Vec<_>>()
+                .join(", ")
+        );
+        let optional = [
+            ("icon", &self.icon),
+            ("thumbnail", &self.thumbnail),
+            ("owner", &self.owner),
+            ("public_url", &self.public_url),
+        ];
+        for (key, value) in optional {
+            if let Some(v) = value {
+                out.push_str(&format!("{} = {}\n", key, toml_str(v)));
+            }
+        }
+        out
+    }
+}
+
+/// One portal card.
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq)]
+pub struct PortalEntry {
+    pub name: String,
+    pub title: String,
+    pub description: String,
+    pub tags: Vec<String>,
+    pub icon: Option<String>,
+    pub owner: Option<String>,
+    pub url: String,
+    /// Relative to the portal root (`thumbs/<name>.png`).
+    pub thumbnail: Option<String>,
+}
+
+impl PortalEntry {
+    pub fn new(name: &str, m: &PlugManifest, thumbnail: Option<String>) -> Self {
+        PortalEntry {
+            name: name.to_string(),
+            title: m.title.trim().to_string(),
+            description: m.description.trim().to_string(),
+            tags: m.tags.clone(),
+            icon: m.icon.clone(),
+            owner: m.owner.clone(),
+            url: m.url(name),
+            thumbnail,
+        }
+    }
+
+    /// Case-insensitive match on name, title, description, tags and owner.
+    pub fn matches(&self, query: &str) -> bool {
+        let q = query.trim().to_lowercase();
+        if q.is_empty() {
+            return true;
+        }
+        let haystack = format!(
+            "{} {} {} {} {}",
+            self.name,
+            self.title,
+            self.description,
+            self.tags.join(" "),
+            self.owner.as_deref().unwrap_or_default()
+        )
+        .to_lowercase();
+        q.split_whitespace().all(|word| haystack.contains(word))
+    }
+}
+
+/// Everything the portal lists, generated by `tools/plug-index`.
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq, Default)]
+pub struct PortalIndex {
+    pub plugs: Vec<PortalEntry>,
+}
+
+impl PortalIndex {
+    /// Every tag with how many plugs carry it, most used first.
+    pub fn tags(&self) -> Vec<(String, usize)> {
+        let mut counts: BTreeMap<&str, usize> = BTreeMap::new();
+        for tag in self.plugs.iter().flat_map(|p| &p.tags) {
+            *counts.entry(tag).or_default() += 1;
+        }
+        let mut out: Vec<(String, usize)> = counts
+            .into_iter()
+            .map(|(t, n)| (t.to_string(), n))
+            .collect();
+        out.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+        out
+    }
+
+    pub fn search(&self, query: &str, tag: Option<&str>) -> Vec<&PortalEntry> {
+        self.plugs
+            .iter()
+            .filter(|p| tag.is_none_or(|t| p.tags.iter().any(|pt| pt == t)))
+            .filter(|p| p.matches(query))
+            .collect()
+    }
+}
+
+/// The plug a deploy run published, from the `Deploy <plug>` run name the
+/// workflow sets.
+pub fn deployed_plug(run: &WorkflowRun) -> Option<&str> {
+    run.display_title.as_deref()?.strip_prefix("Deploy ")
+}
+
+/// Newest deploy run per plug, from runs listed newest first.
+pub fn last_deploys(runs: &[WorkflowRun]) -> BTreeMap<String, WorkflowRun> {
+    let mut out = BTreeMap::new();
+    for run in runs {
+        if let Some(name) = deployed_plug(run) {
+            out.entry(name.to_string()).or_insert_with(|| run.clone());
+        }
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn manifest() -> PlugManifest {
+        PlugManifest {
+            title: "Steady Sip".into(),
+            description: "Hydration reminders.".into(),
+            tags: vec!["health".into(), "timer".into()],
+            icon: Some("💧".into()),
+            ..PlugManifest::default()
+        }
+    }
+
+    fn run(id: u64, title: &str, conclusion: &str) -> WorkflowRun {
+        WorkflowRun {
+            id,
+            html_url: "u".into(),
+            name: None,
+            display_title: Some(title.into()),
+            status: Some("completed".into()),
+            conclusion: Some(conclusion.into()),
+            created_at: None,
+        }
+    }
+
+    #[test]
+    fn valid_manifest_has_no_issues() {
+        assert!(manifest().validate("steady-sip", |_| true).is_empty());
+        assert_eq!(
+            manifest().url("steady-sip"),
+            "https://www.webhtml5.info/steady-sip/"
+        );
+    }
+
+    #[test]
+    fn flags_bad_fields() {
+        let m = PlugManifest {
+            title: " ".into(),
+            tags: vec!["Dev Tools".into()],
+            thumbnail: Some("shot.png".into()),
+            public_url: Some("http://example.com".into()),
+            ..PlugManifest::default()
+        };
+        let issues = m.validate("demo", |_| false);
+        let errors: Vec<&str> = issues
+            .iter()
+            .filter(|i| i.severity == IssueSeverity::Error)
+            .map(|i| i.message.as_str())
+            .collect();
+        assert_eq!(errors.len(), 4, "{:?}", errors);
+        assert!(errors[2].contains("not in plugs/demo"));
+        assert!(issues
+            .iter()
+            .any(|i| i.severity == IssueSeverity::Warning && i.message.contains("description")));
+    }
+
+    #[test]
+    fn to_toml_escapes_and_skips_unset_fields() {
+        let mut m = manifest();
+        m.description = "Say \"when\"".into();
+        assert_eq!(
+            m.to_toml(),
+            "title = \"Steady Sip\"\ndescription = \"Say \\\"when\\\"\"\ntags = [\"health\", \"timer\"]\nicon = \"💧\"\n"
+        );
+    }
+
+    #[test]
+    fn search_by_words_and_tag() {
+        let mut other = manifest();
+        other.title = "Pi Art Generator".into();
+        other.description = "Art from the digits of pi.".into();
+        other.tags = vec!["pi".into(), "art".into()];
+        let index = PortalIndex {
+            plugs: vec![
+                PortalEntry::new("steady-sip", &manifest(), None),
+                PortalEntry::new("pi-art-generator", &other, None),
+            ],
+        };
+
+        assert_eq!(index.search("", None).len(), 2);
+        assert_eq!(index.search("PI art", None)[0].name, "pi-art-generator");
+        assert_eq!(index.search("hydration", Some("pi")).len(), 0);
+        assert_eq!(index.search("", Some("health"))[0].name, "steady-sip");
+        assert_eq!(index.tags().len(), 4);
+        assert_eq!(index.tags()[0], ("art".to_string(), 1));
+    }
+
+    #[test]
+    fn last_deploy_per_plug_is_the_newest() {
+        let runs = vec![
+            run(3, "Deploy steady-sip", "failure"),
+            run(2, "Index", "success"),
+            run(1, "Deploy steady-sip", "success"),
+        ];
+        let last = last_deploys(&runs);
+        assert_eq!(last.len(), 1);
+        assert_eq!(last["steady-sip"].id, 3);
+    }
+}
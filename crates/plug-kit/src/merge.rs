@@ -0,0 +1,357 @@
+//! Line diffs and three-way merges for the file editor.
+//!
+//! When a save hits a 409 the editor has three versions of a file: the base it
+//! loaded, its own edits and what is on GitHub now. `merge3` applies every
+//! change that only one side made and leaves the overlapping ones as conflicts
+//! for the user to pick.
+//!
+//! Lines keep their `\n`, so joining them gives back the exact text.
+
+/// Past this many inserted + removed lines the diff stops looking for matches
+/// and treats the rest as one replaced block.
+const MAX_EDITS: usize = 2000;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DiffKind {
+    Same,
+    Removed,
+    Added,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct DiffLine {
+    pub kind: DiffKind,
+    pub text: String,
+}
+
+fn lines(text: &str) -> Vec<&str> {
+    text.split_inclusive('\n').collect()
+}
+
+/// Index pairs `(i, j)` with `a[i] == b[j]` forming a longest common
+/// subsequence (Myers' O(ND) algorithm), in increasing order.
+fn matches(a: &[&str], b: &[&str]) -> Vec<(usize, usize)> {
+    // Common prefix and suffix are matched without running the search.
+    let prefix = a.iter().zip(b).take_while(|(x, y)| x == y).count();
+    let suffix = a[prefix..]
+        .iter()
+        .rev()
+        .zip(b[prefix..].iter().rev())
+        .take_while(|(x, y)| x == y)
+        .count();
+    let (mid_a, mid_b) = (&a[prefix..a.len() - suffix], &b[prefix..b.len() - suffix]);
+
+    let mut out: Vec<(usize, usize)> = (0..prefix).map(|i| (i, i)).collect();
+    out.extend(
+        myers(mid_a, mid_b)
+            .into_iter()
+            .map(|(i, j)| (i + prefix, j + prefix)),
+    );
+    out.extend((0..suffix).map(|s| (a.len() - suffix + s, b.len() - suffix + s)));
+    out
+}
+
+fn myers(a: &[&str], b: &[&str]) -> Vec<(usize, usize)> {
+    let (n, m) = (a.len() as isize, b.len() as isize);
+    let max = (a.len() + b.len()).min(MAX_EDITS) as isize;
+    let offset = max + 1;
+    let at = |k: isize| (k + offset) as usize;
+
+    let mut v = vec![0isize; 2 * max as usize + 3];
+    let mut trace = Vec::new();
+    let mut found = false;
+
+    'search: for d in 0..=max {
+        trace.push(v.clone());
+        for k in (-d..=d).step_by(2) {
+            let mut x = if k == -d || (k != d && v[at(k - 1)] < v[at(k + 1)]) {
+                v[at(k + 1)]
+            } else {
+                v[at(k - 1)] + 1
+            };
+            let mut y = x - k;
+            while x < n && y < m && a[x as usize] == b[y as usize] {
+                x += 1;
+                y += 1;
+            }
+            v[at(k)] = x;
+            if x >= n && y >= m {
+                found = true;
+                break 'search;
+            }
+        }
+    }
+    if !found {
+        return Vec::new();
+    }
+
+    let mut out = Vec::new();
+    let (mut x, mut y) = (n, m);
+    for (d, v) in trace.iter().enumerate().rev() {
+        let d = d as isize;
+        let k = x - y;
+        let prev_k = if k == -d || (k != d && v[at(k - 1)] < v[at(k + 1)]) {
+            k + 1
+        } else {
+            k - 1
+        };
+        let prev_x = v[at(prev_k)];
+        let prev_y = prev_x - prev_k;
+        while x > prev_x && y > prev_y {
+            x -= 1;
+            y -= 1;
+            out.push((x as usize, y as usize));
+        }
+        x = prev_x;
+        y = prev_y;
+    }
+    out.reverse();
+    out
+}
+
+/// Line diff turning `old` into `new`.
+pub fn diff_lines(old: &str, new: &str) -> Vec<DiffLine> {
+    let (a, b) = (lines(old), lines(new));
+    let line = |kind, text: &str| DiffLine {
+        kind,
+        text: text.trim_end_matches('\n').to_string(),
+    };
+
+    let mut out = Vec::new();
+    let (mut i, mut j) = (0, 0);
+    for (mi, mj) in matches(&a, &b).into_iter().chain([(a.len(), b.len())]) {
+        out.extend(a[i..mi].iter().map(|t| line(DiffKind::Removed, t)));
+        out.extend(b[j..mj].iter().map(|t| line(DiffKind::Added, t)));
+        if mi < a.len() {
+            out.push(line(DiffKind::Same, a[mi]));
+        }
+        (i, j) = (mi + 1, mj + 1);
+    }
+    out
+}
+
+/// Both sides changed the same region differently.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Conflict {
+    pub base: String,
+    pub ours: String,
+    pub theirs: String,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum MergeHunk {
+    Clean(String),
+    Conflict(Conflict),
+}
+
+/// How to settle one conflict.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Resolution {
+    Ours,
+    Theirs,
+    /// Ours, then theirs.
+    Both,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Merge {
+    pub hunks: Vec<MergeHunk>,
+}
+
+impl Merge {
+    pub fn is_clean(&self) -> bool {
+        self.conflicts().next().is_none()
+    }
+
+    pub fn conflicts(&self) -> impl Iterator<Item = &Conflict> {
+        self.hunks.iter().filter_map(|h| match h {
+            MergeHunk::Conflict(c) => Some(c),
+            MergeHunk::Clean(_) => None,
+        })
+    }
+
+    /// The merged text, settling the n-th conflict with `choices[n]`
+    /// (missing choices keep ours).
+    pub fn resolve(&self, choices: &[Resolution]) -> String {
+        let mut out = String::new();
+        let mut n = 0;
+        for hunk in &self.hunks {
+            match hunk {
+                MergeHunk::Clean(text) => out.push_str(text),
+                MergeHunk::Conflict(c) => {
+                    match choices.get(n).copied().unwrap_or(Resolution::Ours) {
+                        Resolution::Ours => out.push_str(&c.ours),
+                        Resolution::Theirs => out.push_str(&c.theirs),
+                        Resolution::Both => {
+                            out.push_str(&c.ours);
+                            if !c.ours.is_empty() && !c.ours.ends_with('\n') {
+                                out.push('\n');
+                            }
+                            out.push_str(&c.theirs);
+                        }
+                    }
+                    n += 1;
+                }
+            }
+        }
+        out
+    }
+
+    /// The merged text when there is nothing to settle.
+    pub fn text(&self) -> Option<String> {
+        self.is_clean().then(|| self.resolve(&[]))
+    }
+}
+
+/// Merge `ours` and `theirs`, both edited from `base`.
+pub fn merge3(base: &str, ours: &str, theirs: &str) -> Merge {
+    let (b, o, t) = (lines(base), lines(ours), lines(theirs));
+
+    let mut to_ours = vec![None; b.len()];
+    for (i, j) in matches(&b, &o) {
+        to_ours[i] = Some(j);
+    }
+    let mut to_theirs = vec![None; b.len()];
+    for (i, k) in matches(&b, &t) {
+        to_theirs[i] = Some(k);
+    }
+
+    fn push_clean(hunks: &mut Vec<MergeHunk>, text: String) {
+        if text.is_empty() {
+            return;
+        }
+        if let Some(MergeHunk::Clean(prev)) = hunks.last_mut() {
+            prev.push_str(&text);
+        } else {
+            hunks.push(MergeHunk::Clean(text));
+        }
+    }
+
+    let mut hunks: Vec<MergeHunk> = Vec::new();
+    let (mut i, mut j, mut k) = (0, 0, 0);
+    loop {
+        // Next base line both sides kept: everything before it is one unstable chunk.
+        let stable = (i..b.len()).find_map(|x| Some((x, to_ours[x]?, to_theirs[x]?)));
+        let (si, sj, sk) = stable.unwrap_or((b.len(), o.len(), t.len()));
+
+        if (si, sj, sk) != (i, j, k) {
+            let base_chunk = b[i..si].concat();
+            let ours_chunk = o[j..sj].concat();
+            let theirs_chunk = t[k..sk].concat();
+            if ours_chunk == base_chunk || ours_chunk == theirs_chunk {
+                push_clean(&mut hunks, theirs_chunk);
+            } else if theirs_chunk == base_chunk {
+                push_clean(&mut hunks, ours_chunk);
+            } else {
+                hunks.push(MergeHunk::Conflict(Conflict {
+                    base: base_chunk,
+                    ours: ours_chunk,
+                    theirs: theirs_chunk,
+                }));
+            }
+        }
+
+        if stable.is_none() {
+            break;
+        }
+        push_clean(&mut hunks, b[si].to_string());
+        (i, j, k) = (si + 1, sj + 1, sk + 1);
+    }
+
+    Merge { hunks }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const BASE: &str =
+        "fn main() {\n    let a = 1;\n    let b = 2;\n    println!(\"{}\", a + b);\n}\n";
+
+    #[test]
+    fn diff_marks_added_and_removed_lines() {
+        let diff = diff_lines("a\nb\nc\n", "a\nx\nc\nd\n");
+        let kinds: Vec<(DiffKind, &str)> = diff.iter().map(|d| (d.kind, d.text.as_str())).collect();
+        assert_eq!(
+            kinds,
+            vec![
+                (DiffKind::Same, "a"),
+                (DiffKind::Removed, "b"),
+                (DiffKind::Added, "x"),
+                (DiffKind::Same, "c"),
+                (DiffKind::Added, "d"),
+            ]
+        );
+    }
+
+    #[test]
+    fn diff_of_unrelated_texts() {
+        let diff = diff_lines("a\nb\n", "c\n");
+        assert_eq!(
+            diff.iter().filter(|d| d.kind == DiffKind::Removed).count(),
+            2
+        );
+        assert_eq!(diff.iter().filter(|d| d.kind == DiffKind::Added).count(), 1);
+        assert!(diff_lines("", "").is_empty());
+    }
+
+    #[test]
+    fn applies_edits_to_different_lines() {
+        let ours = BASE.replace("let a = 1;", "let a = 10;");
+        let theirs = BASE.replace("a + b", "a - b");
+        let merged = merge3(BASE, &ours, &theirs);
+        assert!(merged.is_clean());
+        assert_eq!(
+            merged.text().unwrap(),
+            BASE.replace("let a = 1;", "let a = 10;")
+                .replace("a + b", "a - b")
+        );
+    }
+
+    #[test]
+    fn same_edit_on_both_sides_is_clean() {
+        let edited = BASE.replace("a + b", "a * b");
+        assert_eq!(merge3(BASE, &edited, &edited).text().unwrap(), edited);
+    }
+
+    #[test]
+    fn insertions_at_both_ends_merge() {
+        let ours = format!("use std::fmt;\n{}", BASE);
+        let theirs = format!("{}// end\n", BASE);
+        let merged = merge3(BASE, &ours, &theirs);
+        assert_eq!(
+            merged.text().unwrap(),
+            format!("use std::fmt;\n{}// end\n", BASE)
+        );
+    }
+
+    #[test]
+    fn overlapping_edits_conflict() {
+        let ours = BASE.replace("let b = 2;", "let b = 3;");
+        let theirs = BASE.replace("let b = 2;", "let b = 4;");
+        let merged = merge3(BASE, &ours, &theirs);
+        assert!(!merged.is_clean());
+        assert_eq!(merged.text(), None);
+
+        let c = merged.conflicts().next().unwrap();
+        assert_eq!(c.base, "    let b = 2;\n");
+        assert_eq!(c.ours, "    let b = 3;\n");
+        assert_eq!(c.theirs, "    let b = 4;\n");
+
+        assert_eq!(merged.resolve(&[Resolution::Theirs]), theirs);
+        assert_eq!(merged.resolve(&[]), ours);
+        assert!(merged
+            .resolve(&[Resolution::Both])
+            .contains("    let b = 3;\n    let b = 4;\n"));
+    }
+
+    #[test]
+    fn delete_against_edit_conflicts() {
+        let ours = BASE.replace("    let a = 1;\n", "");
+        let theirs = BASE.replace("let a = 1;", "let a = 5;");
+        let merged = merge3(BASE, &ours, &theirs);
+        let c = merged.conflicts().next().unwrap();
+        assert_eq!(c.ours, "");
+        assert_eq!(c.theirs, "    let a = 5;\n");
+    }
+}
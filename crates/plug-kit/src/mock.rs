@@ -0,0 +1,77 @@
+//! Scripted transport for unit tests: replies are popped in order and every
+//! request is recorded for assertions.
+
+use std::cell::RefCell;
+use std::collections::VecDeque;
+
+use crate::transport::{HttpRequest, HttpResponse, Transport};
+
+#[derive(Default)]
+pub struct MockTransport {
+    replies: RefCell<VecDeque<Result<HttpResponse, String>>>,
+    pub requests: RefCell<Vec<HttpRequest>>,
+    pub sleeps: RefCell<Vec<u64>>,
+}
+
+impl MockTransport {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn reply(self, status: u16, body: &str) -> Self {
+        self.reply_with_headers(status, &[], body)
+    }
+
+    pub fn reply_with_headers(self, status: u16, headers: &[(&str, &str)], body: &str) -> Self {
+        self.replies.borrow_mut().push_back(Ok(HttpResponse {
+            status,
+            headers: headers
+                .iter()
+                .map(|(k, v)| (k.to_ascii_lowercase(), v.to_string()))
+                .collect(),
+            body: body.as_bytes().to_vec(),
+        }));
+        self
+    }
+
+    pub fn reply_bytes(self, status: u16, body: Vec<u8>) -> Self {
+        self.replies.borrow_mut().push_back(Ok(HttpResponse {
+            status,
+            headers: vec![],
+            body,
+        }));
+        self
+    }
+
+    pub fn fail(self, err: &str) -> Self {
+        self.replies.borrow_mut().push_back(Err(err.to_string()));
+        self
+    }
+
+    pub fn request(&self, i: usize) -> HttpRequest {
+        self.requests.borrow()[i].clone()
+    }
+
+    pub fn request_count(&self) -> usize {
+        self.requests.borrow().len()
+    }
+
+    pub fn request_json(&self, i: usize) -> serde_json::Value {
+        let body = self.request(i).body.expect("request has no body");
+        serde_json::from_str(&body).expect("request body is not JSON")
+    }
+}
+
+impl Transport for MockTransport {
+    async fn send(&self, req: HttpRequest) -> Result<HttpResponse, String> {
+        self.requests.borrow_mut().push(req.clone());
+        self.replies
+            .borrow_mut()
+            .pop_front()
+            .unwrap_or_else(|| panic!("unexpected request: {} {}", req.method.as_str(), req.url))
+    }
+
+    async fn sleep(&self, secs: u64) {
+        self.sleeps.borrow_mut().push(secs);
+    }
+}
@@ -0,0 +1,62 @@
+//! Naming conventions shared by everything that creates or deploys a plug.
+
+/// Hostek site root; each plug is published under `/<plug_name>/`.
+pub const SITE_ROOT: &str = "https://www.webhtml5.info";
+
+/// The plug name if it is a valid Hostek folder: lowercase letters, digits and
+/// hyphens. Every tool that creates or deploys a plug checks names this way.
+pub fn sanitize_name(s: &str) -> Option<String> {
+    let p = s.trim();
+    let valid = !p.is_empty()
+        && p.chars()
+            .all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '-');
+    valid.then(|| p.to_string())
+}
+
+pub fn public_url(plug_name: &str) -> String {
+    format!("{}/{}/", SITE_ROOT, plug_name)
+}
+
+/// Repo folder holding the plug's Trunk project.
+pub fn app_dir(plug_name: &str) -> String {
+    format!("plugs/{}", plug_name)
+}
+
+/// Branch used when a change goes through a pull request.
+pub fn feature_branch(plug_name: &str) -> String {
+    format!("plug/{}", plug_name)
+}
+
+/// Hostek folder for branch preview deploys, so previews never overwrite
+/// the live plug.
+pub fn preview_name(plug_name: &str) -> String {
+    format!("{}-preview", plug_name)
+}
+
+/// Hostek folder a deploy lands in: the plug name, or its preview folder.
+pub fn deploy_folder(plug_name: &str, preview: bool) -> String {
+    if preview {
+        preview_name(plug_name)
+    } else {
+        plug_name.to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn sanitize_name_accepts_hostek_folders_only() {
+        assert_eq!(sanitize_name("  my-plug-2 ").as_deref(), Some("my-plug-2"));
+        assert_eq!(sanitize_name("My Plug"), None);
+        assert_eq!(sanitize_name("../etc"), None);
+        assert_eq!(sanitize_name(""), None);
+    }
+
+    #[test]
+    fn preview_deploys_use_their_own_folder() {
+        assert_eq!(deploy_folder("demo", false), "demo");
+        assert_eq!(deploy_folder("demo", true), "demo-preview");
+    }
+}
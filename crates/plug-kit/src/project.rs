@@ -0,0 +1,583 @@
+//! Working copy of one plug folder for the multi-file editor: which files
+//! exist on GitHub, which are open in tabs, what has been edited, created,
+//! renamed or deleted, and the single change set that saves all of it.
+//!
+//! Paths are relative to the plug folder (`src/main.rs`), never `plugs/<name>/…`.
+
+use std::collections::BTreeMap;
+
+use serde::Deserialize;
+
+use crate::error::GitHubError;
+use crate::git_data::FileChange;
+use crate::github::{expect_ok, parse_json, GitHubClient};
+use crate::transport::{Method, Transport};
+
+/// A blob in a plug folder, from the Git Trees API.
+#[derive(Deserialize, Debug, Clone, PartialEq)]
+pub struct TreeFile {
+    pub path: String,
+    pub sha: String,
+    #[serde(default)]
+    pub size: u64,
+}
+
+#[derive(Deserialize)]
+struct TreeResp {
+    tree: Vec<TreeItem>,
+    #[serde(default)]
+    truncated: bool,
+}
+
+#[derive(Deserialize)]
+struct TreeItem {
+    path: String,
+    #[serde(rename = "type")]
+    kind: String,
+    sha: String,
+    #[serde(default)]
+    size: u64,
+}
+
+impl<T: Transport> GitHubClient<T> {
+    /// Every file under `dir` on the configured branch, paths relative to `dir`.
+    pub async fn list_tree(&self, dir: &str) -> Result<Vec<TreeFile>, GitHubError> {
+        let dir = dir.trim_matches('/');
+        let url = self.repo_url(&format!(
+            "git/trees/{}:{}?recursive=1",
+            self.config().branch,
+            dir
+        ));
+        let resp = self.request(Method::Get, &url, None).await?;
+        let resp = expect_ok(resp, dir, "List files")?;
+        let json: TreeResp = parse_json(&resp)?;
+        if json.truncated {
+            return Err(GitHubError::Decode(format!(
+                "file list for {} is truncated",
+                dir
+            )));
+        }
+        Ok(json
+            .tree
+            .into_iter()
+            .filter(|i| i.kind == "blob")
+            .map(|i| TreeFile {
+                path: i.path,
+                sha: i.sha,
+                size: i.size,
+            })
+            .collect())
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+struct Entry {
+    /// Path this file had on GitHub; `None` for files created here.
+    origin: Option<String>,
+    /// Buffer; `None` until the file is loaded.
+    text: Option<String>,
+}
+
+/// One row of the folder tree view.
+#[derive(Debug, Clone, PartialEq)]
+pub struct TreeRow {
+    pub depth: usize,
+    pub name: String,
+    /// Full path for files, `None` for folders.
+    pub file: Option<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct Project {
+    pub plug: String,
+    /// Path → blob sha as last seen on GitHub (`None` after a branch commit).
+    remote: BTreeMap<String, Option<String>>,
+    /// Remote path → content as loaded.
+    base: BTreeMap<String, String>,
+    entries: BTreeMap<String, Entry>,
+    tabs: Vec<String>,
+    active: Option<String>,
+}
+
+/// Reject paths that would escape the plug folder or can't be committed.
+pub fn validate_path(path: &str) -> Result<String, String> {
+    let p = path.trim().trim_start_matches("./");
+    if p.is_empty() {
+        return Err("File name is empty.".into());
+    }
+    if p.starts_with('/') || p.ends_with('/') || p.contains('\\') {
+        return Err(format!("Invalid file path: {}", p));
+    }
+    if p.split('/')
+        .any(|seg| seg.is_empty() || seg == "." || seg == "..")
+    {
+        return Err(format!("Invalid file path: {}", p));
+    }
+    Ok(p.to_string())
+}
+
+impl Project {
+    pub fn new(plug: &str, files: Vec<TreeFile>) -> Self {
+        let mut p = Project {
+            plug: plug.to_string(),
+            ..Project::default()
+        };
+        for f in files {
+            p.remote.insert(f.path.clone(), Some(f.sha));
+            p.entries.insert(
+                f.path.clone(),
+                Entry {
+                    origin: Some(f.path),
+                    text: None,
+                },
+            );
+        }
+        p
+    }
+
+    /// `plugs/<plug>/<path>`.
+    pub fn repo_path(&self, path: &str) -> String {
+        format!("{}/{}", crate::plug::app_dir(&self.plug), path)
+    }
+
+    pub fn paths(&self) -> impl Iterator<Item = &str> {
+        self.entries.keys().map(String::as_str)
+    }
+
+    pub fn contains(&self, path: &str) -> bool {
+        self.entries.contains_key(path)
+    }
+
+    pub fn text(&self, path: &str) -> Option<&str> {
+        self.entries.get(path)?.text.as_deref()
+    }
+
+    pub fn is_loaded(&self, path: &str) -> bool {
+        self.text(path).is_some()
+    }
+
+    /// Path the file has on GitHub; `None` for files created here.
+    pub fn origin(&self, path: &str) -> Option<&str> {
+        self.entries.get(path)?.origin.as_deref()
+    }
+
+    /// Blob sha on GitHub, if the file is there under this same path.
+    pub fn sha(&self, path: &str) -> Option<&str> {
+        let origin = self.origin(path)?;
+        if origin != path {
+            return None;
+        }
+        self.remote.get(origin)?.as_deref()
+    }
+
+    /// Record freshly fetched content. Keeps any unsaved edits in the buffer.
+    pub fn set_loaded(&mut self, path: &str, content: &str, sha: &str) {
+        let Some(entry) = self.entries.get_mut(path) else {
+            return;
+        };
+        let Some(origin) = entry.origin.clone() else {
+            return;
+        };
+        self.base.insert(origin.clone(), content.to_string());
+        self.remote.insert(origin, Some(sha.to_string()));
+        if entry.text.is_none() {
+            entry.text = Some(content.to_string());
+        }
+    }
+
+    /// Content the file had on GitHub when it was loaded (or last saved).
+    pub fn base_text(&self, path: &str) -> Option<&str> {
+        self.base.get(self.origin(path)?).map(String::as_str)
+    }
+
+    /// After merging in a newer remote version: `remote` at `sha` becomes the
+    /// base and `merged` the buffer, so the next save sends the new sha.
+    pub fn rebase(&mut self, path: &str, remote: &str, sha: &str, merged: &str) {
+        let Some(entry) = self.entries.get_mut(path) else {
+            return;
+        };
+        let Some(origin) = entry.origin.clone() else {
+            return;
+        };
+        self.base.insert(origin.clone(), remote.to_string());
+        self.remote.insert(origin, Some(sha.to_string()));
+        entry.text = Some(merged.to_string());
+    }
+
+    /// Edited files whose GitHub copy moved on since it was loaded, judged
+    /// against a fresh `list_tree`. Files deleted on GitHub are not included.
+    pub fn stale_paths(&self, current: &[TreeFile]) -> Vec<String> {
+        self.dirty_paths()
+            .into_iter()
+            .filter(|path| {
+                let Some(origin) = self.origin(path) else {
+                    return false;
+                };
+                let known = self.remote.get(origin).cloned().flatten();
+                self.base.contains_key(origin)
+                    && current
+                        .iter()
+                        .any(|f| f.path == origin && Some(&f.sha) != known.as_ref())
+            })
+            .map(str::to_string)
+            .collect()
+    }
+
+    pub fn edit(&mut self, path: &str, text: &str) {
+        if let Some(entry) = self.entries.get_mut(path) {
+            entry.text = Some(text.to_string());
+        }
+    }
+
+    pub fn is_dirty(&self, path: &str) -> bool {
+        let Some(entry) = self.entries.get(path) else {
+            return false;
+        };
+        match (&entry.origin, &entry.text) {
+            (Some(origin), _) if origin != path => true,
+            (Some(origin), Some(text)) => self.base.get(origin) != Some(text),
+            (Some(_), None) => false,
+            (None, _) => true,
+        }
+    }
+
+    /// Remote files removed here (directly or by renaming them away).
+    pub fn deleted_paths(&self) -> Vec<&str> {
+        self.remote
+            .keys()
+            .map(String::as_str)
+            .filter(|p| !self.contains(p))
+            .collect()
+    }
+
+    pub fn dirty_paths(&self) -> Vec<&str> {
+        self.paths().filter(|p| self.is_dirty(p)).collect()
+    }
+
+    pub fn has_changes(&self) -> bool {
+        !self.dirty_paths().is_empty() || !self.deleted_paths().is_empty()
+    }
+
+    pub fn create(&mut self, path: &str) -> Result<String, String> {
+        let path = validate_path(path)?;
+        if self.contains(&path) {
+            return Err(format!("{} already exists.", path));
+        }
+        self.entries.insert(
+            path.clone(),
+            Entry {
+                origin: None,
+                text: Some(String::new()),
+            },
+        );
+        self.open(&path);
+        Ok(path)
+    }
+
+    /// Move a loaded file to `to`; saved as a delete of the old path plus a write.
+    pub fn rename(&mut self, from: &str, to: &str) -> Result<String, String> {
+        let to = validate_path(to)?;
+        if to == from {
+            return Ok(to);
+        }
+        if self.contains(&to) {
+            return Err(format!("{} already exists.", to));
+        }
+        if !self.is_loaded(from) {
+            return Err(format!("Open {} before renaming it.", from));
+        }
+        let entry = self
+            .entries
+            .remove(from)
+            .ok_or(format!("No file {}", from))?;
+        self.entries.insert(to.clone(), entry);
+        for tab in self.tabs.iter_mut().filter(|t| *t == from) {
+            *tab = to.clone();
+        }
+        if self.active.as_deref() == Some(from) {
+            self.active = Some(to.clone());
+        }
+        Ok(to)
+    }
+
+    pub fn delete(&mut self, path: &str) {
+        self.entries.remove(path);
+        self.close(path);
+    }
+
+    pub fn tabs(&self) -> &[String] {
+        &self.tabs
+    }
+
+    pub fn active(&self) -> Option<&str> {
+        self.active.as_deref()
+    }
+
+    pub fn open(&mut self, path: &str) {
+        if !self.contains(path) {
+            return;
+        }
+        if !self.tabs.iter().any(|t| t == path) {
+            self.tabs.push(path.to_string());
+        }
+        self.active = Some(path.to_string());
+    }
+
+    /// Close a tab; the neighbour to its left (or right) becomes active.
+    pub fn close(&mut self, path: &str) {
+        let Some(i) = self.tabs.iter().position(|t| t == path) else {
+            return;
+        };
+        self.tabs.remove(i);
+        if self.active.as_deref() == Some(path) {
+            self.active = self.tabs.get(i.saturating_sub(1)).cloned();
+        }
+    }
+
+    /// Everything "save all" has to commit, as repo paths. Errors if a changed
+    /// file was never loaded (only renames can get there).
+    pub fn changes(&self) -> Result<Vec<FileChange>, String> {
+        let mut out = Vec::new();
+        for path in self.dirty_paths() {
+            let entry = &self.entries[path];
+            let text = entry
+                .text
+                .as_deref()
+                .or_else(|| self.base.get(entry.origin.as_deref()?).map(String::as_str))
+                .ok_or(format!("{} was never loaded.", path))?;
+            out.push(FileChange::write(&self.repo_path(path), text));
+        }
+        for path in self.deleted_paths() {
+            out.push(FileChange::delete(&self.repo_path(path)));
+        }
+        Ok(out)
+    }
+
+    /// After a successful commit: what is in the buffers is now the base.
+    /// `shas` are the new blob shas (e.g. from `list_tree`); missing ones become `None`.
+    pub fn mark_saved(&mut self, shas: &BTreeMap<String, String>) {
+        self.remote.clear();
+        self.base.clear();
+        for (path, entry) in self.entries.iter_mut() {
+            entry.origin = Some(path.clone());
+            self.remote.insert(path.clone(), shas.get(path).cloned());
+            if let Some(text) = &entry.text {
+                self.base.insert(path.clone(), text.clone());
+            }
+        }
+    }
+
+    /// After a single-file PUT of `path`.
+    pub fn mark_file_saved(&mut self, path: &str, sha: &str) {
+        let Some(entry) = self.entries.get_mut(path) else {
+            return;
+        };
+        entry.origin = Some(path.to_string());
+        self.remote.insert(path.to_string(), Some(sha.to_string()));
+        if let Some(text) = &entry.text {
+            self.base.insert(path.to_string(), text.clone());
+        }
+    }
+
+    /// Folders and files in display order, folders first at each level.
+    pub fn tree_rows(&self) -> Vec<TreeRow> {
+        let mut paths: Vec<&str> = self.paths().collect();
+        // (0, dir) sorts ahead of (1, file) at every level
+        paths.sort_by_key(|p| {
+            let parts: Vec<&str> = p.split('/').collect();
+            let last = parts.len() - 1;
+            parts
+                .into_iter()
+                .enumerate()
+                .map(|(i, seg)| (u8::from(i == last), seg))
+                .collect::<Vec<_>>()
+        });
+
+        let mut rows = Vec::new();
+        let mut open_dirs: Vec<&str> = Vec::new();
+        for path in paths {
+            let parts: Vec<&str> = path.split('/').collect();
+            let dirs = &parts[..parts.len() - 1];
+
+            let shared = open_dirs
+                .iter()
+                .zip(dirs)
+                .take_while(|(a, b)| a == b)
+                .count();
+            open_dirs.truncate(shared);
+            for (depth, dir) in dirs.iter().enumerate().skip(shared) {
+                rows.push(TreeRow {
+                    depth,
+                    name: dir.to_string(),
+                    file: None,
+                });
+                open_dirs.push(dir);
+            }
+            rows.push(TreeRow {
+                depth: dirs.len(),
+                name: parts[parts.len() - 1].to_string(),
+                file: Some(path.to_string()),
+            });
+        }
+        rows
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mock::MockTransport;
+    use crate::GitHubConfig;
+    use futures::executor::block_on;
+
+    fn file(path: &str, sha: &str) -> TreeFile {
+        TreeFile {
+            path: path.into(),
+            sha: sha.into(),
+            size: 0,
+        }
+    }
+
+    fn demo() -> Project {
+        let mut p = Project::new(
+            "demo",
+            vec![
+                file("Cargo.toml", "s1"),
+                file("index.html", "s2"),
+                file("src/main.rs", "s3"),
+                file("src/ui/card.rs", "s4"),
+            ],
+        );
+        p.set_loaded("src/main.rs", "fn main() {}", "s3");
+        p
+    }
+
+    #[test]
+    fn list_tree_uses_branch_path_and_keeps_blobs() {
+        let body = r#"{"sha":"t","truncated":false,"tree":[
+            {"path":"src","type":"tree","sha":"d1"},
+            {"path":"src/main.rs","type":"blob","sha":"b1","size":12},
+            {"path":"index.html","type":"blob","sha":"b2","size":3}]}"#;
+        let gh = GitHubClient::with_transport(
+            "tok",
+            GitHubConfig::default(),
+            MockTransport::new().reply(200, body),
+        );
+        let files = block_on(gh.list_tree("plugs/demo/")).unwrap();
+        assert_eq!(files.len(), 2);
+        assert_eq!(files[0].path, "src/main.rs");
+        assert_eq!(files[0].size, 12);
+        assert!(gh
+            .transport()
+            .request(0)
+            .url
+            .ends_with("git/trees/main:plugs/demo?recursive=1"));
+    }
+
+    #[test]
+    fn edits_track_dirty_per_file() {
+        let mut p = demo();
+        assert!(!p.has_changes());
+        p.edit("src/main.rs", "fn main() { run() }");
+        assert_eq!(p.dirty_paths(), vec!["src/main.rs"]);
+        p.edit("src/main.rs", "fn main() {}");
+        assert!(!p.has_changes());
+    }
+
+    #[test]
+    fn save_all_collects_writes_renames_and_deletes() {
+        let mut p = demo();
+        p.edit("src/main.rs", "fn main() { app() }");
+        p.create("src/app.rs").unwrap();
+        p.edit("src/app.rs", "pub fn app() {}");
+        p.delete("index.html");
+        p.open("src/main.rs");
+        p.rename("src/main.rs", "src/bin.rs").unwrap();
+
+        let changes = p.changes().unwrap();
+        assert_eq!(
+            changes,
+            vec![
+                FileChange::write("plugs/demo/src/app.rs", "pub fn app() {}"),
+                FileChange::write("plugs/demo/src/bin.rs", "fn main() { app() }"),
+                FileChange::delete("plugs/demo/index.html"),
+                FileChange::delete("plugs/demo/src/main.rs"),
+            ]
+        );
+        assert_eq!(p.active(), Some("src/bin.rs"));
+
+        p.mark_saved(&BTreeMap::new());
+        assert!(!p.has_changes());
+        assert_eq!(p.sha("src/bin.rs"), None);
+    }
+
+    #[test]
+    fn rename_needs_loaded_file_and_free_name() {
+        let mut p = demo();
+        assert!(p.rename("Cargo.toml", "C.toml").is_err());
+        assert!(p.rename("src/main.rs", "index.html").is_err());
+        assert!(p.rename("src/main.rs", "../escape.rs").is_err());
+        assert!(p.create("src/main.rs").is_err());
+    }
+
+    #[test]
+    fn closing_active_tab_moves_left() {
+        let mut p = demo();
+        p.open("Cargo.toml");
+        p.open("index.html");
+        p.open("src/main.rs");
+        p.open("index.html");
+        p.close("index.html");
+        assert_eq!(p.tabs(), ["Cargo.toml", "src/main.rs"]);
+        assert_eq!(p.active(), Some("Cargo.toml"));
+    }
+
+    #[test]
+    fn sha_only_for_unmoved_files() {
+        let mut p = demo();
+        assert_eq!(p.sha("src/main.rs"), Some("s3"));
+        p.rename("src/main.rs", "src/lib.rs").unwrap();
+        assert_eq!(p.sha("src/lib.rs"), None);
+        p.mark_file_saved("src/lib.rs", "s9");
+        assert_eq!(p.sha("src/lib.rs"), Some("s9"));
+    }
+
+    #[test]
+    fn stale_paths_and_rebase() {
+        let mut p = demo();
+        p.edit("src/main.rs", "fn main() { run() }");
+        let now = vec![file("src/main.rs", "s7"), file("Cargo.toml", "s1")];
+        assert_eq!(p.stale_paths(&now), vec!["src/main.rs".to_string()]);
+        assert_eq!(p.base_text("src/main.rs"), Some("fn main() {}"));
+
+        p.rebase(
+            "src/main.rs",
+            "// v2\nfn main() {}",
+            "s7",
+            "// v2\nfn main() { run() }",
+        );
+        assert_eq!(p.sha("src/main.rs"), Some("s7"));
+        assert!(p.is_dirty("src/main.rs"));
+        assert!(p.stale_paths(&now).is_empty());
+    }
+
+    #[test]
+    fn tree_rows_nest_folders_first() {
+        let rows: Vec<(usize, String, bool)> = demo()
+            .tree_rows()
+            .into_iter()
+            .map(|r| (r.depth, r.name, r.file.is_some()))
+            .collect();
+        assert_eq!(
+            rows,
+            vec![
+                (0, "src".into(), false),
+                (1, "ui".into(), false),
+                (2, "card.rs".into(), true),
+                (1, "main.rs".into(), true),
+                (0, "Cargo.toml".into(), true),
+                (0, "index.html".into(), true),
+            ]
+        );
+    }
+}
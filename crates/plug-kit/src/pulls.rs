@@ -0,0 +1,438 @@
+//! Feature-branch + pull-request delivery, as an alternative to committing
+//! straight to the deploy branch.
+
+use serde::{Deserialize, Serialize};
+
+use crate::error::GitHubError;
+use crate::git_data::{CommitResult, FileChange};
+use crate::github::{expect_ok, parse_json, GitHubClient};
+use crate::plug;
+use crate::transport::{Method, Transport};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DeliveryMode {
+    /// Commit on the configured branch and deploy right away.
+    Direct,
+    /// Commit on `plug/<name>`, open a PR, deploy once it is merged.
+    PullRequest,
+    /// Like `PullRequest`, plus deploy the branch to `<name>-preview`.
+    PreviewPullRequest,
+}
+
+impl DeliveryMode {
+    pub const ALL: [DeliveryMode; 3] = [
+        DeliveryMode::Direct,
+        DeliveryMode::PullRequest,
+        DeliveryMode::PreviewPullRequest,
+    ];
+
+    pub fn key(self) -> &'static str {
+        match self {
+            DeliveryMode::Direct => "direct",
+            DeliveryMode::PullRequest => "pr",
+            DeliveryMode::PreviewPullRequest => "pr-preview",
+        }
+    }
+
+    pub fn label(self) -> &'static str {
+        match self {
+            DeliveryMode::Direct => "Commit to main + deploy",
+            DeliveryMode::PullRequest => "Branch + pull request (deploy after merge)",
+            DeliveryMode::PreviewPullRequest => "Branch + pull request + preview deploy",
+        }
+    }
+
+    pub fn from_key(key: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|m| m.key() == key)
+    }
+}
+
+#[derive(Deserialize, Debug, Clone, PartialEq)]
+pub struct PullRequest {
+    pub number: u64,
+    pub html_url: String,
+    pub state: String,
+    pub merged_at: Option<String>,
+}
+
+impl PullRequest {
+    pub fn is_merged(&self) -> bool {
+        self.merged_at.is_some()
+    }
+}
+
+/// What `deliver_plug` did.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Delivery {
+    pub branch: String,
+    pub commit: CommitResult,
+    pub pull_request: Option<PullRequest>,
+    /// Hostek folder a deploy was dispatched to, if any.
+    pub deployed_as: Option<String>,
+}
+
+#[derive(Serialize)]
+struct CreateRefBody<'a> {
+    #[serde(rename = "ref")]
+    git_ref: String,
+    sha: &'a str,
+}
+
+/// The part of `GET compare/{base}...{head}` we need.
+#[derive(Deserialize)]
+struct Comparison {
+    ahead_by: u64,
+}
+
+#[derive(Serialize)]
+struct CreatePullBody<'a> {
+    title: &'a str,
+    head: &'a str,
+    base: &'a str,
+    body: &'a str,
+}
+
+/// Markdown body for the PR opened by `deliver_plug`.
+pub fn pull_request_body(plug_name: &str, changes: &[FileChange], mode: DeliveryMode) -> String {
+    let mut out = format!("Plug: `{}`\n\n### Files\n", plug_name);
+    for c in changes {
+        let verb = if c.content.is_some() {
+            "write"
+        } else {
+            "delete"
+        };
+        out.push_str(&format!("- {} `{}`\n", verb, c.path));
+    }
+
+    out.push_str("\n### Deploy\n");
+    if mode == DeliveryMode::PreviewPullRequest {
+        out.push_str(&format!(
+            "- Preview (this branch): {}\n",
+            plug::public_url(&plug::preview_name(plug_name))
+        ));
+    }
+    out.push_str(&format!(
+        "- Production after merge: {}\n",
+        plug::public_url(plug_name)
+    ));
+    out
+}
+
+impl<T: Transport> GitHubClient<T> {
+    /// Create `branch` from the head of `from`. A branch that already exists
+    /// is kept while it has an open PR. Otherwise it is left over from a merged
+    /// or closed one: it is reset to `from` if every commit on it is already
+    /// in `from`, and refused with `UnmergedBranch` if not. Returns `false` if
+    /// it was kept.
+    pub async fn ensure_branch(&self, branch: &str, from: &str) -> Result<bool, GitHubError> {
+        let head = self.get_branch_head(from).await?;
+        let url = self.repo_url("git/refs");
+        let body = CreateRefBody {
+            git_ref: format!("refs/heads/{}", branch),
+            sha: &head,
+        };
+        let resp = self.request_json(Method::Post, &url, &body).await?;
+        if resp.status == 422 && resp.text().contains("Reference already exists") {
+            if self.find_open_pull_request(branch).await?.is_some() {
+                return Ok(false);
+            }
+            if self.commits_ahead(from, branch).await? > 0 {
+                return Err(GitHubError::UnmergedBranch {
+                    branch: branch.to_string(),
+                    base: from.to_string(),
+                });
+            }
+            self.reset_branch(branch, &head).await?;
+            return Ok(true);
+        }
+        expect_ok(resp, &format!("heads/{}", branch), "Create branch").map(|_| true)
+    }
+
+    /// Number of commits on `head` that are not on `base`.
+    async fn commits_ahead(&self, base: &str, head: &str) -> Result<u64, GitHubError> {
+        let url = self.repo_url(&format!("compare/{}...{}", base, head));
+        let resp = self.request(Method::Get, &url, None).await?;
+        let resp = expect_ok(resp, head, "Compare branches")?;
+        let cmp: Comparison = parse_json(&resp)?;
+        Ok(cmp.ahead_by)
+    }
+
+    pub async fn find_open_pull_request(
+        &self,
+        head_branch: &str,
+    ) -> Result<Option<PullRequest>, GitHubError> {
+        let url = self.repo_url(&format!(
+            "pulls?state=open&head={}:{}",
+            self.config().owner,
+            head_branch
+        ));
+        let resp = self.request(Method::Get, &url, None).await?;
+        let resp = expect_ok(resp, head_branch, "List pull requests")?;
+        let list: Vec<PullRequest> = parse_json(&resp)?;
+        Ok(list.into_iter().next())
+    }
+
+    /// Open a PR from `head_branch` into the configured branch, or return the
+    /// one that is already open for it.
+    pub async fn open_pull_request(
+        &self,
+        head_branch: &str,
+        title: &str,
+        body: &str,
+    ) -> Result<PullRequest, GitHubError> {
+        let url = self.repo_url("pulls");
+        let req = CreatePullBody {
+            title,
+            head: head_branch,
+            base: &self.config().branch,
+            body,
+        };
+        let resp = self.request_json(Method::Post, &url, &req).await?;
+        if resp.status == 422 {
+            if let Some(existing) = self.find_open_pull_request(head_branch).await? {
+                return Ok(existing);
+            }
+        }
+        let resp = expect_ok(resp, head_branch, "Open pull request")?;
+        parse_json(&resp)
+    }
+
+    pub async fn get_pull_request(&self, number: u64) -> Result<PullRequest, GitHubError> {
+        let url = self.repo_url(&format!("pulls/{}", number));
+        let resp = self.request(Method::Get, &url, None).await?;
+        let resp = expect_ok(resp, &format!("#{}", number), "GET pull request")?;
+        parse_json(&resp)
+    }
+
+    /// Commit `changes` for a plug and deploy according to `mode`.
+    pub async fn deliver_plug(
+        &self,
+        plug_name: &str,
+        message: &str,
+        changes: &[FileChange],
+        mode: DeliveryMode,
+    ) -> Result<Delivery, GitHubError> {
+        let app_dir = plug::app_dir(plug_name);
+
+        if mode == DeliveryMode::Direct {
+            let branch = self.config().branch.clone();
+            let commit = self.commit_files_to(&branch, message, changes).await?;
+            self.dispatch(plug_name, &app_dir).await?;
+            return Ok(Delivery {
+                branch,
+                commit,
+                pull_request: None,
+                deployed_as: Some(plug_name.to_string()),
+            });
+        }
+
+        let branch = plug::feature_branch(plug_name);
+        self.ensure_branch(&branch, &self.config().branch).await?;
+        let commit = self.commit_files_to(&branch, message, changes).await?;
+
+        let body = pull_request_body(plug_name, changes, mode);
+        let pr = self.open_pull_request(&branch, message, &body).await?;
+
+        let deployed_as = if mode == DeliveryMode::PreviewPullRequest {
+            let preview = plug::preview_name(plug_name);
+            self.dispatch_ref(&branch, &preview, &app_dir).await?;
+            Some(preview)
+        } else {
+            None
+        };
+
+        Ok(Delivery {
+            branch,
+            commit,
+            pull_request: Some(pr),
+            deployed_as,
+        })
+    }
+
+    /// Deploy `plug_name` from the configured branch once PR `number` is merged.
+    /// Returns the PR as fetched; nothing is dispatched while it is unmerged.
+    pub async fn deploy_if_merged(
+        &self,
+        number: u64,
+        plug_name: &str,
+    ) -> Result<PullRequest, GitHubError> {
+        let pr = self.get_pull_request(number).await?;
+        if pr.is_merged() {
+            self.dispatch(plug_name, &plug::app_dir(plug_name)).await?;
+        }
+        Ok(pr)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mock::MockTransport;
+    use crate::GitHubConfig;
+    use futures::executor::block_on;
+
+    fn client(mock: MockTransport) -> GitHubClient<MockTransport> {
+        GitHubClient::with_transport("tok", GitHubConfig::default(), mock)
+    }
+
+    const PR: &str =
+        r#"{"number":12,"html_url":"https://github.com/pr/12","state":"open","merged_at":null}"#;
+
+    fn commit_replies(mock: MockTransport) -> MockTransport {
+        mock.reply(201, r#"{"sha":"b1"}"#)
+            .reply(200, r#"{"object":{"sha":"h1"}}"#)
+            .reply(200, r#"{"tree":{"sha":"base"}}"#)
+            .reply(201, r#"{"sha":"t1"}"#)
+            .reply(201, r#"{"sha":"c1"}"#)
+            .reply(200, "{}")
+    }
+
+    #[test]
+    fn pr_mode_commits_to_feature_branch_without_deploying() {
+        let mock = MockTransport::new()
+            .reply(200, r#"{"object":{"sha":"m1"}}"#)
+            .reply(201, "{}");
+        let mock = commit_replies(mock).reply(201, PR);
+        let gh = client(mock);
+
+        let changes = [FileChange::write("plugs/demo/index.html", "<html>")];
+        let d = block_on(gh.deliver_plug("demo", "Add demo", &changes, DeliveryMode::PullRequest))
+            .unwrap();
+
+        assert_eq!(d.branch, "plug/demo");
+        assert_eq!(d.pull_request.unwrap().number, 12);
+        assert_eq!(d.deployed_as, None);
+
+        let t = gh.transport();
+        assert_eq!(t.request_json(1)["ref"], "refs/heads/plug/demo");
+        assert_eq!(t.request_json(1)["sha"], "m1");
+        assert!(t.request(7).url.ends_with("git/refs/heads/plug/demo"));
+
+        let pr = t.request_json(8);
+        assert_eq!(pr["head"], "plug/demo");
+        assert_eq!(pr["base"], "main");
+        assert!(pr["body"]
+            .as_str()
+            .unwrap()
+            .contains("`plugs/demo/index.html`"));
+        assert_eq!(t.request_count(), 9);
+    }
+
+    #[test]
+    fn preview_mode_deploys_branch_to_preview_folder() {
+        let mock = MockTransport::new()
+            .reply(200, r#"{"object":{"sha":"m1"}}"#)
+            .reply(422, r#"{"message":"Reference already exists"}"#)
+            .reply(200, &format!("[{}]", PR));
+        let mock = commit_replies(mock).reply(201, PR).reply(204, "");
+        let gh = client(mock);
+
+        let changes = [FileChange::write("plugs/demo/index.html", "<html>")];
+        let d = block_on(gh.deliver_plug(
+            "demo",
+            "Edit demo",
+            &changes,
+            DeliveryMode::PreviewPullRequest,
+        ))
+        .unwrap();
+        assert_eq!(d.deployed_as.as_deref(), Some("demo-preview"));
+
+        let dispatch = gh.transport().request_json(10);
+        assert_eq!(dispatch["ref"], "plug/demo");
+        assert_eq!(dispatch["inputs"]["plug_name"], "demo-preview");
+        assert_eq!(dispatch["inputs"]["app_dir"], "plugs/demo");
+    }
+
+    #[test]
+    fn stale_branch_is_reset_to_base() {
+        let gh = client(
+            MockTransport::new()
+                .reply(200, r#"{"object":{"sha":"m2"}}"#)
+                .reply(422, r#"{"message":"Reference already exists"}"#)
+                .reply(200, "[]")
+                .reply(200, r#"{"status":"behind","ahead_by":0,"behind_by":3}"#)
+                .reply(200, "{}"),
+        );
+        assert_eq!(block_on(gh.ensure_branch("plug/demo", "main")), Ok(true));
+
+        let t = gh.transport();
+        assert!(t.request(3).url.ends_with("compare/main...plug/demo"));
+        assert_eq!(t.request(4).method, Method::Patch);
+        assert!(t.request(4).url.ends_with("git/refs/heads/plug/demo"));
+        assert_eq!(t.request_json(4)["sha"], "m2");
+        assert_eq!(t.request_json(4)["force"], true);
+    }
+
+    #[test]
+    fn unmerged_branch_is_not_reset() {
+        let gh = client(
+            MockTransport::new()
+                .reply(200, r#"{"object":{"sha":"m2"}}"#)
+                .reply(422, r#"{"message":"Reference already exists"}"#)
+                .reply(200, "[]")
+                .reply(200, r#"{"status":"diverged","ahead_by":2,"behind_by":1}"#),
+        );
+        let err = block_on(gh.ensure_branch("plug/demo", "main")).unwrap_err();
+        assert_eq!(
+            err,
+            GitHubError::UnmergedBranch {
+                branch: "plug/demo".into(),
+                base: "main".into()
+            }
+        );
+        assert!(err.to_string().contains("plug/demo"));
+        assert_eq!(gh.transport().request_count(), 4);
+    }
+
+    #[test]
+    fn other_create_branch_failures_surface() {
+        let gh = client(
+            MockTransport::new()
+                .reply(200, r#"{"object":{"sha":"m1"}}"#)
+                .reply(422, r#"{"message":"Reference name is not valid"}"#),
+        );
+        let err = block_on(gh.ensure_branch("plug/bad..name", "main")).unwrap_err();
+        assert!(matches!(err, GitHubError::Api { status: 422, .. }));
+        assert_eq!(gh.transport().request_count(), 2);
+    }
+
+    #[test]
+    fn reuses_open_pull_request() {
+        let gh = client(
+            MockTransport::new()
+                .reply(422, "A pull request already exists")
+                .reply(200, &format!("[{}]", PR)),
+        );
+        let pr = block_on(gh.open_pull_request("plug/demo", "t", "b")).unwrap();
+        assert_eq!(pr.number, 12);
+        assert!(gh
+            .transport()
+            .request(1)
+            .url
+            .ends_with("pulls?state=open&head=ekim5sg:plug/demo"));
+    }
+
+    #[test]
+    fn deploy_waits_for_merge() {
+        let gh = client(MockTransport::new().reply(200, PR));
+        let pr = block_on(gh.deploy_if_merged(12, "demo")).unwrap();
+        assert!(!pr.is_merged());
+        assert_eq!(gh.transport().request_count(), 1);
+
+        let merged = PR.replace(
+            r#""merged_at":null"#,
+            r#""merged_at":"2024-05-01T00:00:00Z""#,
+        );
+        let gh = client(MockTransport::new().reply(200, &merged).reply(204, ""));
+        let pr = block_on(gh.deploy_if_merged(12, "demo")).unwrap();
+        assert!(pr.is_merged());
+        assert_eq!(gh.transport().request_json(1)["ref"], "main");
+    }
+
+    #[test]
+    fn mode_keys_round_trip() {
+        for m in DeliveryMode::ALL {
+            assert_eq!(DeliveryMode::from_key(m.key()), Some(m));
+        }
+    }
+}
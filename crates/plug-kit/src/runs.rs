@@ -0,0 +1,329 @@
+//! Following one deploy run from dispatch to conclusion: jobs, steps, and the
+//! logs with the rustc diagnostics in them.
+//!
+//! The dispatch endpoint returns 204 without a run id, so `dispatch_tracked`
+//! notes the newest dispatch run on the ref first and then polls for a newer one.
+
+use serde::Deserialize;
+
+use crate::diagnostics::{parse_diagnostics, Diagnostic};
+use crate::error::GitHubError;
+use crate::github::{expect_ok, parse_json, GitHubClient, WorkflowRun};
+use crate::logs::{unzip_logs, LogFile};
+use crate::transport::{Method, Transport};
+
+#[derive(Deserialize, Debug, Clone, PartialEq)]
+pub struct WorkflowJob {
+    pub id: u64,
+    pub name: String,
+    pub status: String,
+    pub conclusion: Option<String>,
+    pub html_url: Option<String>,
+    #[serde(default)]
+    pub steps: Vec<JobStep>,
+}
+
+#[derive(Deserialize, Debug, Clone, PartialEq)]
+pub struct JobStep {
+    pub number: u32,
+    pub name: String,
+    pub status: String,
+    pub conclusion: Option<String>,
+}
+
+impl WorkflowJob {
+    pub fn failed(&self) -> bool {
+        matches!(self.conclusion.as_deref(), Some("failure" | "timed_out"))
+    }
+
+    /// The step that is running now, or the one that failed.
+    pub fn current_step(&self) -> Option<&JobStep> {
+        self.steps
+            .iter()
+            .find(|s| s.status == "in_progress" || s.conclusion.as_deref() == Some("failure"))
+    }
+}
+
+/// A run plus its jobs, as of one poll.
+#[derive(Debug, Clone, PartialEq)]
+pub struct RunProgress {
+    pub run: WorkflowRun,
+    pub jobs: Vec<WorkflowJob>,
+}
+
+impl RunProgress {
+    pub fn is_completed(&self) -> bool {
+        self.run.status.as_deref() == Some("completed")
+    }
+
+    pub fn failed(&self) -> bool {
+        self.is_completed() && self.run.conclusion.as_deref() != Some("success")
+    }
+
+    /// `(finished, total)` steps across all jobs.
+    pub fn step_counts(&self) -> (usize, usize) {
+        let steps = self.jobs.iter().flat_map(|j| &j.steps);
+        let done = steps.clone().filter(|s| s.status == "completed").count();
+        (done, steps.count())
+    }
+}
+
+/// A run's log archive as downloaded, plus the files in it.
+#[derive(Debug, Clone, PartialEq)]
+pub struct RunLogs {
+    pub zip: Vec<u8>,
+    pub files: Vec<LogFile>,
+}
+
+impl RunLogs {
+    /// Whole-job logs, in archive order.
+    pub fn job_logs(&self) -> impl Iterator<Item = &LogFile> {
+        self.files.iter().filter(|f| f.is_job_log())
+    }
+
+    /// rustc errors and warnings from the whole-job logs. Step logs repeat
+    /// the same lines, so they are skipped.
+    pub fn diagnostics(&self) -> Vec<Diagnostic> {
+        self.job_logs()
+            .flat_map(|f| parse_diagnostics(&f.text))
+            .collect()
+    }
+}
+
+#[derive(Deserialize)]
+struct RunsResp {
+    workflow_runs: Vec<WorkflowRun>,
+}
+
+#[derive(Deserialize)]
+struct JobsResp {
+    jobs: Vec<WorkflowJob>,
+}
+
+impl<T: Transport> GitHubClient<T> {
+    /// Newest `workflow_dispatch` runs of the deploy workflow on `git_ref`.
+    pub async fn list_dispatch_runs(
+        &self,
+        git_ref: &str,
+        per_page: u32,
+    ) -> Result<Vec<WorkflowRun>, GitHubError> {
+        let url = self.repo_url(&format!(
+            "actions/workflows/{}/runs?event=workflow_dispatch&branch={}&per_page={}",
+            self.config().workflow_file,
+            git_ref,
+            per_page
+        ));
+        let resp = self.request(Method::Get, &url, None).await?;
+        let resp = expect_ok(resp, &self.config().workflow_file, "Fetch runs")?;
+        let json: RunsResp = parse_json(&resp)?;
+        Ok(json.workflow_runs)
+    }
+
+    /// Dispatch like `dispatch_ref` and return the run it started.
+    pub async fn dispatch_tracked(
+        &self,
+        git_ref: &str,
+        plug_name: &str,
+        app_dir: &str,
+    ) -> Result<WorkflowRun, GitHubError> {
+        let before = self
+            .list_dispatch_runs(git_ref, 1)
+            .await?
+            .first()
+            .map_or(0, |r| r.id);
+
+        self.dispatch_ref(git_ref, plug_name, app_dir).await?;
+
+        for _ in 0..self.config().run_lookup_attempts {
+            self.transport().sleep(self.config().run_poll_secs).await;
+            let runs = self.list_dispatch_runs(git_ref, 5).await?;
+            if let Some(run) = runs
+                .into_iter()
+                .filter(|r| r.id > before)
+                .min_by_key(|r| r.id)
+            {
+                return Ok(run);
+            }
+        }
+        Err(GitHubError::NotFound(format!(
+            "workflow run for {} (dispatch was accepted)",
+            git_ref
+        )))
+    }
+
+    pub async fn get_run(&self, run_id: u64) -> Result<WorkflowRun, GitHubError> {
+        let url = self.repo_url(&format!("actions/runs/{}", run_id));
+        let resp = self.request(Method::Get, &url, None).await?;
+        let resp = expect_ok(resp, &format!("run {}", run_id), "GET run")?;
+        parse_json(&resp)
+    }
+
+    pub async fn list_jobs(&self, run_id: u64) -> Result<Vec<WorkflowJob>, GitHubError> {
+        let url = self.repo_url(&format!("actions/runs/{}/jobs?per_page=100", run_id));
+        let resp = self.request(Method::Get, &url, None).await?;
+        let resp = expect_ok(resp, &format!("run {}", run_id), "List jobs")?;
+        let json: JobsResp = parse_json(&resp)?;
+        Ok(json.jobs)
+    }
+
+    pub async fn run_progress(&self, run_id: u64) -> Result<RunProgress, GitHubError> {
+        let run = self.get_run(run_id).await?;
+        let jobs = self.list_jobs(run_id).await?;
+        Ok(RunProgress { run, jobs })
+    }
+
+    /// Poll a run until it completes, reporting every snapshot to `on_update`.
+    /// Gives up after `run_max_polls` and returns the last snapshot.
+    pub async fn follow_run(
+        &self,
+        run_id: u64,
+        mut on_update: impl FnMut(&RunProgress),
+    ) -> Result<RunProgress, GitHubError> {
+        let mut polls = 0;
+        loop {
+            let progress = self.run_progress(run_id).await?;
+            on_update(&progress);
+            polls += 1;
+            if progress.is_completed() || polls >= self.config().run_max_polls {
+                return Ok(progress);
+            }
+            self.transport().sleep(self.config().run_poll_secs).await;
+        }
+    }
+
+    /// Download the run's log archive and unpack it.
+    pub async fn run_logs(&self, run_id: u64) -> Result<RunLogs, GitHubError> {
+        let url = self.repo_url(&format!("actions/runs/{}/logs", run_id));
+        let resp = self.request(Method::Get, &url, None).await?;
+        let resp = expect_ok(resp, &format!("run {} logs", run_id), "Download logs")?;
+        let files = unzip_logs(&resp.body).map_err(GitHubError::Decode)?;
+        Ok(RunLogs {
+            zip: resp.body,
+            files,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::logs::tests::zip;
+    use crate::mock::MockTransport;
+    use crate::GitHubConfig;
+    use futures::executor::block_on;
+
+    fn client(mock: MockTransport) -> GitHubClient<MockTransport> {
+        GitHubClient::with_transport("tok", GitHubConfig::default(), mock)
+    }
+
+    fn runs(ids: &[u64], status: &str) -> String {
+        let list: Vec<String> = ids
+            .iter()
+            .map(|id| format!(r#"{{"id":{},"html_url":"u","status":"{}"}}"#, id, status))
+            .collect();
+        format!(r#"{{"workflow_runs":[{}]}}"#, list.join(","))
+    }
+
+    const JOBS: &str = r#"{"total_count":1,"jobs":[{"id":5,"name":"build","status":"in_progress","conclusion":null,"steps":[
+        {"number":1,"name":"Checkout","status":"completed","conclusion":"success"},
+        {"number":2,"name":"trunk build","status":"in_progress","conclusion":null}]}]}"#;
+
+    #[test]
+    fn dispatch_tracked_waits_for_a_newer_run() {
+        let gh = client(
+            MockTransport::new()
+                .reply(200, &runs(&[40], "completed"))
+                .reply(204, "")
+                .reply(200, &runs(&[40], "completed"))
+                .reply(200, &runs(&[42, 41, 40], "queued")),
+        );
+        let run = block_on(gh.dispatch_tracked("main", "demo", "plugs/demo")).unwrap();
+        assert_eq!(run.id, 41);
+
+        let t = gh.transport();
+        assert!(t.request(0).url.ends_with(
+            "deploy-hostek-plug.yml/runs?event=workflow_dispatch&branch=main&per_page=1"
+        ));
+        assert_eq!(t.request_json(1)["inputs"]["plug_name"], "demo");
+        assert_eq!(*t.sleeps.borrow(), vec![5, 5]);
+    }
+
+    #[test]
+    fn dispatch_tracked_gives_up() {
+        let cfg = GitHubConfig {
+            run_lookup_attempts: 1,
+            ..GitHubConfig::default()
+        };
+        let mock = MockTransport::new()
+            .reply(200, &runs(&[], ""))
+            .reply(204, "")
+            .reply(200, &runs(&[], ""));
+        let gh = GitHubClient::with_transport("tok", cfg, mock);
+        let err = block_on(gh.dispatch_tracked("main", "demo", "plugs/demo")).unwrap_err();
+        assert!(err.is_not_found());
+    }
+
+    #[test]
+    fn follow_run_polls_until_completed() {
+        let run = |status: &str, conclusion: &str| {
+            format!(
+                r#"{{"id":9,"html_url":"u","status":"{}","conclusion":{}}}"#,
+                status, conclusion
+            )
+        };
+        let gh = client(
+            MockTransport::new()
+                .reply(200, &run("in_progress", "null"))
+                .reply(200, JOBS)
+                .reply(200, &run("completed", r#""failure""#))
+                .reply(200, r#"{"jobs":[]}"#),
+        );
+
+        let mut seen = vec![];
+        let last = block_on(gh.follow_run(9, |p| seen.push(p.step_counts()))).unwrap();
+        assert_eq!(seen, vec![(1, 2), (0, 0)]);
+        assert!(last.failed());
+        assert_eq!(*gh.transport().sleeps.borrow(), vec![5]);
+        assert!(gh
+            .transport()
+            .request(1)
+            .url
+            .ends_with("actions/runs/9/jobs?per_page=100"));
+    }
+
+    #[test]
+    fn current_step_is_the_running_one() {
+        let jobs: JobsResp = serde_json::from_str(JOBS).unwrap();
+        assert_eq!(jobs.jobs[0].current_step().unwrap().name, "trunk build");
+        assert!(!jobs.jobs[0].failed());
+    }
+
+    #[test]
+    fn run_logs_read_diagnostics_from_job_logs_only() {
+        let log = "2024-05-01T12:00:01.0000000Z error[E0308]: mismatched types\n  --> src/main.rs:3:9\n\n";
+        let archive = zip(&[("0_build.txt", log), ("build/4_trunk build.txt", log)]);
+        let gh = client(MockTransport::new().reply_bytes(200, archive.clone()));
+
+        let logs = block_on(gh.run_logs(9)).unwrap();
+        assert_eq!(logs.zip, archive);
+        let jobs: Vec<&str> = logs.job_logs().map(|f| f.job_name()).collect();
+        assert_eq!(jobs, ["build"]);
+        let errs = logs.diagnostics();
+        assert_eq!(errs.len(), 1);
+        assert_eq!(errs[0].code.as_deref(), Some("E0308"));
+        assert!(gh
+            .transport()
+            .request(0)
+            .url
+            .ends_with("actions/runs/9/logs"));
+    }
+
+    #[test]
+    fn expired_log_archive_is_a_decode_error() {
+        let gh = client(MockTransport::new().reply(200, "<Error>AuthenticationFailed</Error>"));
+        assert!(matches!(
+            block_on(gh.run_logs(9)),
+            Err(GitHubError::Decode(_))
+        ));
+    }
+}
@@ -0,0 +1,281 @@
+//! Starter templates for new plugs.
+//!
+//! Each template is a folder under `templates/` with a `main.rs.tmpl`, a
+//! `Cargo.toml.tmpl` and optionally an `extra.css.tmpl` appended to the shared
+//! stylesheet. Files are baked in with `include_str!` and filled in with plain
+//! token replacement, so the Rust sources can keep their `{}` braces.
+
+use crate::git_data::FileChange;
+use crate::manifest::{PlugManifest, MANIFEST_FILE};
+use crate::plug;
+
+const INDEX_HTML: &str = include_str!("../templates/_shared/index.html.tmpl");
+const STYLES_CSS: &str = include_str!("../templates/_shared/styles.css.tmpl");
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Template {
+    pub id: &'static str,
+    pub name: &'static str,
+    pub description: &'static str,
+    main_rs: &'static str,
+    cargo_toml: &'static str,
+    extra_css: &'static str,
+}
+
+macro_rules! template {
+    ($id:literal, $name:literal, $description:literal, extra_css: $css:expr) => {
+        Template {
+            id: $id,
+            name: $name,
+            description: $description,
+            main_rs: include_str!(concat!("../templates/", $id, "/main.rs.tmpl")),
+            cargo_toml: include_str!(concat!("../templates/", $id, "/Cargo.toml.tmpl")),
+            extra_css: $css,
+        }
+    };
+    ($id:literal, $name:literal, $description:literal, extra_css) => {
+        template!($id, $name, $description,
+            extra_css: include_str!(concat!("../templates/", $id, "/extra.css.tmpl")))
+    };
+    ($id:literal, $name:literal, $description:literal) => {
+        template!($id, $name, $description, extra_css: "")
+    };
+}
+
+/// Every starter, in the order pickers should list them. The first is the default.
+pub const CATALOG: &[Template] = &[
+    template!(
+        "hello",
+        "Hello card",
+        "One card with the title and public URL. The original scaffold."
+    ),
+    template!(
+        "countdown",
+        "Countdown timer",
+        "Minutes input with start, pause and reset. Uses gloo-timers.",
+        extra_css
+    ),
+    template!(
+        "crud-list",
+        "CRUD list",
+        "Add, edit, check off and delete items, persisted to localStorage.",
+        extra_css
+    ),
+    template!(
+        "canvas-game",
+        "Canvas game",
+        "A small dodge game: canvas drawing, a game loop and pointer input.",
+        extra_css
+    ),
+    template!(
+        "json-tool",
+        "JSON tool",
+        "Validate, pretty-print and minify JSON with line/column errors."
+    ),
+    template!(
+        "audio-synth",
+        "Audio synth",
+        "Waveform, frequency and length controls that play or download a WAV."
+    ),
+];
+
+pub fn default_template() -> &'static Template {
+    &CATALOG[0]
+}
+
+pub fn find(id: &str) -> Option<&'static Template> {
+    CATALOG.iter().find(|t| t.id == id)
+}
+
+/// Values substituted into a template.
+#[derive(Debug, Clone, PartialEq)]
+pub struct TemplateVars {
+    pub plug_name: String,
+    pub title: String,
+}
+
+impl TemplateVars {
+    pub fn new(plug_name: &str, title: &str) -> Self {
+        Self {
+            plug_name: plug_name.to_string(),
+            title: title.to_string(),
+        }
+    }
+
+    /// `(token, value)` pairs. `*_LIT__` values are Rust string literals.
+    pub fn tokens(&self) -> Vec<(&'static str, String)> {
+        let title = if self.title.trim().is_empty() {
+            "Untitled"
+        } else {
+            self.title.trim()
+        };
+        vec![
+            ("__PLUG_NAME__", self.plug_name.clone()),
+            ("__CRATE_NAME__", self.plug_name.replace('-', "_")),
+            ("__TITLE__", escape_html(title)),
+            ("__TITLE_LIT__", rust_str_lit(title)),
+            (
+                "__URL_LIT__",
+                rust_str_lit(&plug::public_url(&self.plug_name)),
+            ),
+            (
+                "__STORAGE_KEY_LIT__",
+                rust_str_lit(&format!("{}.v1", self.plug_name)),
+            ),
+        ]
+    }
+}
+
+/// Replace every `__TOKEN__` in `source`.
+pub fn render(source: &str, tokens: &[(&str, String)]) -> String {
+    tokens
+        .iter()
+        .fold(source.to_string(), |out, (token, value)| {
+            out.replace(token, value)
+        })
+}
+
+fn escape_html(s: &str) -> String {
+    s.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+fn rust_str_lit(s: &str) -> String {
+    // JSON string escaping is a subset of what Rust string literals accept
+    serde_json::to_string(s).unwrap_or_else(|_| "\"\"".to_string())
+}
+
+impl Template {
+    pub fn index_html(&self, vars: &TemplateVars) -> String {
+        render(INDEX_HTML, &vars.tokens())
+    }
+
+    pub fn styles_css(&self, vars: &TemplateVars) -> String {
+        render(&format!("{}{}", STYLES_CSS, self.extra_css), &vars.tokens())
+    }
+
+    pub fn cargo_toml(&self, vars: &TemplateVars) -> String {
+        render(self.cargo_toml, &vars.tokens())
+    }
+
+    pub fn main_rs(&self, vars: &TemplateVars) -> String {
+        render(self.main_rs, &vars.tokens())
+    }
+
+    /// `plug.toml` for the portal, tagged with the starter it came from.
+    pub fn plug_toml(&self, vars: &TemplateVars) -> String {
+        let title = if vars.title.trim().is_empty() {
+            "Untitled"
+        } else {
+            vars.title.trim()
+        };
+        let mut m = PlugManifest::starter(title, "");
+        m.tags = vec![self.id.to_string()];
+        m.to_toml()
+    }
+
+    /// The scaffold files under `plugs/<plug_name>/`.
+    pub fn files(&self, vars: &TemplateVars) -> Vec<FileChange> {
+        let dir = plug::app_dir(&vars.plug_name);
+        vec![
+            FileChange::write(&format!("{}/index.html", dir), &self.index_html(vars)),
+            FileChange::write(&format!("{}/styles.css", dir), &self.styles_css(vars)),
+            FileChange::write(&format!("{}/Cargo.toml", dir), &self.cargo_toml(vars)),
+            FileChange::write(&format!("{}/src/main.rs", dir), &self.main_rs(vars)),
+            FileChange::write(&format!("{}/{}", dir, MANIFEST_FILE), &self.plug_toml(vars)),
+        ]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn vars() -> TemplateVars {
+        TemplateVars::new("my-cool-plug", r#"Tom & "Jerry" <3"#)
+    }
+
+    #[test]
+    fn every_template_renders_without_leftover_tokens() {
+        for t in CATALOG {
+            for file in t.files(&vars()) {
+                let content = file.content.unwrap();
+                assert!(
+                    !content.contains("__"),
+                    "{} {} has an unreplaced token",
+                    t.id,
+                    file.path
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn ids_are_unique_and_findable() {
+        for t in CATALOG {
+            assert_eq!(find(t.id), Some(t));
+        }
+        assert_eq!(default_template().id, "hello");
+        assert_eq!(find("nope"), None);
+    }
+
+    #[test]
+    fn crate_name_and_title_are_escaped_per_target() {
+        let t = find("hello").unwrap();
+        let v = vars();
+        assert!(t.cargo_toml(&v).contains(r#"name = "my_cool_plug""#));
+        assert!(t
+            .index_html(&v)
+            .contains("<title>Tom &amp; &quot;Jerry&quot; &lt;3</title>"));
+        let main = t.main_rs(&v);
+        assert!(main.contains(r#"{ "Tom & \"Jerry\" <3" }"#));
+        assert!(main.contains(r#""https://www.webhtml5.info/my-cool-plug/""#));
+    }
+
+    #[test]
+    fn extra_css_is_appended_to_shared_styles() {
+        let v = vars();
+        let base = find("hello").unwrap().styles_css(&v);
+        let countdown = find("countdown").unwrap().styles_css(&v);
+        assert!(countdown.starts_with(&base));
+        assert!(countdown.contains(".clock"));
+    }
+
+    #[test]
+    fn blank_title_falls_back() {
+        let v = TemplateVars::new("x", "  ");
+        assert!(find("hello")
+            .unwrap()
+            .index_html(&v)
+            .contains("<title>Untitled</title>"));
+    }
+
+    #[test]
+    fn files_land_in_plug_dir() {
+        let paths: Vec<_> = find("json-tool")
+            .unwrap()
+            .files(&vars())
+            .into_iter()
+            .map(|f| f.path)
+            .collect();
+        assert_eq!(
+            paths,
+            [
+                "plugs/my-cool-plug/index.html",
+                "plugs/my-cool-plug/styles.css",
+                "plugs/my-cool-plug/Cargo.toml",
+                "plugs/my-cool-plug/src/main.rs",
+                "plugs/my-cool-plug/plug.toml",
+            ]
+        );
+    }
+
+    #[test]
+    fn plug_toml_is_tagged_with_the_template() {
+        let toml = find("countdown").unwrap().plug_toml(&vars());
+        assert!(toml.starts_with("title = \"Tom & \\\"Jerry\\\" <3\"\n"));
+        assert!(toml.contains("tags = [\"countdown\"]"));
+    }
+}
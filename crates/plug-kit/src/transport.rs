@@ -0,0 +1,119 @@
+//! Minimal HTTP abstraction so the GitHub client can run against `fetch` in the
+//! browser and against a scripted mock in unit tests.
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Method {
+    Get,
+    Post,
+    Put,
+    Patch,
+    Delete,
+}
+
+impl Method {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Method::Get => "GET",
+            Method::Post => "POST",
+            Method::Put => "PUT",
+            Method::Patch => "PATCH",
+            Method::Delete => "DELETE",
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct HttpRequest {
+    pub method: Method,
+    pub url: String,
+    pub headers: Vec<(String, String)>,
+    /// JSON body, already serialized.
+    pub body: Option<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct HttpResponse {
+    pub status: u16,
+    /// Header names are stored lowercase.
+    pub headers: Vec<(String, String)>,
+    pub body: Vec<u8>,
+}
+
+impl HttpResponse {
+    pub fn ok(&self) -> bool {
+        (200..300).contains(&self.status)
+    }
+
+    pub fn header(&self, name: &str) -> Option<&str> {
+        let name = name.to_ascii_lowercase();
+        self.headers
+            .iter()
+            .find(|(k, _)| *k == name)
+            .map(|(_, v)| v.as_str())
+    }
+
+    pub fn text(&self) -> String {
+        String::from_utf8_lossy(&self.body).into_owned()
+    }
+}
+
+// Plugs run single-threaded in wasm, so the futures don't need to be Send.
+#[allow(async_fn_in_trait)]
+pub trait Transport {
+    async fn send(&self, req: HttpRequest) -> Result<HttpResponse, String>;
+
+    /// Used to wait out short secondary rate limits.
+    async fn sleep(&self, secs: u64);
+}
+
+/// `fetch`-backed transport used by the plugs in the browser.
+#[cfg(feature = "gloo")]
+#[derive(Debug, Clone, Copy, Default)]
+pub struct GlooTransport;
+
+#[cfg(feature = "gloo")]
+impl Transport for GlooTransport {
+    async fn send(&self, req: HttpRequest) -> Result<HttpResponse, String> {
+        use gloo_net::http::{Method as GlooMethod, RequestBuilder};
+
+        let method = match req.method {
+            Method::Get => GlooMethod::GET,
+            Method::Post => GlooMethod::POST,
+            Method::Put => GlooMethod::PUT,
+            Method::Patch => GlooMethod::PATCH,
+            Method::Delete => GlooMethod::DELETE,
+        };
+
+        let mut builder = RequestBuilder::new(&req.url).method(method);
+        for (k, v) in &req.headers {
+            builder = builder.header(k, v);
+        }
+
+        let request = match req.body {
+            Some(body) => builder.body(body),
+            None => builder.build(),
+        }
+        .map_err(|e| e.to_string())?;
+
+        let resp = request.send().await.map_err(|e| e.to_string())?;
+
+        let status = resp.status();
+        let headers = resp
+            .headers()
+            .entries()
+            .map(|(k, v)| (k.to_ascii_lowercase(), v))
+            .collect();
+        let body = resp.binary().await.unwrap_or_default();
+
+        Ok(HttpResponse {
+            status,
+            headers,
+            body,
+        })
+    }
+
+    async fn sleep(&self, secs: u64) {
+        let ms = secs.saturating_mul(1000).min(u32::MAX as u64) as u32;
+        gloo_timers::future::TimeoutFuture::new(ms).await;
+    }
+}
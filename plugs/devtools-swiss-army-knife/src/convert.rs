@@ -0,0 +1,660 @@
+// src/convert.rs
+//
+// The Convert tab: JSON ⇄ YAML ⇄ TOML ⇄ CSV, always going through a
+// `serde_json::Value`. Anything the target format can't hold (null or an array
+// root in TOML, NaN in JSON, rows that aren't objects in CSV) is refused with
+// the path of the offending value instead of being dropped quietly.
+//
+// CSV is an array of objects, one per row. Writing flattens nested keys into
+// headers (`user.name`, `tags[0]`) and infers the header from every row, in
+// first-seen order; reading puts them back together. A cell holds text as is,
+// or a JSON literal: a number, true/false, null, `[]`/`{}`, or a JSON-quoted
+// string for text that would otherwise read as one of those (`"123"`, `""`).
+// An empty cell is a key the row doesn't have.
+
+use serde_json::{Map, Number, Value};
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum Format {
+    Json,
+    Yaml,
+    Toml,
+    Csv,
+}
+
+impl Format {
+    pub const ALL: [Format; 4] = [Format::Json, Format::Yaml, Format::Toml, Format::Csv];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            Format::Json => "JSON",
+            Format::Yaml => "YAML",
+            Format::Toml => "TOML",
+            Format::Csv => "CSV",
+        }
+    }
+}
+
+pub fn convert(from: Format, to: Format, input: &str) -> Result<String, String> {
+    let v = parse(from, input)?;
+    render(to, &v)
+}
+
+pub fn parse(fmt: Format, input: &str) -> Result<Value, String> {
+    let res = match fmt {
+        Format::Json => serde_json::from_str(input).map_err(|e| e.to_string()),
+        Format::Yaml => serde_yaml::from_str::<serde_yaml::Value>(input)
+            .map_err(|e| e.to_string())
+            .and_then(|mut y| {
+                y.apply_merge().map_err(|e| e.to_string())?;
+                from_yaml(y, "")
+            }),
+        Format::Toml => toml::from_str::<toml::Table>(input)
+            .map_err(|e| match e.span() {
+                Some(span) => {
+                    let (line, col) = line_col(input, span.start);
+                    let msg = e.message().trim_end().replace('\n', "; ");
+                    format!("{msg} at line {line} column {col}")
+                }
+                None => e.message().to_string(),
+            })
+            .and_then(|t| from_toml(toml::Value::Table(t), "")),
+        Format::Csv => from_csv(input),
+    };
+    res.map_err(|e| format!("{} parse error: {e}", fmt.label()))
+}
+
+pub fn render(fmt: Format, v: &Value) -> Result<String, String> {
+    let res = match fmt {
+        Format::Json => serde_json::to_string_pretty(v).map_err(|e| e.to_string()),
+        Format::Yaml => serde_yaml::to_string(v).map_err(|e| e.to_string()),
+        Format::Toml => to_toml(v, "").and_then(|t| match t {
+            toml::Value::Table(t) => toml::to_string_pretty(&t).map_err(|e| e.to_string()),
+            _ => Err(format!(
+                "a TOML document is a table, but the root here is {}; wrap it in an object, e.g. {{\"items\": …}}",
+                kind(v)
+            )),
+        }),
+        Format::Csv => to_csv(v),
+    };
+    res.map_err(|e| format!("Can't write {}: {e}", fmt.label()))
+}
+
+/// `/a/0/b`, or "the root".
+fn at(path: &str) -> String {
+    if path.is_empty() {
+        "the root".to_string()
+    } else {
+        path.to_string()
+    }
+}
+
+/// 1-based line and column of a byte offset.
+fn line_col(input: &str, offset: usize) -> (usize, usize) {
+    let before = &input[..offset.min(input.len())];
+    let line_start = before.rfind('\n').map_or(0, |i| i + 1);
+    (
+        before.matches('\n').count() + 1,
+        before[line_start..].chars().count() + 1,
+    )
+}
+
+fn kind(v: &Value) -> &'static str {
+    match v {
+        Value::Null => "null",
+        Value::Bool(_) => "a boolean",
+        Value::Number(_) => "a number",
+        Value::String(_) => "a string",
+        Value::Array(_) => "an array",
+        Value::Object(_) => "an object",
+    }
+}
+
+fn child(path: &str, key: &str) -> String {
+    format!("{path}/{}", key.replace('~', "~0").replace('/', "~1"))
+}
+
+fn float(f: f64, path: &str) -> Result<Value, String> {
+    Number::from_f64(f)
+        .map(Value::Number)
+        .ok_or_else(|| format!("{f} at {} has no JSON equivalent", at(path)))
+}
+
+/* ---------- YAML ---------- */
+
+fn from_yaml(y: serde_yaml::Value, path: &str) -> Result<Value, String> {
+    use serde_yaml::Value as Y;
+    Ok(match y {
+        Y::Null => Value::Null,
+        Y::Bool(b) => Value::Bool(b),
+        Y::Number(n) => {
+            if let Some(i) = n.as_i64() {
+                Value::from(i)
+            } else if let Some(u) = n.as_u64() {
+                Value::from(u)
+            } else {
+                float(n.as_f64().unwrap_or(f64::NAN), path)?
+            }
+        }
+        Y::String(s) => Value::String(s),
+        Y::Sequence(items) => Value::Array(
+            items
+                .into_iter()
+                .enumerate()
+                .map(|(i, it)| from_yaml(it, &format!("{path}/{i}")))
+                .collect::<Result<_, _>>()?,
+        ),
+        Y::Mapping(m) => {
+            let mut out = Map::new();
+            for (k, v) in m {
+                let key = match k {
+                    Y::String(s) => s,
+                    Y::Number(n) => n.to_string(),
+                    Y::Bool(b) => b.to_string(),
+                    Y::Null => "null".to_string(),
+                    _ => {
+                        return Err(format!(
+                            "a list or map used as a key at {} can't be a JSON object key",
+                            at(path)
+                        ))
+                    }
+                };
+                let v = from_yaml(v, &child(path, &key))?;
+                out.insert(key, v);
+            }
+            Value::Object(out)
+        }
+        // `!Tag value`: keep the value, the tag has nowhere to go
+        Y::Tagged(t) => from_yaml(t.value, path)?,
+    })
+}
+
+/* ---------- TOML ---------- */
+
+fn from_toml(t: toml::Value, path: &str) -> Result<Value, String> {
+    Ok(match t {
+        toml::Value::String(s) => Value::String(s),
+        toml::Value::Integer(i) => Value::from(i),
+        toml::Value::Float(f) => float(f, path)?,
+        toml::Value::Boolean(b) => Value::Bool(b),
+        toml::Value::Datetime(d) => Value::String(d.to_string()),
+        toml::Value::Array(items) => Value::Array(
+            items
+                .into_iter()
+                .enumerate()
+                .map(|(i, it)| from_toml(it, &format!("{path}/{i}")))
+                .collect::<Result<_, _>>()?,
+        ),
+        toml::Value::Table(tbl) => {
+            let mut out = Map::new();
+            for (k, v) in tbl {
+                let v = from_toml(v, &child(path, &k))?;
+                out.insert(k, v);
+            }
+            Value::Object(out)
+        }
+    })
+}
+
+fn to_toml(v: &Value, path: &str) -> Result<toml::Value, String> {
+    Ok(match v {
+        Value::Null => {
+            return Err(format!(
+                "{} is null, and TOML has no null; remove the key or give it a value",
+                at(path)
+            ))
+        }
+        Value::Bool(b) => toml::Value::Boolean(*b),
+        Value::Number(n) => match (n.as_i64(), n.as_u64()) {
+            (Some(i), _) => toml::Value::Integer(i),
+            (None, Some(u)) => {
+                return Err(format!(
+                    "{u} at {} is too large for a TOML integer (64-bit signed)",
+                    at(path)
+                ))
+            }
+            _ => toml::Value::Float(n.as_f64().unwrap_or_default()),
+        },
+        Value::String(s) => toml::Value::String(s.clone()),
+        Value::Array(items) => toml::Value::Array(
+            items
+                .iter()
+                .enumerate()
+                .map(|(i, it)| to_toml(it, &format!("{path}/{i}")))
+                .collect::<Result<_, _>>()?,
+        ),
+        Value::Object(m) => {
+            let mut out = toml::Table::new();
+            for (k, v) in m {
+                out.insert(k.clone(), to_toml(v, &child(path, k))?);
+            }
+            toml::Value::Table(out)
+        }
+    })
+}
+
+/* ---------- CSV ---------- */
+
+fn to_csv(v: &Value) -> Result<String, String> {
+    let rows: Vec<&Map<String, Value>> = match v {
+        Value::Object(m) => vec![m],
+        Value::Array(items) if items.is_empty() => {
+            return Err("an empty array has no rows to infer a header from".to_string())
+        }
+        Value::Array(items) => items
+            .iter()
+            .enumerate()
+            .map(|(i, it)| {
+                it.as_object().ok_or_else(|| {
+                    format!(
+                        "row {} (/{i}) is {}; CSV rows must be objects",
+                        i + 1,
+                        kind(it)
+                    )
+                })
+            })
+            .collect::<Result<_, _>>()?,
+        other => {
+            return Err(format!(
+                "the root is {}; CSV needs an array of objects (one per row)",
+                kind(other)
+            ))
+        }
+    };
+
+    let mut header: Vec<String> = vec![];
+    let mut flat_rows = Vec::with_capacity(rows.len());
+    for (i, row) in rows.iter().enumerate() {
+        let mut cells = vec![];
+        for (k, v) in row.iter() {
+            flatten(
+                v,
+                column(k, "", &format!("/{i}"))?,
+                &mut cells,
+                &child(&format!("/{i}"), k),
+            )?;
+        }
+        for (col, _) in &cells {
+            if !header.contains(col) {
+                header.push(col.clone());
+            }
+        }
+        flat_rows.push(cells);
+    }
+
+    let mut w = csv::Writer::from_writer(vec![]);
+    w.write_record(&header).map_err(|e| e.to_string())?;
+    for cells in flat_rows {
+        let record = header.iter().map(|h| {
+            cells
+                .iter()
+                .find(|(c, _)| c == h)
+                .map(|(_, v)| v.as_str())
+                .unwrap_or("")
+        });
+        w.write_record(record).map_err(|e| e.to_string())?;
+    }
+    let bytes = w.into_inner().map_err(|e| e.to_string())?;
+    String::from_utf8(bytes).map_err(|e| e.to_string())
+}
+
+/// Header for `key` under `prefix`; keys that would read back as a path are refused.
+fn column(key: &str, prefix: &str, path: &str) -> Result<String, String> {
+    if key.is_empty() || key.contains(['.', '[', ']']) {
+        return Err(format!(
+            "key \"{key}\" at {} can't be a CSV column: keys must be non-empty and free of '.', '[' and ']'",
+            at(path)
+        ));
+    }
+    Ok(if prefix.is_empty() {
+        key.to_string()
+    } else {
+        format!("{prefix}.{key}")
+    })
+}
+
+fn flatten(
+    v: &Value,
+    col: String,
+    out: &mut Vec<(String, String)>,
+    path: &str,
+) -> Result<(), String> {
+    match v {
+        Value::Null => out.push((col, "null".to_string())),
+        Value::Bool(b) => out.push((col, b.to_string())),
+        Value::Number(n) => out.push((col, n.to_string())),
+        Value::String(s) if s.is_empty() || cell_value(s) != Ok(Value::String(s.clone())) => {
+            out.push((col, Value::String(s.clone()).to_string()))
+        }
+        Value::String(s) => out.push((col, s.clone())),
+        Value::Array(items) if items.is_empty() => out.push((col, "[]".to_string())),
+        Value::Object(m) if m.is_empty() => out.push((col, "{}".to_string())),
+        Value::Array(items) => {
+            for (i, it) in items.iter().enumerate() {
+                flatten(it, format!("{col}[{i}]"), out, &format!("{path}/{i}"))?;
+            }
+        }
+        Value::Object(m) => {
+            for (k, it) in m {
+                flatten(it, column(k, &col, path)?, out, &child(path, k))?;
+            }
+        }
+    }
+    Ok(())
+}
+
+enum Seg {
+    Key(String),
+    Index(usize),
+}
+
+/// `user.tags[0].name` → [Key(user), Key(tags), Index(0), Key(name)]
+fn header_path(h: &str) -> Result<Vec<Seg>, String> {
+    let bad = || format!("header \"{h}\" isn't a valid key path (like user.name or tags[0])");
+    let mut segs = vec![];
+    for part in h.split('.') {
+        let (name, mut rest) = part.split_at(part.find('[').unwrap_or(part.len()));
+        if name.is_empty() || name.contains(']') {
+            return Err(bad());
+        }
+        segs.push(Seg::Key(name.to_string()));
+        while !rest.is_empty() {
+            let close = rest.find(']').ok_or_else(bad)?;
+            if !rest.starts_with('[') {
+                return Err(bad());
+            }
+            let i = rest[1..close].parse::<usize>().map_err(|_| bad())?;
+            segs.push(Seg::Index(i));
+            rest = &rest[close + 1..];
+        }
+    }
+    Ok(segs)
+}
+
+fn from_csv(input: &str) -> Result<Value, String> {
+    let mut r = csv::ReaderBuilder::new().from_reader(input.as_bytes());
+    let headers = r.headers().map_err(csv_error)?.clone();
+    if headers.iter().all(str::is_empty) {
+        return Err("the first line should be a header row".to_string());
+    }
+    let mut paths = Vec::with_capacity(headers.len());
+    for (i, h) in headers.iter().enumerate() {
+        if headers.iter().take(i).any(|o| o == h) {
+            return Err(format!("header \"{h}\" appears more than once"));
+        }
+        paths.push(header_path(h)?);
+    }
+
+    let mut rows = vec![];
+    for rec in r.records() {
+        let rec = rec.map_err(csv_error)?;
+        let mut row = Value::Object(Map::new());
+        for ((h, path), cell) in headers.iter().zip(&paths).zip(rec.iter()) {
+            if !cell.is_empty() {
+                cell_value(cell)
+                    .and_then(|v| insert(&mut row, path, v))
+                    .map_err(|e| format!("row {}: column \"{h}\" {e}", rows.len() + 1))?;
+            }
+        }
+        rows.push(row);
+    }
+    Ok(Value::Array(rows))
+}
+
+fn csv_error(e: csv::Error) -> String {
+    match e.kind() {
+        csv::ErrorKind::UnequalLengths {
+            pos: Some(pos),
+            expected_len,
+            len,
+        } => format!(
+            "line {} has {len} field(s) but the header has {expected_len}",
+            pos.line()
+        ),
+        csv::ErrorKind::Utf8 { pos: Some(pos), .. } => {
+            format!("line {} isn't valid UTF-8", pos.line())
+        }
+        _ => e.to_string(),
+    }
+}
+
+fn insert(mut node: &mut Value, path: &[Seg], v: Value) -> Result<(), String> {
+    const CLASH: &str = "clashes with another column over the same key";
+    for (n, seg) in path.iter().enumerate() {
+        let last = n + 1 == path.len();
+        let next = || match path.get(n + 1) {
+            Some(Seg::Index(_)) => Value::Array(vec![]),
+            _ => Value::Object(Map::new()),
+        };
+        node = match (seg, node) {
+            (Seg::Key(k), Value::Object(m)) => {
+                if last {
+                    return match m.insert(k.clone(), v) {
+                        None => Ok(()),
+                        Some(_) => Err(CLASH.to_string()),
+                    };
+                }
+                m.entry(k.clone()).or_insert_with(next)
+            }
+            (Seg::Index(i), Value::Array(a)) => {
+                if a.len() <= *i {
+                    a.resize(i + 1, Value::Null);
+                }
+                if last {
+                    return match std::mem::replace(&mut a[*i], v) {
+                        Value::Null => Ok(()),
+                        _ => Err(CLASH.to_string()),
+                    };
+                }
+                if a[*i].is_null() {
+                    a[*i] = next();
+                }
+                &mut a[*i]
+            }
+            _ => return Err(CLASH.to_string()),
+        };
+    }
+    Ok(())
+}
+
+/// A cell's value: JSON literals are typed and a JSON-quoted string is
+/// unquoted; anything else, zero-padded numbers like 007 included, is text.
+fn cell_value(s: &str) -> Result<Value, String> {
+    match s {
+        "true" => return Ok(Value::Bool(true)),
+        "false" => return Ok(Value::Bool(false)),
+        "null" => return Ok(Value::Null),
+        "[]" => return Ok(Value::Array(vec![])),
+        "{}" => return Ok(Value::Object(Map::new())),
+        _ => {}
+    }
+    if s.starts_with('"') {
+        if let Ok(text) = serde_json::from_str::<String>(s) {
+            return Ok(Value::String(text));
+        }
+    }
+    let digits = s.strip_prefix('-').unwrap_or(s);
+    let numeric = digits.starts_with(|c: char| c.is_ascii_digit())
+        && digits
+            .chars()
+            .all(|c| c.is_ascii_digit() || matches!(c, '.' | 'e' | 'E' | '+' | '-'))
+        && !(digits.len() > 1 && digits.starts_with('0') && !digits[1..].starts_with('.'));
+    if !numeric {
+        return Ok(Value::String(s.to_string()));
+    }
+    if digits.chars().all(|c| c.is_ascii_digit()) {
+        return s
+            .parse::<i64>()
+            .map(Value::from)
+            .or_else(|_| s.parse::<u64>().map(Value::from))
+            .map_err(|_| {
+                format!(
+                    "holds {s}, which is too large for a 64-bit integer; JSON-quote it (\"{s}\") to keep it as text"
+                )
+            });
+    }
+    Ok(s.parse::<f64>()
+        .ok()
+        .and_then(Number::from_f64)
+        .map_or_else(|| Value::String(s.to_string()), Value::Number))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn csv_round_trip(v: &Value) -> Value {
+        let csv = render(Format::Csv, v).unwrap();
+        parse(Format::Csv, &csv).unwrap_or_else(|e| panic!("{e}\n{csv}"))
+    }
+
+    #[test]
+    fn json_yaml_toml_round_trip() {
+        let doc = json!({
+            "package": {"name": "devpocket", "version": "0.2.0", "edition": "2021"},
+            "dependencies": {"yew": {"version": "0.21", "features": ["csr"]}, "serde_json": "1"},
+            "nums": [1, -2, 3.5, 12345678901234567i64],
+            "flags": {"on": true, "off": false},
+            "empty": {"list": [], "table": {}},
+            "matrix": [[1, 2], ["a"]],
+            "dotted.key": "x"
+        });
+        for fmt in Format::ALL {
+            if fmt == Format::Csv {
+                continue;
+            }
+            let text = render(fmt, &doc).unwrap();
+            assert_eq!(
+                parse(fmt, &text),
+                Ok(doc.clone()),
+                "{}:\n{text}",
+                fmt.label()
+            );
+        }
+        // key order survives, so Cargo.toml keeps [package] ahead of [dependencies]
+        let toml = convert(Format::Json, Format::Toml, &doc.to_string()).unwrap();
+        assert!(
+            toml.find("[package]") < toml.find("[dependencies]"),
+            "{toml}"
+        );
+        assert!(toml.contains("name = \"devpocket\"\nversion"), "{toml}");
+
+        let yaml = "defaults: &d {retries: 3}\njob:\n  <<: *d\n  on: [push]\n1: one\n";
+        assert_eq!(
+            parse(Format::Yaml, yaml),
+            Ok(
+                json!({"defaults": {"retries": 3}, "job": {"retries": 3, "on": ["push"]}, "1": "one"})
+            )
+        );
+        assert_eq!(
+            parse(Format::Toml, "when = 1979-05-27T07:32:00Z"),
+            Ok(json!({"when": "1979-05-27T07:32:00Z"}))
+        );
+    }
+
+    #[test]
+    fn csv_flattens_and_keeps_header_order() {
+        let rows = json!([
+            {"name": "Ann", "user": {"id": 7, "tags": ["a", "b"]}, "age": 31},
+            {"zip": "02134", "name": "Bo", "user": {"id": 8, "tags": []}}
+        ]);
+        let csv = render(Format::Csv, &rows).unwrap();
+        assert_eq!(
+            csv,
+            "name,user.id,user.tags[0],user.tags[1],age,zip,user.tags\n\
+             Ann,7,a,b,31,,\n\
+             Bo,8,,,,02134,[]\n"
+        );
+        assert_eq!(csv_round_trip(&rows), rows);
+        // a single object is one row
+        assert_eq!(
+            csv_round_trip(&json!({"a": {"b": 1}})),
+            json!([{"a": {"b": 1}}])
+        );
+    }
+
+    #[test]
+    fn csv_keeps_types_nulls_and_empty_strings() {
+        let rows = json!([{
+            "null": null,
+            "empty": "",
+            "num_text": "123",
+            "bool_text": "true",
+            "null_text": "null",
+            "list_text": "[]",
+            "quoted": "\"hi\"",
+            "padded": "007",
+            "big": 12345678901234567890u64,
+            "neg": -5,
+            "float": 0.25,
+            "list": [null, 1],
+            "comma, \"q\"": "a,b\nc"
+        }]);
+        assert_eq!(csv_round_trip(&rows), rows);
+
+        let csv = render(Format::Csv, &rows).unwrap();
+        let line = csv.lines().nth(1).unwrap();
+        assert!(
+            line.starts_with(r#"null,"""""","""123""","""true""","#),
+            "{line}"
+        );
+        assert!(
+            line.contains(",007,12345678901234567890,-5,0.25,null,1,"),
+            "{line}"
+        );
+    }
+
+    #[test]
+    fn csv_reads_hand_written_files() {
+        let csv = "id,name,score,tags[1],note\n1,Ann,9.5,x,\n02,\"Bo, Jr\",,,\"\"\"7\"\"\"\n";
+        assert_eq!(
+            parse(Format::Csv, csv),
+            Ok(json!([
+                {"id": 1, "name": "Ann", "score": 9.5, "tags": [null, "x"]},
+                {"id": "02", "name": "Bo, Jr", "note": "7"}
+            ]))
+        );
+    }
+
+    #[test]
+    fn unrepresentable_values_are_refused() {
+        let err = |from: Format, to: Format, s: &str| convert(from, to, s).unwrap_err();
+
+        assert_eq!(
+            err(Format::Json, Format::Toml, r#"{"a": {"b": [1, null]}}"#),
+            "Can't write TOML: /a/b/1 is null, and TOML has no null; remove the key or give it a value"
+        );
+        assert!(err(Format::Json, Format::Toml, "[1]").contains("root here is an array"));
+        assert!(
+            err(Format::Json, Format::Toml, r#"{"n": 18446744073709551615}"#)
+                .contains("/n is too large for a TOML integer")
+        );
+        assert!(err(Format::Yaml, Format::Json, "a: .nan").contains("NaN at /a"));
+        assert!(
+            err(Format::Yaml, Format::Json, "? [a]\n: b").contains("can't be a JSON object key")
+        );
+        assert!(err(Format::Toml, Format::Json, "f = inf").contains("inf at /f"));
+        assert!(err(Format::Toml, Format::Json, "a = ").contains("line 1 column 5"));
+
+        assert!(err(Format::Json, Format::Csv, "[1, 2]").contains("row 1 (/0) is a number"));
+        assert!(err(Format::Json, Format::Csv, "[]").contains("empty array"));
+        assert!(err(Format::Json, Format::Csv, "\"x\"").contains("the root is a string"));
+        assert_eq!(
+            err(Format::Json, Format::Csv, r#"[{"a": {"b.c": 1}}]"#),
+            "Can't write CSV: key \"b.c\" at /0/a can't be a CSV column: keys must be non-empty and free of '.', '[' and ']'"
+        );
+        assert!(err(Format::Json, Format::Csv, r#"[{"x[0]": 1}]"#).contains("key \"x[0]\""));
+
+        assert!(err(Format::Csv, Format::Json, "a,a.b\n1,2\n")
+            .contains("row 1: column \"a.b\" clashes"));
+        assert!(err(Format::Csv, Format::Json, "a,b\n1\n")
+            .contains("line 2 has 1 field(s) but the header has 2"));
+        assert!(err(Format::Csv, Format::Json, "a,a\n1,2\n").contains("appears more than once"));
+        assert!(err(Format::Csv, Format::Json, "a]b\n1\n").contains("isn't a valid key path"));
+        assert!(
+            err(Format::Csv, Format::Json, "id\n123456789012345678901\n")
+                .contains("too large for a 64-bit integer")
+        );
+    }
+}
@@ -0,0 +1,600 @@
+// src/jsondiff.rs
+//
+// Structural JSON diff for the Diff tab: what changed, by JSON Pointer, and
+// the same difference as an RFC 6902 JSON Patch or an RFC 7386 merge patch.
+// Arrays are compared by index, or by a key field so that reordered elements
+// match up (`id` in `[{"id": 1, ...}, ...]`).
+
+use serde_json::{Map, Value};
+
+#[derive(Clone, PartialEq)]
+pub enum ArrayMode {
+    Index,
+    /// Falls back to `Index` for arrays where some element lacks the key or
+    /// two elements share a value.
+    Key(String),
+}
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum Kind {
+    Added,
+    Removed,
+    Changed,
+    TypeChanged,
+}
+
+pub struct Change {
+    /// JSON Pointer (empty for the whole document); array indices are the left side's for removals and the
+    /// right side's otherwise.
+    pub path: String,
+    pub kind: Kind,
+    pub old: Option<Value>,
+    pub new: Option<Value>,
+}
+
+fn type_name(v: &Value) -> &'static str {
+    match v {
+        Value::Null => "null",
+        Value::Bool(_) => "boolean",
+        Value::Number(_) => "number",
+        Value::String(_) => "string",
+        Value::Array(_) => "array",
+        Value::Object(_) => "object",
+    }
+}
+
+impl Change {
+    /// `~ /a/b  1 → 2`
+    pub fn line(&self) -> String {
+        let show = |v: &Option<Value>| v.as_ref().map(|v| short(v, 80)).unwrap_or_default();
+        let path = if self.path.is_empty() {
+            "(root)"
+        } else {
+            &self.path
+        };
+        match self.kind {
+            Kind::Added => format!("+ {path}  {}", show(&self.new)),
+            Kind::Removed => format!("- {path}  {}", show(&self.old)),
+            Kind::Changed => format!("~ {path}  {} → {}", show(&self.old), show(&self.new)),
+            Kind::TypeChanged => format!(
+                "! {path}  {} {} → {} {}",
+                type_name(self.old.as_ref().unwrap_or(&Value::Null)),
+                show(&self.old),
+                type_name(self.new.as_ref().unwrap_or(&Value::Null)),
+                show(&self.new)
+            ),
+        }
+    }
+}
+
+/// Compact JSON, cut to `max` chars.
+fn short(v: &Value, max: usize) -> String {
+    let s = v.to_string();
+    if s.chars().count() <= max {
+        s
+    } else {
+        format!("{}…", s.chars().take(max).collect::<String>())
+    }
+}
+
+/// `a/b~c` → `a~1b~0c`
+fn escape(token: &str) -> String {
+    token.replace('~', "~0").replace('/', "~1")
+}
+
+fn unescape(token: &str) -> String {
+    token.replace("~1", "/").replace("~0", "~")
+}
+
+fn child(path: &str, token: &str) -> String {
+    format!("{path}/{}", escape(token))
+}
+
+/// How the elements of two arrays line up by key.
+struct Keyed {
+    removed: Vec<usize>,
+    /// For each right element, its left match.
+    right: Vec<Option<usize>>,
+}
+
+fn match_by_key(a: &[Value], b: &[Value], key: &str) -> Option<Keyed> {
+    let keys = |arr: &[Value]| -> Option<Vec<String>> {
+        let ks = arr
+            .iter()
+            .map(|v| v.get(key).map(Value::to_string))
+            .collect::<Option<Vec<_>>>()?;
+        let mut sorted = ks.clone();
+        sorted.sort();
+        sorted.dedup();
+        (sorted.len() == ks.len()).then_some(ks)
+    };
+    let (ka, kb) = (keys(a)?, keys(b)?);
+    let right = kb
+        .iter()
+        .map(|k| ka.iter().position(|x| x == k))
+        .collect::<Vec<_>>();
+    let removed = (0..a.len())
+        .filter(|i| !right.contains(&Some(*i)))
+        .collect();
+    Some(Keyed { removed, right })
+}
+
+fn keyed(mode: &ArrayMode, a: &[Value], b: &[Value]) -> Option<Keyed> {
+    match mode {
+        ArrayMode::Index => None,
+        ArrayMode::Key(k) => match_by_key(a, b, k),
+    }
+}
+
+/// Every difference from `a` to `b`, in document order.
+pub fn changes(a: &Value, b: &Value, mode: &ArrayMode) -> Vec<Change> {
+    let mut out = vec![];
+    walk(a, b, "", mode, &mut out);
+    out
+}
+
+fn walk(a: &Value, b: &Value, path: &str, mode: &ArrayMode, out: &mut Vec<Change>) {
+    let change = |kind, old: Option<&Value>, new: Option<&Value>| Change {
+        path: path.to_string(),
+        kind,
+        old: old.cloned(),
+        new: new.cloned(),
+    };
+    match (a, b) {
+        (Value::Object(x), Value::Object(y)) => {
+            for (k, va) in x {
+                match y.get(k) {
+                    Some(vb) => walk(va, vb, &child(path, k), mode, out),
+                    None => out.push(Change {
+                        path: child(path, k),
+                        kind: Kind::Removed,
+                        old: Some(va.clone()),
+                        new: None,
+                    }),
+                }
+            }
+            for (k, vb) in y {
+                if !x.contains_key(k) {
+                    out.push(Change {
+                        path: child(path, k),
+                        kind: Kind::Added,
+                        old: None,
+                        new: Some(vb.clone()),
+                    });
+                }
+            }
+        }
+        (Value::Array(x), Value::Array(y)) => {
+            if let Some(m) = keyed(mode, x, y) {
+                for &i in &m.removed {
+                    out.push(Change {
+                        path: format!("{path}/{i}"),
+                        kind: Kind::Removed,
+                        old: Some(x[i].clone()),
+                        new: None,
+                    });
+                }
+                for (j, from) in m.right.iter().enumerate() {
+                    match from {
+                        Some(i) => walk(&x[*i], &y[j], &format!("{path}/{j}"), mode, out),
+                        None => out.push(Change {
+                            path: format!("{path}/{j}"),
+                            kind: Kind::Added,
+                            old: None,
+                            new: Some(y[j].clone()),
+                        }),
+                    }
+                }
+                return;
+            }
+            for i in 0..x.len().max(y.len()) {
+                let p = format!("{path}/{i}");
+                match (x.get(i), y.get(i)) {
+                    (Some(va), Some(vb)) => walk(va, vb, &p, mode, out),
+                    (Some(va), None) => out.push(Change {
+                        path: p,
+                        kind: Kind::Removed,
+                        old: Some(va.clone()),
+                        new: None,
+                    }),
+                    (None, Some(vb)) => out.push(Change {
+                        path: p,
+                        kind: Kind::Added,
+                        old: None,
+                        new: Some(vb.clone()),
+                    }),
+                    (None, None) => {}
+                }
+            }
+        }
+        _ if a == b => {}
+        _ if type_name(a) == type_name(b) => out.push(change(Kind::Changed, Some(a), Some(b))),
+        _ => out.push(change(Kind::TypeChanged, Some(a), Some(b))),
+    }
+}
+
+/* ---------- RFC 6902 JSON Patch ---------- */
+
+fn op(name: &str, path: &str, value: Option<&Value>) -> Value {
+    let mut o = Map::new();
+    o.insert("op".into(), name.into());
+    o.insert("path".into(), path.into());
+    if let Some(v) = value {
+        o.insert("value".into(), v.clone());
+    }
+    Value::Object(o)
+}
+
+/// Operations that turn `a` into `b` when applied in order.
+pub fn json_patch(a: &Value, b: &Value, mode: &ArrayMode) -> Value {
+    let mut ops = vec![];
+    patch_ops(a, b, "", mode, &mut ops);
+    Value::Array(ops)
+}
+
+fn patch_ops(a: &Value, b: &Value, path: &str, mode: &ArrayMode, ops: &mut Vec<Value>) {
+    match (a, b) {
+        (Value::Object(x), Value::Object(y)) => {
+            for (k, va) in x {
+                match y.get(k) {
+                    Some(vb) => patch_ops(va, vb, &child(path, k), mode, ops),
+                    None => ops.push(op("remove", &child(path, k), None)),
+                }
+            }
+            for (k, vb) in y {
+                if !x.contains_key(k) {
+                    ops.push(op("add", &child(path, k), Some(vb)));
+                }
+            }
+        }
+        (Value::Array(x), Value::Array(y)) => {
+            if let Some(m) = keyed(mode, x, y) {
+                // drop the unmatched (last first, so indices hold), then build
+                // the right side front to back: everything before `j` is final
+                for &i in m.removed.iter().rev() {
+                    ops.push(op("remove", &format!("{path}/{i}"), None));
+                }
+                let mut current = (0..x.len())
+                    .filter(|i| !m.removed.contains(i))
+                    .map(Some)
+                    .collect::<Vec<_>>();
+                for (j, from) in m.right.iter().enumerate() {
+                    let at = format!("{path}/{j}");
+                    match from {
+                        Some(i) => {
+                            let now = current
+                                .iter()
+                                .position(|c| c == &Some(*i))
+                                .expect("matched");
+                            if now != j {
+                                ops.push(Value::Object(Map::from_iter([
+                                    ("op".to_string(), Value::from("move")),
+                                    ("from".to_string(), Value::from(format!("{path}/{now}"))),
+                                    ("path".to_string(), Value::from(at.clone())),
+                                ])));
+                                let moved = current.remove(now);
+                                current.insert(j, moved);
+                            }
+                            patch_ops(&x[*i], &y[j], &at, mode, ops);
+                        }
+                        None => {
+                            ops.push(op("add", &at, Some(&y[j])));
+                            current.insert(j, None);
+                        }
+                    }
+                }
+                return;
+            }
+            for i in 0..x.len().min(y.len()) {
+                patch_ops(&x[i], &y[i], &format!("{path}/{i}"), mode, ops);
+            }
+            for i in (y.len()..x.len()).rev() {
+                ops.push(op("remove", &format!("{path}/{i}"), None));
+            }
+            for v in y.iter().skip(x.len()) {
+                ops.push(op("add", &format!("{path}/-"), Some(v)));
+            }
+        }
+        _ if a == b => {}
+        _ => ops.push(op("replace", path, Some(b))),
+    }
+}
+
+fn parse_pointer(path: &str) -> Result<Vec<String>, String> {
+    if path.is_empty() {
+        return Ok(vec![]);
+    }
+    let Some(rest) = path.strip_prefix('/') else {
+        return Err(format!(
+            "Invalid JSON Pointer \"{path}\" (must start with /)."
+        ));
+    };
+    Ok(rest.split('/').map(unescape).collect())
+}
+
+fn array_index(token: &str, len: usize, allow_end: bool) -> Result<usize, String> {
+    if allow_end && token == "-" {
+        return Ok(len);
+    }
+    let i = token
+        .parse::<usize>()
+        .ok()
+        .filter(|_| token == "0" || !token.starts_with('0'))
+        .ok_or_else(|| format!("Invalid array index \"{token}\"."))?;
+    let max = if allow_end {
+        len
+    } else {
+        len.saturating_sub(1)
+    };
+    if i > max || (!allow_end && len == 0) {
+        return Err(format!("Array index {i} is out of bounds (length {len})."));
+    }
+    Ok(i)
+}
+
+fn pointer_mut<'v>(doc: &'v mut Value, tokens: &[String]) -> Result<&'v mut Value, String> {
+    let mut at = doc;
+    for t in tokens {
+        at = match at {
+            Value::Object(o) => o
+                .get_mut(t)
+                .ok_or_else(|| format!("Path segment \"{t}\" doesn't exist."))?,
+            Value::Array(a) => {
+                let i = array_index(t, a.len(), false)?;
+                &mut a[i]
+            }
+            _ => {
+                return Err(format!(
+                    "Path segment \"{t}\" goes into a scalar (invalid)."
+                ))
+            }
+        };
+    }
+    Ok(at)
+}
+
+fn patch_add(doc: &mut Value, path: &str, value: Value) -> Result<(), String> {
+    let tokens = parse_pointer(path)?;
+    let Some((last, parent)) = tokens.split_last() else {
+        *doc = value;
+        return Ok(());
+    };
+    match pointer_mut(doc, parent)? {
+        Value::Object(o) => {
+            o.insert(last.clone(), value);
+        }
+        Value::Array(a) => {
+            let i = array_index(last, a.len(), true)?;
+            a.insert(i, value);
+        }
+        _ => {
+            return Err(format!(
+                "Can't add at {path}: the parent is a scalar (invalid)."
+            ))
+        }
+    }
+    Ok(())
+}
+
+fn patch_remove(doc: &mut Value, path: &str) -> Result<Value, String> {
+    let tokens = parse_pointer(path)?;
+    let Some((last, parent)) = tokens.split_last() else {
+        return Err("Removing the whole document is invalid.".to_string());
+    };
+    match pointer_mut(doc, parent)? {
+        Value::Object(o) => o
+            .remove(last)
+            .ok_or_else(|| format!("Can't remove {path}: it doesn't exist.")),
+        Value::Array(a) => {
+            let i = array_index(last, a.len(), false)?;
+            Ok(a.remove(i))
+        }
+        _ => Err(format!(
+            "Can't remove {path}: the parent is a scalar (invalid)."
+        )),
+    }
+}
+
+/// Apply RFC 6902 operations (add, remove, replace, move, copy, test).
+pub fn apply_patch(doc: &Value, patch: &Value) -> Result<Value, String> {
+    let Value::Array(ops) = patch else {
+        return Err("A JSON Patch must be an array of operations (invalid).".to_string());
+    };
+    let mut doc = doc.clone();
+    for (n, o) in ops.iter().enumerate() {
+        let field = |name: &str| -> Result<&str, String> {
+            o.get(name)
+                .and_then(Value::as_str)
+                .ok_or_else(|| format!("Operation #{n} has no \"{name}\" (invalid)."))
+        };
+        let value = || {
+            o.get("value")
+                .cloned()
+                .ok_or_else(|| format!("Operation #{n} has no \"value\" (invalid)."))
+        };
+        let path = field("path")?;
+        let step = match field("op")? {
+            "add" => patch_add(&mut doc, path, value()?),
+            "remove" => patch_remove(&mut doc, path).map(|_| ()),
+            "replace" => {
+                let target = pointer_mut(&mut doc, &parse_pointer(path)?)?;
+                *target = value()?;
+                Ok(())
+            }
+            "move" => {
+                let from = field("from")?;
+                if path.starts_with(&format!("{from}/")) {
+                    Err(format!(
+                        "Can't move {from} into its own child {path} (invalid)."
+                    ))
+                } else {
+                    patch_remove(&mut doc, from).and_then(|v| patch_add(&mut doc, path, v))
+                }
+            }
+            "copy" => {
+                let v = pointer_mut(&mut doc, &parse_pointer(field("from")?)?)?.clone();
+                patch_add(&mut doc, path, v)
+            }
+            "test" => {
+                let at = pointer_mut(&mut doc, &parse_pointer(path)?)?;
+                if *at == value()? {
+                    Ok(())
+                } else {
+                    Err(format!("Test failed at {path}."))
+                }
+            }
+            other => Err(format!("Unknown op \"{other}\" (invalid).")),
+        };
+        step.map_err(|e| format!("Patch operation #{n}: {e}"))?;
+    }
+    Ok(doc)
+}
+
+/* ---------- RFC 7386 merge patch ---------- */
+
+/// A merge patch from `a` to `b`. Arrays are replaced whole, and since `null`
+/// means "delete", a `null` value in `b` can't be expressed.
+pub fn merge_patch(a: &Value, b: &Value) -> Value {
+    match (a, b) {
+        (Value::Object(x), Value::Object(y)) => {
+            let mut out = Map::new();
+            for k in x.keys().filter(|k| !y.contains_key(*k)) {
+                out.insert(k.clone(), Value::Null);
+            }
+            for (k, vb) in y {
+                match x.get(k) {
+                    Some(va) if va == vb => {}
+                    Some(va @ Value::Object(_)) if vb.is_object() => {
+                        out.insert(k.clone(), merge_patch(va, vb));
+                    }
+                    _ => {
+                        out.insert(k.clone(), vb.clone());
+                    }
+                }
+            }
+            Value::Object(out)
+        }
+        _ => b.clone(),
+    }
+}
+
+pub fn apply_merge_patch(doc: &Value, patch: &Value) -> Value {
+    let Value::Object(p) = patch else {
+        return patch.clone();
+    };
+    let mut out = match doc {
+        Value::Object(o) => o.clone(),
+        _ => Map::new(),
+    };
+    for (k, v) in p {
+        if v.is_null() {
+            out.remove(k);
+        } else {
+            let merged = apply_merge_patch(out.get(k).unwrap_or(&Value::Null), v);
+            out.insert(k.clone(), merged);
+        }
+    }
+    Value::Object(out)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn modes() -> [ArrayMode; 2] {
+        [ArrayMode::Index, ArrayMode::Key("id".to_string())]
+    }
+
+    fn pairs() -> Vec<(Value, Value)> {
+        vec![
+            (
+                json!({"name": "a", "tags": ["x", "y"], "n": 1, "gone": true}),
+                json!({"name": "b", "tags": ["y", "x", "z"], "n": "1", "new": {"k": [1]}}),
+            ),
+            (
+                json!({"items": [{"id": 1, "q": 1}, {"id": 2, "q": 2}, {"id": 3, "q": 3}]}),
+                json!({"items": [{"id": 3, "q": 3}, {"id": 1, "q": 10}, {"id": 4, "q": 4}]}),
+            ),
+            (json!([1, 2, 3, 4]), json!([4])),
+            (json!([]), json!([{"id": 1}, {"id": 2}])),
+            (json!({"a": 1}), json!([1])),
+            (json!(null), json!({"a": null})),
+        ]
+    }
+
+    #[test]
+    fn json_patch_applies_back_in_both_modes() {
+        for mode in modes() {
+            for (a, b) in pairs() {
+                let patch = json_patch(&a, &b, &mode);
+                assert_eq!(
+                    apply_patch(&a, &patch).as_ref(),
+                    Ok(&b),
+                    "{a} → {b}: {patch}"
+                );
+                let back = json_patch(&b, &a, &mode);
+                assert_eq!(apply_patch(&b, &back).as_ref(), Ok(&a), "{b} → {a}: {back}");
+            }
+            let same = json!({"a": [1, {"id": 1}]});
+            assert_eq!(json_patch(&same, &same, &mode), json!([]));
+        }
+    }
+
+    #[test]
+    fn pointer_tokens_are_escaped() {
+        let a = json!({"a/b": {"m~n": 1}, "~": [0]});
+        let b = json!({"a/b": {"m~n": 2}, "~": [0, 1]});
+        for mode in modes() {
+            let patch = json_patch(&a, &b, &mode);
+            let paths: Vec<&str> = patch
+                .as_array()
+                .unwrap()
+                .iter()
+                .map(|o| o["path"].as_str().unwrap())
+                .collect();
+            assert!(paths.contains(&"/a~1b/m~0n"), "{paths:?}");
+            assert!(paths.iter().any(|p| p.starts_with("/~0/")), "{paths:?}");
+            assert_eq!(apply_patch(&a, &patch), Ok(b.clone()));
+        }
+        let lines: Vec<String> = changes(&a, &b, &ArrayMode::Index)
+            .iter()
+            .map(Change::line)
+            .collect();
+        assert!(
+            lines.contains(&"~ /a~1b/m~0n  1 → 2".to_string()),
+            "{lines:?}"
+        );
+    }
+
+    #[test]
+    fn merge_patch_applies_back_without_nulls() {
+        let a = json!({"title": "Hello", "author": {"given": "J", "family": "D"}, "tags": ["a"], "x": 1});
+        let b =
+            json!({"title": "Hi", "author": {"given": "J"}, "tags": ["a", "b"], "phone": "555"});
+        let patch = merge_patch(&a, &b);
+        assert_eq!(
+            patch,
+            json!({"title": "Hi", "author": {"family": null}, "tags": ["a", "b"], "phone": "555", "x": null})
+        );
+        assert_eq!(apply_merge_patch(&a, &patch), b);
+        assert_eq!(apply_merge_patch(&b, &merge_patch(&b, &a)), a);
+    }
+
+    #[test]
+    fn bad_patches_are_reported() {
+        let doc = json!({"a": [1]});
+        assert!(apply_patch(&doc, &json!({})).is_err());
+        let err = apply_patch(&doc, &json!([{"op": "remove", "path": "/nope"}])).unwrap_err();
+        assert!(err.starts_with("Patch operation #0"), "{err}");
+        assert!(apply_patch(&doc, &json!([{"op": "test", "path": "/a/0", "value": 2}])).is_err());
+        assert!(apply_patch(&doc, &json!([{"op": "move", "from": "/a", "path": "/a/0"}])).is_err());
+        assert_eq!(
+            apply_patch(
+                &doc,
+                &json!([{"op": "add", "path": "/a/-", "value": 2}, {"op": "copy", "from": "/a", "path": "/b"}])
+            ),
+            Ok(json!({"a": [1, 2], "b": [1, 2]}))
+        );
+    }
+}
@@ -1,4 +1,10 @@
 // src/main.rs
+mod jsondiff;
+mod jwt;
+mod query;
+mod schema;
+mod convert;
+
 use base64::engine::general_purpose::URL_SAFE_NO_PAD;
 use base64::Engine;
 use regex::Regex;
@@ -12,6 +18,9 @@ use yew::prelude::*;
 #[derive(Clone, Copy, PartialEq, Eq)]
 enum Tab {
     Json,
+    Query,
+    Schema,
+    Convert,
     Jwt,
     Base64,
     Url,
@@ -24,6 +33,9 @@ enum Tab {
 fn tab_label(t: Tab) -> &'static str {
     match t {
         Tab::Json => "JSON",
+        Tab::Query => "Query",
+        Tab::Schema => "Schema",
+        Tab::Convert => "Convert",
         Tab::Jwt => "JWT",
         Tab::Base64 => "Base64",
         Tab::Url => "URL",
@@ -90,6 +102,56 @@ fn normalize_json_for_diff(input: &str) -> Result<String, String> {
     serde_json::to_string_pretty(&v).map_err(|e| format!("JSON stringify error: {e}"))
 }
 
+/* ---------- Query helpers ---------- */
+
+fn run_query(expr: &str, input: &str) -> Result<(Vec<query::Hit>, String), String> {
+    let doc: serde_json::Value =
+        serde_json::from_str(input).map_err(|e| format!("Input: JSON parse error: {e}"))?;
+    let (lang, hits) = query::run(expr, &doc)?;
+    let msg = format!("{}: {} result(s).", lang.name(), hits.len());
+    Ok((hits, msg))
+}
+
+/* ---------- Schema helpers ---------- */
+
+/// One or more JSON documents, back to back or one per line.
+fn json_documents(input: &str, what: &str) -> Result<Vec<serde_json::Value>, String> {
+    let docs = serde_json::Deserializer::from_str(input)
+        .into_iter::<serde_json::Value>()
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(|e| format!("{what}: JSON parse error: {e}"))?;
+    if docs.is_empty() {
+        return Err(format!("{what}: paste at least one JSON document (invalid: empty)."));
+    }
+    Ok(docs)
+}
+
+/// (document number when there are several, violation)
+type SchemaReport = Vec<(Option<usize>, schema::Violation)>;
+
+fn validate_documents(schema_src: &str, docs_src: &str) -> Result<(SchemaReport, String), String> {
+    let schema_v: serde_json::Value =
+        serde_json::from_str(schema_src).map_err(|e| format!("Schema: JSON parse error: {e}"))?;
+    let docs = json_documents(docs_src, "Document")?;
+    let several = docs.len() > 1;
+    let mut report = vec![];
+    for (i, d) in docs.iter().enumerate() {
+        let n = several.then_some(i + 1);
+        report.extend(schema::validate(&schema_v, d).into_iter().map(|v| (n, v)));
+    }
+    let docs_label = if several {
+        format!("{} documents", docs.len())
+    } else {
+        "The document".to_string()
+    };
+    let msg = if report.is_empty() {
+        format!("{docs_label}: valid, no violations.")
+    } else {
+        format!("{docs_label}: {} violation(s) (invalid).", report.len())
+    };
+    Ok((report, msg))
+}
+
 /* ---------- JWT helpers ---------- */
 
 fn decode_jwt_part(part: &str) -> Result<String, String> {
@@ -103,6 +165,10 @@ fn decode_jwt_part(part: &str) -> Result<String, String> {
     }
 }
 
+fn now_unix() -> i64 {
+    (js_sys::Date::now() / 1000.0) as i64
+}
+
 /* ---------- Hash helpers ---------- */
 
 fn sha256_hex(input: &str) -> String {
@@ -129,6 +195,57 @@ fn hex_lower(bytes: &[u8]) -> String {
 
 /* ---------- Diff helpers ---------- */
 
+struct Structural {
+    changes: Vec<jsondiff::Change>,
+    patch: String,
+    msg: String,
+}
+
+/// Path-level changes from left to right, plus a JSON Patch (or merge patch)
+/// that is checked by applying it back to the left side.
+fn structural_diff(left: &str, right: &str, key: &str, merge: bool) -> Result<Structural, String> {
+    let a: serde_json::Value =
+        serde_json::from_str(left).map_err(|e| format!("Left: JSON parse error: {e}"))?;
+    let b: serde_json::Value =
+        serde_json::from_str(right).map_err(|e| format!("Right: JSON parse error: {e}"))?;
+    let mode = match key.trim() {
+        "" => jsondiff::ArrayMode::Index,
+        k => jsondiff::ArrayMode::Key(k.to_string()),
+    };
+    let changes = jsondiff::changes(&a, &b, &mode);
+    let (patch, applied) = if merge {
+        let p = jsondiff::merge_patch(&a, &b);
+        let applied = jsondiff::apply_merge_patch(&a, &p);
+        (p, Ok(applied))
+    } else {
+        let p = jsondiff::json_patch(&a, &b, &mode);
+        let applied = jsondiff::apply_patch(&a, &p);
+        (p, applied)
+    };
+    let arrays = match &mode {
+        jsondiff::ArrayMode::Index => "arrays by index".to_string(),
+        jsondiff::ArrayMode::Key(k) => format!("arrays by \"{k}\""),
+    };
+    let msg = match applied {
+        Ok(v) if v == b => format!(
+            "{} structural change(s), {arrays}; the {} reproduces Right.",
+            changes.len(),
+            if merge { "merge patch" } else { "JSON Patch" }
+        ),
+        Ok(_) if merge => format!(
+            "{} structural change(s), {arrays}; round-trip failed: a merge patch can't set a value to null.",
+            changes.len()
+        ),
+        Ok(_) => format!("{} structural change(s); round-trip failed.", changes.len()),
+        Err(e) => format!("{} structural change(s); round-trip failed: {e}", changes.len()),
+    };
+    Ok(Structural {
+        changes,
+        patch: serde_json::to_string_pretty(&patch).map_err(|e| format!("JSON stringify error: {e}"))?,
+        msg,
+    })
+}
+
 fn unified_diff(a: &str, b: &str) -> String {
     let diff = TextDiff::from_lines(a, b);
     diff.unified_diff().header("left", "right").to_string()
@@ -213,52 +330,78 @@ fn app() -> Html {
     let tab = use_state(|| Tab::Json);
 
     // JSON
-    let json_in = use_state(|| String::new());
-    let json_out = use_state(|| String::new());
-    let json_msg = use_state(|| String::new());
+    let json_in = use_state(String::new);
+    let json_out = use_state(String::new);
+    let json_msg = use_state(String::new);
+
+    // Query (runs against the JSON tab's input)
+    let q_expr = use_state(String::new);
+    let q_live = use_state(|| true);
+    let q_hits = use_state(Vec::<query::Hit>::new);
+    let q_msg = use_state(String::new);
+
+    // Convert
+    let cv_in = use_state(String::new);
+    let cv_out = use_state(String::new);
+    let cv_from = use_state(|| convert::Format::Json);
+    let cv_to = use_state(|| convert::Format::Yaml);
+    let cv_msg = use_state(String::new);
+
+    // Schema
+    let sch_schema = use_state(String::new);
+    let sch_doc = use_state(String::new);
+    let sch_out = use_state(SchemaReport::new);
+    let sch_msg = use_state(String::new);
 
     // JWT
-    let jwt_in = use_state(|| String::new());
-    let jwt_header = use_state(|| String::new());
-    let jwt_payload = use_state(|| String::new());
-    let jwt_msg = use_state(|| String::new());
+    let jwt_in = use_state(String::new);
+    let jwt_header = use_state(String::new);
+    let jwt_payload = use_state(String::new);
+    let jwt_msg = use_state(String::new);
+    let jwt_key = use_state(String::new);
+    let jwt_key_b64 = use_state(|| false);
+    let jwt_claims = use_state(Vec::<jwt::Claim>::new);
 
     // Base64
-    let b64_in = use_state(|| String::new());
-    let b64_out = use_state(|| String::new());
-    let b64_msg = use_state(|| String::new());
+    let b64_in = use_state(String::new);
+    let b64_out = use_state(String::new);
+    let b64_msg = use_state(String::new);
 
     // URL
-    let url_in = use_state(|| String::new());
-    let url_out = use_state(|| String::new());
-    let url_msg = use_state(|| String::new());
+    let url_in = use_state(String::new);
+    let url_out = use_state(String::new);
+    let url_msg = use_state(String::new);
 
     // UUID
-    let uuid_out = use_state(|| String::new());
+    let uuid_out = use_state(String::new);
     let uuid_upper = use_state(|| false);
-    let uuid_msg = use_state(|| String::new());
+    let uuid_msg = use_state(String::new);
 
     // Hash
-    let hash_in = use_state(|| String::new());
-    let hash_sha = use_state(|| String::new());
-    let hash_md5 = use_state(|| String::new());
-    let hash_msg = use_state(|| String::new());
+    let hash_in = use_state(String::new);
+    let hash_sha = use_state(String::new);
+    let hash_md5 = use_state(String::new);
+    let hash_msg = use_state(String::new);
 
     // Diff
-    let diff_left = use_state(|| String::new());
-    let diff_right = use_state(|| String::new());
+    let diff_left = use_state(String::new);
+    let diff_right = use_state(String::new);
     let diff_is_json = use_state(|| true);
     let diff_show_meta = use_state(|| true);
-    let diff_out = use_state(|| Vec::<DiffLine>::new());
-    let diff_msg = use_state(|| String::new());
+    let diff_out = use_state(Vec::<DiffLine>::new);
+    let diff_msg = use_state(String::new);
+    let diff_key = use_state(String::new);
+    let diff_merge = use_state(|| false);
+    let diff_changes = use_state(Vec::<jsondiff::Change>::new);
+    let diff_patch = use_state(String::new);
 
     // Regex
-    let rx_pat = use_state(|| String::new());
-    let rx_text = use_state(|| String::new());
+    let rx_pat = use_state(String::new);
+    let rx_text = use_state(String::new);
     let rx_live = use_state(|| true);
-    let rx_out = use_state(|| String::new());
+    let rx_out = use_state(String::new);
     let rx_count = use_state(|| 0usize);
-    let rx_msg = use_state(|| String::new());
+    let rx_msg = use_state(String::new);
 
     let set_tab = {
         let tab = tab.clone();
@@ -339,6 +482,208 @@ fn app() -> Html {
         })
     };
 
+    /* ---------- Query actions ---------- */
+
+    let on_query_toggle_live = {
+        let q_live = q_live.clone();
+        Callback::from(move |_| q_live.set(!*q_live))
+    };
+
+    let on_query_run = {
+        let q_expr = q_expr.clone();
+        let json_in = json_in.clone();
+        let q_hits = q_hits.clone();
+        let q_msg = q_msg.clone();
+        Callback::from(move |_| {
+            if q_expr.trim().is_empty() {
+                q_msg.set("Enter a JSONPath ($...) or jq (.foo) expression.".to_string());
+                q_hits.set(vec![]);
+                return;
+            }
+            match run_query(&q_expr, &json_in) {
+                Ok((hits, msg)) => {
+                    q_hits.set(hits);
+                    q_msg.set(msg);
+                }
+                Err(e) => {
+                    q_hits.set(vec![]);
+                    q_msg.set(e);
+                }
+            }
+        })
+    };
+
+    let on_query_copy = {
+        let q_hits = q_hits.clone();
+        let q_msg = q_msg.clone();
+        Callback::from(move |_| {
+            let values = q_hits.iter().map(|h| h.value.clone()).collect::<Vec<_>>();
+            let txt = serde_json::to_string_pretty(&values).unwrap_or_default();
+            let q_msg2 = q_msg.clone();
+            wasm_bindgen_futures::spawn_local(async move {
+                match copy_to_clipboard(txt).await {
+                    Ok(_) => q_msg2.set("Copied results as a JSON array.".to_string()),
+                    Err(e) => q_msg2.set(e),
+                }
+            });
+        })
+    };
+
+    // Live query evaluation whenever expression/input changes (when Live mode is ON)
+    {
+        let expr = (*q_expr).clone();
+        let input = (*json_in).clone();
+        let live = *q_live;
+
+        let q_hits = q_hits.clone();
+        let q_msg = q_msg.clone();
+
+        use_effect_with((expr, input, live), move |deps: &(String, String, bool)| {
+            let (e, i, l) = deps;
+
+            if !*l {
+                return;
+            }
+
+            if e.trim().is_empty() {
+                q_hits.set(vec![]);
+                q_msg.set("Enter a JSONPath ($...) or jq (.foo) expression.".to_string());
+                return;
+            }
+
+            match run_query(e, i) {
+                Ok((hits, msg)) => {
+                    q_hits.set(hits);
+                    q_msg.set(format!("Live: {msg}"));
+                }
+                Err(err) => {
+                    q_hits.set(vec![]);
+                    q_msg.set(err);
+                }
+            }
+        });
+    }
+
+    /* ---------- Convert actions ---------- */
+
+    // Convert whenever the input or either format changes
+    {
+        let input = (*cv_in).clone();
+        let from = *cv_from;
+        let to = *cv_to;
+
+        let cv_out = cv_out.clone();
+        let cv_msg = cv_msg.clone();
+
+        use_effect_with(
+            (input, from, to),
+            move |deps: &(String, convert::Format, convert::Format)| {
+                let (i, from, to) = deps;
+
+                if i.trim().is_empty() {
+                    cv_out.set(String::new());
+                    cv_msg.set(format!("Paste {} to convert.", from.label()));
+                    return;
+                }
+
+                match convert::convert(*from, *to, i) {
+                    Ok(out) => {
+                        cv_msg.set(format!(
+                            "{} → {}: {} line(s).",
+                            from.label(),
+                            to.label(),
+                            out.lines().count()
+                        ));
+                        cv_out.set(out);
+                    }
+                    Err(e) => {
+                        cv_out.set(String::new());
+                        cv_msg.set(e);
+                    }
+                }
+            },
+        );
+    }
+
+    let on_convert_swap = {
+        let cv_in = cv_in.clone();
+        let cv_out = cv_out.clone();
+        let cv_from = cv_from.clone();
+        let cv_to = cv_to.clone();
+        Callback::from(move |_| {
+            if !cv_out.is_empty() {
+                cv_in.set((*cv_out).clone());
+            }
+            cv_from.set(*cv_to);
+            cv_to.set(*cv_from);
+        })
+    };
+
+    let on_convert_copy = {
+        let cv_out = cv_out.clone();
+        let cv_msg = cv_msg.clone();
+        Callback::from(move |_| {
+            let txt = (*cv_out).clone();
+            let cv_msg2 = cv_msg.clone();
+            wasm_bindgen_futures::spawn_local(async move {
+                match copy_to_clipboard(txt).await {
+                    Ok(_) => cv_msg2.set("Copied output.".to_string()),
+                    Err(e) => cv_msg2.set(e),
+                }
+            });
+        })
+    };
+
+    /* ---------- Schema actions ---------- */
+
+    let on_schema_validate = {
+        let sch_schema = sch_schema.clone();
+        let sch_doc = sch_doc.clone();
+        let sch_out = sch_out.clone();
+        let sch_msg = sch_msg.clone();
+        Callback::from(move |_| match validate_documents(&sch_schema, &sch_doc) {
+            Ok((report, msg)) => {
+                sch_out.set(report);
+                sch_msg.set(msg);
+            }
+            Err(e) => {
+                sch_out.set(vec![]);
+                sch_msg.set(e);
+            }
+        })
+    };
+
+    let on_schema_infer = {
+        let sch_schema = sch_schema.clone();
+        let sch_doc = sch_doc.clone();
+        let sch_out = sch_out.clone();
+        let sch_msg = sch_msg.clone();
+        Callback::from(move |_| match json_documents(&sch_doc, "Samples") {
+            Ok(samples) => {
+                let inferred = schema::infer(&samples);
+                sch_schema.set(serde_json::to_string_pretty(&inferred).unwrap_or_default());
+                sch_out.set(vec![]);
+                sch_msg.set(format!("Inferred a schema from {} sample(s).", samples.len()));
+            }
+            Err(e) => sch_msg.set(e),
+        })
+    };
+
+    let on_schema_copy = {
+        let sch_schema = sch_schema.clone();
+        let sch_msg = sch_msg.clone();
+        Callback::from(move |_| {
+            let txt = (*sch_schema).clone();
+            let sch_msg2 = sch_msg.clone();
+            wasm_bindgen_futures::spawn_local(async move {
+                match copy_to_clipboard(txt).await {
+                    Ok(_) => sch_msg2.set("Copied schema.".to_string()),
+                    Err(e) => sch_msg2.set(e),
+                }
+            });
+        })
+    };
+
     /* ---------- JWT actions ---------- */
 
     let on_jwt_decode = {
@@ -346,6 +691,7 @@ fn app() -> Html {
         let jwt_header = jwt_header.clone();
         let jwt_payload = jwt_payload.clone();
         let jwt_msg = jwt_msg.clone();
+        let jwt_claims = jwt_claims.clone();
         Callback::from(move |_| {
             let token = (*jwt_in).trim().to_string();
             if token.is_empty() {
@@ -365,7 +711,10 @@ fn app() -> Html {
                 }
             }
             match decode_jwt_part(parts[1]) {
-                Ok(p) => jwt_payload.set(p),
+                Ok(p) => {
+                    jwt_claims.set(jwt::time_claims(&p, now_unix()));
+                    jwt_payload.set(p);
+                }
                 Err(e) => {
                     jwt_msg.set(format!("Payload: {e}"));
                     return;
@@ -375,6 +724,67 @@ fn app() -> Html {
         })
     };
 
+    let on_jwt_toggle_b64 = {
+        let jwt_key_b64 = jwt_key_b64.clone();
+        Callback::from(move |_| jwt_key_b64.set(!*jwt_key_b64))
+    };
+
+    let on_jwt_verify = {
+        let jwt_in = jwt_in.clone();
+        let jwt_key = jwt_key.clone();
+        let jwt_key_b64 = jwt_key_b64.clone();
+        let jwt_claims = jwt_claims.clone();
+        let jwt_msg = jwt_msg.clone();
+        Callback::from(move |_| {
+            let token = (*jwt_in).trim().to_string();
+            if token.is_empty() {
+                jwt_msg.set("Paste a JWT first.".to_string());
+                return;
+            }
+            let claims = token
+                .split('.')
+                .nth(1)
+                .and_then(|p| decode_jwt_part(p).ok())
+                .map(|p| jwt::time_claims(&p, now_unix()))
+                .unwrap_or_default();
+            let failing = claims
+                .iter()
+                .filter(|c| !c.ok)
+                .map(|c| c.name)
+                .collect::<Vec<_>>();
+            jwt_claims.set(claims);
+            match jwt::verify(&token, &jwt_key, *jwt_key_b64) {
+                Ok(m) if failing.is_empty() => jwt_msg.set(m),
+                Ok(m) => jwt_msg.set(format!(
+                    "{} but the {} check failed.",
+                    m.trim_end_matches('.'),
+                    failing.join("/")
+                )),
+                Err(e) => jwt_msg.set(e),
+            }
+        })
+    };
+
+    let on_jwt_sign = {
+        let jwt_in = jwt_in.clone();
+        let jwt_header = jwt_header.clone();
+        let jwt_payload = jwt_payload.clone();
+        let jwt_key = jwt_key.clone();
+        let jwt_key_b64 = jwt_key_b64.clone();
+        let jwt_claims = jwt_claims.clone();
+        let jwt_msg = jwt_msg.clone();
+        Callback::from(move |_| {
+            match jwt::sign(&jwt_header, &jwt_payload, &jwt_key, *jwt_key_b64) {
+                Ok((token, alg)) => {
+                    jwt_claims.set(jwt::time_claims(&jwt_payload, now_unix()));
+                    jwt_in.set(token);
+                    jwt_msg.set(format!("Signed a new {} token from the header + payload.", alg.name()));
+                }
+                Err(e) => jwt_msg.set(e),
+            }
+        })
+    };
+
     let on_jwt_copy_header = {
         let jwt_header = jwt_header.clone();
         let jwt_msg = jwt_msg.clone();
@@ -459,7 +869,7 @@ fn app() -> Html {
         let url_out = url_out.clone();
         let url_msg = url_msg.clone();
         Callback::from(move |_| {
-            url_out.set(encode(&*url_in).to_string());
+            url_out.set(encode(&url_in).to_string());
             url_msg.set("Encoded OK.".to_string());
         })
     };
@@ -469,7 +879,7 @@ fn app() -> Html {
         let url_out = url_out.clone();
         let url_msg = url_msg.clone();
         Callback::from(move |_| {
-            match decode(&*url_in) {
+            match decode(&url_in) {
                 Ok(s) => {
                     url_out.set(s.to_string());
                     url_msg.set("Decoded OK.".to_string());
@@ -635,6 +1045,88 @@ fn app() -> Html {
         })
     };
 
+    let run_structural = {
+        let diff_left = diff_left.clone();
+        let diff_right = diff_right.clone();
+        let diff_key = diff_key.clone();
+        let diff_changes = diff_changes.clone();
+        let diff_patch = diff_patch.clone();
+        let diff_msg = diff_msg.clone();
+        Callback::from(move |merge: bool| {
+            match structural_diff(&diff_left, &diff_right, &diff_key, merge) {
+                Ok(r) => {
+                    diff_changes.set(r.changes);
+                    diff_patch.set(r.patch);
+                    diff_msg.set(r.msg);
+                }
+                Err(e) => diff_msg.set(e),
+            }
+        })
+    };
+
+    let on_diff_structural = {
+        let run_structural = run_structural.clone();
+        let diff_merge = diff_merge.clone();
+        Callback::from(move |_| run_structural.emit(*diff_merge))
+    };
+
+    let on_diff_toggle_patch = {
+        let diff_merge = diff_merge.clone();
+        Callback::from(move |_| {
+            diff_merge.set(!*diff_merge);
+            run_structural.emit(!*diff_merge);
+        })
+    };
+
+    let on_diff_apply_patch = {
+        let diff_left = diff_left.clone();
+        let diff_right = diff_right.clone();
+        let diff_patch = diff_patch.clone();
+        let diff_merge = diff_merge.clone();
+        let diff_msg = diff_msg.clone();
+        Callback::from(move |_| {
+            let parse = |what: &str, s: &str| {
+                serde_json::from_str::<serde_json::Value>(s)
+                    .map_err(|e| format!("{what}: JSON parse error: {e}"))
+            };
+            let applied = parse("Left", &diff_left).and_then(|doc| {
+                let patch = parse("Patch", &diff_patch)?;
+                if *diff_merge {
+                    Ok(jsondiff::apply_merge_patch(&doc, &patch))
+                } else {
+                    jsondiff::apply_patch(&doc, &patch)
+                }
+            });
+            match applied {
+                Ok(v) => {
+                    let matches = parse("Right", &diff_right).is_ok_and(|b| b == v);
+                    diff_left.set(serde_json::to_string_pretty(&v).unwrap_or_default());
+                    diff_msg.set(if matches {
+                        "Patch applied to Left; it now matches Right.".to_string()
+                    } else {
+                        "Patch applied to Left (it still differs from Right).".to_string()
+                    });
+                }
+                Err(e) => diff_msg.set(e),
+            }
+        })
+    };
+
+    let on_diff_copy_patch = {
+        let diff_patch = diff_patch.clone();
+        let diff_msg = diff_msg.clone();
+        Callback::from(move |_| {
+            let txt = (*diff_patch).clone();
+            let diff_msg2 = diff_msg.clone();
+            wasm_bindgen_futures::spawn_local(async move {
+                match copy_to_clipboard(txt).await {
+                    Ok(_) => diff_msg2.set("Copied patch.".to_string()),
+                    Err(e) => diff_msg2.set(e),
+                }
+            });
+        })
+    };
+
     let on_diff_copy = {
         let diff_out = diff_out.clone();
         let diff_msg = diff_msg.clone();
@@ -819,6 +1311,213 @@ fn app() -> Html {
             </div>
         },
 
+        Tab::Query => html! {
+            <div class="panel">
+              <div class="block">
+                <div class="block-head">
+                  <div class="block-title">{ "Query (JSONPath or jq)" }</div>
+                  <div class="btnrow">
+                    <button class="btn" onclick={on_query_toggle_live}>
+                      { if *q_live { "Live: ON" } else { "Live: OFF" } }
+                    </button>
+                    <button class="btn" onclick={on_query_run}>{ "Run" }</button>
+                    <button class="btn" onclick={on_query_copy}>{ "Copy Results" }</button>
+                  </div>
+                </div>
+
+                <div class="textline">
+                  <input
+                    type="text"
+                    value={(*q_expr).clone()}
+                    oninput={{
+                      let q_expr = q_expr.clone();
+                      Callback::from(move |e: InputEvent| {
+                        let v = e.target_unchecked_into::<web_sys::HtmlInputElement>().value();
+                        q_expr.set(v);
+                      })
+                    }}
+                    placeholder="$.items[?(@.qty > 1)].name   or   .items[] | select(.qty > 1) | .name"
+                  />
+                </div>
+
+                <textarea
+                  value={(*json_in).clone()}
+                  oninput={{
+                    let json_in = json_in.clone();
+                    Callback::from(move |e: InputEvent| {
+                      let v = e.target_unchecked_into::<web_sys::HtmlTextAreaElement>().value();
+                      json_in.set(v);
+                    })
+                  }}
+                  placeholder="Input JSON (shared with the JSON tab)"
+                />
+              </div>
+
+              { msg_view(&q_msg) }
+
+              <div class="block">
+                <div class="block-head">
+                  <div class="block-title">{ "Results" }</div>
+                  <div class="btnrow">
+                    <span class="btn" style="cursor:default;">
+                      { format!("Results: {}", q_hits.len()) }
+                    </span>
+                  </div>
+                </div>
+                <pre class="diff">
+                  {
+                    for q_hits.iter().map(|h| html!{
+                      <>
+                        <span class="meta">{ format!("{}\n", h.path.as_deref().unwrap_or("(computed)")) }</span>
+                        <span class="ctx">{ format!("{}\n", serde_json::to_string_pretty(&h.value).unwrap_or_default()) }</span>
+                      </>
+                    })
+                  }
+                </pre>
+                <div class="kv">
+                  <span class="tag">{ "JSONPath: $ .name ['name'] [0] [-1] [0:2] * .. [?(@.price < 10 && @.tag)]" }</span>
+                  <span class="tag">{ "jq: .foo .[] .[0] | , select() map() keys length not type add to_entries" }</span>
+                </div>
+              </div>
+            </div>
+        },
+
+        Tab::Schema => html! {
+            <div class="panel">
+              <div class="panel two-col">
+                <div class="block">
+                  <div class="block-head">
+                    <div class="block-title">{ "JSON Schema (draft 2020-12)" }</div>
+                    <div class="btnrow">
+                      <button class="btn" onclick={on_schema_copy}>{ "Copy" }</button>
+                    </div>
+                  </div>
+                  <textarea
+                    value={(*sch_schema).clone()}
+                    oninput={{
+                      let sch_schema = sch_schema.clone();
+                      Callback::from(move |e: InputEvent| {
+                        let v = e.target_unchecked_into::<web_sys::HtmlTextAreaElement>().value();
+                        sch_schema.set(v);
+                      })
+                    }}
+                    placeholder="{ \"type\": \"object\", \"required\": [\"id\"] }"
+                  />
+                </div>
+
+                <div class="block">
+                  <div class="block-head">
+                    <div class="block-title">{ "Document(s)" }</div>
+                    <div class="btnrow">
+                      <button class="btn" onclick={on_schema_validate}>{ "Validate" }</button>
+                      <button class="btn" onclick={on_schema_infer}>{ "← Infer Schema" }</button>
+                    </div>
+                  </div>
+                  <textarea
+                    value={(*sch_doc).clone()}
+                    oninput={{
+                      let sch_doc = sch_doc.clone();
+                      Callback::from(move |e: InputEvent| {
+                        let v = e.target_unchecked_into::<web_sys::HtmlTextAreaElement>().value();
+                        sch_doc.set(v);
+                      })
+                    }}
+                    placeholder="One JSON document, or several samples back to back / one per line"
+                  />
+                </div>
+              </div>
+
+              { msg_view(&sch_msg) }
+
+              <div class="block">
+                <div class="block-head">
+                  <div class="block-title">{ "Violations" }</div>
+                  <div class="btnrow">
+                    <span class="btn" style="cursor:default;">
+                      { format!("Violations: {}", sch_out.len()) }
+                    </span>
+                  </div>
+                </div>
+                <pre class="diff">
+                  {
+                    for sch_out.iter().map(|(n, v)| {
+                      let doc = n.map(|n| format!("doc {n} ")).unwrap_or_default();
+                      let path = if v.instance_path.is_empty() { "(root)" } else { v.instance_path.as_str() };
+                      html!{
+                        <span class="del">
+                          { format!("{doc}{path}  {}\n", v.message) }
+                          <span class="meta">{ format!("  at {}\n", v.schema_path) }</span>
+                        </span>
+                      }
+                    })
+                  }
+                </pre>
+              </div>
+
+              <div class="smallnote">
+                { "Keywords: type, enum, const, required, properties, patternProperties, additionalProperties, items, prefixItems, min/maxItems, uniqueItems, min/maxLength, pattern, minimum/maximum (+ exclusive), multipleOf, min/maxProperties, allOf/anyOf/oneOf/not, local $ref. Infer builds a schema every sample satisfies." }
+              </div>
+            </div>
+        },
+
+        Tab::Convert => {
+            let format_picker = |current: &UseStateHandle<convert::Format>| {
+                html! {
+                  for convert::Format::ALL.into_iter().map(|f| {
+                    let current = current.clone();
+                    let cls = if *current == f { "btn sel" } else { "btn" };
+                    html!{
+                      <button class={cls} onclick={Callback::from(move |_| current.set(f))}>
+                        { f.label() }
+                      </button>
+                    }
+                  })
+                }
+            };
+
+            html! {
+            <div class="panel">
+              <div class="panel two-col">
+                <div class="block">
+                  <div class="block-head">
+                    <div class="block-title">{ "From" }</div>
+                    <div class="btnrow">{ format_picker(&cv_from) }</div>
+                  </div>
+                  <textarea
+                    value={(*cv_in).clone()}
+                    oninput={{
+                      let cv_in = cv_in.clone();
+                      Callback::from(move |e: InputEvent| {
+                        let v = e.target_unchecked_into::<web_sys::HtmlTextAreaElement>().value();
+                        cv_in.set(v);
+                      })
+                    }}
+                    placeholder="Cargo.toml, a workflow .yml, a CSV export, JSON…"
+                  />
+                </div>
+
+                <div class="block">
+                  <div class="block-head">
+                    <div class="block-title">{ "To" }</div>
+                    <div class="btnrow">
+                      { format_picker(&cv_to) }
+                      <button class="btn" onclick={on_convert_swap}>{ "⇄ Swap" }</button>
+                      <button class="btn" onclick={on_convert_copy}>{ "Copy" }</button>
+                    </div>
+                  </div>
+                  <textarea value={(*cv_out).clone()} readonly=true placeholder="Converted output" />
+                </div>
+              </div>
+
+              { msg_view(&cv_msg) }
+
+              <div class="smallnote">
+                { "CSV is an array of objects, one per row: nested keys become columns like user.name and tags[0], and the header is the union of every row's keys. Cells hold text or JSON literals (numbers, true/false, null, [] / {}); text that would read as a literal is written JSON-quoted, and an empty cell is a missing key. TOML has no null and needs an object at the root." }
+              </div>
+            </div>
+            }
+        }
+
         Tab::Jwt => html! {
             <div class="panel">
               <div class="block">
@@ -849,7 +1548,17 @@ fn app() -> Html {
                       <button class="btn" onclick={on_jwt_copy_header}>{ "Copy" }</button>
                     </div>
                   </div>
-                  <textarea value={(*jwt_header).clone()} placeholder="Decoded header (pretty JSON if possible)" />
+                  <textarea
+                    value={(*jwt_header).clone()}
+                    oninput={{
+                      let jwt_header = jwt_header.clone();
+                      Callback::from(move |e: InputEvent| {
+                        let v = e.target_unchecked_into::<web_sys::HtmlTextAreaElement>().value();
+                        jwt_header.set(v);
+                      })
+                    }}
+                    placeholder="Decoded header (edit, then Sign)"
+                  />
                 </div>
 
                 <div class="block">
@@ -859,12 +1568,65 @@ fn app() -> Html {
                       <button class="btn" onclick={on_jwt_copy_payload}>{ "Copy" }</button>
                     </div>
                   </div>
-                  <textarea value={(*jwt_payload).clone()} placeholder="Decoded payload (pretty JSON if possible)" />
+                  <textarea
+                    value={(*jwt_payload).clone()}
+                    oninput={{
+                      let jwt_payload = jwt_payload.clone();
+                      Callback::from(move |e: InputEvent| {
+                        let v = e.target_unchecked_into::<web_sys::HtmlTextAreaElement>().value();
+                        jwt_payload.set(v);
+                      })
+                    }}
+                    placeholder="Decoded payload (edit, then Sign)"
+                  />
+                </div>
+              </div>
+
+              <div class="block">
+                <div class="block-head">
+                  <div class="block-title">{ "Secret / Key" }</div>
+                  <div class="btnrow">
+                    <button class="btn" onclick={on_jwt_toggle_b64}>
+                      { if *jwt_key_b64 { "Secret base64: ON" } else { "Secret base64: OFF" } }
+                    </button>
+                    <button class="btn" onclick={on_jwt_verify}>{ "Verify" }</button>
+                    <button class="btn" onclick={on_jwt_sign}>{ "Sign ↑" }</button>
+                  </div>
                 </div>
+                <textarea
+                  value={(*jwt_key).clone()}
+                  oninput={{
+                    let jwt_key = jwt_key.clone();
+                    Callback::from(move |e: InputEvent| {
+                      let v = e.target_unchecked_into::<web_sys::HtmlTextAreaElement>().value();
+                      jwt_key.set(v);
+                    })
+                  }}
+                  placeholder="HS*: shared secret • RS*/ES256: PEM or JWK/JWKS (public to verify, private to sign)"
+                />
               </div>
 
               { msg_view(&jwt_msg) }
-              <div class="smallnote">{ "Note: this decodes base64url; it does not verify signatures." }</div>
+
+              if !jwt_claims.is_empty() {
+                <div class="block">
+                  <div class="block-head">
+                    <div class="block-title">{ "Time Claims" }</div>
+                  </div>
+                  <pre class="diff">
+                    {
+                      for jwt_claims.iter().map(|c| {
+                        let (cls, mark) = if c.ok { ("add", "✓") } else { ("del", "✗") };
+                        html!{ <span class={cls}>{ format!("{mark} {:<4} {}\n", c.name, c.note) }</span> }
+                      })
+                    }
+                  </pre>
+                </div>
+              }
+
+              <div class="smallnote">
+                { "Verify checks the signature with the header's alg (HS256/384/512, RS256/384/512, ES256). Sign builds a new token from the header + payload above; exp/nbf/iat are checked against your clock." }
+              </div>
             </div>
         },
 
@@ -1088,8 +1850,70 @@ fn app() -> Html {
                 { render_diff }
               </div>
 
+              <div class="block">
+                <div class="block-head">
+                  <div class="block-title">{ "Structural Changes" }</div>
+                  <div class="btnrow">
+                    <span class="btn" style="cursor:default;">
+                      { format!("Changes: {}", diff_changes.len()) }
+                    </span>
+                    <button class="btn" onclick={on_diff_structural}>{ "Structural Diff" }</button>
+                  </div>
+                </div>
+                <div class="textline">
+                  <input
+                    type="text"
+                    value={(*diff_key).clone()}
+                    oninput={{
+                      let diff_key = diff_key.clone();
+                      Callback::from(move |e: InputEvent| {
+                        let v = e.target_unchecked_into::<web_sys::HtmlInputElement>().value();
+                        diff_key.set(v);
+                      })
+                    }}
+                    placeholder="Array key field (e.g. id); blank compares arrays by index"
+                  />
+                </div>
+                <pre class="diff">
+                  {
+                    for diff_changes.iter().map(|c| {
+                      let cls = match c.kind {
+                          jsondiff::Kind::Added => "add",
+                          jsondiff::Kind::Removed => "del",
+                          jsondiff::Kind::Changed | jsondiff::Kind::TypeChanged => "chg",
+                      };
+                      html!{ <span class={cls}>{ format!("{}\n", c.line()) }</span> }
+                    })
+                  }
+                </pre>
+              </div>
+
+              <div class="block">
+                <div class="block-head">
+                  <div class="block-title">{ "Patch" }</div>
+                  <div class="btnrow">
+                    <button class="btn" onclick={on_diff_toggle_patch}>
+                      { if *diff_merge { "Format: Merge Patch (RFC 7386)" } else { "Format: JSON Patch (RFC 6902)" } }
+                    </button>
+                    <button class="btn" onclick={on_diff_apply_patch}>{ "Apply to Left" }</button>
+                    <button class="btn" onclick={on_diff_copy_patch}>{ "Copy" }</button>
+                  </div>
+                </div>
+                <textarea
+                  value={(*diff_patch).clone()}
+                  oninput={{
+                    let diff_patch = diff_patch.clone();
+                    Callback::from(move |e: InputEvent| {
+                      let v = e.target_unchecked_into::<web_sys::HtmlTextAreaElement>().value();
+                      diff_patch.set(v);
+                    })
+                  }}
+                  placeholder="Run Structural Diff, or paste a patch to apply to Left"
+                />
+              </div>
+
               <div class="smallnote">
-                { "JSON mode parses + sorts keys + pretty-prints before diffing. Text mode diffs raw input. Meta toggle hides headers/markers." }
+                { "JSON mode parses + sorts keys + pretty-prints before diffing. Text mode diffs raw input. Meta toggle hides headers/markers. Structural Diff reports changes by JSON Pointer (+ added, - removed, ~ changed, ! type changed)." }
               </div>
             </div>
         },
@@ -1162,8 +1986,8 @@ fn app() -> Html {
         <div class="tabs" role="tablist" aria-label="DevPocket Tabs">
           {
             for [
-              Tab::Json, Tab::Jwt, Tab::Base64, Tab::Url,
-              Tab::Uuid, Tab::Hash, Tab::Diff, Tab::Regex
+              Tab::Json, Tab::Query, Tab::Schema, Tab::Convert, Tab::Jwt, Tab::Base64,
+              Tab::Url, Tab::Uuid, Tab::Hash, Tab::Diff, Tab::Regex
             ].into_iter().map(|t| {
               let is_active = *tab == t;
               let cls = if is_active { "tab active" } else { "tab" };
@@ -0,0 +1,1367 @@
+// src/query.rs
+//
+// The Query tab: JSONPath (`$.store.book[?(@.price < 10)].title`) and a jq
+// subset (`.items[] | select(.qty > 1) | .name`). An expression starting with
+// `$` is JSONPath, anything else jq. Every result carries the path it was
+// found at, unless it was computed (`length`, `map(...)`, a literal, ...).
+// Parse errors give the 1-based column of the offending token.
+
+use std::cmp::Ordering;
+
+use serde_json::{Map, Value};
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum Lang {
+    JsonPath,
+    Jq,
+}
+
+impl Lang {
+    pub fn name(self) -> &'static str {
+        match self {
+            Lang::JsonPath => "JSONPath",
+            Lang::Jq => "jq",
+        }
+    }
+}
+
+pub struct Hit {
+    /// `$.store.book[0]` / `.items[2].name`; `None` for computed values.
+    pub path: Option<String>,
+    pub value: Value,
+}
+
+/// Evaluate `expr` against `input`.
+pub fn run(expr: &str, input: &Value) -> Result<(Lang, Vec<Hit>), String> {
+    let toks = lex(expr)?;
+    let mut p = Parser { toks, i: 0 };
+    if p.peek() == &Tok::Dollar {
+        let path = p.jsonpath()?;
+        let hits = eval_steps(&path, vec![(vec![], input)], input)?
+            .into_iter()
+            .map(|(segs, v)| Hit {
+                path: Some(fmt_path("$", &segs, '\'')),
+                value: v.clone(),
+            })
+            .collect();
+        Ok((Lang::JsonPath, hits))
+    } else {
+        let ast = p.jq()?;
+        let start = Node {
+            path: Some(vec![]),
+            value: input.clone(),
+        };
+        let hits = jq_eval(&ast, start)?
+            .into_iter()
+            .map(|n| Hit {
+                path: n.path.map(|segs| fmt_path("", &segs, '"')),
+                value: n.value,
+            })
+            .collect();
+        Ok((Lang::Jq, hits))
+    }
+}
+
+#[derive(Clone, PartialEq)]
+enum Seg {
+    Key(String),
+    Index(usize),
+}
+
+fn is_ident(s: &str) -> bool {
+    let mut chars = s.chars();
+    matches!(chars.next(), Some(c) if c.is_ascii_alphabetic() || c == '_')
+        && chars.all(|c| c.is_ascii_alphanumeric() || c == '_')
+}
+
+/// `$.a['b c'][0]` (JSONPath) or `.a["b c"][0]` (jq, `.` for the root).
+fn fmt_path(root: &str, segs: &[Seg], quote: char) -> String {
+    let mut s = root.to_string();
+    for seg in segs {
+        match seg {
+            Seg::Key(k) if is_ident(k) => {
+                s.push('.');
+                s.push_str(k);
+            }
+            Seg::Key(k) => {
+                let body = serde_json::to_string(k).unwrap_or_default();
+                let body = &body[1..body.len() - 1];
+                if quote == '\'' {
+                    s.push_str(&format!("['{}']", body.replace('\'', "\\'")));
+                } else {
+                    s.push_str(&format!("[\"{body}\"]"));
+                }
+            }
+            Seg::Index(i) => s.push_str(&format!("[{i}]")),
+        }
+    }
+    if s.is_empty() {
+        ".".to_string()
+    } else {
+        s
+    }
+}
+
+fn type_name(v: &Value) -> &'static str {
+    match v {
+        Value::Null => "null",
+        Value::Bool(_) => "boolean",
+        Value::Number(_) => "number",
+        Value::String(_) => "string",
+        Value::Array(_) => "array",
+        Value::Object(_) => "object",
+    }
+}
+
+fn truthy(v: &Value) -> bool {
+    !matches!(v, Value::Null | Value::Bool(false))
+}
+
+/* ---------- Lexer ---------- */
+
+#[derive(Clone, Debug, PartialEq)]
+enum Tok {
+    Dot,
+    DotDot,
+    LBrack,
+    RBrack,
+    LParen,
+    RParen,
+    Comma,
+    Colon,
+    Pipe,
+    Star,
+    Question,
+    At,
+    Dollar,
+    Op(&'static str),
+    Ident(String),
+    Str(String),
+    Num(f64),
+    End,
+}
+
+fn describe(t: &Tok) -> String {
+    match t {
+        Tok::Dot => "'.'".into(),
+        Tok::DotDot => "'..'".into(),
+        Tok::LBrack => "'['".into(),
+        Tok::RBrack => "']'".into(),
+        Tok::LParen => "'('".into(),
+        Tok::RParen => "')'".into(),
+        Tok::Comma => "','".into(),
+        Tok::Colon => "':'".into(),
+        Tok::Pipe => "'|'".into(),
+        Tok::Star => "'*'".into(),
+        Tok::Question => "'?'".into(),
+        Tok::At => "'@'".into(),
+        Tok::Dollar => "'$'".into(),
+        Tok::Op(o) => format!("'{o}'"),
+        Tok::Ident(s) => format!("'{s}'"),
+        Tok::Str(s) => format!("string {}", Value::from(s.as_str())),
+        Tok::Num(n) => format!("number {n}"),
+        Tok::End => "end of input".into(),
+    }
+}
+
+fn parse_err(col: usize, msg: impl std::fmt::Display) -> String {
+    format!("Parse error at column {col}: {msg}")
+}
+
+/// Tokens with their 1-based column.
+fn lex(src: &str) -> Result<Vec<(Tok, usize)>, String> {
+    let chars: Vec<char> = src.chars().collect();
+    let mut out = vec![];
+    let mut i = 0;
+    while i < chars.len() {
+        let c = chars[i];
+        let col = i + 1;
+        let next = chars.get(i + 1).copied();
+        if c.is_whitespace() {
+            i += 1;
+            continue;
+        }
+        let two: String = chars[i..(i + 2).min(chars.len())].iter().collect();
+        let op2 = ["==", "!=", "<=", ">=", "&&", "||"]
+            .into_iter()
+            .find(|o| *o == two);
+        let (tok, len) = if let Some(o) = op2 {
+            (Tok::Op(o), 2)
+        } else if c == '.' && next == Some('.') {
+            (Tok::DotDot, 2)
+        } else if c == '-' && next.is_some_and(|d| d.is_ascii_digit()) || c.is_ascii_digit() {
+            let start = i;
+            i += 1;
+            while i < chars.len() && (chars[i].is_ascii_digit() || ".eE".contains(chars[i])) {
+                // `.` only as a decimal point, so `.[0:2]`-style slices stay apart
+                if chars[i] == '.' && !chars.get(i + 1).is_some_and(|d| d.is_ascii_digit()) {
+                    break;
+                }
+                if "eE".contains(chars[i]) && chars.get(i + 1).is_some_and(|s| "+-".contains(*s)) {
+                    i += 1;
+                }
+                i += 1;
+            }
+            let text: String = chars[start..i].iter().collect();
+            let n = text
+                .parse::<f64>()
+                .map_err(|_| parse_err(col, format!("bad number '{text}'")))?;
+            out.push((Tok::Num(n), col));
+            continue;
+        } else if c == '"' || c == '\'' {
+            let mut s = String::new();
+            i += 1;
+            loop {
+                match chars.get(i) {
+                    None => return Err(parse_err(col, "unterminated string")),
+                    Some(q) if *q == c => break,
+                    Some('\\') => {
+                        let esc = chars.get(i + 1).copied();
+                        i += 2;
+                        match esc {
+                            Some('n') => s.push('\n'),
+                            Some('t') => s.push('\t'),
+                            Some('r') => s.push('\r'),
+                            Some('u') => {
+                                let hex: String =
+                                    chars.get(i..i + 4).unwrap_or(&[]).iter().collect();
+                                let ch = u32::from_str_radix(&hex, 16)
+                                    .ok()
+                                    .and_then(char::from_u32)
+                                    .ok_or_else(|| parse_err(i - 1, "bad \\u escape"))?;
+                                s.push(ch);
+                                i += 4;
+                            }
+                            Some(other) => s.push(other),
+                            None => return Err(parse_err(col, "unterminated string")),
+                        }
+                        continue;
+                    }
+                    Some(ch) => s.push(*ch),
+                }
+                i += 1;
+            }
+            out.push((Tok::Str(s), col));
+            i += 1;
+            continue;
+        } else if c.is_ascii_alphabetic() || c == '_' {
+            let start = i;
+            while i < chars.len() && (chars[i].is_ascii_alphanumeric() || chars[i] == '_') {
+                i += 1;
+            }
+            out.push((Tok::Ident(chars[start..i].iter().collect()), col));
+            continue;
+        } else {
+            let tok = match c {
+                '.' => Tok::Dot,
+                '[' => Tok::LBrack,
+                ']' => Tok::RBrack,
+                '(' => Tok::LParen,
+                ')' => Tok::RParen,
+                ',' => Tok::Comma,
+                ':' => Tok::Colon,
+                '|' => Tok::Pipe,
+                '*' => Tok::Star,
+                '?' => Tok::Question,
+                '@' => Tok::At,
+                '$' => Tok::Dollar,
+                '<' => Tok::Op("<"),
+                '>' => Tok::Op(">"),
+                '!' => Tok::Op("!"),
+                other => return Err(parse_err(col, format!("unexpected character '{other}'"))),
+            };
+            (tok, 1)
+        };
+        out.push((tok, col));
+        i += len;
+    }
+    out.push((Tok::End, chars.len() + 1));
+    Ok(out)
+}
+
+/* ---------- Parser ---------- */
+
+struct Parser {
+    toks: Vec<(Tok, usize)>,
+    i: usize,
+}
+
+impl Parser {
+    fn peek(&self) -> &Tok {
+        &self.toks[self.i].0
+    }
+
+    fn col(&self) -> usize {
+        self.toks[self.i].1
+    }
+
+    fn bump(&mut self) -> Tok {
+        let t = self.toks[self.i].0.clone();
+        if t != Tok::End {
+            self.i += 1;
+        }
+        t
+    }
+
+    fn eat(&mut self, t: &Tok) -> bool {
+        if self.peek() == t {
+            self.bump();
+            true
+        } else {
+            false
+        }
+    }
+
+    fn unexpected(&self, wanted: &str) -> String {
+        parse_err(
+            self.col(),
+            format!("expected {wanted}, found {}", describe(self.peek())),
+        )
+    }
+
+    fn expect(&mut self, t: Tok, wanted: &str) -> Result<(), String> {
+        if self.eat(&t) {
+            Ok(())
+        } else {
+            Err(self.unexpected(wanted))
+        }
+    }
+
+    fn int(&mut self) -> Result<i64, String> {
+        match self.peek() {
+            Tok::Num(n) if n.fract() == 0.0 => {
+                let n = *n as i64;
+                self.bump();
+                Ok(n)
+            }
+            _ => Err(self.unexpected("an integer")),
+        }
+    }
+
+    fn opt_int(&mut self) -> Result<Option<i64>, String> {
+        match self.peek() {
+            Tok::Num(_) => self.int().map(Some),
+            _ => Ok(None),
+        }
+    }
+}
+
+/* ---------- JSONPath ---------- */
+
+enum Step {
+    Child(Vec<Selector>),
+    Descend(Vec<Selector>),
+}
+
+enum Selector {
+    Name(String),
+    Wild,
+    Index(i64),
+    Slice(Option<i64>, Option<i64>, Option<i64>),
+    Filter(Filter),
+}
+
+enum Filter {
+    /// `@...` (relative) or `$...` (absolute)
+    Path(bool, Vec<Step>),
+    Lit(Value),
+    Not(Box<Filter>),
+    Cmp(Box<Filter>, &'static str, Box<Filter>),
+    And(Box<Filter>, Box<Filter>),
+    Or(Box<Filter>, Box<Filter>),
+}
+
+impl Parser {
+    fn jsonpath(&mut self) -> Result<Vec<Step>, String> {
+        self.expect(Tok::Dollar, "'$'")?;
+        let steps = self.steps()?;
+        if self.peek() != &Tok::End {
+            return Err(self.unexpected("'.', '..', '[' or the end"));
+        }
+        Ok(steps)
+    }
+
+    fn steps(&mut self) -> Result<Vec<Step>, String> {
+        let mut steps = vec![];
+        loop {
+            match self.peek() {
+                Tok::Dot => {
+                    self.bump();
+                    steps.push(Step::Child(vec![self.dot_selector()?]));
+                }
+                Tok::DotDot => {
+                    self.bump();
+                    let sel = if self.peek() == &Tok::LBrack {
+                        self.bracket()?
+                    } else {
+                        vec![self.dot_selector()?]
+                    };
+                    steps.push(Step::Descend(sel));
+                }
+                Tok::LBrack => steps.push(Step::Child(self.bracket()?)),
+                _ => return Ok(steps),
+            }
+        }
+    }
+
+    fn dot_selector(&mut self) -> Result<Selector, String> {
+        let sel = match self.peek() {
+            Tok::Ident(name) => Selector::Name(name.clone()),
+            Tok::Star => Selector::Wild,
+            _ => return Err(self.unexpected("a member name or '*'")),
+        };
+        self.bump();
+        Ok(sel)
+    }
+
+    fn bracket(&mut self) -> Result<Vec<Selector>, String> {
+        self.expect(Tok::LBrack, "'['")?;
+        if self.eat(&Tok::Question) {
+            let f = self.filter_or()?;
+            self.expect(Tok::RBrack, "']' after the filter")?;
+            return Ok(vec![Selector::Filter(f)]);
+        }
+        let mut sels = vec![];
+        loop {
+            let sel = match self.peek().clone() {
+                Tok::Str(s) => {
+                    self.bump();
+                    Selector::Name(s)
+                }
+                Tok::Star => {
+                    self.bump();
+                    Selector::Wild
+                }
+                Tok::Num(_) | Tok::Colon => {
+                    let start = self.opt_int()?;
+                    if self.eat(&Tok::Colon) {
+                        let end = self.opt_int()?;
+                        let step = if self.eat(&Tok::Colon) {
+                            self.opt_int()?
+                        } else {
+                            None
+                        };
+                        Selector::Slice(start, end, step)
+                    } else {
+                        Selector::Index(start.expect("a number was peeked"))
+                    }
+                }
+                _ => return Err(self.unexpected("a quoted name, index, slice, '*' or '?'")),
+            };
+            sels.push(sel);
+            if !self.eat(&Tok::Comma) {
+                break;
+            }
+        }
+        self.expect(Tok::RBrack, "',' or ']'")?;
+        Ok(sels)
+    }
+
+    fn filter_or(&mut self) -> Result<Filter, String> {
+        let mut l = self.filter_and()?;
+        while self.eat(&Tok::Op("||")) {
+            l = Filter::Or(Box::new(l), Box::new(self.filter_and()?));
+        }
+        Ok(l)
+    }
+
+    fn filter_and(&mut self) -> Result<Filter, String> {
+        let mut l = self.filter_unary()?;
+        while self.eat(&Tok::Op("&&")) {
+            l = Filter::And(Box::new(l), Box::new(self.filter_unary()?));
+        }
+        Ok(l)
+    }
+
+    fn filter_unary(&mut self) -> Result<Filter, String> {
+        if self.eat(&Tok::Op("!")) {
+            return Ok(Filter::Not(Box::new(self.filter_unary()?)));
+        }
+        let l = self.filter_primary()?;
+        if let Tok::Op(op @ ("==" | "!=" | "<" | "<=" | ">" | ">=")) = *self.peek() {
+            self.bump();
+            let r = self.filter_primary()?;
+            return Ok(Filter::Cmp(Box::new(l), op, Box::new(r)));
+        }
+        Ok(l)
+    }
+
+    fn filter_primary(&mut self) -> Result<Filter, String> {
+        let col = self.col();
+        Ok(match self.bump() {
+            Tok::LParen => {
+                let f = self.filter_or()?;
+                self.expect(Tok::RParen, "')'")?;
+                f
+            }
+            Tok::At => Filter::Path(false, self.steps()?),
+            Tok::Dollar => Filter::Path(true, self.steps()?),
+            Tok::Str(s) => Filter::Lit(Value::String(s)),
+            Tok::Num(n) => Filter::Lit(num(n)),
+            Tok::Ident(w) => Filter::Lit(match w.as_str() {
+                "true" => Value::Bool(true),
+                "false" => Value::Bool(false),
+                "null" => Value::Null,
+                _ => {
+                    return Err(parse_err(
+                        col,
+                        format!("unknown word '{w}' (use @.{w} for a member)"),
+                    ))
+                }
+            }),
+            t => {
+                return Err(parse_err(
+                    col,
+                    format!("expected @, $, a literal or '(', found {}", describe(&t)),
+                ))
+            }
+        })
+    }
+}
+
+fn num(n: f64) -> Value {
+    if n.fract() == 0.0 && n.abs() < 9e15 {
+        Value::from(n as i64)
+    } else {
+        Value::from(n)
+    }
+}
+
+type Nodes<'v> = Vec<(Vec<Seg>, &'v Value)>;
+
+fn children<'v>(path: &[Seg], v: &'v Value) -> Nodes<'v> {
+    let at = |seg: Seg| {
+        let mut p = path.to_vec();
+        p.push(seg);
+        p
+    };
+    match v {
+        Value::Object(o) => o
+            .iter()
+            .map(|(k, c)| (at(Seg::Key(k.clone())), c))
+            .collect(),
+        Value::Array(a) => a
+            .iter()
+            .enumerate()
+            .map(|(i, c)| (at(Seg::Index(i)), c))
+            .collect(),
+        _ => vec![],
+    }
+}
+
+fn descendants<'v>(path: Vec<Seg>, v: &'v Value, out: &mut Nodes<'v>) {
+    out.push((path.clone(), v));
+    for (p, c) in children(&path, v) {
+        descendants(p, c, out);
+    }
+}
+
+/// RFC 9535 slice bounds.
+fn slice_indices(
+    len: usize,
+    start: Option<i64>,
+    end: Option<i64>,
+    step: Option<i64>,
+) -> Vec<usize> {
+    let len = len as i64;
+    let step = step.unwrap_or(1);
+    let norm = |i: i64| if i < 0 { len + i } else { i };
+    let mut out = vec![];
+    if step > 0 {
+        let lo = norm(start.unwrap_or(0)).clamp(0, len);
+        let hi = norm(end.unwrap_or(len)).clamp(0, len);
+        let mut i = lo;
+        while i < hi {
+            out.push(i as usize);
+            i += step;
+        }
+    } else if step < 0 {
+        let hi = norm(start.unwrap_or(len - 1)).clamp(-1, len - 1);
+        let lo = end.map_or(-1, |e| norm(e).clamp(-1, len - 1));
+        let mut i = hi;
+        while i > lo {
+            out.push(i as usize);
+            i += step;
+        }
+    }
+    out
+}
+
+fn select<'v>(
+    sel: &Selector,
+    path: &[Seg],
+    v: &'v Value,
+    root: &'v Value,
+) -> Result<Nodes<'v>, String> {
+    let at = |seg: Seg| {
+        let mut p = path.to_vec();
+        p.push(seg);
+        p
+    };
+    Ok(match (sel, v) {
+        (Selector::Name(n), Value::Object(o)) => o
+            .get(n)
+            .map(|c| vec![(at(Seg::Key(n.clone())), c)])
+            .unwrap_or_default(),
+        (Selector::Wild, _) => children(path, v),
+        (Selector::Index(i), Value::Array(a)) => {
+            let idx = if *i < 0 { a.len() as i64 + i } else { *i };
+            match usize::try_from(idx)
+                .ok()
+                .and_then(|k| a.get(k).map(|c| (k, c)))
+            {
+                Some((k, c)) => vec![(at(Seg::Index(k)), c)],
+                None => vec![],
+            }
+        }
+        (Selector::Slice(s, e, st), Value::Array(a)) => slice_indices(a.len(), *s, *e, *st)
+            .into_iter()
+            .map(|k| (at(Seg::Index(k)), &a[k]))
+            .collect(),
+        (Selector::Filter(f), _) => {
+            let mut out = vec![];
+            for (p, c) in children(path, v) {
+                if filter_truthy(f, c, root)? {
+                    out.push((p, c));
+                }
+            }
+            out
+        }
+        _ => vec![],
+    })
+}
+
+fn eval_steps<'v>(
+    steps: &[Step],
+    mut nodes: Nodes<'v>,
+    root: &'v Value,
+) -> Result<Nodes<'v>, String> {
+    for step in steps {
+        let (sels, descend) = match step {
+            Step::Child(s) => (s, false),
+            Step::Descend(s) => (s, true),
+        };
+        let mut next = vec![];
+        for (path, v) in nodes {
+            let targets = if descend {
+                let mut all = vec![];
+                descendants(path, v, &mut all);
+                all
+            } else {
+                vec![(path, v)]
+            };
+            for (p, t) in targets {
+                for sel in sels {
+                    next.extend(select(sel, &p, t, root)?);
+                }
+            }
+        }
+        nodes = next;
+    }
+    Ok(nodes)
+}
+
+/// A filter operand: a single value, or nothing (a path matching zero or
+/// several nodes).
+fn operand(f: &Filter, current: &Value, root: &Value) -> Result<Option<Value>, String> {
+    Ok(match f {
+        Filter::Path(absolute, steps) => {
+            let start = if *absolute { root } else { current };
+            let nodes = eval_steps(steps, vec![(vec![], start)], root)?;
+            match nodes.as_slice() {
+                [(_, v)] => Some((*v).clone()),
+                _ => None,
+            }
+        }
+        Filter::Lit(v) => Some(v.clone()),
+        other => Some(Value::Bool(filter_truthy(other, current, root)?)),
+    })
+}
+
+fn filter_truthy(f: &Filter, current: &Value, root: &Value) -> Result<bool, String> {
+    Ok(match f {
+        Filter::Path(absolute, steps) => {
+            let start = if *absolute { root } else { current };
+            !eval_steps(steps, vec![(vec![], start)], root)?.is_empty()
+        }
+        Filter::Lit(v) => truthy(v),
+        Filter::Not(inner) => !filter_truthy(inner, current, root)?,
+        Filter::And(l, r) => filter_truthy(l, current, root)? && filter_truthy(r, current, root)?,
+        Filter::Or(l, r) => filter_truthy(l, current, root)? || filter_truthy(r, current, root)?,
+        Filter::Cmp(l, op, r) => {
+            let (a, b) = (operand(l, current, root)?, operand(r, current, root)?);
+            match (*op, a, b) {
+                ("==", a, b) => a == b,
+                ("!=", a, b) => a != b,
+                (op, Some(a), Some(b)) => {
+                    // ordering only between two numbers or two strings
+                    let ord = match (&a, &b) {
+                        (Value::Number(x), Value::Number(y)) => x.as_f64().partial_cmp(&y.as_f64()),
+                        (Value::String(x), Value::String(y)) => Some(x.cmp(y)),
+                        _ => None,
+                    };
+                    match (op, ord) {
+                        ("<", Some(o)) => o == Ordering::Less,
+                        ("<=", Some(o)) => o != Ordering::Greater,
+                        (">", Some(o)) => o == Ordering::Greater,
+                        (">=", Some(o)) => o != Ordering::Less,
+                        _ => false,
+                    }
+                }
+                _ => false,
+            }
+        }
+    })
+}
+
+/* ---------- jq ---------- */
+
+enum Jq {
+    Identity,
+    Recurse,
+    Lit(Value),
+    Path(Box<Jq>, Vec<(Suffix, bool)>),
+    Pipe(Box<Jq>, Box<Jq>),
+    Comma(Box<Jq>, Box<Jq>),
+    Cmp(Box<Jq>, &'static str, Box<Jq>),
+    And(Box<Jq>, Box<Jq>),
+    Or(Box<Jq>, Box<Jq>),
+    Array(Option<Box<Jq>>),
+    Call(&'static str, Option<Box<Jq>>),
+}
+
+enum Suffix {
+    Field(String),
+    Index(i64),
+    Iter,
+    Slice(Option<i64>, Option<i64>),
+}
+
+/// Name and whether it takes one filter argument.
+const FUNCTIONS: [(&str, bool); 9] = [
+    ("select", true),
+    ("map", true),
+    ("keys", false),
+    ("length", false),
+    ("not", false),
+    ("type", false),
+    ("add", false),
+    ("empty", false),
+    ("to_entries", false),
+];
+
+impl Parser {
+    fn jq(&mut self) -> Result<Jq, String> {
+        let q = self.jq_pipe()?;
+        if self.peek() != &Tok::End {
+            return Err(self.unexpected("'|', ',' or the end"));
+        }
+        Ok(q)
+    }
+
+    fn jq_pipe(&mut self) -> Result<Jq, String> {
+        let mut l = self.jq_comma()?;
+        while self.eat(&Tok::Pipe) {
+            l = Jq::Pipe(Box::new(l), Box::new(self.jq_comma()?));
+        }
+        Ok(l)
+    }
+
+    fn jq_comma(&mut self) -> Result<Jq, String> {
+        let mut l = self.jq_or()?;
+        while self.eat(&Tok::Comma) {
+            l = Jq::Comma(Box::new(l), Box::new(self.jq_or()?));
+        }
+        Ok(l)
+    }
+
+    fn jq_or(&mut self) -> Result<Jq, String> {
+        let mut l = self.jq_and()?;
+        while self.eat(&Tok::Ident("or".into())) {
+            l = Jq::Or(Box::new(l), Box::new(self.jq_and()?));
+        }
+        Ok(l)
+    }
+
+    fn jq_and(&mut self) -> Result<Jq, String> {
+        let mut l = self.jq_cmp()?;
+        while self.eat(&Tok::Ident("and".into())) {
+            l = Jq::And(Box::new(l), Box::new(self.jq_cmp()?));
+        }
+        Ok(l)
+    }
+
+    fn jq_cmp(&mut self) -> Result<Jq, String> {
+        let l = self.jq_postfix()?;
+        if let Tok::Op(op @ ("==" | "!=" | "<" | "<=" | ">" | ">=")) = *self.peek() {
+            self.bump();
+            let r = self.jq_postfix()?;
+            return Ok(Jq::Cmp(Box::new(l), op, Box::new(r)));
+        }
+        Ok(l)
+    }
+
+    fn jq_postfix(&mut self) -> Result<Jq, String> {
+        let (base, mut suffixes) = self.jq_primary()?;
+        loop {
+            match self.peek() {
+                Tok::Dot
+                    if matches!(
+                        self.toks[self.i + 1].0,
+                        Tok::Ident(_) | Tok::Str(_) | Tok::LBrack
+                    ) =>
+                {
+                    self.bump();
+                    if self.peek() != &Tok::LBrack {
+                        suffixes.push((self.jq_field()?, false));
+                    }
+                }
+                Tok::LBrack => suffixes.push((self.jq_bracket()?, false)),
+                Tok::Question => {
+                    self.bump();
+                    match suffixes.last_mut() {
+                        Some(last) => last.1 = true,
+                        None => {
+                            return Err(parse_err(self.col() - 1, "'?' needs a path before it"))
+                        }
+                    }
+                }
+                _ => break,
+            }
+        }
+        Ok(if suffixes.is_empty() {
+            base
+        } else {
+            Jq::Path(Box::new(base), suffixes)
+        })
+    }
+
+    fn jq_field(&mut self) -> Result<Suffix, String> {
+        let field = match self.peek() {
+            Tok::Ident(s) | Tok::Str(s) => Suffix::Field(s.clone()),
+            _ => return Err(self.unexpected("a field name")),
+        };
+        self.bump();
+        Ok(field)
+    }
+
+    fn jq_bracket(&mut self) -> Result<Suffix, String> {
+        self.expect(Tok::LBrack, "'['")?;
+        let suffix = match self.peek().clone() {
+            Tok::RBrack => Suffix::Iter,
+            Tok::Str(s) => {
+                self.bump();
+                Suffix::Field(s)
+            }
+            Tok::Num(_) | Tok::Colon => {
+                let start = self.opt_int()?;
+                if self.eat(&Tok::Colon) {
+                    Suffix::Slice(start, self.opt_int()?)
+                } else {
+                    Suffix::Index(start.expect("a number was peeked"))
+                }
+            }
+            _ => return Err(self.unexpected("']', a string, an index or a slice")),
+        };
+        self.expect(Tok::RBrack, "']'")?;
+        Ok(suffix)
+    }
+
+    /// A term, plus any path suffixes it started with (`.a` in `.a.b`).
+    fn jq_primary(&mut self) -> Result<(Jq, Vec<(Suffix, bool)>), String> {
+        let col = self.col();
+        Ok(match self.bump() {
+            Tok::Dot => match self.peek() {
+                Tok::Ident(_) | Tok::Str(_) => (Jq::Identity, vec![(self.jq_field()?, false)]),
+                _ => (Jq::Identity, vec![]),
+            },
+            Tok::DotDot => (Jq::Recurse, vec![]),
+            Tok::Str(s) => (Jq::Lit(Value::String(s)), vec![]),
+            Tok::Num(n) => (Jq::Lit(num(n)), vec![]),
+            Tok::LParen => {
+                let q = self.jq_pipe()?;
+                self.expect(Tok::RParen, "')'")?;
+                (q, vec![])
+            }
+            Tok::LBrack => {
+                if self.eat(&Tok::RBrack) {
+                    return Ok((Jq::Array(None), vec![]));
+                }
+                let q = self.jq_pipe()?;
+                self.expect(Tok::RBrack, "']'")?;
+                (Jq::Array(Some(Box::new(q))), vec![])
+            }
+            Tok::Ident(w) => match w.as_str() {
+                "true" => (Jq::Lit(Value::Bool(true)), vec![]),
+                "false" => (Jq::Lit(Value::Bool(false)), vec![]),
+                "null" => (Jq::Lit(Value::Null), vec![]),
+                _ => {
+                    let Some(&(name, takes_arg)) = FUNCTIONS.iter().find(|(n, _)| *n == w) else {
+                        let known = FUNCTIONS
+                            .iter()
+                            .map(|(n, _)| *n)
+                            .collect::<Vec<_>>()
+                            .join(", ");
+                        return Err(parse_err(
+                            col,
+                            format!("unknown function '{w}' (supported: {known})"),
+                        ));
+                    };
+                    let arg = if takes_arg {
+                        self.expect(Tok::LParen, &format!("'(' after {name}"))?;
+                        let q = self.jq_pipe()?;
+                        self.expect(Tok::RParen, "')'")?;
+                        Some(Box::new(q))
+                    } else {
+                        None
+                    };
+                    (Jq::Call(name, arg), vec![])
+                }
+            },
+            t => {
+                return Err(parse_err(
+                    col,
+                    format!("expected a filter, found {}", describe(&t)),
+                ))
+            }
+        })
+    }
+}
+
+#[derive(Clone)]
+struct Node {
+    path: Option<Vec<Seg>>,
+    value: Value,
+}
+
+impl Node {
+    fn computed(value: Value) -> Self {
+        Node { path: None, value }
+    }
+
+    fn child(&self, seg: Seg, value: Value) -> Self {
+        Node {
+            path: self.path.as_ref().map(|p| {
+                let mut p = p.clone();
+                p.push(seg);
+                p
+            }),
+            value,
+        }
+    }
+}
+
+fn jq_error(msg: impl std::fmt::Display) -> String {
+    format!("jq error: {msg}")
+}
+
+fn apply_suffix(s: &Suffix, n: &Node) -> Result<Vec<Node>, String> {
+    Ok(match (s, &n.value) {
+        (Suffix::Field(k), Value::Object(o)) => {
+            vec![n.child(
+                Seg::Key(k.clone()),
+                o.get(k).cloned().unwrap_or(Value::Null),
+            )]
+        }
+        (Suffix::Field(k), Value::Null) => vec![n.child(Seg::Key(k.clone()), Value::Null)],
+        (Suffix::Index(i), Value::Array(a)) => {
+            let idx = if *i < 0 { a.len() as i64 + i } else { *i };
+            match usize::try_from(idx) {
+                Ok(k) => vec![n.child(Seg::Index(k), a.get(k).cloned().unwrap_or(Value::Null))],
+                Err(_) => vec![Node::computed(Value::Null)],
+            }
+        }
+        (Suffix::Index(_) | Suffix::Slice(..), Value::Null) => vec![Node::computed(Value::Null)],
+        (Suffix::Iter, Value::Array(a)) => a
+            .iter()
+            .enumerate()
+            .map(|(i, v)| n.child(Seg::Index(i), v.clone()))
+            .collect(),
+        (Suffix::Iter, Value::Object(o)) => o
+            .iter()
+            .map(|(k, v)| n.child(Seg::Key(k.clone()), v.clone()))
+            .collect(),
+        (Suffix::Slice(s, e), Value::Array(a)) => {
+            let idx = slice_indices(a.len(), *s, *e, None);
+            vec![Node::computed(Value::Array(
+                idx.into_iter().map(|k| a[k].clone()).collect(),
+            ))]
+        }
+        (Suffix::Slice(s, e), Value::String(st)) => {
+            let chars: Vec<char> = st.chars().collect();
+            let idx = slice_indices(chars.len(), *s, *e, None);
+            vec![Node::computed(Value::String(
+                idx.into_iter().map(|k| chars[k]).collect(),
+            ))]
+        }
+        (Suffix::Field(k), v) => {
+            return Err(jq_error(format!(
+                "cannot index {} with \"{k}\"",
+                type_name(v)
+            )))
+        }
+        (Suffix::Index(_) | Suffix::Slice(..), v) => {
+            return Err(jq_error(format!(
+                "cannot index {} with a number",
+                type_name(v)
+            )))
+        }
+        (Suffix::Iter, v) => return Err(jq_error(format!("cannot iterate over {}", type_name(v)))),
+    })
+}
+
+/// jq's ordering: null < false < true < numbers < strings < arrays < objects.
+fn jq_cmp(a: &Value, b: &Value) -> Ordering {
+    let rank = |v: &Value| match v {
+        Value::Null => 0,
+        Value::Bool(false) => 1,
+        Value::Bool(true) => 2,
+        Value::Number(_) => 3,
+        Value::String(_) => 4,
+        Value::Array(_) => 5,
+        Value::Object(_) => 6,
+    };
+    match (a, b) {
+        (Value::Number(x), Value::Number(y)) => x
+            .as_f64()
+            .partial_cmp(&y.as_f64())
+            .unwrap_or(Ordering::Equal),
+        (Value::String(x), Value::String(y)) => x.cmp(y),
+        (Value::Array(x), Value::Array(y)) => x
+            .iter()
+            .zip(y)
+            .map(|(p, q)| jq_cmp(p, q))
+            .find(|o| o.is_ne())
+            .unwrap_or(x.len().cmp(&y.len())),
+        (Value::Object(x), Value::Object(y)) => {
+            let (kx, ky) = (x.keys().collect::<Vec<_>>(), y.keys().collect::<Vec<_>>());
+            kx.cmp(&ky).then_with(|| {
+                x.values()
+                    .zip(y.values())
+                    .map(|(p, q)| jq_cmp(p, q))
+                    .find(|o| o.is_ne())
+                    .unwrap_or(Ordering::Equal)
+            })
+        }
+        _ => rank(a).cmp(&rank(b)),
+    }
+}
+
+fn jq_add(items: &[Value]) -> Result<Value, String> {
+    let mut acc = Value::Null;
+    for v in items {
+        acc = match (acc, v) {
+            (Value::Null, v) => v.clone(),
+            (a, Value::Null) => a,
+            (Value::Number(a), Value::Number(b)) => {
+                num(a.as_f64().unwrap_or(0.0) + b.as_f64().unwrap_or(0.0))
+            }
+            (Value::String(a), Value::String(b)) => Value::String(a + b),
+            (Value::Array(mut a), Value::Array(b)) => {
+                a.extend(b.iter().cloned());
+                Value::Array(a)
+            }
+            (Value::Object(mut a), Value::Object(b)) => {
+                a.extend(b.iter().map(|(k, v)| (k.clone(), v.clone())));
+                Value::Object(a)
+            }
+            (a, b) => {
+                return Err(jq_error(format!(
+                    "{} and {} cannot be added",
+                    type_name(&a),
+                    type_name(b)
+                )))
+            }
+        };
+    }
+    Ok(acc)
+}
+
+fn jq_eval(q: &Jq, input: Node) -> Result<Vec<Node>, String> {
+    Ok(match q {
+        Jq::Identity => vec![input],
+        Jq::Recurse => {
+            let mut out = vec![];
+            let mut stack = vec![input];
+            while let Some(n) = stack.pop() {
+                let kids = match &n.value {
+                    Value::Array(_) | Value::Object(_) => apply_suffix(&Suffix::Iter, &n)?,
+                    _ => vec![],
+                };
+                out.push(n);
+                stack.extend(kids.into_iter().rev());
+            }
+            out
+        }
+        Jq::Lit(v) => vec![Node::computed(v.clone())],
+        Jq::Path(base, suffixes) => {
+            let mut nodes = jq_eval(base, input)?;
+            for (s, optional) in suffixes {
+                let mut next = vec![];
+                for n in &nodes {
+                    match apply_suffix(s, n) {
+                        Ok(v) => next.extend(v),
+                        Err(_) if *optional => {}
+                        Err(e) => return Err(e),
+                    }
+                }
+                nodes = next;
+            }
+            nodes
+        }
+        Jq::Pipe(l, r) => {
+            let mut out = vec![];
+            for n in jq_eval(l, input)? {
+                out.extend(jq_eval(r, n)?);
+            }
+            out
+        }
+        Jq::Comma(l, r) => {
+            let mut out = jq_eval(l, input.clone())?;
+            out.extend(jq_eval(r, input)?);
+            out
+        }
+        Jq::Cmp(l, op, r) => {
+            let mut out = vec![];
+            for b in jq_eval(r, input.clone())? {
+                for a in jq_eval(l, input.clone())? {
+                    let o = jq_cmp(&a.value, &b.value);
+                    let res = match *op {
+                        "==" => o.is_eq(),
+                        "!=" => o.is_ne(),
+                        "<" => o.is_lt(),
+                        "<=" => o.is_le(),
+                        ">" => o.is_gt(),
+                        _ => o.is_ge(),
+                    };
+                    out.push(Node::computed(Value::Bool(res)));
+                }
+            }
+            out
+        }
+        Jq::And(l, r) | Jq::Or(l, r) => {
+            let is_and = matches!(q, Jq::And(..));
+            let mut out = vec![];
+            for a in jq_eval(l, input.clone())? {
+                let a = truthy(&a.value);
+                if a != is_and {
+                    // false for `and`, true for `or`: decided without the right side
+                    out.push(Node::computed(Value::Bool(a)));
+                    continue;
+                }
+                for b in jq_eval(r, input.clone())? {
+                    out.push(Node::computed(Value::Bool(truthy(&b.value))));
+                }
+            }
+            out
+        }
+        Jq::Array(None) => vec![Node::computed(Value::Array(vec![]))],
+        Jq::Array(Some(inner)) => {
+            let items = jq_eval(inner, input)?
+                .into_iter()
+                .map(|n| n.value)
+                .collect();
+            vec![Node::computed(Value::Array(items))]
+        }
+        Jq::Call(name, arg) => call(name, arg.as_deref(), input)?,
+    })
+}
+
+fn call(name: &str, arg: Option<&Jq>, input: Node) -> Result<Vec<Node>, String> {
+    let v = &input.value;
+    let one = |v: Value| Ok(vec![Node::computed(v)]);
+    match (name, arg) {
+        ("select", Some(f)) => {
+            let keep = jq_eval(f, input.clone())?
+                .iter()
+                .filter(|r| truthy(&r.value))
+                .count();
+            Ok(vec![input; keep])
+        }
+        ("map", Some(f)) => {
+            let mut items = vec![];
+            for n in apply_suffix(&Suffix::Iter, &input)? {
+                items.extend(jq_eval(f, n)?.into_iter().map(|r| r.value));
+            }
+            one(Value::Array(items))
+        }
+        ("keys", _) => match v {
+            Value::Object(o) => one(Value::Array(
+                o.keys().map(|k| Value::from(k.as_str())).collect(),
+            )),
+            Value::Array(a) => one(Value::Array((0..a.len()).map(Value::from).collect())),
+            other => Err(jq_error(format!("{} has no keys", type_name(other)))),
+        },
+        ("length", _) => match v {
+            Value::Null => one(Value::from(0)),
+            Value::Number(n) => one(num(n.as_f64().unwrap_or(0.0).abs())),
+            Value::String(s) => one(Value::from(s.chars().count())),
+            Value::Array(a) => one(Value::from(a.len())),
+            Value::Object(o) => one(Value::from(o.len())),
+            Value::Bool(_) => Err(jq_error("boolean has no length")),
+        },
+        ("not", _) => one(Value::Bool(!truthy(v))),
+        ("type", _) => one(Value::from(type_name(v))),
+        ("add", _) => match v {
+            Value::Array(a) => one(jq_add(a)?),
+            Value::Object(o) => one(jq_add(&o.values().cloned().collect::<Vec<_>>())?),
+            other => Err(jq_error(format!(
+                "cannot add up the values of {}",
+                type_name(other)
+            ))),
+        },
+        ("empty", _) => Ok(vec![]),
+        ("to_entries", _) => match v {
+            Value::Object(o) => one(Value::Array(
+                o.iter()
+                    .map(|(k, v)| {
+                        let mut e = Map::new();
+                        e.insert("key".into(), Value::from(k.as_str()));
+                        e.insert("value".into(), v.clone());
+                        Value::Object(e)
+                    })
+                    .collect(),
+            )),
+            other => Err(jq_error(format!("{} has no entries", type_name(other)))),
+        },
+        _ => Err(jq_error(format!("{name} is not callable like this"))),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn store() -> Value {
+        json!({"store": {
+            "book": [
+                {"category": "reference", "author": "Nigel Rees", "title": "Sayings of the Century", "price": 8.95},
+                {"category": "fiction", "author": "Evelyn Waugh", "title": "Sword of Honour", "price": 12.99},
+                {"category": "fiction", "author": "Herman Melville", "title": "Moby Dick", "isbn": "0-553-21311-3", "price": 8.99},
+                {"category": "fiction", "author": "J. R. R. Tolkien", "title": "The Lord of the Rings", "isbn": "0-395-19395-8", "price": 22.99}
+            ],
+            "bicycle": {"color": "red", "price": 19.95}
+        }})
+    }
+
+    fn values(expr: &str) -> Vec<Value> {
+        let (_, hits) = run(expr, &store()).unwrap_or_else(|e| panic!("{expr}: {e}"));
+        hits.into_iter().map(|h| h.value).collect()
+    }
+
+    fn paths(expr: &str) -> Vec<Option<String>> {
+        let (_, hits) = run(expr, &store()).unwrap();
+        hits.into_iter().map(|h| h.path).collect()
+    }
+
+    #[test]
+    fn jsonpath_filters_and_paths() {
+        assert_eq!(
+            values("$.store.book[?(@.price < 10)].title"),
+            [json!("Sayings of the Century"), json!("Moby Dick")]
+        );
+        assert_eq!(
+            values("$.store.book[?(@.isbn && @.price > 20 || @.author == 'Nigel Rees')].price"),
+            [json!(8.95), json!(22.99)]
+        );
+        assert_eq!(values("$.store.book[?(!@.isbn)]").len(), 2);
+        assert_eq!(
+            paths("$.store.book[?(@.category == 'reference')].author"),
+            [Some("$.store.book[0].author".to_string())]
+        );
+        assert_eq!(run("$.store", &store()).unwrap().0.name(), "JSONPath");
+    }
+
+    #[test]
+    fn jsonpath_descent_slices_and_unions() {
+        assert_eq!(values("$..author").len(), 4);
+        // object members in document order: book before bicycle
+        assert_eq!(
+            values("$..price"),
+            [
+                json!(8.95),
+                json!(12.99),
+                json!(8.99),
+                json!(22.99),
+                json!(19.95)
+            ]
+        );
+        assert_eq!(values("$.store.*").len(), 2);
+        assert_eq!(
+            values("$..book[-1:].title"),
+            [json!("The Lord of the Rings")]
+        );
+        assert_eq!(values("$..book[0:4:2].price"), [json!(8.95), json!(8.99)]);
+        assert_eq!(values("$..book[:2].price"), [json!(8.95), json!(12.99)]);
+        assert_eq!(
+            paths("$.store.book[0,2]['title']"),
+            [
+                Some("$.store.book[0].title".to_string()),
+                Some("$.store.book[2].title".to_string())
+            ]
+        );
+        assert!(values("$.store.book[9]").is_empty());
+    }
+
+    #[test]
+    fn jq_pipes_and_builtins() {
+        assert_eq!(
+            values(".store.book[] | select(.price > 10) | .title"),
+            [json!("Sword of Honour"), json!("The Lord of the Rings")]
+        );
+        assert_eq!(
+            paths(".store.book[] | select(.price > 20) | .title"),
+            [Some(".store.book[3].title".to_string())]
+        );
+        assert_eq!(
+            values(".store.book | map(.price) | add"),
+            [json!(8.95 + 12.99 + 8.99 + 22.99)]
+        );
+        assert_eq!(values("[.store.book[].category] | length"), [json!(4)]);
+        assert_eq!(values(".store.bicycle | keys"), [json!(["color", "price"])]);
+        assert_eq!(
+            values(".store.bicycle.color, .store.bicycle.price"),
+            [json!("red"), json!(19.95)]
+        );
+        assert_eq!(values(".store.book[0].isbn"), [Value::Null]);
+        assert_eq!(values(".store.bicycle.color[]?"), Vec::<Value>::new());
+        assert_eq!(
+            values(".store.book[] | select(.isbn and .price < 10) | .author"),
+            [json!("Herman Melville")]
+        );
+        assert_eq!(
+            values(".store.bicycle | to_entries | map(.key)"),
+            [json!(["color", "price"])]
+        );
+        assert_eq!(values(".store.book[0].price | type"), [json!("number")]);
+        assert_eq!(paths(".store.book | length"), [None]);
+        assert_eq!(run(".", &store()).unwrap().0.name(), "jq");
+    }
+
+    #[test]
+    fn errors_name_the_column() {
+        let err = |expr: &str| run(expr, &store()).err().unwrap_or_default();
+        assert!(
+            err("$.store.book[?(@.price <)]").starts_with("Parse error at column 25"),
+            "{}",
+            err("$.store.book[?(@.price <)]")
+        );
+        assert!(
+            err(".a | #").starts_with("Parse error at column 6"),
+            "{}",
+            err(".a | #")
+        );
+        assert!(
+            err(".a[\"x").starts_with("Parse error at column 4"),
+            "{}",
+            err(".a[\"x")
+        );
+        assert!(err(".store | nosuchfn").contains("nosuchfn"));
+        assert!(
+            err(".store.bicycle.color[]").contains("string"),
+            "{}",
+            err(".store.bicycle.color[]")
+        );
+    }
+}
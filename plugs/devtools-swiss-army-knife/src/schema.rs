@@ -0,0 +1,642 @@
+// src/schema.rs
+//
+// The Schema tab: validate a document against a JSON Schema (draft 2020-12
+// core and validation keywords, `$ref` within the schema) reporting every
+// violation rather than the first, and infer a schema from sample documents.
+//
+// Covered: type, enum, const, required, properties, patternProperties,
+// additionalProperties, items, prefixItems, min/maxItems, uniqueItems,
+// min/maxLength, pattern, minimum, maximum, exclusiveMinimum/Maximum,
+// multipleOf, min/maxProperties, allOf, anyOf, oneOf, not, $ref, $defs.
+// `format` and other annotations are accepted and ignored.
+
+use std::collections::{BTreeMap, BTreeSet};
+
+use regex::Regex;
+use serde_json::{Map, Value};
+
+pub const DRAFT: &str = "https://json-schema.org/draft/2020-12/schema";
+
+/// `$ref` hops at one place in the document before giving up. The count
+/// starts over at each child value, so recursive schemas nest freely.
+const MAX_DEPTH: usize = 64;
+
+pub struct Violation {
+    /// JSON Pointer into the document (empty for the whole document).
+    pub instance_path: String,
+    /// Where the failing keyword sits, `#/properties/age/minimum`.
+    pub schema_path: String,
+    pub message: String,
+}
+
+/// Every violation of `schema` by `doc`; a value's own violations come
+/// before those of its children.
+pub fn validate(schema: &Value, doc: &Value) -> Vec<Violation> {
+    let mut v = Validator {
+        root: schema,
+        out: vec![],
+    };
+    v.check(schema, doc, "", "#", 0);
+    v.out
+}
+
+struct Validator<'s> {
+    root: &'s Value,
+    out: Vec<Violation>,
+}
+
+fn escape(token: &str) -> String {
+    token.replace('~', "~0").replace('/', "~1")
+}
+
+fn type_of(v: &Value) -> &'static str {
+    match v {
+        Value::Null => "null",
+        Value::Bool(_) => "boolean",
+        Value::Number(n) if n.is_i64() || n.is_u64() => "integer",
+        Value::Number(n) if n.as_f64().is_some_and(|f| f.fract() == 0.0) => "integer",
+        Value::Number(_) => "number",
+        Value::String(_) => "string",
+        Value::Array(_) => "array",
+        Value::Object(_) => "object",
+    }
+}
+
+fn is_type(v: &Value, t: &str) -> bool {
+    let actual = type_of(v);
+    actual == t || (t == "number" && actual == "integer")
+}
+
+/// 1 and 1.0 are the same number to JSON Schema.
+fn json_eq(a: &Value, b: &Value) -> bool {
+    match (a, b) {
+        (Value::Number(x), Value::Number(y)) => x.as_f64() == y.as_f64(),
+        (Value::Array(x), Value::Array(y)) => {
+            x.len() == y.len() && x.iter().zip(y).all(|(p, q)| json_eq(p, q))
+        }
+        (Value::Object(x), Value::Object(y)) => {
+            x.len() == y.len()
+                && x.iter()
+                    .all(|(k, p)| y.get(k).is_some_and(|q| json_eq(p, q)))
+        }
+        _ => a == b,
+    }
+}
+
+fn short(v: &Value) -> String {
+    let s = v.to_string();
+    if s.chars().count() <= 60 {
+        s
+    } else {
+        format!("{}…", s.chars().take(60).collect::<String>())
+    }
+}
+
+impl<'s> Validator<'s> {
+    fn fail(&mut self, inst: &str, schema_path: String, message: String) {
+        self.out.push(Violation {
+            instance_path: inst.to_string(),
+            schema_path,
+            message,
+        });
+    }
+
+    /// Whether `doc` passes `schema`, without recording anything.
+    fn passes(&self, schema: &'s Value, doc: &Value, depth: usize) -> bool {
+        let mut probe = Validator {
+            root: self.root,
+            out: vec![],
+        };
+        probe.check(schema, doc, "", "#", depth);
+        probe.out.is_empty()
+    }
+
+    fn resolve(&self, reference: &str) -> Option<&'s Value> {
+        let pointer = reference.strip_prefix('#')?;
+        if pointer.is_empty() {
+            return Some(self.root);
+        }
+        self.root.pointer(pointer)
+    }
+
+    fn check(&mut self, schema: &'s Value, doc: &Value, inst: &str, at: &str, depth: usize) {
+        let s = match schema {
+            Value::Bool(true) => return,
+            Value::Bool(false) => {
+                return self.fail(
+                    inst,
+                    at.to_string(),
+                    "no value is allowed here (schema is false)".into(),
+                )
+            }
+            Value::Object(s) => s,
+            _ => {
+                return self.fail(
+                    inst,
+                    at.to_string(),
+                    "invalid schema: must be an object or boolean".into(),
+                )
+            }
+        };
+        let kw = |k: &str| format!("{at}/{k}");
+
+        if let Some(Value::String(r)) = s.get("$ref") {
+            match self.resolve(r) {
+                _ if depth >= MAX_DEPTH => self.fail(
+                    inst,
+                    kw("$ref"),
+                    format!("$ref {r} nests too deeply (a cycle?)"),
+                ),
+                Some(target) => self.check(target, doc, inst, r, depth + 1),
+                None => self.fail(
+                    inst,
+                    kw("$ref"),
+                    format!("unresolvable $ref {r} (only refs within this schema are supported)"),
+                ),
+            }
+        }
+
+        if let Some(t) = s.get("type") {
+            let allowed = match t {
+                Value::String(t) => vec![t.as_str()],
+                Value::Array(ts) => ts.iter().filter_map(Value::as_str).collect(),
+                _ => vec![],
+            };
+            if !allowed.is_empty() && !allowed.iter().any(|t| is_type(doc, t)) {
+                self.fail(
+                    inst,
+                    kw("type"),
+                    format!("expected {}, got {}", allowed.join(" or "), type_of(doc)),
+                );
+            }
+        }
+        if let Some(Value::Array(options)) = s.get("enum") {
+            if !options.iter().any(|o| json_eq(o, doc)) {
+                let list = options.iter().map(short).collect::<Vec<_>>().join(", ");
+                self.fail(
+                    inst,
+                    kw("enum"),
+                    format!("{} is not one of {list}", short(doc)),
+                );
+            }
+        }
+        if let Some(c) = s.get("const") {
+            if !json_eq(c, doc) {
+                self.fail(inst, kw("const"), format!("must equal {}", short(c)));
+            }
+        }
+
+        match doc {
+            Value::Number(n) => self.number(s, n.as_f64().unwrap_or(0.0), inst, at),
+            Value::String(st) => self.string(s, st, inst, at),
+            Value::Array(a) => self.array(s, a, inst, at),
+            Value::Object(o) => self.object(s, o, inst, at),
+            _ => {}
+        }
+
+        if let Some(Value::Array(subs)) = s.get("allOf") {
+            for (i, sub) in subs.iter().enumerate() {
+                self.check(sub, doc, inst, &format!("{at}/allOf/{i}"), depth);
+            }
+        }
+        if let Some(Value::Array(subs)) = s.get("anyOf") {
+            if !subs.iter().any(|sub| self.passes(sub, doc, depth)) {
+                self.fail(
+                    inst,
+                    kw("anyOf"),
+                    format!("matches none of the {} anyOf schemas", subs.len()),
+                );
+            }
+        }
+        if let Some(Value::Array(subs)) = s.get("oneOf") {
+            let matched = subs
+                .iter()
+                .filter(|sub| self.passes(sub, doc, depth))
+                .count();
+            if matched != 1 {
+                self.fail(
+                    inst,
+                    kw("oneOf"),
+                    format!("matches {matched} of the oneOf schemas, expected exactly 1"),
+                );
+            }
+        }
+        if let Some(sub) = s.get("not") {
+            if self.passes(sub, doc, depth) {
+                self.fail(inst, kw("not"), "must not match the \"not\" schema".into());
+            }
+        }
+    }
+
+    fn number(&mut self, s: &Map<String, Value>, n: f64, inst: &str, at: &str) {
+        let bound = |k: &str| s.get(k).and_then(Value::as_f64);
+        type Check = (&'static str, fn(f64, f64) -> bool, &'static str);
+        let checks: [Check; 4] = [
+            ("minimum", |n, b| n >= b, ">="),
+            ("maximum", |n, b| n <= b, "<="),
+            ("exclusiveMinimum", |n, b| n > b, ">"),
+            ("exclusiveMaximum", |n, b| n < b, "<"),
+        ];
+        for (k, ok, op) in checks {
+            if let Some(b) = bound(k) {
+                if !ok(n, b) {
+                    self.fail(inst, format!("{at}/{k}"), format!("{n} must be {op} {b}"));
+                }
+            }
+        }
+        if let Some(m) = bound("multipleOf").filter(|m| *m > 0.0) {
+            let q = n / m;
+            if (q - q.round()).abs() > 1e-9 {
+                self.fail(
+                    inst,
+                    format!("{at}/multipleOf"),
+                    format!("{n} is not a multiple of {m}"),
+                );
+            }
+        }
+    }
+
+    fn string(&mut self, s: &Map<String, Value>, st: &str, inst: &str, at: &str) {
+        let len = st.chars().count() as u64;
+        if let Some(min) = s.get("minLength").and_then(Value::as_u64) {
+            if len < min {
+                self.fail(
+                    inst,
+                    format!("{at}/minLength"),
+                    format!("length {len} is shorter than {min}"),
+                );
+            }
+        }
+        if let Some(max) = s.get("maxLength").and_then(Value::as_u64) {
+            if len > max {
+                self.fail(
+                    inst,
+                    format!("{at}/maxLength"),
+                    format!("length {len} is longer than {max}"),
+                );
+            }
+        }
+        if let Some(Value::String(p)) = s.get("pattern") {
+            match Regex::new(p) {
+                Ok(re) if !re.is_match(st) => self.fail(
+                    inst,
+                    format!("{at}/pattern"),
+                    format!("{} doesn't match /{p}/", short(&Value::from(st))),
+                ),
+                Ok(_) => {}
+                Err(e) => self.fail(
+                    inst,
+                    format!("{at}/pattern"),
+                    format!("invalid schema pattern: {e}"),
+                ),
+            }
+        }
+    }
+
+    fn array(&mut self, s: &'s Map<String, Value>, a: &[Value], inst: &str, at: &str) {
+        let len = a.len() as u64;
+        if let Some(min) = s.get("minItems").and_then(Value::as_u64) {
+            if len < min {
+                self.fail(
+                    inst,
+                    format!("{at}/minItems"),
+                    format!("{len} item(s), at least {min} required"),
+                );
+            }
+        }
+        if let Some(max) = s.get("maxItems").and_then(Value::as_u64) {
+            if len > max {
+                self.fail(
+                    inst,
+                    format!("{at}/maxItems"),
+                    format!("{len} item(s), at most {max} allowed"),
+                );
+            }
+        }
+        if s.get("uniqueItems") == Some(&Value::Bool(true)) {
+            for j in 1..a.len() {
+                if let Some(i) = (0..j).find(|i| json_eq(&a[*i], &a[j])) {
+                    self.fail(
+                        &format!("{inst}/{j}"),
+                        format!("{at}/uniqueItems"),
+                        format!("duplicate of item {i}"),
+                    );
+                }
+            }
+        }
+        let prefix = match s.get("prefixItems") {
+            Some(Value::Array(p)) => p.as_slice(),
+            _ => &[],
+        };
+        for (i, item) in a.iter().enumerate() {
+            let here = format!("{inst}/{i}");
+            if let Some(sub) = prefix.get(i) {
+                self.check(sub, item, &here, &format!("{at}/prefixItems/{i}"), 0);
+            } else if let Some(sub) = s.get("items") {
+                self.check(sub, item, &here, &format!("{at}/items"), 0);
+            }
+        }
+    }
+
+    fn object(&mut self, s: &'s Map<String, Value>, o: &Map<String, Value>, inst: &str, at: &str) {
+        if let Some(Value::Array(req)) = s.get("required") {
+            for k in req.iter().filter_map(Value::as_str) {
+                if !o.contains_key(k) {
+                    self.fail(
+                        inst,
+                        format!("{at}/required"),
+                        format!("missing required property \"{k}\""),
+                    );
+                }
+            }
+        }
+        let count = o.len() as u64;
+        if let Some(min) = s.get("minProperties").and_then(Value::as_u64) {
+            if count < min {
+                self.fail(
+                    inst,
+                    format!("{at}/minProperties"),
+                    format!("{count} properties, at least {min} required"),
+                );
+            }
+        }
+        if let Some(max) = s.get("maxProperties").and_then(Value::as_u64) {
+            if count > max {
+                self.fail(
+                    inst,
+                    format!("{at}/maxProperties"),
+                    format!("{count} properties, at most {max} allowed"),
+                );
+            }
+        }
+
+        let props = s.get("properties").and_then(Value::as_object);
+        let patterns = s
+            .get("patternProperties")
+            .and_then(Value::as_object)
+            .map(|pp| {
+                pp.iter()
+                    .map(|(p, sub)| (p, Regex::new(p), sub))
+                    .collect::<Vec<_>>()
+            })
+            .unwrap_or_default();
+        for (k, v) in o {
+            let here = format!("{inst}/{}", escape(k));
+            let mut covered = false;
+            if let Some(sub) = props.and_then(|p| p.get(k)) {
+                covered = true;
+                self.check(sub, v, &here, &format!("{at}/properties/{}", escape(k)), 0);
+            }
+            for (p, re, sub) in &patterns {
+                match re {
+                    Ok(re) if re.is_match(k) => {
+                        covered = true;
+                        self.check(
+                            sub,
+                            v,
+                            &here,
+                            &format!("{at}/patternProperties/{}", escape(p)),
+                            0,
+                        );
+                    }
+                    Ok(_) => {}
+                    Err(e) => self.fail(
+                        inst,
+                        format!("{at}/patternProperties/{}", escape(p)),
+                        format!("invalid schema pattern: {e}"),
+                    ),
+                }
+            }
+            if !covered {
+                match s.get("additionalProperties") {
+                    Some(Value::Bool(false)) => self.fail(
+                        &here,
+                        format!("{at}/additionalProperties"),
+                        format!("property \"{k}\" is not allowed"),
+                    ),
+                    Some(sub) => {
+                        self.check(sub, v, &here, &format!("{at}/additionalProperties"), 0)
+                    }
+                    None => {}
+                }
+            }
+        }
+    }
+}
+
+/* ---------- Inference ---------- */
+
+#[derive(Default)]
+struct Shape {
+    types: BTreeSet<&'static str>,
+    /// Values seen as objects, to tell required properties from optional ones.
+    objects: usize,
+    props: BTreeMap<String, (usize, Shape)>,
+    items: Option<Box<Shape>>,
+}
+
+impl Shape {
+    fn add(&mut self, v: &Value) {
+        self.types.insert(type_of(v));
+        match v {
+            Value::Object(o) => {
+                self.objects += 1;
+                for (k, child) in o {
+                    let (seen, shape) = self.props.entry(k.clone()).or_default();
+                    *seen += 1;
+                    shape.add(child);
+                }
+            }
+            Value::Array(a) => {
+                let items = self.items.get_or_insert_with(Box::default);
+                for child in a {
+                    items.add(child);
+                }
+            }
+            _ => {}
+        }
+    }
+
+    fn schema(&self) -> Value {
+        let mut out = Map::new();
+        let mut types = self.types.clone();
+        if types.contains("number") {
+            types.remove("integer");
+        }
+        match types.len() {
+            0 => {}
+            1 => {
+                out.insert(
+                    "type".into(),
+                    Value::from(*types.iter().next().expect("one type")),
+                );
+            }
+            _ => {
+                out.insert(
+                    "type".into(),
+                    types.iter().map(|t| Value::from(*t)).collect(),
+                );
+            }
+        }
+        if self.objects > 0 {
+            let props = self
+                .props
+                .iter()
+                .map(|(k, (_, shape))| (k.clone(), shape.schema()))
+                .collect::<Map<_, _>>();
+            let required = self
+                .props
+                .iter()
+                .filter(|(_, (seen, _))| *seen == self.objects)
+                .map(|(k, _)| Value::from(k.as_str()))
+                .collect::<Vec<_>>();
+            out.insert("properties".into(), Value::Object(props));
+            if !required.is_empty() {
+                out.insert("required".into(), Value::Array(required));
+            }
+        }
+        if let Some(items) = self.items.as_ref().filter(|i| !i.types.is_empty()) {
+            out.insert("items".into(), items.schema());
+        }
+        Value::Object(out)
+    }
+}
+
+/// A schema every sample satisfies: types are unioned, and a property is
+/// required only when every object at that spot has it.
+pub fn infer(samples: &[Value]) -> Value {
+    let mut shape = Shape::default();
+    for s in samples {
+        shape.add(s);
+    }
+    let mut schema = Map::new();
+    schema.insert("$schema".into(), Value::from(DRAFT));
+    if let Value::Object(body) = shape.schema() {
+        schema.extend(body);
+    }
+    Value::Object(schema)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    /// `(instance_path, schema_path)` of every violation.
+    fn report(schema: Value, doc: Value) -> Vec<(String, String)> {
+        validate(&schema, &doc)
+            .into_iter()
+            .map(|v| (v.instance_path, v.schema_path))
+            .collect()
+    }
+
+    fn pair(inst: &str, at: &str) -> (String, String) {
+        (inst.to_string(), at.to_string())
+    }
+
+    #[test]
+    fn reports_every_violation_with_paths() {
+        let schema = json!({
+            "type": "object",
+            "required": ["id", "name"],
+            "properties": {
+                "id": {"type": "integer", "minimum": 1},
+                "name": {"type": "string", "pattern": "^[A-Z]"},
+                "role": {"enum": ["admin", "user"]},
+                "tags": {"type": "array", "items": {"type": "string"}, "uniqueItems": true}
+            },
+            "additionalProperties": false
+        });
+        let doc = json!({"id": 0, "role": "root", "tags": ["a", 2, "a"], "x/y": 1});
+        assert_eq!(
+            report(schema.clone(), doc),
+            [
+                pair("", "#/required"),
+                pair("/id", "#/properties/id/minimum"),
+                pair("/role", "#/properties/role/enum"),
+                pair("/tags/2", "#/properties/tags/uniqueItems"),
+                pair("/tags/1", "#/properties/tags/items/type"),
+                pair("/x~1y", "#/additionalProperties"),
+            ]
+        );
+        let ok = json!({"id": 3, "name": "Ann", "tags": ["a", "b"]});
+        assert!(validate(&schema, &ok).is_empty());
+        assert_eq!(
+            report(schema, json!({"id": 1.5, "name": "ann"})),
+            [
+                pair("/id", "#/properties/id/type"),
+                pair("/name", "#/properties/name/pattern"),
+            ]
+        );
+        let msgs = validate(&json!({"type": "string"}), &json!(4));
+        assert_eq!(msgs[0].message, "expected string, got integer");
+    }
+
+    #[test]
+    fn refs_and_combinators() {
+        let schema = json!({
+            "$defs": {"pos": {"type": "number", "exclusiveMinimum": 0}},
+            "type": "array",
+            "prefixItems": [{"$ref": "#/$defs/pos"}, {"anyOf": [{"type": "string"}, {"type": "null"}]}],
+            "items": {"oneOf": [{"type": "integer", "maximum": 5}, {"minimum": 3}]}
+        });
+        assert!(validate(&schema, &json!([1, null, 2, 9.5])).is_empty());
+        assert_eq!(
+            report(schema, json!([0, 1, 2.25, 4])),
+            [
+                pair("/0", "#/$defs/pos/exclusiveMinimum"),
+                pair("/1", "#/prefixItems/1/anyOf"),
+                pair("/2", "#/items/oneOf"),
+                pair("/3", "#/items/oneOf"),
+            ]
+        );
+        assert_eq!(
+            report(json!({"$ref": "#/$defs/missing"}), json!(1)),
+            [pair("", "#/$ref")]
+        );
+        assert_eq!(
+            report(
+                json!({"$defs": {"a": {"$ref": "#/$defs/a"}}, "$ref": "#/$defs/a"}),
+                json!(1)
+            ),
+            [pair("", "#/$defs/a/$ref")]
+        );
+    }
+
+    #[test]
+    fn recursive_schema_validates_deep_documents() {
+        let schema = json!({
+            "$defs": {"n": {"properties": {"next": {"$ref": "#/$defs/n"}, "v": {"type": "integer"}}}},
+            "$ref": "#/$defs/n"
+        });
+        let mut doc = json!({"v": 0});
+        for i in 1..=100 {
+            doc = json!({"v": i, "next": doc});
+        }
+        assert!(validate(&schema, &doc).is_empty());
+
+        let mut bad = json!({"v": "deep"});
+        for i in 1..=100 {
+            bad = json!({"v": i, "next": bad});
+        }
+        let out = report(schema, bad);
+        assert_eq!(out.len(), 1);
+        assert_eq!(out[0].0, "/next".repeat(100) + "/v");
+    }
+
+    #[test]
+    fn inferred_schema_accepts_its_samples() {
+        let samples = [
+            json!({"id": 1, "name": "a", "tags": ["x"], "score": 1.5}),
+            json!({"id": 2, "name": null, "tags": [], "extra": true}),
+        ];
+        let schema = infer(&samples);
+        assert_eq!(schema["$schema"], DRAFT);
+        assert_eq!(schema["required"], json!(["id", "name", "tags"]));
+        for s in &samples {
+            assert!(validate(&schema, s).is_empty(), "{schema}");
+        }
+        assert!(!validate(&schema, &json!({"id": "1", "name": "a", "tags": []})).is_empty());
+        assert!(!validate(&schema, &json!({"id": 1, "tags": []})).is_empty());
+    }
+}
@@ -0,0 +1,153 @@
+#!/usr/bin/env python3
+"""Local log source for trying LogLens live tail (stdlib only).
+
+    python3 fixtures/live_server.py [port]     # default 8765
+
+  ws://localhost:8765/ws          WebSocket, one entry per message
+  http://localhost:8765/sse       Server-Sent Events
+  http://localhost:8765/poll      HTTP poll: {"lines": [...], "cursor": "N"}
+  http://localhost:8765/app.log   HTTP poll: a plain file that keeps growing
+
+Every endpoint serves the same generated stream, about `RATE` lines a second.
+"""
+
+import base64
+import hashlib
+import json
+import sys
+import threading
+import time
+from datetime import datetime, timezone
+from http.server import BaseHTTPRequestHandler, ThreadingHTTPServer
+from urllib.parse import parse_qs, urlparse
+
+RATE = 5
+WS_GUID = "258EAFA5-E914-47DA-95CA-C5AB0DC85B11"
+STARTED = time.time()
+
+
+def line(n):
+    """Line n of the stream: a mix of plain, JSON and multi-line entries."""
+    ts = datetime.fromtimestamp(STARTED + n / RATE, timezone.utc)
+    stamp = ts.strftime("%Y-%m-%dT%H:%M:%S.") + f"{ts.microsecond // 1000:03d}Z"
+    kind = n % 6
+    if kind == 0:
+        return f"{stamp} INFO GET /api/orders/{n % 97} 200 {12 + n % 40}ms"
+    if kind == 1:
+        return json.dumps({
+            "timestamp": stamp,
+            "level": "DEBUG",
+            "service": "cart",
+            "traceId": f"tr-{n // 6:04x}",
+            "message": f"cart {n % 13} has {n % 5} items",
+        })
+    if kind == 2:
+        return f"{stamp} WARN Slow query on orders ({200 + n % 300}ms)"
+    if kind == 3 and n % 4 == 3:
+        return (f"{stamp} ERROR Order sync failed for order {5000 + n}\n"
+                "java.sql.SQLException: Timeout while waiting for connection\n"
+                "\tat com.acme.orders.OrderRepo.find(OrderRepo.java:42)")
+    return f"{stamp} INFO Server health check OK"
+
+
+def produced():
+    """How many lines exist by now."""
+    return int((time.time() - STARTED) * RATE)
+
+
+def ws_frame(text):
+    data = text.encode()
+    if len(data) < 126:
+        head = bytes([0x81, len(data)])
+    elif len(data) < 1 << 16:
+        head = bytes([0x81, 126]) + len(data).to_bytes(2, "big")
+    else:
+        head = bytes([0x81, 127]) + len(data).to_bytes(8, "big")
+    return head + data
+
+
+class Handler(BaseHTTPRequestHandler):
+    protocol_version = "HTTP/1.1"
+
+    def cors(self):
+        self.send_header("Access-Control-Allow-Origin", "*")
+        self.send_header("Access-Control-Expose-Headers", "X-Cursor")
+
+    def do_GET(self):
+        url = urlparse(self.path)
+        if url.path == "/ws":
+            self.websocket()
+        elif url.path == "/sse":
+            self.sse()
+        elif url.path == "/poll":
+            self.poll(parse_qs(url.query))
+        elif url.path == "/app.log":
+            self.growing_file()
+        else:
+            self.send_error(404)
+
+    def websocket(self):
+        key = self.headers.get("Sec-WebSocket-Key")
+        if not key:
+            return self.send_error(400, "expected a WebSocket upgrade")
+        accept = base64.b64encode(hashlib.sha1((key + WS_GUID).encode()).digest())
+        self.send_response(101)
+        self.send_header("Upgrade", "websocket")
+        self.send_header("Connection", "Upgrade")
+        self.send_header("Sec-WebSocket-Accept", accept.decode())
+        self.end_headers()
+        self.stream(ws_frame)
+
+    def sse(self):
+        self.send_response(200)
+        self.send_header("Content-Type", "text/event-stream")
+        self.send_header("Cache-Control", "no-cache")
+        self.cors()
+        self.end_headers()
+        # multi-line entries become multi-line `data:` fields
+        self.stream(lambda text: "".join(f"data: {l}\n" for l in text.split("\n")).encode() + b"\n")
+
+    def stream(self, encode):
+        n = produced()
+        try:
+            while True:
+                while n < produced():
+                    self.wfile.write(encode(line(n)))
+                    n += 1
+                self.wfile.flush()
+                time.sleep(1 / RATE)
+        except (BrokenPipeError, ConnectionResetError):
+            pass
+        self.close_connection = True
+
+    def poll(self, query):
+        start = int(query.get("cursor", [max(produced() - 20, 0)])[0])
+        end = produced()
+        self.send_json({"lines": [line(n) for n in range(start, end)], "cursor": str(end)})
+
+    def growing_file(self):
+        body = "".join(line(n) + "\n" for n in range(produced())).encode()
+        self.send_response(200)
+        self.send_header("Content-Type", "text/plain; charset=utf-8")
+        self.send_header("Content-Length", str(len(body)))
+        self.cors()
+        self.end_headers()
+        self.wfile.write(body)
+
+    def send_json(self, value):
+        body = json.dumps(value).encode()
+        self.send_response(200)
+        self.send_header("Content-Type", "application/json")
+        self.send_header("Content-Length", str(len(body)))
+        self.cors()
+        self.end_headers()
+        self.wfile.write(body)
+
+    def log_message(self, fmt, *args):
+        sys.stderr.write(f"[{threading.current_thread().name}] {fmt % args}\n")
+
+
+if __name__ == "__main__":
+    port = int(sys.argv[1]) if len(sys.argv) > 1 else 8765
+    print(f"LogLens live fixture on :{port} — /ws /sse /poll /app.log")
+    ThreadingHTTPServer(("", port), Handler).serve_forever()
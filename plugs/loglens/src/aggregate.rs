@@ -0,0 +1,235 @@
+// src/aggregate.rs — field summaries for the Aggregate tab
+//
+// For one field over a set of entries: the most frequent values and, when
+// every value is a number, min/max/mean, percentiles and a distribution. An
+// optional second field splits this into one summary per group.
+
+use std::collections::HashMap;
+
+use crate::query::Record;
+
+/// Distribution chart columns.
+pub const BINS: usize = 24;
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Stats {
+    pub min: f64,
+    pub max: f64,
+    pub mean: f64,
+    pub p50: f64,
+    pub p95: f64,
+    pub p99: f64,
+    /// Counts over `BINS` equal slices of min..=max (one slice when min == max).
+    pub bins: Vec<usize>,
+}
+
+impl Stats {
+    /// `None` unless every value is a finite number.
+    fn of(values: &[String]) -> Option<Self> {
+        let mut nums = values
+            .iter()
+            .map(|v| v.trim().parse::<f64>().ok().filter(|n| n.is_finite()))
+            .collect::<Option<Vec<_>>>()?;
+        if nums.is_empty() {
+            return None;
+        }
+        nums.sort_by(f64::total_cmp);
+        let (min, max) = (nums[0], nums[nums.len() - 1]);
+        // nearest rank
+        let pct = |p: f64| nums[((p / 100.0 * nums.len() as f64).ceil() as usize).max(1) - 1];
+
+        let slices = if max > min { BINS } else { 1 };
+        let mut bins = vec![0; slices];
+        for &n in &nums {
+            let at = ((n - min) / (max - min) * slices as f64) as usize;
+            bins[at.min(slices - 1)] += 1;
+        }
+        Some(Self {
+            min,
+            max,
+            mean: nums.iter().sum::<f64>() / nums.len() as f64,
+            p50: pct(50.0),
+            p95: pct(95.0),
+            p99: pct(99.0),
+            bins,
+        })
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Summary {
+    /// The group-by value; `None` when not grouping or the field is missing.
+    pub group: Option<String>,
+    pub entries: usize,
+    /// Entries that carry the field.
+    pub present: usize,
+    pub distinct: usize,
+    /// Most frequent first; ties by value.
+    pub top: Vec<(String, usize)>,
+    pub numeric: Option<Stats>,
+}
+
+fn summary(group: Option<String>, entries: usize, values: Vec<String>, top_n: usize) -> Summary {
+    let mut counts: HashMap<&str, usize> = HashMap::new();
+    for v in &values {
+        *counts.entry(v.as_str()).or_default() += 1;
+    }
+    let mut top = counts
+        .into_iter()
+        .map(|(v, n)| (v.to_string(), n))
+        .collect::<Vec<_>>();
+    top.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+    let distinct = top.len();
+    top.truncate(top_n);
+    Summary {
+        group,
+        entries,
+        present: values.len(),
+        distinct,
+        top,
+        numeric: Stats::of(&values),
+    }
+}
+
+/// One summary of `field`, or one per value of `by` (largest groups first,
+/// entries without `by` last).
+pub fn summarize<R: Record>(
+    records: &[R],
+    field: &str,
+    by: Option<&str>,
+    top_n: usize,
+) -> Vec<Summary> {
+    let Some(by) = by else {
+        let values = records.iter().filter_map(|r| r.field(field)).collect();
+        return vec![summary(None, records.len(), values, top_n)];
+    };
+
+    let mut groups: Vec<(Option<String>, usize, Vec<String>)> = Vec::new();
+    let mut at: HashMap<Option<String>, usize> = HashMap::new();
+    for r in records {
+        let key = r.field(by);
+        let g = *at.entry(key.clone()).or_insert_with(|| {
+            groups.push((key, 0, Vec::new()));
+            groups.len() - 1
+        });
+        groups[g].1 += 1;
+        groups[g].2.extend(r.field(field));
+    }
+    groups.sort_by(|a, b| {
+        (a.0.is_none(), b.1)
+            .cmp(&(b.0.is_none(), a.1))
+            .then_with(|| a.0.cmp(&b.0))
+    });
+    groups
+        .into_iter()
+        .map(|(key, entries, values)| summary(key, entries, values, top_n))
+        .collect()
+}
+
+/// `12`, `0.25`, `1234.57`, `1.2e-7`
+pub fn format_number(n: f64) -> String {
+    if n.fract() == 0.0 && n.abs() < 1e15 {
+        format!("{n:.0}")
+    } else if n.abs() >= 0.01 {
+        let s = format!("{n:.2}");
+        s.trim_end_matches('0').trim_end_matches('.').to_string()
+    } else {
+        format!("{n:.1e}")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    struct Rec(Vec<(&'static str, &'static str)>);
+
+    impl Record for Rec {
+        fn field(&self, path: &str) -> Option<String> {
+            self.0
+                .iter()
+                .find(|(k, _)| *k == path)
+                .map(|(_, v)| v.to_string())
+        }
+
+        fn text(&self) -> &str {
+            ""
+        }
+    }
+
+    fn recs(rows: &[&[(&'static str, &'static str)]]) -> Vec<Rec> {
+        rows.iter().map(|r| Rec(r.to_vec())).collect()
+    }
+
+    #[test]
+    fn counts_values_and_stats_numbers() {
+        let mut rows: Vec<&[(&str, &str)]> = vec![&[("status", "200"), ("ms", "10")]; 6];
+        rows.extend([
+            &[("status", "500"), ("ms", "90")][..],
+            &[("status", "404"), ("ms", "30")],
+            &[("status", "500"), ("ms", " 110 ")],
+            &[("ms", "20")],
+        ]);
+        let records = recs(&rows);
+
+        let s = &summarize(&records, "status", None, 2)[0];
+        assert_eq!((s.entries, s.present, s.distinct), (10, 9, 3));
+        assert_eq!(s.top, [("200".to_string(), 6), ("500".to_string(), 2)]);
+
+        let ms = summarize(&records, "ms", None, 5)[0]
+            .numeric
+            .clone()
+            .unwrap();
+        assert_eq!((ms.min, ms.max, ms.mean), (10.0, 110.0, 31.0));
+        assert_eq!((ms.p50, ms.p95, ms.p99), (10.0, 110.0, 110.0));
+        assert_eq!(ms.bins.len(), BINS);
+        assert_eq!((ms.bins[0], ms.bins[BINS - 1]), (6, 1));
+        assert_eq!(ms.bins.iter().sum::<usize>(), 10);
+
+        let one = recs(&[&[("ms", "5")], &[("ms", "5")]]);
+        assert_eq!(
+            summarize(&one, "ms", None, 5)[0]
+                .numeric
+                .as_ref()
+                .unwrap()
+                .bins,
+            [2]
+        );
+        // one non-number makes the field categorical
+        assert!(
+            summarize(&recs(&[&[("ms", "5")], &[("ms", "n/a")]]), "ms", None, 5)[0]
+                .numeric
+                .is_none()
+        );
+    }
+
+    #[test]
+    fn groups_by_a_second_field() {
+        let records = recs(&[
+            &[("svc", "cart"), ("ms", "10")],
+            &[("ms", "99")],
+            &[("svc", "orders"), ("ms", "30")],
+            &[("svc", "orders"), ("ms", "50")],
+            &[("svc", "cart"), ("ms", "20")],
+            &[("svc", "auth")],
+        ]);
+        let groups = summarize(&records, "ms", Some("svc"), 5);
+        let keys = groups
+            .iter()
+            .map(|g| g.group.as_deref())
+            .collect::<Vec<_>>();
+        assert_eq!(keys, [Some("cart"), Some("orders"), Some("auth"), None]);
+        assert_eq!(groups[1].numeric.as_ref().unwrap().mean, 40.0);
+        assert_eq!((groups[2].entries, groups[2].present), (1, 0));
+        assert!(groups[2].numeric.is_none());
+    }
+
+    #[test]
+    fn numbers_read_naturally() {
+        assert_eq!(format_number(12.0), "12");
+        assert_eq!(format_number(0.25), "0.25");
+        assert_eq!(format_number(1234.5678), "1234.57");
+        assert_eq!(format_number(-3.10), "-3.1");
+        assert_eq!(format_number(0.00000012), "1.2e-7");
+    }
+}
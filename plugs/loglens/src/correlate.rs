@@ -0,0 +1,284 @@
+// src/correlate.rs — group entries by a correlation key, lay out span trees
+//
+// A key is a field name or several comma-separated alternatives
+// (`traceId, trace_id`) tried in order; entries carrying none of them are left
+// out. Within a group, entries that name a span (`spanId` + `parentSpanId`)
+// are nested under their parent span for the waterfall.
+
+use std::collections::{HashMap, HashSet};
+
+use crate::query::{level_rank, Record};
+
+const SPAN_KEYS: [&str; 3] = ["spanId", "span_id", "span.id"];
+const PARENT_KEYS: [&str; 4] = ["parentSpanId", "parent_span_id", "parentId", "parent.id"];
+
+/// The alternatives in a key spec, in order.
+pub fn key_fields(spec: &str) -> Vec<&str> {
+    spec.split(',')
+        .map(|s| s.trim())
+        .filter(|s| !s.is_empty())
+        .collect()
+}
+
+fn first_field(rec: &impl Record, keys: &[&str]) -> Option<String> {
+    keys.iter()
+        .find_map(|k| rec.field(k))
+        .filter(|v| !v.is_empty())
+}
+
+/// The more severe of two levels; a known level beats an unknown one.
+fn worse(a: Option<String>, b: Option<String>) -> Option<String> {
+    let rank = |l: &Option<String>| l.as_deref().and_then(level_rank);
+    match (rank(&a), rank(&b)) {
+        (Some(x), Some(y)) if y > x => b,
+        (None, Some(_)) => b,
+        _ => a.or(b),
+    }
+}
+
+/// Earliest/latest timestamp and worst level over a set of entries.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Extent {
+    pub start: Option<i64>,
+    pub end: Option<i64>,
+    pub worst: Option<String>,
+}
+
+impl Extent {
+    fn add(&mut self, ts: Option<i64>, level: Option<String>) {
+        if let Some(t) = ts {
+            self.start = Some(self.start.map_or(t, |s| s.min(t)));
+            self.end = Some(self.end.map_or(t, |e| e.max(t)));
+        }
+        self.worst = worse(self.worst.take(), level);
+    }
+
+    pub fn duration(&self) -> Option<i64> {
+        Some(self.end? - self.start?)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Group {
+    pub key: String,
+    /// Indices into the slice given to `group_by`, in time order (untimed
+    /// entries last, in log order).
+    pub members: Vec<usize>,
+    pub extent: Extent,
+}
+
+/// Groups in order of their first timestamp; untimed groups come last.
+pub fn group_by<R: Record>(
+    records: &[R],
+    keys: &[&str],
+    ts: impl Fn(&R) -> Option<i64>,
+) -> Vec<Group> {
+    let mut groups: Vec<Group> = Vec::new();
+    let mut at: HashMap<String, usize> = HashMap::new();
+    for (i, r) in records.iter().enumerate() {
+        let Some(key) = first_field(r, keys) else {
+            continue;
+        };
+        let g = *at.entry(key.clone()).or_insert_with(|| {
+            groups.push(Group {
+                key,
+                members: Vec::new(),
+                extent: Extent::default(),
+            });
+            groups.len() - 1
+        });
+        groups[g].members.push(i);
+        groups[g].extent.add(ts(r), r.field("level"));
+    }
+    for g in &mut groups {
+        g.members
+            .sort_by_key(|&i| ts(&records[i]).unwrap_or(i64::MAX));
+    }
+    groups.sort_by_key(|g| g.extent.start.unwrap_or(i64::MAX));
+    groups
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct SpanRow {
+    pub id: String,
+    pub parent: Option<String>,
+    /// Nesting level in the waterfall (0 = root).
+    pub depth: usize,
+    pub members: Vec<usize>,
+    pub extent: Extent,
+}
+
+/// One group's spans in tree order: each span, then its children by start
+/// time. Spans whose parent is not in the group become roots. Empty when no
+/// member names a span.
+pub fn waterfall<R: Record>(
+    records: &[R],
+    members: &[usize],
+    ts: impl Fn(&R) -> Option<i64>,
+) -> Vec<SpanRow> {
+    let mut spans: Vec<SpanRow> = Vec::new();
+    let mut at: HashMap<String, usize> = HashMap::new();
+    for &i in members {
+        let r = &records[i];
+        let Some(id) = first_field(r, &SPAN_KEYS) else {
+            continue;
+        };
+        let s = *at.entry(id.clone()).or_insert_with(|| {
+            spans.push(SpanRow {
+                id: id.clone(),
+                parent: None,
+                depth: 0,
+                members: Vec::new(),
+                extent: Extent::default(),
+            });
+            spans.len() - 1
+        });
+        let span = &mut spans[s];
+        if span.parent.is_none() {
+            span.parent = first_field(r, &PARENT_KEYS).filter(|p| *p != id);
+        }
+        span.members.push(i);
+        span.extent.add(ts(r), r.field("level"));
+    }
+
+    let mut children: HashMap<Option<usize>, Vec<usize>> = HashMap::new();
+    for (s, span) in spans.iter().enumerate() {
+        let parent = span.parent.as_ref().and_then(|p| at.get(p).copied());
+        children.entry(parent).or_default().push(s);
+    }
+    for list in children.values_mut() {
+        list.sort_by_key(|&s| (spans[s].extent.start.unwrap_or(i64::MAX), s));
+    }
+
+    fn visit(
+        s: usize,
+        depth: usize,
+        spans: &[SpanRow],
+        children: &HashMap<Option<usize>, Vec<usize>>,
+        seen: &mut HashSet<usize>,
+        out: &mut Vec<SpanRow>,
+    ) {
+        if !seen.insert(s) {
+            return;
+        }
+        out.push(SpanRow {
+            depth,
+            ..spans[s].clone()
+        });
+        for &c in children.get(&Some(s)).into_iter().flatten() {
+            visit(c, depth + 1, spans, children, seen, out);
+        }
+    }
+
+    let mut out = Vec::with_capacity(spans.len());
+    let mut seen = HashSet::new();
+    for &root in children.get(&None).into_iter().flatten() {
+        visit(root, 0, &spans, &children, &mut seen, &mut out);
+    }
+    // parent cycles never reach a root; show them from their first span
+    for s in 0..spans.len() {
+        visit(s, 0, &spans, &children, &mut seen, &mut out);
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    struct Rec {
+        fields: Vec<(&'static str, &'static str)>,
+        ts: Option<i64>,
+    }
+
+    impl Record for Rec {
+        fn field(&self, path: &str) -> Option<String> {
+            self.fields
+                .iter()
+                .find(|(k, _)| *k == path)
+                .map(|(_, v)| v.to_string())
+        }
+
+        fn text(&self) -> &str {
+            ""
+        }
+    }
+
+    fn rec(ts: Option<i64>, fields: &[(&'static str, &'static str)]) -> Rec {
+        Rec {
+            fields: fields.to_vec(),
+            ts,
+        }
+    }
+
+    #[test]
+    fn groups_by_the_first_key_present() {
+        let recs = [
+            rec(Some(500), &[("traceId", "b"), ("level", "INFO")]),
+            rec(Some(100), &[("trace_id", "a"), ("level", "INFO")]),
+            rec(None, &[("level", "ERROR")]),
+            rec(Some(900), &[("traceId", "b"), ("level", "WARN")]),
+            rec(Some(300), &[("traceId", "b"), ("level", "weird")]),
+            rec(Some(150), &[("trace_id", "a")]),
+        ];
+        let groups = group_by(&recs, &key_fields("traceId, trace_id"), |r| r.ts);
+        assert_eq!(groups.len(), 2);
+
+        assert_eq!(groups[0].key, "a");
+        assert_eq!(groups[0].members, [1, 5]);
+        assert_eq!(groups[0].extent.duration(), Some(50));
+
+        assert_eq!(groups[1].key, "b");
+        assert_eq!(groups[1].members, [4, 0, 3]);
+        assert_eq!(groups[1].extent.duration(), Some(600));
+        assert_eq!(groups[1].extent.worst.as_deref(), Some("WARN"));
+    }
+
+    #[test]
+    fn waterfall_nests_children_under_parents() {
+        let recs = [
+            rec(Some(0), &[("spanId", "root")]),
+            rec(Some(40), &[("spanId", "db"), ("parentSpanId", "orders")]),
+            rec(Some(10), &[("spanId", "auth"), ("parentSpanId", "root")]),
+            rec(Some(30), &[("spanId", "orders"), ("parentSpanId", "root")]),
+            rec(Some(90), &[("spanId", "db"), ("level", "ERROR")]),
+            rec(Some(20), &[("spanId", "orphan"), ("parentSpanId", "gone")]),
+            rec(Some(95), &[("message", "no span")]),
+            rec(Some(100), &[("spanId", "root")]),
+        ];
+        let rows = waterfall(&recs, &[0, 1, 2, 3, 4, 5, 6, 7], |r| r.ts);
+        let tree = rows
+            .iter()
+            .map(|r| (r.id.as_str(), r.depth))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            tree,
+            [
+                ("root", 0),
+                ("auth", 1),
+                ("orders", 1),
+                ("db", 2),
+                ("orphan", 0)
+            ]
+        );
+        let db = &rows[3];
+        assert_eq!(db.members, [1, 4]);
+        assert_eq!(db.extent.duration(), Some(50));
+        assert_eq!(db.extent.worst.as_deref(), Some("ERROR"));
+        assert_eq!(rows[0].extent.duration(), Some(100));
+    }
+
+    #[test]
+    fn waterfall_survives_parent_cycles_and_missing_spans() {
+        let recs = [
+            rec(Some(0), &[("spanId", "a"), ("parentSpanId", "b")]),
+            rec(Some(5), &[("spanId", "b"), ("parentSpanId", "a")]),
+        ];
+        let rows = waterfall(&recs, &[0, 1], |r| r.ts);
+        assert_eq!(rows.len(), 2);
+        assert_eq!((rows[0].depth, rows[1].depth), (0, 1));
+
+        let plain = [rec(Some(0), &[("traceId", "t")])];
+        assert!(waterfall(&plain, &[0], |r| r.ts).is_empty());
+    }
+}
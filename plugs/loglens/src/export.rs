@@ -0,0 +1,137 @@
+// src/export.rs — getting results out: CSV rows and investigation bundles
+//
+// A bundle is one JSON file holding everything needed to pick an
+// investigation back up: the log text, how it was parsed, the filters and
+// highlight, the time window, pinned entries and notes. Importing it restores
+// the session as it was.
+
+use serde::{Deserialize, Serialize};
+
+use crate::group::FoldRules;
+use crate::Preset;
+
+/// One CSV record (RFC 4180), without the line break.
+pub fn csv_row<S: AsRef<str>>(cells: impl IntoIterator<Item = S>) -> String {
+    cells
+        .into_iter()
+        .map(|c| {
+            let c = c.as_ref();
+            if c.contains([',', '"', '\n', '\r']) {
+                format!("\"{}\"", c.replace('"', "\"\""))
+            } else {
+                c.to_string()
+            }
+        })
+        .collect::<Vec<_>>()
+        .join(",")
+}
+
+/// Bumped when a field changes meaning; newer bundles are refused.
+pub const BUNDLE_VERSION: u32 = 1;
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Bundle {
+    /// Doubles as the marker telling a bundle from any other JSON file.
+    #[serde(rename = "loglens_bundle")]
+    pub version: u32,
+    #[serde(default)]
+    pub source: Option<String>,
+    /// The log text as loaded (pasted, opened or tailed).
+    pub input: String,
+    /// `Format` by its Debug name (see `parse::format_from_debug`).
+    pub format: String,
+    #[serde(default)]
+    pub custom_format: String,
+    #[serde(default)]
+    pub fold_rules: FoldRules,
+    /// Filters, query and highlight; the name is the preset name at export.
+    pub view: Preset,
+    #[serde(default)]
+    pub time_window: Option<(i64, i64)>,
+    /// Line numbers (`Entry::idx`) of pinned and expanded entries.
+    #[serde(default)]
+    pub pinned: Vec<usize>,
+    #[serde(default)]
+    pub expanded: Vec<usize>,
+    #[serde(default)]
+    pub notes: String,
+}
+
+impl Bundle {
+    pub fn to_json(&self) -> String {
+        serde_json::to_string_pretty(self).expect("bundle serializes")
+    }
+
+    pub fn from_json(s: &str) -> Result<Self, String> {
+        let b: Self = serde_json::from_str(s).map_err(|e| format!("Not a LogLens bundle: {e}"))?;
+        if b.version > BUNDLE_VERSION {
+            return Err(format!(
+                "Bundle version {} is newer than this LogLens understands ({BUNDLE_VERSION}).",
+                b.version
+            ));
+        }
+        Ok(b)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn csv_quotes_only_when_needed() {
+        assert_eq!(csv_row(["a", "b c", ""]), "a,b c,");
+        assert_eq!(
+            csv_row(["1,2", "say \"hi\"", "two\nlines"]),
+            "\"1,2\",\"say \"\"hi\"\"\",\"two\nlines\""
+        );
+    }
+
+    #[test]
+    fn bundles_round_trip_and_refuse_the_unknown() {
+        let b = Bundle {
+            version: BUNDLE_VERSION,
+            source: Some("app.log (3 KB)".to_string()),
+            input: "2026-02-24T20:11:04Z ERROR \"boom\"\n".to_string(),
+            format: "Auto".to_string(),
+            custom_format: String::new(),
+            fold_rules: FoldRules {
+                no_timestamp: true,
+                ..FoldRules::default()
+            },
+            view: Preset {
+                name: "incident".to_string(),
+                level: "ERROR".to_string(),
+                needle: String::new(),
+                json_only: false,
+                hl_enabled: true,
+                hl_case_insensitive: true,
+                hl_pat: r"order \d+".to_string(),
+                query: "service:orders".to_string(),
+            },
+            time_window: Some((1, 2)),
+            pinned: vec![0, 7],
+            expanded: vec![7],
+            notes: "DB pool ran dry at 20:11".to_string(),
+        };
+        assert_eq!(Bundle::from_json(&b.to_json()), Ok(b.clone()));
+
+        // fields added later default when missing
+        let mut v = serde_json::to_value(&b).unwrap();
+        let o = v.as_object_mut().unwrap();
+        for k in ["notes", "pinned", "expanded", "fold_rules", "time_window"] {
+            o.remove(k);
+        }
+        let old = Bundle::from_json(&v.to_string()).unwrap();
+        assert_eq!(
+            (old.notes.as_str(), old.fold_rules),
+            ("", FoldRules::default())
+        );
+
+        assert!(Bundle::from_json(r#"{"level": "INFO"}"#).is_err());
+        let newer = b
+            .to_json()
+            .replacen("\"loglens_bundle\": 1", "\"loglens_bundle\": 9", 1);
+        assert!(Bundle::from_json(&newer).unwrap_err().contains("newer"));
+    }
+}
@@ -0,0 +1,156 @@
+// src/group.rs — fold multi-line records (stack traces) into one entry
+//
+// A line either starts a new entry or continues the previous one. Each rule
+// below can claim a line as a continuation; with every rule off, LogLens goes
+// back to one entry per physical line.
+
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct FoldRules {
+    pub enabled: bool,
+    /// Lines starting with a space or tab.
+    pub indented: bool,
+    /// Java frames: `at com.acme.Foo.bar(Foo.java:42)` and `... 12 more`.
+    pub at_frames: bool,
+    /// `Caused by: …` and `Suppressed: …` chains.
+    pub caused_by: bool,
+    /// Exception headers (`java.sql.SQLException: …`, `TimeoutError: …`)
+    /// and Python's `Traceback (most recent call last):`.
+    pub exceptions: bool,
+    /// Anything that does not look like the start of a record (no timestamp,
+    /// JSON object, level word, syslog priority or client IP in front).
+    pub no_timestamp: bool,
+}
+
+impl Default for FoldRules {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            indented: true,
+            at_frames: true,
+            caused_by: true,
+            exceptions: true,
+            no_timestamp: false,
+        }
+    }
+}
+
+pub struct Grouper {
+    rules: FoldRules,
+    exception: Regex,
+    more: Regex,
+    record_start: Regex,
+}
+
+impl Grouper {
+    pub fn new(rules: &FoldRules) -> Self {
+        Self {
+            rules: rules.clone(),
+            exception: Regex::new(
+                r"^(?:[A-Za-z_$][\w$]*\.)*[A-Za-z_$][\w$]*(?:Exception|Error|Throwable|Interrupt|Exit)(?::\s|:?$)",
+            )
+            .expect("exception regex"),
+            more: Regex::new(r"^\.\.\. \d+ (?:more|common frames omitted)").expect("more regex"),
+            record_start: Regex::new(
+                r"^(?:\[?\d{4}-\d{2}-\d{2}[T ]\d|\d{10,13}\b|<\d{1,3}>|[A-Z][a-z]{2} [ \d]\d \d{2}:\d{2}|\{|\[?(?i:trace|debug|info|warn|warning|error|fatal)\b|\d{1,3}(?:\.\d{1,3}){3} )",
+            )
+            .expect("record start regex"),
+        }
+    }
+
+    pub fn is_continuation(&self, line: &str) -> bool {
+        let r = &self.rules;
+        if !r.enabled {
+            return false;
+        }
+        let trimmed = line.trim_start();
+        (r.indented && trimmed.len() < line.len())
+            || (r.at_frames && (trimmed.starts_with("at ") || self.more.is_match(trimmed)))
+            || (r.caused_by
+                && (trimmed.starts_with("Caused by:") || trimmed.starts_with("Suppressed:")))
+            || (r.exceptions
+                && (trimmed.starts_with("Traceback (most recent call last)")
+                    || self.exception.is_match(trimmed)))
+            || (r.no_timestamp && !self.record_start.is_match(trimmed))
+    }
+
+    /// `(first line index, lines)` per entry. Blank lines are dropped.
+    pub fn group<'a>(&self, input: &'a str) -> Vec<(usize, Vec<&'a str>)> {
+        let mut out: Vec<(usize, Vec<&'a str>)> = Vec::new();
+        for (idx, line) in input.lines().enumerate() {
+            if line.trim().is_empty() {
+                continue;
+            }
+            match out.last_mut() {
+                Some((_, lines)) if self.is_continuation(line) => lines.push(line),
+                _ => out.push((idx, vec![line])),
+            }
+        }
+        out
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const JAVA: &str = "2026-02-24T20:11:04Z ERROR Order sync failed
+java.sql.SQLException: Timeout while waiting for connection
+\tat com.zaxxer.hikari.pool.HikariPool.getConnection(HikariPool.java:181)
+\tat com.acme.orders.OrderRepo.find(OrderRepo.java:42)
+Caused by: java.net.SocketTimeoutException: Read timed out
+\t... 12 more
+2026-02-24T20:11:05Z INFO Server health check OK";
+
+    const PYTHON: &str = "ERROR Failed to process request after timeout
+Traceback (most recent call last):
+  File \"/app/worker.py\", line 42, in handle
+    rows = db.fetch(order_id)
+TimeoutError: database timeout
+{\"level\":\"INFO\",\"message\":\"next\"}";
+
+    fn sizes(g: &Grouper, input: &str) -> Vec<(usize, usize)> {
+        g.group(input)
+            .into_iter()
+            .map(|(idx, lines)| (idx, lines.len()))
+            .collect()
+    }
+
+    #[test]
+    fn folds_java_and_python_traces_into_their_parent() {
+        let g = Grouper::new(&FoldRules::default());
+        assert_eq!(sizes(&g, JAVA), [(0, 6), (6, 1)]);
+        assert_eq!(sizes(&g, PYTHON), [(0, 5), (5, 1)]);
+    }
+
+    #[test]
+    fn rules_can_be_switched_off() {
+        let off = Grouper::new(&FoldRules {
+            enabled: false,
+            ..FoldRules::default()
+        });
+        assert_eq!(off.group(JAVA).len(), 7);
+
+        let indent_only = Grouper::new(&FoldRules {
+            at_frames: false,
+            caused_by: false,
+            exceptions: false,
+            ..FoldRules::default()
+        });
+        // header and Caused by start their own entries; tab-indented frames still fold
+        assert_eq!(sizes(&indent_only, JAVA), [(0, 1), (1, 3), (4, 2), (6, 1)]);
+    }
+
+    #[test]
+    fn no_timestamp_rule_folds_free_text() {
+        let g = Grouper::new(&FoldRules {
+            no_timestamp: true,
+            ..FoldRules::default()
+        });
+        let input = "2026-02-24T20:11:04Z WARN retrying\nstill waiting on db\n127.0.0.1 - - [24/Feb/2026:20:11:05 +0000] \"GET / HTTP/1.1\" 200 5";
+        assert_eq!(sizes(&g, input), [(0, 2), (2, 1)]);
+    }
+}
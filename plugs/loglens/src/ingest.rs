@@ -0,0 +1,288 @@
+// src/ingest.rs — getting logs in: files (gzip too) and incremental parsing
+//
+// `Buffer` owns the raw text and its entries. An append re-parses from the
+// last entry onward — that entry may still gain continuation lines or the
+// rest of a half-written line — and leaves everything before it alone, so a
+// live tail or a chunked file load costs only what is new.
+
+use std::io::Read;
+
+use flate2::read::MultiGzDecoder;
+
+use crate::group::Grouper;
+use crate::parse::LineParser;
+use crate::template::Masker;
+use crate::{parse_entry, time, Entry};
+
+/// File bytes to text. Gzip is recognised by its magic bytes, so a `.gz`
+/// with the wrong name (or a plain file named `.gz`) still loads.
+pub fn decode(bytes: &[u8]) -> Result<String, String> {
+    let raw = if bytes.starts_with(&[0x1f, 0x8b]) {
+        let mut out = Vec::new();
+        MultiGzDecoder::new(bytes)
+            .read_to_end(&mut out)
+            .map_err(|e| format!("gzip error: {e}"))?;
+        out
+    } else {
+        bytes.to_vec()
+    };
+    Ok(match String::from_utf8(raw) {
+        Ok(s) => s,
+        Err(e) => String::from_utf8_lossy(e.as_bytes()).into_owned(),
+    })
+}
+
+/// Pieces of roughly `size` bytes, each ending just after a newline (the
+/// last one excepted).
+pub fn chunks(text: &str, size: usize) -> impl Iterator<Item = &str> {
+    let mut rest = text;
+    std::iter::from_fn(move || {
+        if rest.is_empty() {
+            return None;
+        }
+        let cut = rest.as_bytes()[size.min(rest.len())..]
+            .iter()
+            .position(|&b| b == b'\n')
+            .map_or(rest.len(), |p| size + p + 1);
+        let (head, tail) = rest.split_at(cut);
+        rest = tail;
+        Some(head)
+    })
+}
+
+/// `512 B`, `3.4 KB`, `52.1 MB`
+pub fn format_size(bytes: u64) -> String {
+    const UNITS: [&str; 4] = ["KB", "MB", "GB", "TB"];
+    if bytes < 1024 {
+        return format!("{bytes} B");
+    }
+    let mut n = bytes as f64 / 1024.0;
+    let mut unit = 0;
+    while n >= 1024.0 && unit + 1 < UNITS.len() {
+        n /= 1024.0;
+        unit += 1;
+    }
+    format!("{n:.1} {}", UNITS[unit])
+}
+
+#[derive(Default)]
+pub struct Buffer {
+    pub text: String,
+    pub entries: Vec<Entry>,
+    /// Bumped on every change; views memoize on it.
+    pub rev: u64,
+    /// Bumped by `clear`, so a chunked load can tell it was cancelled.
+    pub epoch: u64,
+    /// Syslog dates carry no year; borrowed from the first full timestamp.
+    year: Option<i64>,
+    masker: Masker,
+}
+
+impl Buffer {
+    pub fn clear(&mut self) {
+        self.text.clear();
+        self.entries.clear();
+        self.year = None;
+        self.rev += 1;
+        self.epoch += 1;
+    }
+
+    /// Append raw text and parse what it changed.
+    pub fn append(&mut self, chunk: &str, parsers: &[Box<dyn LineParser>], grouper: &Grouper) {
+        // A half-written last line may turn out to continue the entry before
+        // it once complete, so that entry is reopened too.
+        let reopen = if self.text.is_empty() || self.text.ends_with('\n') {
+            1
+        } else {
+            2
+        };
+        self.text.push_str(chunk);
+        let keep = self.entries.len().saturating_sub(reopen);
+        let (offset, line) = self.entries.get(keep).map_or((0, 0), |e| (e.offset, e.idx));
+        self.entries.truncate(keep);
+
+        let rest = &self.text[offset..];
+        let base = rest.as_ptr() as usize;
+        let fresh = grouper
+            .group(rest)
+            .into_iter()
+            .map(|(rel, lines)| {
+                let at = offset + (lines[0].as_ptr() as usize - base);
+                parse_entry(line + rel, at, &lines, parsers, &self.masker)
+            })
+            .collect::<Vec<_>>();
+
+        let mut from = self.entries.len();
+        self.entries.extend(fresh);
+        if self.year.is_none() {
+            self.year = self.entries[from..]
+                .iter()
+                .find_map(|e| time::parse_timestamp(e.timestamp.as_deref()?, None))
+                .map(time::year_of);
+            if self.year.is_some() {
+                // earlier syslog entries can be dated now
+                from = 0;
+            }
+        }
+        let year = self.year.unwrap_or(1970);
+        for e in &mut self.entries[from..] {
+            e.ts_ms = e
+                .timestamp
+                .as_deref()
+                .and_then(|t| time::parse_timestamp(t, Some(year)));
+        }
+        self.rev += 1;
+    }
+
+    /// Append whole lines, starting a new line first if needed.
+    pub fn append_line(&mut self, line: &str, parsers: &[Box<dyn LineParser>], grouper: &Grouper) {
+        if self.text.is_empty() || self.text.ends_with('\n') {
+            self.append(line, parsers, grouper);
+        } else {
+            self.append(&format!("\n{line}"), parsers, grouper);
+        }
+    }
+
+    /// Drop the oldest entries beyond `max` (a live tail's ring). The rest keep
+    /// their line numbers. Returns how many went.
+    pub fn trim_front(&mut self, max: usize) -> usize {
+        let Some(n) = self
+            .entries
+            .len()
+            .checked_sub(max.max(1))
+            .filter(|&n| n > 0)
+        else {
+            return 0;
+        };
+        let cut = self.entries[n].offset;
+        self.text.drain(..cut);
+        self.entries.drain(..n);
+        for e in &mut self.entries {
+            e.offset -= cut;
+        }
+        self.rev += 1;
+        n
+    }
+
+    /// Parse the whole text again (after a format or fold rule change).
+    pub fn reparse(&mut self, parsers: &[Box<dyn LineParser>], grouper: &Grouper) {
+        let text = std::mem::take(&mut self.text);
+        self.entries.clear();
+        self.year = None;
+        self.append(&text, parsers, grouper);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Write;
+
+    use flate2::write::GzEncoder;
+    use flate2::Compression;
+
+    use super::*;
+    use crate::group::FoldRules;
+    use crate::parse::{parsers, Format};
+
+    const LOG: &str = "Feb 24 20:11:03 web1 nginx: upstream slow
+2026-02-24T20:11:04Z ERROR Order sync failed
+java.sql.SQLException: Timeout while waiting for connection
+\tat com.acme.orders.OrderRepo.find(OrderRepo.java:42)
+
+{\"timestamp\":\"2026-02-24T20:11:05Z\",\"level\":\"INFO\",\"message\":\"ok\"}
+2026-02-24T20:11:06Z WARN retrying
+";
+
+    fn summary(buf: &Buffer) -> Vec<(usize, usize, usize, Option<i64>, String)> {
+        buf.entries
+            .iter()
+            .map(|e| (e.idx, e.offset, e.lines, e.ts_ms, e.raw.clone()))
+            .collect()
+    }
+
+    #[test]
+    fn appending_in_pieces_matches_a_single_parse() {
+        let p = parsers(Format::Auto, "").unwrap();
+        let g = Grouper::new(&FoldRules::default());
+        let mut whole = Buffer::default();
+        whole.append(LOG, &p, &g);
+        assert_eq!(whole.entries.len(), 4);
+        assert_eq!(whole.entries[1].lines, 3);
+        assert_eq!(&LOG[whole.entries[2].offset..][..1], "{");
+
+        // every split point, including mid-line and mid-trace
+        for cut in (1..LOG.len()).filter(|&i| LOG.is_char_boundary(i)) {
+            let mut buf = Buffer::default();
+            buf.append(&LOG[..cut], &p, &g);
+            buf.append(&LOG[cut..], &p, &g);
+            assert_eq!(summary(&buf), summary(&whole), "split at {cut}");
+        }
+
+        let mut lines = Buffer::default();
+        for chunk in chunks(LOG, 16) {
+            lines.append(chunk, &p, &g);
+        }
+        assert_eq!(summary(&lines), summary(&whole));
+    }
+
+    #[test]
+    fn syslog_entries_borrow_a_year_that_arrives_later() {
+        let p = parsers(Format::Auto, "").unwrap();
+        let g = Grouper::new(&FoldRules::default());
+        let mut buf = Buffer::default();
+        buf.append_line("Feb 24 20:11:03 web1 nginx: upstream slow", &p, &g);
+        assert_eq!(time::year_of(buf.entries[0].ts_ms.unwrap()), 1970);
+        buf.append_line("2026-02-24T20:11:04Z INFO up", &p, &g);
+        assert_eq!(time::year_of(buf.entries[0].ts_ms.unwrap()), 2026);
+        assert_eq!(buf.text.lines().count(), 2);
+    }
+
+    #[test]
+    fn a_trimmed_ring_keeps_line_numbers_and_keeps_appending() {
+        let p = parsers(Format::Auto, "").unwrap();
+        let g = Grouper::new(&FoldRules::default());
+        let mut buf = Buffer::default();
+        buf.append(LOG, &p, &g);
+        assert_eq!(buf.trim_front(10), 0);
+        assert_eq!(buf.trim_front(2), 2);
+        assert_eq!(buf.entries[0].idx, 5);
+        assert!(buf.text.starts_with("{\"timestamp\""));
+        assert_eq!(&buf.text[buf.entries[1].offset..][..4], "2026");
+
+        buf.append_line("\tat continued.trace(X.java:1)", &p, &g);
+        buf.append_line("2026-02-24T20:11:07Z INFO done", &p, &g);
+        let last = buf
+            .entries
+            .iter()
+            .map(|e| (e.idx, e.lines))
+            .collect::<Vec<_>>();
+        assert_eq!(last, [(5, 1), (6, 2), (8, 1)]);
+    }
+
+    #[test]
+    fn decodes_plain_and_gzipped_bytes() {
+        assert_eq!(decode(b"a\nb").unwrap(), "a\nb");
+
+        let mut gz = GzEncoder::new(Vec::new(), Compression::fast());
+        gz.write_all(LOG.as_bytes()).unwrap();
+        assert_eq!(decode(&gz.finish().unwrap()).unwrap(), LOG);
+
+        assert!(decode(&[0x1f, 0x8b, 0, 0]).is_err());
+    }
+
+    #[test]
+    fn sizes_read_like_a_file_manager() {
+        assert_eq!(format_size(512), "512 B");
+        assert_eq!(format_size(3_500), "3.4 KB");
+        assert_eq!(format_size(52_100_000), "49.7 MB");
+    }
+
+    #[test]
+    fn chunks_end_on_newlines() {
+        let text = "aaaa\nbb\ncccccc\nd";
+        let parts = chunks(text, 3).collect::<Vec<_>>();
+        assert_eq!(parts, ["aaaa\n", "bb\ncccccc\n", "d"]);
+        assert_eq!(parts.concat(), text);
+        assert_eq!(chunks("", 3).count(), 0);
+    }
+}
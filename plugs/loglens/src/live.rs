@@ -0,0 +1,224 @@
+// src/live.rs — live tail plumbing between a source and the buffer
+//
+// Sources (the demo generator, a WebSocket, Server-Sent Events, an HTTP
+// poller) push lines into a `Feed`; a timer drains it into the buffer in
+// batches, so a chatty source costs one parse per tick rather than one per
+// message. The queue is bounded: while paused or falling behind, the oldest
+// queued lines are dropped and counted. The HTTP poller holds off instead,
+// which loses nothing. The buffer itself is a ring of the last `keep` entries.
+
+use std::collections::VecDeque;
+
+use serde_json::Value;
+
+/// Queued lines before the oldest are dropped.
+pub const QUEUE: usize = 20_000;
+/// Lines appended per drain tick.
+pub const BATCH: usize = 2_000;
+/// Default ring size, in entries.
+pub const KEEP: usize = 50_000;
+/// Drain timer period, ms.
+pub const DRAIN_MS: u32 = 250;
+
+pub struct Feed {
+    queue: VecDeque<String>,
+    /// Lines keep queueing (and dropping) while paused; nothing is appended.
+    pub paused: bool,
+    /// The buffer is trimmed to this many entries while tailing.
+    pub keep: usize,
+    /// Connection state for the status line ("open", "closed (1006)", …).
+    pub status: String,
+    pub received: u64,
+    /// Lines lost to a full queue.
+    pub dropped: u64,
+    /// Entries trimmed off the front of the buffer.
+    pub trimmed: u64,
+}
+
+impl Default for Feed {
+    fn default() -> Self {
+        Self {
+            queue: VecDeque::new(),
+            paused: false,
+            keep: KEEP,
+            status: String::new(),
+            received: 0,
+            dropped: 0,
+            trimmed: 0,
+        }
+    }
+}
+
+impl Feed {
+    /// Forget the previous source (the ring size stays).
+    pub fn reset(&mut self) {
+        *self = Self {
+            keep: self.keep,
+            ..Self::default()
+        };
+    }
+
+    /// Queue the lines of one message; blank lines are skipped.
+    pub fn push(&mut self, text: &str) {
+        for line in text.lines().filter(|l| !l.trim().is_empty()) {
+            if self.queue.len() == QUEUE {
+                self.queue.pop_front();
+                self.dropped += 1;
+            }
+            self.queue.push_back(line.to_string());
+            self.received += 1;
+        }
+    }
+
+    pub fn queued(&self) -> usize {
+        self.queue.len()
+    }
+
+    /// Whether a source that can wait (the HTTP poller) should.
+    pub fn holding(&self) -> bool {
+        self.paused || self.queue.len() >= QUEUE / 2
+    }
+
+    /// Up to `max` queued lines joined by newlines; `None` while paused or empty.
+    pub fn take(&mut self, max: usize) -> Option<String> {
+        if self.paused || self.queue.is_empty() {
+            return None;
+        }
+        let n = max.min(self.queue.len());
+        Some(self.queue.drain(..n).collect::<Vec<_>>().join("\n"))
+    }
+}
+
+/// Where the next HTTP poll resumes. Three kinds of endpoint work:
+///
+/// - JSON `{"lines": [...], "cursor": "…"}`: the cursor goes back as `?cursor=`
+/// - any body with an `X-Cursor` header: same, the whole body is new
+/// - a plain file that grows: everything past the last complete line seen
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Cursor {
+    token: Option<String>,
+    /// Bytes of a growing file already taken.
+    seen: usize,
+}
+
+impl Cursor {
+    pub fn url(&self, base: &str) -> String {
+        match &self.token {
+            None => base.to_string(),
+            Some(t) => {
+                let sep = if base.contains('?') { '&' } else { '?' };
+                format!("{base}{sep}cursor={}", encode(t))
+            }
+        }
+    }
+
+    /// The new text in one response.
+    pub fn advance(&mut self, body: &str, header: Option<String>) -> String {
+        if let Ok(Value::Object(o)) = serde_json::from_str::<Value>(body) {
+            if let Some(Value::Array(lines)) = o.get("lines") {
+                let token =
+                    ["cursor", "next_cursor", "next"]
+                        .iter()
+                        .find_map(|k| match o.get(*k)? {
+                            Value::String(s) => Some(s.clone()),
+                            Value::Number(n) => Some(n.to_string()),
+                            _ => None,
+                        });
+                if token.is_some() {
+                    self.token = token;
+                }
+                return lines
+                    .iter()
+                    .map(|l| match l {
+                        Value::String(s) => s.clone(),
+                        other => other.to_string(),
+                    })
+                    .collect::<Vec<_>>()
+                    .join("\n");
+            }
+        }
+        if header.is_some() {
+            self.token = header;
+            return body.to_string();
+        }
+
+        // a shorter file was truncated or rotated: start over
+        if body.len() < self.seen || !body.is_char_boundary(self.seen) {
+            self.seen = 0;
+        }
+        let fresh = &body[self.seen..];
+        let complete = fresh.rfind('\n').map_or(0, |i| i + 1);
+        self.seen += complete;
+        fresh[..complete].to_string()
+    }
+}
+
+/// Percent-encoding for a query value.
+fn encode(s: &str) -> String {
+    s.bytes()
+        .map(|b| match b {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'_' | b'.' | b'~' => {
+                (b as char).to_string()
+            }
+            _ => format!("%{b:02X}"),
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn feed_batches_pauses_and_drops_the_oldest() {
+        let mut feed = Feed::default();
+        feed.push("a\r\n\nb\n");
+        feed.push("c");
+        assert_eq!((feed.received, feed.queued()), (3, 3));
+        assert_eq!(feed.take(2).as_deref(), Some("a\nb"));
+
+        feed.paused = true;
+        assert!(feed.holding());
+        assert_eq!(feed.take(10), None);
+        for i in 0..QUEUE + 5 {
+            feed.push(&i.to_string());
+        }
+        assert_eq!((feed.queued(), feed.dropped), (QUEUE, 6));
+
+        feed.paused = false;
+        let batch = feed.take(BATCH).unwrap();
+        assert_eq!(batch.lines().next(), Some("5"));
+        assert_eq!(batch.lines().count(), BATCH);
+
+        feed.keep = 10;
+        feed.reset();
+        assert_eq!((feed.queued(), feed.received, feed.keep), (0, 0, 10));
+    }
+
+    #[test]
+    fn cursor_follows_json_header_and_growing_file_endpoints() {
+        let mut c = Cursor::default();
+        assert_eq!(c.url("http://h/logs"), "http://h/logs");
+        let text = c.advance(
+            r#"{"lines": ["a", {"level": "INFO"}], "cursor": "p 2/3"}"#,
+            None,
+        );
+        assert_eq!(text, "a\n{\"level\":\"INFO\"}");
+        assert_eq!(
+            c.url("http://h/logs?app=x"),
+            "http://h/logs?app=x&cursor=p%202%2F3"
+        );
+
+        let mut c = Cursor::default();
+        assert_eq!(c.advance("x\ny", Some("41".into())), "x\ny");
+        assert_eq!(c.url("/tail"), "/tail?cursor=41");
+
+        // a growing file: a half-written line waits for the next poll
+        let mut c = Cursor::default();
+        assert_eq!(c.advance("one\ntw", None), "one\n");
+        assert_eq!(c.advance("one\ntwo\nthr", None), "two\n");
+        assert_eq!(c.advance("one\ntwo\nthr", None), "");
+        assert_eq!(c.advance("new\n", None), "new\n");
+        assert_eq!(c.url("/app.log"), "/app.log");
+    }
+}
@@ -3,15 +3,56 @@
 // - Live streaming log tail simulator (Interval timer)
 // - Saved filter presets (localStorage)
 // - Highlighted match navigation (next/prev match) with scrollIntoView
+// - Pluggable line parsers: JSON, logfmt, access logs, syslog, Kubernetes/CRI, custom regex
+// - Multi-line grouping: stack traces fold into their parent entry (collapse/expand in preview)
+// - Query language: level>=WARN AND service:orders AND NOT message~"health"
+// - Timestamps: level histogram timeline, brush to narrow the time window, relative offsets
+// - Correlate tab: group by traceId / request_id, per-group timeline, span waterfall
+// - Large files: open / drop (.gz too), incremental parsing, windowed preview
+// - Templates tab: variable tokens masked, messages clustered, click a template to filter
+// - Live sources: WebSocket, Server-Sent Events, HTTP polling; pause/resume, ring buffer
+// - Aggregate tab: top values, numeric stats / percentiles / distribution, group by a second field
+// - Export: CSV / NDJSON downloads, pinned entries + notes, investigation bundles (export / import)
 //
 // Fixes applied (to keep this working version compiling on Yew 0.21):
 // - TailMode now derives Debug (required by format!("{:?}", *tail_mode))
 // - Live tail use_effect_with teardown now returns a single closure type (no mismatched closures)
-
+// - Live tail appends through a shared buffer (state handles captured by the interval went stale)
+
+mod aggregate;
+mod correlate;
+mod export;
+mod group;
+mod ingest;
+mod live;
+mod parse;
+mod query;
+mod rows;
+mod template;
+mod time;
+
+use std::cell::RefCell;
+use std::collections::{BTreeSet, HashSet};
+use std::rc::Rc;
+
+use futures::future::{AbortHandle, Abortable};
+use futures::StreamExt;
+use gloo_file::callbacks::FileReader;
+use gloo_net::eventsource::futures::EventSource;
+use gloo_net::eventsource::State as EventSourceState;
+use gloo_net::http::Request;
+use gloo_net::websocket::futures::WebSocket;
+use gloo_net::websocket::{Message, WebSocketError};
 use gloo_timers::callback::Interval;
+use gloo_timers::future::TimeoutFuture;
+use group::{FoldRules, Grouper};
+use parse::{format_from_debug, format_label, Format, LineParser, FORMATS};
 use regex::Regex;
+use rows::Rows;
 use serde::{Deserialize, Serialize};
+use time::Histogram;
 use serde_json::Value;
+use web_sys::wasm_bindgen::JsCast;
 use web_sys::{window, Storage};
 use yew::prelude::*;
 
@@ -21,12 +62,18 @@ const LS_KEY_PRESETS: &str = "loglens_presets_v1";
 enum Tab {
     Explore,
     Extract,
+    Correlate,
+    Templates,
+    Aggregate,
 }
 
 fn tab_label(t: Tab) -> &'static str {
     match t {
         Tab::Explore => "Explore",
         Tab::Extract => "Extract",
+        Tab::Correlate => "Correlate",
+        Tab::Templates => "Templates",
+        Tab::Aggregate => "Aggregate",
     }
 }
 
@@ -41,13 +88,43 @@ async fn copy_to_clipboard(text: String) -> Result<(), String> {
     Ok(())
 }
 
+/// Save `content` as a file through a temporary link.
+fn download(name: &str, mime: &str, content: &str) -> Result<(), String> {
+    let url = gloo_file::ObjectUrl::from(gloo_file::Blob::new_with_options(content, Some(mime)));
+    let a = window()
+        .and_then(|w| w.document())
+        .and_then(|d| d.create_element("a").ok())
+        .ok_or("No document".to_string())?
+        .unchecked_into::<web_sys::HtmlAnchorElement>();
+    a.set_href(&url);
+    a.set_download(name);
+    a.click();
+    // the browser reads the blob after this returns
+    gloo_timers::callback::Timeout::new(10_000, move || drop(url)).forget();
+    Ok(())
+}
+
 #[derive(Clone)]
 struct Entry {
     idx: usize,
+    // byte offset of the first line in the buffer text
+    offset: usize,
     raw: String,
     is_json: bool,
+    // pretty-printed first line when it is JSON
     json_pretty: Option<String>,
     level: Option<String>,
+    // physical lines folded into this entry (1 = no continuation)
+    lines: usize,
+    // which parser matched: json / logfmt / access / syslog / kubernetes / custom / text
+    format: &'static str,
+    timestamp: Option<String>,
+    // epoch ms (UTC) parsed from `timestamp`
+    ts_ms: Option<i64>,
+    // structured fields (always an object) for extract_field
+    fields: Value,
+    // the message with variable tokens masked (template.rs)
+    template: String,
 }
 
 fn detect_level(s: &str) -> Option<String> {
@@ -68,29 +145,71 @@ fn detect_level(s: &str) -> Option<String> {
     None
 }
 
-fn parse_entries(input: &str) -> Vec<Entry> {
-    let mut out = vec![];
-    for (idx, line) in input.lines().enumerate() {
-        let raw = line.to_string();
-        let trimmed = line.trim();
-        if trimmed.is_empty() {
-            continue;
-        }
+fn parse_entry(
+    idx: usize,
+    offset: usize,
+    lines: &[&str],
+    parsers: &[Box<dyn LineParser>],
+    masker: &template::Masker,
+) -> Entry {
+    let raw = lines.join("\n");
+    // format and fields come from the first line; the rest is trace text
+    let trimmed = lines[0].trim();
+
+    let (is_json, pretty) = match serde_json::from_str::<Value>(trimmed) {
+        Ok(v) => (true, serde_json::to_string_pretty(&v).ok()),
+        Err(_) => (false, None),
+    };
 
-        let (is_json, pretty) = match serde_json::from_str::<Value>(trimmed) {
-            Ok(v) => (true, serde_json::to_string_pretty(&v).ok()),
-            Err(_) => (false, None),
-        };
+    let p = parse::parse_line(parsers, trimmed);
+    let level = p
+        .level
+        .or_else(|| detect_level(trimmed))
+        .or_else(|| detect_level(&raw));
+    let template = masker.mask(p.message.as_deref().unwrap_or(trimmed).lines().next().unwrap_or(""));
+    Entry {
+        idx,
+        offset,
+        raw,
+        is_json,
+        json_pretty: pretty,
+        level,
+        lines: lines.len(),
+        format: p.format,
+        timestamp: p.timestamp,
+        // filled by the buffer, which knows the year for syslog dates
+        ts_ms: None,
+        fields: Value::Object(p.fields),
+        template,
+    }
+}
 
-        out.push(Entry {
-            idx,
-            raw,
-            is_json,
-            json_pretty: pretty,
-            level: detect_level(trimmed),
-        });
+impl query::Record for Entry {
+    fn field(&self, path: &str) -> Option<String> {
+        match path {
+            "level" => self.level.clone(),
+            "format" => Some(self.format.to_string()),
+            "template" => Some(self.template.clone()),
+            "raw" | "text" => Some(self.raw.clone()),
+            _ => {
+                // flat keys may contain dots (logfmt `http.status`); try them first
+                let found = match self.fields.get(path) {
+                    Some(Value::String(s)) => Some(s.clone()),
+                    Some(Value::Null) | None => extract_field(&self.fields, path),
+                    Some(other) => Some(other.to_string()),
+                };
+                match path {
+                    "message" | "msg" => found.or_else(|| Some(self.raw.clone())),
+                    "timestamp" => found.or_else(|| self.timestamp.clone()),
+                    _ => found,
+                }
+            }
+        }
+    }
+
+    fn text(&self) -> &str {
+        &self.raw
     }
-    out
 }
 
 fn extract_field(v: &Value, path: &str) -> Option<String> {
@@ -113,7 +232,7 @@ fn extract_field(v: &Value, path: &str) -> Option<String> {
 
 // ---------- presets (localStorage) ----------
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 struct Preset {
     name: String,
     level: String,
@@ -122,6 +241,9 @@ struct Preset {
     hl_enabled: bool,
     hl_case_insensitive: bool,
     hl_pat: String,
+    // older presets predate the query language
+    #[serde(default)]
+    query: String,
 }
 
 fn get_storage() -> Option<Storage> {
@@ -150,6 +272,19 @@ enum TailMode {
     DemoMixed,
     DemoJsonl,
     DemoErrors,
+    DemoTraces,
+    WebSocket,
+    Sse,
+    Poll,
+}
+
+impl TailMode {
+    fn is_demo(self) -> bool {
+        matches!(
+            self,
+            TailMode::DemoMixed | TailMode::DemoJsonl | TailMode::DemoErrors | TailMode::DemoTraces
+        )
+    }
 }
 
 fn tail_mode_label(m: TailMode) -> &'static str {
@@ -158,13 +293,17 @@ fn tail_mode_label(m: TailMode) -> &'static str {
         TailMode::DemoMixed => "Demo: mixed",
         TailMode::DemoJsonl => "Demo: JSONL",
         TailMode::DemoErrors => "Demo: errors",
+        TailMode::DemoTraces => "Demo: traces",
+        TailMode::WebSocket => "WebSocket",
+        TailMode::Sse => "Server-Sent Events",
+        TailMode::Poll => "HTTP poll",
     }
 }
 
 fn gen_tail_line(mode: TailMode, n: u64) -> String {
     // deterministic, no rand dependency
     match mode {
-        TailMode::Off => "".to_string(),
+        TailMode::Off | TailMode::WebSocket | TailMode::Sse | TailMode::Poll => "".to_string(),
         TailMode::DemoMixed => match n % 5 {
             0 => format!("2026-02-24T20:11:{:02}Z INFO Server health check OK", (n % 60)),
             1 => format!(
@@ -187,7 +326,17 @@ fn gen_tail_line(mode: TailMode, n: u64) -> String {
                 (n * 11) & 0xffff,
                 1000 + (n % 50)
             ),
-            _ => "java.sql.SQLException: Timeout while waiting for connection".to_string(),
+            _ => format!(
+                "2026-02-24T20:11:{:02}Z ERROR Order sync failed for order {}\n\
+                 java.sql.SQLException: Timeout while waiting for connection\n\
+                 \tat com.zaxxer.hikari.pool.HikariPool.getConnection(HikariPool.java:181)\n\
+                 \tat com.acme.orders.OrderRepo.find(OrderRepo.java:42)\n\
+                 \tat com.acme.orders.SyncJob.run(SyncJob.java:77)\n\
+                 Caused by: java.net.SocketTimeoutException: Read timed out\n\
+                 \t... 12 more",
+                (n % 60),
+                5000 + (n % 90)
+            ),
         },
         TailMode::DemoJsonl => format!(
             r#"{{"timestamp":"2026-02-24T20:11:{:02}Z","level":"INFO","service":"orders","request_id":"req-{:06x}","traceId":"tr-{:04x}","userId":"u{}","spanId":"sp-{:02}","message":"Order lookup started"}}"#,
@@ -207,8 +356,129 @@ fn gen_tail_line(mode: TailMode, n: u64) -> String {
                 1200 + (n % 2500)
             ),
             1 => "ERROR Failed to process request after timeout".to_string(),
-            _ => "Traceback (most recent call last):".to_string(),
+            _ => "Traceback (most recent call last):\n  \
+                  File \"/app/worker.py\", line 42, in handle\n    \
+                  rows = db.fetch(order_id)\n  \
+                  File \"/app/db.py\", line 118, in fetch\n    \
+                  raise TimeoutError(\"database timeout\")\n\
+                  TimeoutError: database timeout"
+                .to_string(),
         },
+        TailMode::DemoTraces => {
+            // seven lines per request: gateway → auth, orders → db and back
+            let (t, step) = (n / 7, n % 7);
+            let slow = t % 4 == 3;
+            let db_ms = if slow { 640 } else { 60 + (t % 5) * 20 };
+            let (service, span, parent, level, message, at_ms) = match step {
+                0 => ("gateway", "a", None, "INFO", "GET /api/v1/orders", 0),
+                1 => ("auth-api", "b", Some("a"), "DEBUG", "Token verified", 12),
+                2 => ("orders", "c", Some("a"), "INFO", "Order lookup started", 31),
+                3 => ("orders-db", "d", Some("c"), "DEBUG", "SELECT * FROM orders", 45),
+                4 => (
+                    "orders-db",
+                    "d",
+                    Some("c"),
+                    if slow { "ERROR" } else { "DEBUG" },
+                    if slow { "Query timeout" } else { "Query done" },
+                    45 + db_ms,
+                ),
+                5 => ("orders", "c", Some("a"), "INFO", "Order lookup finished", 60 + db_ms),
+                _ => ("gateway", "a", None, if slow { "WARN" } else { "INFO" }, "Response sent", 75 + db_ms),
+            };
+            let parent = parent
+                .map(|p| format!(r#","parentSpanId":"sp-{t:04x}{p}""#))
+                .unwrap_or_default();
+            format!(
+                r#"{{"timestamp":"2026-02-24T20:12:{:02}.{:03}Z","level":"{level}","service":"{service}","traceId":"tr-{:04x}","spanId":"sp-{t:04x}{span}"{parent},"message":"{message}"}}"#,
+                (t * 2) % 60 + at_ms / 1000,
+                at_ms % 1000,
+                (t * 41 + 7) & 0xffff,
+            )
+        }
+    }
+}
+
+// ---------- live sources ----------
+
+// Each runs until the connection ends or the live tail effect aborts it.
+
+fn set_status(feed: &RefCell<live::Feed>, redraw: &UseForceUpdateHandle, status: String) {
+    feed.borrow_mut().status = status;
+    redraw.force_update();
+}
+
+async fn tail_websocket(url: String, feed: Rc<RefCell<live::Feed>>, redraw: UseForceUpdateHandle) {
+    let mut ws = match WebSocket::open(&url) {
+        Ok(ws) => ws,
+        Err(e) => return set_status(&feed, &redraw, format!("error: {e}")),
+    };
+    set_status(&feed, &redraw, "connecting".to_string());
+    while let Some(msg) = ws.next().await {
+        let text = match msg {
+            Ok(Message::Text(t)) => t,
+            Ok(Message::Bytes(b)) => String::from_utf8_lossy(&b).into_owned(),
+            Err(WebSocketError::ConnectionClose(e)) => {
+                return set_status(&feed, &redraw, format!("closed ({})", e.code));
+            }
+            Err(e) => return set_status(&feed, &redraw, format!("error: {e}")),
+        };
+        let mut f = feed.borrow_mut();
+        f.push(&text);
+        f.status = "open".to_string();
+    }
+    set_status(&feed, &redraw, "closed".to_string());
+}
+
+async fn tail_sse(url: String, feed: Rc<RefCell<live::Feed>>, redraw: UseForceUpdateHandle) {
+    let subscribed = EventSource::new(&url).and_then(|mut es| {
+        let sub = es.subscribe("message")?;
+        Ok((es, sub))
+    });
+    let (es, mut sub) = match subscribed {
+        Ok(pair) => pair,
+        Err(e) => return set_status(&feed, &redraw, format!("error: {e}")),
+    };
+    set_status(&feed, &redraw, "connecting".to_string());
+    while let Some(event) = sub.next().await {
+        match event {
+            Ok((_, e)) => {
+                let mut f = feed.borrow_mut();
+                f.push(&e.data().as_string().unwrap_or_default());
+                f.status = "open".to_string();
+            }
+            // the browser retries on its own unless the stream is unusable
+            Err(_) if matches!(es.state(), EventSourceState::Closed) => break,
+            Err(_) => set_status(&feed, &redraw, "reconnecting".to_string()),
+        }
+    }
+    set_status(&feed, &redraw, "closed".to_string());
+}
+
+async fn tail_poll(url: String, every_ms: u32, feed: Rc<RefCell<live::Feed>>, redraw: UseForceUpdateHandle) {
+    let mut cursor = live::Cursor::default();
+    loop {
+        // paused or behind: the server keeps the data, so just wait
+        let holding = feed.borrow().holding();
+        if !holding {
+            let status = match Request::get(&cursor.url(&url)).send().await {
+                Ok(resp) if resp.ok() => {
+                    let header = resp.headers().get("x-cursor");
+                    match resp.text().await {
+                        Ok(body) => {
+                            feed.borrow_mut().push(&cursor.advance(&body, header));
+                            "polling".to_string()
+                        }
+                        Err(e) => format!("error: {e}"),
+                    }
+                }
+                Ok(resp) => format!("HTTP {} — retrying", resp.status()),
+                Err(e) => format!("error: {e} — retrying"),
+            };
+            if feed.borrow().status != status {
+                set_status(&feed, &redraw, status);
+            }
+        }
+        TimeoutFuture::new(every_ms).await;
     }
 }
 
@@ -250,6 +520,195 @@ fn highlight_line(
     (html! { <>{ for out }</> }, count)
 }
 
+// ---------- histogram timeline ----------
+
+const HIST_BUCKETS: usize = 60;
+
+fn histogram_view(
+    h: &Histogram,
+    selected: Option<(i64, i64)>,
+    brush: Option<(usize, usize)>,
+    on_down: Callback<usize>,
+    on_enter: Callback<usize>,
+    on_up: Callback<usize>,
+) -> Html {
+    const W: usize = 10;
+    const H: f64 = 60.0;
+    let tallest = h.tallest().max(1) as f64;
+    let n = h.buckets.len();
+
+    let cols = h.buckets.iter().enumerate().map(|(i, counts)| {
+        let x = (i * W).to_string();
+        let (lo, hi) = h.range(i, i);
+        let in_brush = brush.is_some_and(|(a, b)| (a.min(b)..=a.max(b)).contains(&i));
+        let in_window = selected.is_some_and(|(from, to)| lo <= to && hi >= from);
+        let mut y = H;
+        let bars = counts.iter().enumerate().filter(|(_, c)| **c > 0).map(|(band, c)| {
+            let bh = (*c as f64 / tallest * (H - 4.0)).max(1.0);
+            y -= bh;
+            html! {
+              <rect class={format!("hb-{}", time::BANDS[band])} x={(i * W + 1).to_string()} y={format!("{y:.2}")}
+                width={(W - 2).to_string()} height={format!("{bh:.2}")} />
+            }
+        }).collect::<Vec<_>>();
+        let total: usize = counts.iter().sum();
+        let title = format!("{} • {} entries", time::format_ms(lo), total);
+        let (on_down, on_enter, on_up) = (on_down.clone(), on_enter.clone(), on_up.clone());
+        html! {
+          <g>
+            if in_brush || in_window {
+              <rect class="hb-sel" x={x.clone()} y="0" width={W.to_string()} height={H.to_string()} />
+            }
+            { for bars }
+            <rect class="hb-hit" x={x} y="0" width={W.to_string()} height={H.to_string()}
+              onpointerdown={Callback::from(move |e: PointerEvent| { e.prevent_default(); on_down.emit(i) })}
+              onpointerenter={Callback::from(move |_| on_enter.emit(i))}
+              onpointerup={Callback::from(move |_| on_up.emit(i))}>
+              <title>{ title }</title>
+            </rect>
+          </g>
+        }
+    });
+
+    html! {
+      <div class="hist">
+        <svg viewBox={format!("0 0 {} {}", n * W, H)} preserveAspectRatio="none">
+          { for cols }
+        </svg>
+        <div class="hist-axis">
+          <span>{ time::format_ms(h.start) }</span>
+          <span>{ format!("{} buckets", time::format_offset(h.width).trim_start_matches('+')) }</span>
+          <span>{ time::format_ms(h.start + n as i64 * h.width - 1) }</span>
+        </div>
+      </div>
+    }
+}
+
+// ---------- filtered view + windowed preview ----------
+
+// Indices into the buffer's entries that pass the filters.
+struct Filtered {
+    // every filter but the time window; the histogram is drawn from these
+    untimed: Vec<usize>,
+    shown: Vec<usize>,
+    histogram: Option<Histogram>,
+}
+
+impl Filtered {
+    fn entries<'a>(&'a self, all: &'a [Entry]) -> impl Iterator<Item = &'a Entry> + 'a {
+        self.shown.iter().filter_map(|&i| all.get(i))
+    }
+}
+
+// Preview rows are fixed-height (no wrapping); must match `.vlist-rows`.
+const ROW_PX: f64 = 18.0;
+const VIEW_ROWS: usize = 30;
+const OVERSCAN: usize = 12;
+// Browsers cap element heights (~17.9M px in Firefox); taller lists scale the scrollbar.
+const MAX_SCROLL_PX: f64 = 15_000_000.0;
+// File loads are parsed in pieces, yielding to the browser in between.
+const LOAD_CHUNK: usize = 2 << 20;
+
+// What the preview prints under an entry's header line.
+fn entry_payload(e: &Entry, open: bool) -> String {
+    let mut raw_lines = e.raw.lines();
+    let head = raw_lines.next().unwrap_or_default();
+    let mut payload = if e.is_json {
+        e.json_pretty.clone().unwrap_or_else(|| head.to_string())
+    } else {
+        head.to_string()
+    };
+    if open {
+        for line in raw_lines {
+            payload.push('\n');
+            payload.push_str(line);
+        }
+    }
+    payload
+}
+
+// Header + payload + blank separator, without building the payload.
+fn entry_rows(e: &Entry, open: bool) -> usize {
+    let head = match (e.is_json, &e.json_pretty) {
+        (true, Some(p)) => p.lines().count().max(1),
+        _ => 1,
+    };
+    let rest = if open { e.lines - 1 } else { 0 };
+    head + rest + 2
+}
+
+/// One NDJSON record: JSON entries as written, others as an object.
+fn entry_ndjson(e: &Entry) -> String {
+    if e.is_json && e.lines == 1 {
+        return e.raw.trim().to_string();
+    }
+    let mut o = e.fields.as_object().cloned().unwrap_or_default();
+    o.insert("line".to_string(), e.idx.into());
+    for (k, v) in [
+        ("timestamp", e.timestamp.clone()),
+        ("level", e.level.clone()),
+        ("message", query::Record::field(e, "message")),
+    ] {
+        if let Some(v) = v {
+            o.entry(k).or_insert(v.into());
+        }
+    }
+    Value::Object(o).to_string()
+}
+
+fn set_preview_scroll(top: f64) {
+    if let Some(el) = window()
+        .and_then(|w| w.document())
+        .and_then(|d| d.get_element_by_id("preview"))
+    {
+        el.set_scroll_top(top as i32);
+    }
+}
+
+/// Parse `text` into the buffer a chunk at a time, yielding to the browser in
+/// between; another load or Clear cancels it.
+fn load_in_chunks(
+    name: String,
+    text: String,
+    parsers: Vec<Box<dyn LineParser>>,
+    grouper: Grouper,
+    buffer: Rc<RefCell<ingest::Buffer>>,
+    redraw: UseForceUpdateHandle,
+    msg: UseStateHandle<String>,
+) {
+    let epoch = {
+        let mut buf = buffer.borrow_mut();
+        buf.clear();
+        buf.epoch
+    };
+    wasm_bindgen_futures::spawn_local(async move {
+        let total = text.len().max(1);
+        let mut done = 0;
+        for chunk in ingest::chunks(&text, LOAD_CHUNK) {
+            {
+                let mut buf = buffer.borrow_mut();
+                // Clear or another file took over
+                if buf.epoch != epoch {
+                    return;
+                }
+                buf.append(chunk, &parsers, &grouper);
+                done += chunk.len();
+                msg.set(format!(
+                    "Loading {name}: {}% • {} entries",
+                    done * 100 / total,
+                    buf.entries.len()
+                ));
+            }
+            redraw.force_update();
+            TimeoutFuture::new(0).await;
+        }
+        msg.set(format!(
+            "Loaded {name}: {} entries.",
+            buffer.borrow().entries.len()
+        ));
+    });
+}
+
 fn scroll_to_match(idx: usize) {
     let Some(w) = window() else { return; };
     let Some(doc) = w.document() else { return; };
@@ -263,105 +722,178 @@ fn scroll_to_match(idx: usize) {
 fn app() -> Html {
     let tab = use_state(|| Tab::Explore);
 
-    // input + parsed
-    let log_in = use_state(|| String::new());
-    let parsed = use_state(|| Vec::<Entry>::new());
+    // pasted input; parsed entries live in a buffer that grows in place
+    let log_in = use_state(String::new);
+    let buffer = use_mut_ref(ingest::Buffer::default);
+    let redraw = use_force_update();
+    // loaded file name + size (None = pasted text / live tail)
+    let source = use_state(|| None::<String>);
+    let reader = use_state(|| None::<FileReader>);
 
     // filters
     let want_level = use_state(|| "ANY".to_string());
-    let needle = use_state(|| String::new());
+    let needle = use_state(String::new);
     let show_json_only = use_state(|| false);
+    let query_text = use_state(String::new);
 
     // extract tab
     let field_list = use_state(|| "request_id\ntraceId\nuserId\nspanId".to_string());
-    let extracted_out = use_state(|| String::new());
+    let extracted_out = use_state(String::new);
 
     // highlight
-    let hl_pat = use_state(|| String::new());
+    let hl_pat = use_state(String::new);
     let hl_enabled = use_state(|| false);
     let hl_case_insensitive = use_state(|| true);
 
     // match navigation
     let current_match = use_state(|| None::<usize>);
-    let total_matches = use_state(|| 0usize);
+    let scroll_top = use_state(|| 0f64);
 
     // presets
-    let presets = use_state(|| load_presets());
+    let presets = use_state(load_presets);
     let preset_name = use_state(|| "My preset".to_string());
 
     // live tail
     let tail_mode = use_state(|| TailMode::Off);
     let tail_rate_ms = use_state(|| 650u32);
-    let tail_counter = use_state(|| 0u64);
+    let tail_counter = use_mut_ref(|| 0u64);
+    let tail_url = use_state(String::new);
+    let feed = use_mut_ref(live::Feed::default);
+
+    // line format
+    let log_format = use_state(|| Format::Auto);
+    let custom_format = use_state(String::new);
+
+    // multi-line grouping
+    let fold_rules = use_state(FoldRules::default);
+    let expanded = use_state(HashSet::<usize>::new);
+
+    // investigation: pinned entries (by line) and free-form notes
+    let pinned = use_state(BTreeSet::<usize>::new);
+    let notes = use_state(String::new);
+
+    // time window (epoch ms, inclusive) + histogram brush (bucket indices)
+    let time_window = use_state(|| None::<(i64, i64)>);
+    let brush = use_state(|| None::<(usize, usize)>);
+    let rel_time = use_state(|| false);
+
+    // correlate (key spec: comma-separated alternatives) + the open group
+    let corr_key = use_state(|| "traceId, trace_id".to_string());
+    let corr_open = use_state(|| None::<String>);
+
+    // aggregate: field, optional group-by field, values listed per summary
+    let agg_field = use_state(|| "level".to_string());
+    let agg_by = use_state(String::new);
+    let agg_top = use_state(|| 10usize);
 
     // status msg
-    let msg = use_state(|| String::new());
+    let msg = use_state(String::new);
 
     // --- effects ---
 
     // Reset current match when filters/highlight inputs change
     {
         let current_match = current_match.clone();
-        let total_matches = total_matches.clone();
         let deps = (
             (*want_level).clone(),
             (*needle).clone(),
             *show_json_only,
+            (*query_text).clone(),
+            *time_window,
             (*hl_pat).clone(),
             *hl_enabled,
             *hl_case_insensitive,
-            (*parsed).len(),
+            buffer.borrow().entries.len(),
         );
         use_effect_with(deps, move |_| {
             current_match.set(None);
-            total_matches.set(0);
             || ()
         });
     }
 
-    // Live tail simulator interval
+    // Live tail: the source fills the feed, a timer drains it into the buffer
     {
-        let tail_mode = tail_mode.clone();
-        let tail_rate_ms = tail_rate_ms.clone();
         let tail_counter = tail_counter.clone();
-        let log_in = log_in.clone();
-        let parsed = parsed.clone();
-        let msg = msg.clone();
+        let feed = feed.clone();
+        let buffer = buffer.clone();
+        let redraw = redraw.clone();
 
-        let deps = (*tail_mode, *tail_rate_ms);
+        // a WebSocket or SSE stream ignores the rate, a demo ignores the URL
+        let mode = *tail_mode;
+        let deps = (
+            mode,
+            if matches!(mode, TailMode::WebSocket | TailMode::Sse) { 0 } else { *tail_rate_ms },
+            if mode.is_demo() { String::new() } else { tail_url.trim().to_string() },
+            *log_format,
+            (*custom_format).clone(),
+            (*fold_rules).clone(),
+        );
 
         // FIX: single teardown closure type (no early return with a different closure)
-        use_effect_with(deps, move |(mode, rate)| {
-            let mut interval: Option<Interval> = None;
-
-            if *mode != TailMode::Off {
-                let m = *mode;
-                let r = *rate;
-
-                interval = Some(Interval::new(r, move || {
-                    let n = *tail_counter;
-                    tail_counter.set(n + 1);
-
-                    let line = gen_tail_line(m, n);
-                    if line.trim().is_empty() {
-                        return;
-                    }
+        use_effect_with(deps, move |(mode, rate, url, format, custom, rules)| {
+            let mut timers: Vec<Interval> = Vec::new();
+            let mut task: Option<AbortHandle> = None;
+            // a bad custom regex falls back to auto-detect while tailing
+            let parsers = parse::parsers(*format, custom)
+                .or_else(|_| parse::parsers(Format::Auto, ""))
+                .unwrap_or_default();
+            let grouper = Grouper::new(rules);
+            feed.borrow_mut().reset();
+
+            let (m, r) = (*mode, *rate);
+            if m.is_demo() {
+                feed.borrow_mut().status = format!("{} @ {}ms", tail_mode_label(m), r);
+                let feed = feed.clone();
+                timers.push(Interval::new(r, move || {
+                    let n = *tail_counter.borrow();
+                    *tail_counter.borrow_mut() = n + 1;
+                    feed.borrow_mut().push(&gen_tail_line(m, n));
+                }));
+            } else if m != TailMode::Off && url.is_empty() {
+                feed.borrow_mut().status = "enter a URL".to_string();
+            } else if m != TailMode::Off {
+                let (handle, registration) = AbortHandle::new_pair();
+                let (url, feed, redraw) = (url.clone(), feed.clone(), redraw.clone());
+                wasm_bindgen_futures::spawn_local(async move {
+                    let source = async move {
+                        match m {
+                            TailMode::WebSocket => tail_websocket(url, feed, redraw).await,
+                            TailMode::Sse => tail_sse(url, feed, redraw).await,
+                            _ => tail_poll(url, r, feed, redraw).await,
+                        }
+                    };
+                    let _ = Abortable::new(source, registration).await;
+                });
+                task = Some(handle);
+            }
 
-                    let mut cur = (*log_in).clone();
-                    if !cur.is_empty() && !cur.ends_with('\n') {
-                        cur.push('\n');
+            if m != TailMode::Off {
+                let mut shown = 0;
+                timers.push(Interval::new(live::DRAIN_MS, move || {
+                    let mut f = feed.borrow_mut();
+                    match f.take(live::BATCH) {
+                        // only the new lines (and the entry they may continue) are parsed
+                        Some(lines) => {
+                            let mut buf = buffer.borrow_mut();
+                            buf.append_line(&lines, &parsers, &grouper);
+                            f.trimmed += buf.trim_front(f.keep) as u64;
+                        }
+                        // paused: redraw only for the queue counter
+                        None if f.received == shown => return,
+                        None => {}
                     }
-                    cur.push_str(&line);
-                    log_in.set(cur);
-
-                    // auto-parse on each tick for “live” feel
-                    let entries = parse_entries(&log_in);
-                    parsed.set(entries);
-                    msg.set(format!("Live tail: {} @ {}ms", tail_mode_label(m), r));
+                    shown = f.received;
+                    drop(f);
+                    redraw.force_update();
                 }));
             }
 
-            move || drop(interval)
+            move || {
+                drop(timers);
+                if let Some(task) = task {
+                    task.abort();
+                }
+            }
         });
     }
 
@@ -384,45 +916,216 @@ fn app() -> Html {
 
     let on_parse = {
         let log_in = log_in.clone();
-        let parsed = parsed.clone();
+        let buffer = buffer.clone();
+        let redraw = redraw.clone();
+        let source = source.clone();
+        let log_format = log_format.clone();
+        let custom_format = custom_format.clone();
+        let fold_rules = fold_rules.clone();
+        let pinned = pinned.clone();
         let msg = msg.clone();
         Callback::from(move |_| {
-            let entries = parse_entries(&log_in);
-            let json_count = entries.iter().filter(|e| e.is_json).count();
+            let parsers = match parse::parsers(*log_format, &custom_format) {
+                Ok(p) => p,
+                Err(e) => {
+                    msg.set(e);
+                    return;
+                }
+            };
+            let grouper = Grouper::new(&fold_rules);
+            let mut buf = buffer.borrow_mut();
+            // an empty textarea re-parses what is loaded (file or live tail)
+            if log_in.trim().is_empty() {
+                buf.reparse(&parsers, &grouper);
+            } else {
+                buf.clear();
+                buf.append(&log_in, &parsers, &grouper);
+                source.set(None);
+                pinned.set(BTreeSet::new());
+            }
+            let entries = &buf.entries;
             let total = entries.len();
-            parsed.set(entries);
-            msg.set(format!("Parsed {total} entries ({json_count} JSON lines detected)."));
+            let folded = entries.iter().filter(|e| e.lines > 1).count();
+            let mut by_format: Vec<(&str, usize)> = Vec::new();
+            for e in entries {
+                match by_format.iter_mut().find(|(f, _)| *f == e.format) {
+                    Some((_, n)) => *n += 1,
+                    None => by_format.push((e.format, 1)),
+                }
+            }
+            let breakdown = by_format
+                .iter()
+                .map(|(f, n)| format!("{n} {f}"))
+                .collect::<Vec<_>>()
+                .join(", ");
+            msg.set(format!(
+                "Parsed {total} entries ({breakdown}; {folded} multi-line)."
+            ));
+            redraw.force_update();
         })
     };
 
-    let filtered_entries = {
-        let entries = (*parsed).clone();
-        let lv = (*want_level).clone();
-        let n = needle.trim().to_lowercase();
-        let json_only = *show_json_only;
-
-        entries
-            .into_iter()
-            .filter(|e| {
-                if json_only && !e.is_json {
-                    return false;
+    let on_load_file = {
+        let buffer = buffer.clone();
+        let redraw = redraw.clone();
+        let reader = reader.clone();
+        let source = source.clone();
+        let log_in = log_in.clone();
+        let log_format = log_format.clone();
+        let custom_format = custom_format.clone();
+        let fold_rules = fold_rules.clone();
+        let pinned = pinned.clone();
+        let msg = msg.clone();
+        Callback::from(move |file: web_sys::File| {
+            let parsers = match parse::parsers(*log_format, &custom_format) {
+                Ok(p) => p,
+                Err(e) => {
+                    msg.set(e);
+                    return;
                 }
-                if lv != "ANY" {
-                    match &e.level {
-                        Some(elv) => {
-                            if elv != &lv {
-                                return false;
+            };
+            let grouper = Grouper::new(&fold_rules);
+            let file = gloo_file::File::from(file);
+            let name = file.name();
+            source.set(Some(format!("{name} ({})", ingest::format_size(file.size()))));
+            log_in.set(String::new());
+            pinned.set(BTreeSet::new());
+            msg.set(format!("Reading {name}…"));
+
+            let buffer = buffer.clone();
+            let redraw = redraw.clone();
+            let msg = msg.clone();
+            let task = gloo_file::callbacks::read_as_bytes(&file, move |res| {
+                let text = match res
+                    .map_err(|e| format!("File read error: {e:?}"))
+                    .and_then(|bytes| ingest::decode(&bytes))
+                {
+                    Ok(t) => t,
+                    Err(e) => {
+                        msg.set(e);
+                        return;
+                    }
+                };
+                load_in_chunks(name, text, parsers, grouper, buffer, redraw, msg);
+            });
+            reader.set(Some(task));
+        })
+    };
+
+    // an invalid query is reported inline and filters nothing
+    let query_result = query::parse(&query_text);
+
+    // Filtering is memoized on the buffer revision: scrolling the preview and
+    // brushing re-render often and must not re-scan every entry.
+    let filter_deps = (
+        buffer.borrow().rev,
+        (*want_level).clone(),
+        needle.trim().to_lowercase(),
+        *show_json_only,
+        (*query_text).clone(),
+        *time_window,
+    );
+    let view = {
+        let buffer = buffer.clone();
+        use_memo(filter_deps.clone(), move |(_, lv, n, json_only, q, window)| {
+            let buf = buffer.borrow();
+            let query = query::parse(q).ok().flatten();
+            let untimed = buf
+                .entries
+                .iter()
+                .enumerate()
+                .filter(|(_, e)| {
+                    if *json_only && !e.is_json {
+                        return false;
+                    }
+                    if lv != "ANY" {
+                        match &e.level {
+                            Some(elv) => {
+                                if elv != lv {
+                                    return false;
+                                }
                             }
+                            None => return false,
                         }
-                        None => return false,
                     }
-                }
-                if !n.is_empty() && !e.raw.to_lowercase().contains(&n) {
-                    return false;
-                }
-                true
-            })
-            .collect::<Vec<_>>()
+                    if !n.is_empty() && !e.raw.to_lowercase().contains(n) {
+                        return false;
+                    }
+                    if let Some(q) = &query {
+                        return q.matches(*e);
+                    }
+                    true
+                })
+                .map(|(i, _)| i)
+                .collect::<Vec<_>>();
+
+            let points = untimed
+                .iter()
+                .filter_map(|&i| {
+                    let e = &buf.entries[i];
+                    Some((e.ts_ms?, time::band(e.level.as_deref())))
+                })
+                .collect::<Vec<_>>();
+            let histogram = Histogram::build(&points, HIST_BUCKETS);
+
+            let shown = match *window {
+                Some((from, to)) => untimed
+                    .iter()
+                    .copied()
+                    .filter(|&i| buf.entries[i].ts_ms.is_some_and(|t| t >= from && t <= to))
+                    .collect(),
+                None => untimed.clone(),
+            };
+            Filtered {
+                untimed,
+                shown,
+                histogram,
+            }
+        })
+    };
+    let histogram = view.histogram.clone();
+    let buf = buffer.borrow();
+    let all = &buf.entries;
+
+    // "relative to first entry": the first entry (in log order) with a timestamp
+    let first_ts = all.iter().find_map(|e| e.ts_ms);
+
+    let on_brush_down = {
+        let brush = brush.clone();
+        Callback::from(move |i: usize| brush.set(Some((i, i))))
+    };
+    let on_brush_enter = {
+        let brush = brush.clone();
+        Callback::from(move |i: usize| {
+            if let Some((a, _)) = *brush {
+                brush.set(Some((a, i)));
+            }
+        })
+    };
+    let on_brush_up = {
+        let brush = brush.clone();
+        let time_window = time_window.clone();
+        let hist = histogram.clone();
+        Callback::from(move |i: usize| {
+            if let (Some((a, _)), Some(h)) = (*brush, hist.as_ref()) {
+                time_window.set(Some(h.range(a, i)));
+            }
+            brush.set(None);
+        })
+    };
+
+    let timeline_html = match &histogram {
+        Some(h) => histogram_view(
+            h,
+            *time_window,
+            *brush,
+            on_brush_down,
+            on_brush_enter,
+            on_brush_up,
+        ),
+        None => html! {
+          <div class="smallnote hist-empty">{ "No parseable timestamps in the current filter — the timeline appears once entries carry time." }</div>
+        },
     };
 
     // compile regex
@@ -448,13 +1151,14 @@ fn app() -> Html {
 
     let on_copy_filtered = {
         let msg = msg.clone();
-        let lines = filtered_entries
-            .iter()
-            .map(|e| e.raw.as_str())
-            .collect::<Vec<_>>()
-            .join("\n");
+        let buffer = buffer.clone();
+        let view = view.clone();
         Callback::from(move |_| {
-            let txt = lines.clone();
+            let txt = view
+                .entries(&buffer.borrow().entries)
+                .map(|e| e.raw.as_str())
+                .collect::<Vec<_>>()
+                .join("\n");
             let msg2 = msg.clone();
             wasm_bindgen_futures::spawn_local(async move {
                 match copy_to_clipboard(txt).await {
@@ -467,13 +1171,14 @@ fn app() -> Html {
 
     let on_export_jsonl = {
         let msg = msg.clone();
-        let lines = filtered_entries
-            .iter()
-            .filter_map(|e| if e.is_json { Some(e.raw.as_str()) } else { None })
-            .collect::<Vec<_>>()
-            .join("\n");
+        let buffer = buffer.clone();
+        let view = view.clone();
         Callback::from(move |_| {
-            let txt = lines.clone();
+            let txt = view
+                .entries(&buffer.borrow().entries)
+                .filter_map(|e| if e.is_json { Some(e.raw.as_str()) } else { None })
+                .collect::<Vec<_>>()
+                .join("\n");
             let msg2 = msg.clone();
             wasm_bindgen_futures::spawn_local(async move {
                 match copy_to_clipboard(txt).await {
@@ -484,11 +1189,218 @@ fn app() -> Html {
         })
     };
 
+    // Downloads; CSV columns are the Extract field list after the basics
+    let on_download_csv = {
+        let msg = msg.clone();
+        let field_list = field_list.clone();
+        let buffer = buffer.clone();
+        let view = view.clone();
+        Callback::from(move |_| {
+            let fields = field_list
+                .lines()
+                .map(|s| s.trim())
+                .filter(|s| !s.is_empty())
+                .collect::<Vec<_>>();
+            let mut out = export::csv_row(["line", "timestamp", "level", "message"].into_iter().chain(fields.iter().copied()));
+            out.push('\n');
+            let mut rows = 0usize;
+            for e in view.entries(&buffer.borrow().entries) {
+                let basics = [
+                    e.idx.to_string(),
+                    e.timestamp.clone().unwrap_or_default(),
+                    e.level.clone().unwrap_or_default(),
+                    query::Record::field(e, "message").unwrap_or_default(),
+                ];
+                let extracted = fields.iter().map(|f| query::Record::field(e, f).unwrap_or_default());
+                out.push_str(&export::csv_row(basics.into_iter().chain(extracted)));
+                out.push('\n');
+                rows += 1;
+            }
+            match download("loglens-filtered.csv", "text/csv", &out) {
+                Ok(()) => msg.set(format!("Downloaded {rows} entries as CSV ({} columns).", 4 + fields.len())),
+                Err(e) => msg.set(e),
+            }
+        })
+    };
+
+    let on_download_ndjson = {
+        let msg = msg.clone();
+        let buffer = buffer.clone();
+        let view = view.clone();
+        Callback::from(move |_| {
+            let mut out = String::new();
+            let mut rows = 0usize;
+            for e in view.entries(&buffer.borrow().entries) {
+                out.push_str(&entry_ndjson(e));
+                out.push('\n');
+                rows += 1;
+            }
+            match download("loglens-filtered.ndjson", "application/x-ndjson", &out) {
+                Ok(()) => msg.set(format!("Downloaded {rows} entries as NDJSON.")),
+                Err(e) => msg.set(e),
+            }
+        })
+    };
+
+    let on_download_bundle = {
+        let msg = msg.clone();
+        let buffer = buffer.clone();
+        let source = source.clone();
+        let log_format = log_format.clone();
+        let custom_format = custom_format.clone();
+        let fold_rules = fold_rules.clone();
+        let preset_name = preset_name.clone();
+        let want_level = want_level.clone();
+        let needle = needle.clone();
+        let show_json_only = show_json_only.clone();
+        let query_text = query_text.clone();
+        let hl_enabled = hl_enabled.clone();
+        let hl_case_insensitive = hl_case_insensitive.clone();
+        let hl_pat = hl_pat.clone();
+        let time_window = time_window.clone();
+        let pinned = pinned.clone();
+        let expanded = expanded.clone();
+        let notes = notes.clone();
+        Callback::from(move |_| {
+            let mut open = expanded.iter().copied().collect::<Vec<_>>();
+            open.sort_unstable();
+            let bundle = export::Bundle {
+                version: export::BUNDLE_VERSION,
+                source: (*source).clone(),
+                input: buffer.borrow().text.clone(),
+                format: format!("{:?}", *log_format),
+                custom_format: (*custom_format).clone(),
+                fold_rules: (*fold_rules).clone(),
+                view: Preset {
+                    name: (*preset_name).trim().to_string(),
+                    level: (*want_level).clone(),
+                    needle: (*needle).clone(),
+                    json_only: *show_json_only,
+                    hl_enabled: *hl_enabled,
+                    hl_case_insensitive: *hl_case_insensitive,
+                    hl_pat: (*hl_pat).clone(),
+                    query: (*query_text).clone(),
+                },
+                time_window: *time_window,
+                pinned: pinned.iter().copied().collect(),
+                expanded: open,
+                notes: (*notes).clone(),
+            };
+            match download("loglens-bundle.json", "application/json", &bundle.to_json()) {
+                Ok(()) => msg.set(format!(
+                    "Saved an investigation bundle ({} entries, {} pinned).",
+                    buffer.borrow().entries.len(),
+                    bundle.pinned.len()
+                )),
+                Err(e) => msg.set(e),
+            }
+        })
+    };
+
+    let on_import_bundle = {
+        let msg = msg.clone();
+        let buffer = buffer.clone();
+        let redraw = redraw.clone();
+        let reader = reader.clone();
+        let source = source.clone();
+        let log_in = log_in.clone();
+        let tail_mode = tail_mode.clone();
+        let log_format = log_format.clone();
+        let custom_format = custom_format.clone();
+        let fold_rules = fold_rules.clone();
+        let preset_name = preset_name.clone();
+        let want_level = want_level.clone();
+        let needle = needle.clone();
+        let show_json_only = show_json_only.clone();
+        let query_text = query_text.clone();
+        let hl_enabled = hl_enabled.clone();
+        let hl_case_insensitive = hl_case_insensitive.clone();
+        let hl_pat = hl_pat.clone();
+        let time_window = time_window.clone();
+        let pinned = pinned.clone();
+        let expanded = expanded.clone();
+        let notes = notes.clone();
+        Callback::from(move |file: web_sys::File| {
+            let file = gloo_file::File::from(file);
+            let name = file.name();
+            msg.set(format!("Reading {name}…"));
+
+            let msg = msg.clone();
+            let buffer = buffer.clone();
+            let redraw = redraw.clone();
+            let source = source.clone();
+            let log_in = log_in.clone();
+            let tail_mode = tail_mode.clone();
+            let log_format = log_format.clone();
+            let custom_format = custom_format.clone();
+            let fold_rules = fold_rules.clone();
+            let preset_name = preset_name.clone();
+            let want_level = want_level.clone();
+            let needle = needle.clone();
+            let show_json_only = show_json_only.clone();
+            let query_text = query_text.clone();
+            let hl_enabled = hl_enabled.clone();
+            let hl_case_insensitive = hl_case_insensitive.clone();
+            let hl_pat = hl_pat.clone();
+            let time_window = time_window.clone();
+            let pinned = pinned.clone();
+            let expanded = expanded.clone();
+            let notes = notes.clone();
+            let task = gloo_file::callbacks::read_as_text(&file, move |res| {
+                let bundle = match res
+                    .map_err(|e| format!("File read error: {e:?}"))
+                    .and_then(|text| export::Bundle::from_json(&text))
+                {
+                    Ok(b) => b,
+                    Err(e) => {
+                        msg.set(e);
+                        return;
+                    }
+                };
+                let format = format_from_debug(&bundle.format);
+                let parsers = match parse::parsers(format, &bundle.custom_format) {
+                    Ok(p) => p,
+                    Err(e) => {
+                        msg.set(e);
+                        return;
+                    }
+                };
+                let grouper = Grouper::new(&bundle.fold_rules);
+
+                tail_mode.set(TailMode::Off);
+                log_in.set(String::new());
+                source.set(Some(match &bundle.source {
+                    Some(from) => format!("{name} — {from}"),
+                    None => name.clone(),
+                }));
+                log_format.set(format);
+                custom_format.set(bundle.custom_format);
+                fold_rules.set(bundle.fold_rules);
+                let v = bundle.view;
+                preset_name.set(v.name);
+                want_level.set(v.level);
+                needle.set(v.needle);
+                show_json_only.set(v.json_only);
+                hl_enabled.set(v.hl_enabled);
+                hl_case_insensitive.set(v.hl_case_insensitive);
+                hl_pat.set(v.hl_pat);
+                query_text.set(v.query);
+                time_window.set(bundle.time_window);
+                pinned.set(bundle.pinned.into_iter().collect());
+                expanded.set(bundle.expanded.into_iter().collect());
+                notes.set(bundle.notes);
+                load_in_chunks(name, bundle.input, parsers, grouper, buffer, redraw, msg);
+            });
+            reader.set(Some(task));
+        })
+    };
+
     let on_extract = {
         let msg = msg.clone();
         let extracted_out = extracted_out.clone();
         let field_list = field_list.clone();
-        let filtered = filtered_entries.clone();
+        let buffer = buffer.clone();
+        let view = view.clone();
         Callback::from(move |_| {
             let fields = field_list
                 .lines()
@@ -512,14 +1424,11 @@ fn app() -> Html {
 
             let mut rows = 0usize;
 
-            for e in &filtered {
-                if !e.is_json {
+            for e in view.entries(&buffer.borrow().entries) {
+                let v = &e.fields;
+                if v.as_object().is_none_or(|o| o.is_empty()) {
                     continue;
                 }
-                let v: Value = match serde_json::from_str(&e.raw) {
-                    Ok(v) => v,
-                    Err(_) => continue,
-                };
 
                 out.push_str(&format!(
                     "{}\t{}",
@@ -528,9 +1437,9 @@ fn app() -> Html {
                 ));
 
                 for f in &fields {
-                    let val = extract_field(&v, f).unwrap_or_else(|| "".to_string());
+                    let val = extract_field(v, f).unwrap_or_default();
                     out.push('\t');
-                    out.push_str(&val.replace('\t', " ").replace('\n', " "));
+                    out.push_str(&val.replace(['\t', '\n'], " "));
                 }
                 out.push('\n');
                 rows += 1;
@@ -538,7 +1447,7 @@ fn app() -> Html {
 
             extracted_out.set(out);
             msg.set(format!(
-                "Extracted {rows} JSON entries into TSV (copy/paste into Excel/Sheets)."
+                "Extracted {rows} structured entries into TSV (copy/paste into Excel/Sheets)."
             ));
         })
     };
@@ -565,6 +1474,7 @@ fn app() -> Html {
         let want_level = want_level.clone();
         let needle = needle.clone();
         let show_json_only = show_json_only.clone();
+        let query_text = query_text.clone();
         let hl_enabled = hl_enabled.clone();
         let hl_case_insensitive = hl_case_insensitive.clone();
         let hl_pat = hl_pat.clone();
@@ -586,6 +1496,7 @@ fn app() -> Html {
                 hl_enabled: *hl_enabled,
                 hl_case_insensitive: *hl_case_insensitive,
                 hl_pat: (*hl_pat).clone(),
+                query: (*query_text).clone(),
             };
 
             // upsert by name
@@ -631,6 +1542,7 @@ fn app() -> Html {
         let want_level = want_level.clone();
         let needle = needle.clone();
         let show_json_only = show_json_only.clone();
+        let query_text = query_text.clone();
         let hl_enabled = hl_enabled.clone();
         let hl_case_insensitive = hl_case_insensitive.clone();
         let hl_pat = hl_pat.clone();
@@ -645,6 +1557,7 @@ fn app() -> Html {
                 hl_enabled.set(p.hl_enabled);
                 hl_case_insensitive.set(p.hl_case_insensitive);
                 hl_pat.set(p.hl_pat.clone());
+                query_text.set(p.query.clone());
                 msg.set("Preset applied.".to_string());
             } else {
                 msg.set("Preset not found.".to_string());
@@ -652,13 +1565,53 @@ fn app() -> Html {
         })
     };
 
-    // preview rendering + match counting
-    let (preview_html, highlight_status_line, matches_found) = {
-        let mut rows: Vec<Html> = Vec::new();
-        let mut shown = 0usize;
-        let mut match_total = 0usize;
-        let mut next_match_idx = 0usize;
+    // Preview layout and highlight matches cover the whole filtered set, but
+    // only the entries scrolled into view are rendered.
+    let layout = {
+        let buffer = buffer.clone();
+        let view = view.clone();
+        use_memo(
+            (filter_deps.clone(), (*expanded).clone()),
+            move |(_, open)| {
+                let buf = buffer.borrow();
+                Rows::new(
+                    view.entries(&buf.entries)
+                        .map(|e| entry_rows(e, open.contains(&e.idx))),
+                )
+            },
+        )
+    };
+    let matches = {
+        let buffer = buffer.clone();
+        let view = view.clone();
+        let re = hl_regex.clone().ok().flatten().filter(|_| *hl_enabled);
+        use_memo(
+            (
+                filter_deps,
+                (*expanded).clone(),
+                (*hl_pat).clone(),
+                *hl_enabled,
+                *hl_case_insensitive,
+            ),
+            move |(_, open, ..)| {
+                let Some(re) = re else {
+                    return Rows::new([]);
+                };
+                let buf = buffer.borrow();
+                Rows::new(view.entries(&buf.entries).map(|e| {
+                    entry_payload(e, open.contains(&e.idx))
+                        .lines()
+                        .map(|line| re.find_iter(line).count())
+                        .sum()
+                }))
+            },
+        )
+    };
+    let total_matches = matches.total();
+    let scroll_scale = (layout.total() as f64 * ROW_PX / MAX_SCROLL_PX).max(1.0);
 
+    let (preview_html, highlight_status_line) = {
+        let mut rows: Vec<Html> = Vec::new();
         let current = *current_match;
 
         let status_base = match &hl_regex {
@@ -668,26 +1621,65 @@ fn app() -> Html {
             Err(e) => e.clone(),
         };
 
-        for e in &filtered_entries {
-            if shown >= 200 {
-                rows.push(html! { <span>{ "\n… (preview truncated; export/copy for full output)\n" }</span> });
-                break;
-            }
+        let exact_row = *scroll_top * scroll_scale / ROW_PX;
+        let range = layout.visible(
+            (exact_row as usize).saturating_sub(OVERSCAN),
+            VIEW_ROWS + 2 * OVERSCAN,
+        );
+        let mut next_match_idx = matches.start(range.start);
+        // the first rendered row sits where its (scaled) scroll position is
+        let block_top =
+            *scroll_top - (exact_row - layout.start(range.start) as f64) * ROW_PX;
 
+        for e in view.shown[range].iter().filter_map(|&i| all.get(i)) {
             let level_tag = e.level.clone().unwrap_or_else(|| "-".to_string());
-            rows.push(html! { <span>{ format!("— #{:04}  {}\n", e.idx, level_tag) }</span> });
-
-            let payload = if e.is_json {
-                e.json_pretty.clone().unwrap_or_else(|| e.raw.clone())
+            let ts_tag = match (*rel_time, e.ts_ms, first_ts) {
+                (true, Some(t), Some(first)) => time::format_offset(t - first),
+                _ => e.timestamp.clone().unwrap_or_default(),
+            };
+            let is_open = expanded.contains(&e.idx);
+            let fold = if e.lines > 1 {
+                let expanded = expanded.clone();
+                let idx = e.idx;
+                let label = if is_open {
+                    "▾ collapse".to_string()
+                } else {
+                    format!("▸ +{} lines", e.lines - 1)
+                };
+                html! {
+                  <button class="fold" onclick={Callback::from(move |_| {
+                    let mut set = (*expanded).clone();
+                    if !set.remove(&idx) {
+                        set.insert(idx);
+                    }
+                    expanded.set(set);
+                  })}>{ label }</button>
+                }
             } else {
-                e.raw.clone()
+                html! {}
             };
+            let pin = {
+                let pinned = pinned.clone();
+                let idx = e.idx;
+                let on = pinned.contains(&idx);
+                html! {
+                  <button class={classes!("fold", "pin", on.then_some("on"))} title={if on { "Unpin" } else { "Pin" }}
+                    onclick={Callback::from(move |_| {
+                      let mut set = (*pinned).clone();
+                      if !set.remove(&idx) {
+                          set.insert(idx);
+                      }
+                      pinned.set(set);
+                    })}>{ if on { "★" } else { "☆" } }</button>
+                }
+            };
+            rows.push(html! { <><span>{ format!("— #{:04}  {}  {}  {}  ", e.idx, level_tag, e.format, ts_tag) }</span>{ pin }<span>{ " " }</span>{ fold }<span>{ "\n" }</span></> });
 
+            let payload = entry_payload(e, is_open);
             match &hl_regex {
                 Ok(Some(re)) if *hl_enabled => {
                     for line in payload.lines() {
-                        let (h, c) = highlight_line(line, re, &mut next_match_idx, current);
-                        match_total += c;
+                        let (h, _) = highlight_line(line, re, &mut next_match_idx, current);
                         rows.push(html! { <>{ h }<span>{ "\n" }</span></> });
                     }
                     rows.push(html! { <span>{ "\n" }</span> });
@@ -697,13 +1689,11 @@ fn app() -> Html {
                     rows.push(html! { <span>{ "\n\n" }</span> });
                 }
             }
-
-            shown += 1;
         }
 
         let status = if *hl_enabled {
-            if match_total > 0 && hl_regex.as_ref().ok().and_then(|x| x.as_ref()).is_some() {
-                format!("{status_base} • matches in preview: {match_total}")
+            if total_matches > 0 && hl_regex.as_ref().ok().and_then(|x| x.as_ref()).is_some() {
+                format!("{status_base} • matches: {total_matches}")
             } else {
                 status_base
             }
@@ -711,24 +1701,76 @@ fn app() -> Html {
             String::new()
         };
 
-        (html! { <>{ for rows }</> }, status, match_total)
+        let pad_px = layout.total() as f64 * ROW_PX / scroll_scale;
+        let on_scroll = {
+            let scroll_top = scroll_top.clone();
+            Callback::from(move |e: Event| {
+                let el = e.target_unchecked_into::<web_sys::Element>();
+                scroll_top.set(el.scroll_top() as f64);
+            })
+        };
+        let html = html! {
+          <div class="vlist" id="preview" onscroll={on_scroll}>
+            <div class="vlist-pad" style={format!("height:{pad_px:.0}px")}>
+              <pre class="mono vlist-rows" style={format!("top:{block_top:.0}px")}>{ for rows }</pre>
+            </div>
+          </div>
+        };
+        (html, status)
     };
 
-    // update total_matches state (safe: derived value; keep in sync)
-    {
-        let total_matches = total_matches.clone();
-        use_effect_with(matches_found, move |m| {
-            total_matches.set(*m);
-            || ()
-        });
-    }
+    // bring the current match into view once it has been rendered
+    use_effect_with(*current_match, |m| {
+        if let Some(k) = *m {
+            scroll_to_match(k);
+        }
+        || ()
+    });
 
     // match nav handlers
+    let jump_to_match = {
+        let current_match = current_match.clone();
+        let scroll_top = scroll_top.clone();
+        let layout = layout.clone();
+        let matches = matches.clone();
+        Callback::from(move |k: usize| {
+            // scroll the entry holding match k into the rendered window first
+            let row = layout.start(matches.find(k)).saturating_sub(2);
+            let top = row as f64 * ROW_PX / scroll_scale;
+            set_preview_scroll(top);
+            scroll_top.set(top);
+            current_match.set(Some(k));
+        })
+    };
+
+    // scroll a pinned entry (by line) into the preview
+    let jump_to_entry = {
+        let scroll_top = scroll_top.clone();
+        let layout = layout.clone();
+        let buffer = buffer.clone();
+        let view = view.clone();
+        let msg = msg.clone();
+        Callback::from(move |idx: usize| {
+            let entries = &buffer.borrow().entries;
+            let Some(pos) = view
+                .shown
+                .iter()
+                .position(|&i| entries.get(i).is_some_and(|e| e.idx == idx))
+            else {
+                msg.set(format!("Entry #{idx:04} is hidden by the current filters."));
+                return;
+            };
+            let top = layout.start(pos) as f64 * ROW_PX / scroll_scale;
+            set_preview_scroll(top);
+            scroll_top.set(top);
+        })
+    };
+
     let on_match_prev = {
         let current_match = current_match.clone();
-        let total_matches = total_matches.clone();
+        let jump_to_match = jump_to_match.clone();
         Callback::from(move |_| {
-            let total = *total_matches;
+            let total = total_matches;
             if total == 0 {
                 current_match.set(None);
                 return;
@@ -738,16 +1780,14 @@ fn app() -> Html {
                 Some(0) => total - 1,
                 Some(i) => i - 1,
             };
-            current_match.set(Some(next));
-            scroll_to_match(next);
+            jump_to_match.emit(next);
         })
     };
 
     let on_match_next = {
         let current_match = current_match.clone();
-        let total_matches = total_matches.clone();
         Callback::from(move |_| {
-            let total = *total_matches;
+            let total = total_matches;
             if total == 0 {
                 current_match.set(None);
                 return;
@@ -756,8 +1796,7 @@ fn app() -> Html {
                 None => 0,
                 Some(i) => (i + 1) % total,
             };
-            current_match.set(Some(next));
-            scroll_to_match(next);
+            jump_to_match.emit(next);
         })
     };
 
@@ -783,11 +1822,41 @@ fn app() -> Html {
     // Views
     let explore_view = html! {
       <div class="panel">
-        <div class="block">
+        <div
+          class="block"
+          ondragover={Callback::from(|e: DragEvent| e.prevent_default())}
+          ondrop={{
+            let on_load_file = on_load_file.clone();
+            Callback::from(move |e: DragEvent| {
+              e.prevent_default();
+              if let Some(f) = e.data_transfer().and_then(|d| d.files()).and_then(|l| l.get(0)) {
+                  on_load_file.emit(f);
+              }
+            })
+          }}
+        >
           <div class="block-head">
-            <div class="block-title">{ "Paste Logs" }</div>
+            <div class="block-title">{ "Paste or Open Logs" }</div>
             <div class="btnrow">
               <button class="btn" onclick={on_parse.clone()}>{ "Parse" }</button>
+              <label class="btn file-btn">
+                { "Open file…" }
+                <input
+                  type="file"
+                  accept=".log,.txt,.json,.jsonl,.ndjson,.gz"
+                  onchange={{
+                    let on_load_file = on_load_file.clone();
+                    Callback::from(move |e: Event| {
+                      let input = e.target_unchecked_into::<web_sys::HtmlInputElement>();
+                      if let Some(f) = input.files().and_then(|l| l.get(0)) {
+                          on_load_file.emit(f);
+                      }
+                      // allow opening the same file again
+                      input.set_value("");
+                    })
+                  }}
+                />
+              </label>
               <button class="btn" onclick={on_copy_filtered.clone()}>{ "Copy Filtered (raw)" }</button>
               <button class="btn" onclick={on_export_jsonl.clone()}>{ "Copy Filtered (JSONL)" }</button>
             </div>
@@ -802,13 +1871,75 @@ fn app() -> Html {
                 log_in.set(v);
               })
             }}
-            placeholder="Paste JSONL logs or plain text logs here…"
+            placeholder="Paste JSONL, logfmt, access/syslog/container logs or plain text here… or drop a .log / .gz file"
           />
 
+          <div class="textline">
+            <div class="row">
+              <select
+                value={format!("{:?}", *log_format)}
+                onchange={{
+                  let log_format = log_format.clone();
+                  Callback::from(move |e: Event| {
+                    let v = e.target_unchecked_into::<web_sys::HtmlSelectElement>().value();
+                    log_format.set(format_from_debug(&v));
+                  })
+                }}
+              >
+                { for FORMATS.into_iter().map(|f| html! {
+                  <option value={format!("{f:?}")} selected={*log_format == f}>{ format_label(f) }</option>
+                }) }
+              </select>
+
+              <input
+                type="text"
+                value={(*custom_format).clone()}
+                oninput={{
+                  let custom_format = custom_format.clone();
+                  Callback::from(move |e: InputEvent| {
+                    let v = e.target_unchecked_into::<web_sys::HtmlInputElement>().value();
+                    custom_format.set(v);
+                  })
+                }}
+                placeholder={r"Custom regex, e.g. ^(?P<ts>\S+) (?P<level>\w+) (?P<msg>.*)$"}
+              />
+            </div>
+          </div>
+
+          <div class="btnrow" style="padding-top:10px;">
+            { for [
+                ("Multi-line", (|r: &mut FoldRules| &mut r.enabled) as fn(&mut FoldRules) -> &mut bool),
+                ("Indented", |r: &mut FoldRules| &mut r.indented),
+                ("at …", |r: &mut FoldRules| &mut r.at_frames),
+                ("Caused by", |r: &mut FoldRules| &mut r.caused_by),
+                ("Exceptions", |r: &mut FoldRules| &mut r.exceptions),
+                ("No timestamp", |r: &mut FoldRules| &mut r.no_timestamp),
+              ].into_iter().map(|(label, field)| {
+                let mut rules = (*fold_rules).clone();
+                let on = *field(&mut rules);
+                let fold_rules = fold_rules.clone();
+                html! {
+                  <button class="btn small" onclick={Callback::from(move |_| {
+                    let mut r = (*fold_rules).clone();
+                    let f = field(&mut r);
+                    *f = !*f;
+                    fold_rules.set(r);
+                  })}>
+                    { format!("{label}: {}", if on { "ON" } else { "OFF" }) }
+                  </button>
+                }
+              }) }
+          </div>
+          <div class="smallnote" style="padding-top:6px;">
+            { "Continuation rules fold stack traces into the line above them. They apply on Parse and to live tail." }
+          </div>
+
           <div class="kv">
-            <span class="tag">{ "Tip: JSONL = one JSON object per line" }</span>
+            <span class="tag">{ "Auto-detects JSON, logfmt, access, syslog, CRI" }</span>
             <span class="tag">{ "Everything stays local in the browser" }</span>
-            <span class="tag">{ "Live tail simulator available below" }</span>
+            <span class="tag">{ format!("Source: {}", (*source).clone().unwrap_or_else(|| "pasted text / live tail".to_string())) }</span>
+            <span class="tag">{ format!("Entries: {}", all.len()) }</span>
+            <span class="tag">{ "Parse with an empty box re-parses the loaded file" }</span>
           </div>
         </div>
 
@@ -855,6 +1986,33 @@ fn app() -> Html {
               />
             </div>
 
+            <div class="textline">
+              <input
+                type="text"
+                class={if query_result.is_err() { "mono-input bad" } else { "mono-input" }}
+                value={(*query_text).clone()}
+                oninput={{
+                  let query_text = query_text.clone();
+                  Callback::from(move |e: InputEvent| {
+                    let v = e.target_unchecked_into::<web_sys::HtmlInputElement>().value();
+                    query_text.set(v);
+                  })
+                }}
+                placeholder={r#"Query: level>=WARN AND service:orders AND NOT message~"health""#}
+              />
+              {
+                match &query_result {
+                  Err(err) => html! {
+                    <div class="alert query-err">
+                      <pre class="query-caret">{ format!("{}\n{}^", *query_text, " ".repeat(query_text[..err.pos].chars().count())) }</pre>
+                      { format!("Query error at column {}: {}", query_text[..err.pos].chars().count() + 1, err.message) }
+                    </div>
+                  },
+                  Ok(_) => html! {},
+                }
+              }
+            </div>
+
             <div class="textline">
               <input
                 type="text"
@@ -886,8 +2044,9 @@ fn app() -> Html {
             </div>
 
             <div class="kv">
-              <span class="tag">{ format!("Showing: {}", filtered_entries.len()) }</span>
+              <span class="tag">{ format!("Showing: {}", view.shown.len()) }</span>
               <span class="tag">{ "Try: error|warn|traceId" }</span>
+              <span class="tag">{ "Query: field:value  field>n  field~\"re\"  AND OR NOT ( )" }</span>
               <span class="tag">{ "Highlight wraps matches in preview" }</span>
             </div>
 
@@ -928,6 +2087,10 @@ fn app() -> Html {
                         "DemoMixed" => TailMode::DemoMixed,
                         "DemoJsonl" => TailMode::DemoJsonl,
                         "DemoErrors" => TailMode::DemoErrors,
+                        "DemoTraces" => TailMode::DemoTraces,
+                        "WebSocket" => TailMode::WebSocket,
+                        "Sse" => TailMode::Sse,
+                        "Poll" => TailMode::Poll,
                         _ => TailMode::Off,
                       };
                       tail_mode.set(m);
@@ -938,6 +2101,10 @@ fn app() -> Html {
                   <option value="DemoMixed">{ tail_mode_label(TailMode::DemoMixed) }</option>
                   <option value="DemoJsonl">{ tail_mode_label(TailMode::DemoJsonl) }</option>
                   <option value="DemoErrors">{ tail_mode_label(TailMode::DemoErrors) }</option>
+                  <option value="DemoTraces">{ tail_mode_label(TailMode::DemoTraces) }</option>
+                  <option value="WebSocket">{ tail_mode_label(TailMode::WebSocket) }</option>
+                  <option value="Sse">{ tail_mode_label(TailMode::Sse) }</option>
+                  <option value="Poll">{ tail_mode_label(TailMode::Poll) }</option>
                 </select>
 
                 <input
@@ -953,7 +2120,40 @@ fn app() -> Html {
                       }
                     })
                   }}
-                  placeholder="Tail interval (ms)"
+                  placeholder="Demo / poll interval (ms)"
+                />
+              </div>
+
+              <div class="row" style="padding-top:10px;">
+                <input
+                  type="url"
+                  value={(*tail_url).clone()}
+                  onchange={{
+                    // committed on Enter / blur, so typing does not reconnect
+                    let tail_url = tail_url.clone();
+                    Callback::from(move |e: Event| {
+                      let v = e.target_unchecked_into::<web_sys::HtmlInputElement>().value();
+                      tail_url.set(v);
+                    })
+                  }}
+                  placeholder="ws://… · https://…/events · https://…/app.log"
+                />
+                <input
+                  type="number"
+                  value={feed.borrow().keep.to_string()}
+                  onchange={{
+                    let feed = feed.clone();
+                    let redraw = redraw.clone();
+                    Callback::from(move |e: Event| {
+                      let v = e.target_unchecked_into::<web_sys::HtmlInputElement>().value();
+                      if let Ok(n) = v.parse::<usize>() {
+                        feed.borrow_mut().keep = n.clamp(100, 1_000_000);
+                        redraw.force_update();
+                      }
+                    })
+                  }}
+                  title="Ring buffer: entries kept while tailing"
+                  placeholder="Keep last N entries"
                 />
               </div>
 
@@ -961,10 +2161,40 @@ fn app() -> Html {
                 <button class="btn small" onclick={on_tail_toggle.clone()}>
                   { if *tail_mode == TailMode::Off { "Start Live Tail" } else { "Stop Live Tail" } }
                 </button>
+                <button
+                  class="btn small"
+                  disabled={*tail_mode == TailMode::Off}
+                  onclick={{
+                    let feed = feed.clone();
+                    let redraw = redraw.clone();
+                    Callback::from(move |_| {
+                      let mut f = feed.borrow_mut();
+                      f.paused = !f.paused;
+                      drop(f);
+                      redraw.force_update();
+                    })
+                  }}
+                >
+                  { if feed.borrow().paused { "Resume" } else { "Pause" } }
+                </button>
               </div>
 
+              { if *tail_mode == TailMode::Off { html! {} } else {
+                  let f = feed.borrow();
+                  html! {
+                    <div class="kv">
+                      <span class="tag">{ format!("{}: {}", tail_mode_label(*tail_mode), f.status) }</span>
+                      { if f.paused { html! { <span class="tag">{ "paused" }</span> } } else { html! {} } }
+                      <span class="tag">{ format!("Received: {}", f.received) }</span>
+                      <span class="tag">{ format!("Queued: {}", f.queued()) }</span>
+                      <span class="tag">{ format!("Dropped: {}", f.dropped) }</span>
+                      <span class="tag">{ format!("Trimmed: {} (keeping {})", f.trimmed, f.keep) }</span>
+                    </div>
+                  }
+              } }
+
               <div class="smallnote" style="padding-top:8px;">
-                { "Live tail is simulated locally (no network). It appends new lines into the textarea." }
+                { "Demo modes are generated locally. WebSocket and SSE messages hold one or more lines; HTTP poll reads JSON {\"lines\", \"cursor\"}, an X-Cursor header, or a growing text file. While paused, lines queue up and the oldest are dropped past 20,000 (polling just waits). fixtures/live_server.py serves all three." }
               </div>
             </div>
 
@@ -973,32 +2203,76 @@ fn app() -> Html {
 
           <div class="block">
             <div class="block-head">
-              <div class="block-title">{ "Preview (first 200)" }</div>
+              <div class="block-title">{ "Preview" }</div>
               <div class="btnrow">
                 <button class="btn small" onclick={on_match_prev.clone()}>{ "◀ Prev match" }</button>
                 <button class="btn small" onclick={on_match_next.clone()}>{ "Next match ▶" }</button>
+                <button class="btn small" onclick={{
+                  let expanded = expanded.clone();
+                  let buffer = buffer.clone();
+                  let view = view.clone();
+                  Callback::from(move |_| {
+                    if expanded.is_empty() {
+                        let all = view
+                          .entries(&buffer.borrow().entries)
+                          .filter(|e| e.lines > 1)
+                          .map(|e| e.idx)
+                          .collect::<HashSet<_>>();
+                        expanded.set(all);
+                    } else {
+                        expanded.set(HashSet::new());
+                    }
+                  })
+                }}>{ if expanded.is_empty() { "Expand all" } else { "Collapse all" } }</button>
 
                 <button class="btn small" onclick={{
                   let msg = msg.clone();
                   let log_in = log_in.clone();
-                  let parsed = parsed.clone();
+                  let buffer = buffer.clone();
+                  let redraw = redraw.clone();
+                  let source = source.clone();
                   let tail_mode = tail_mode.clone();
                   let tail_counter = tail_counter.clone();
+                  let time_window = time_window.clone();
+                  let pinned = pinned.clone();
                   Callback::from(move |_| {
                     tail_mode.set(TailMode::Off);
-                    tail_counter.set(0);
+                    *tail_counter.borrow_mut() = 0;
                     log_in.set(String::new());
-                    parsed.set(Vec::new());
+                    buffer.borrow_mut().clear();
+                    source.set(None);
+                    time_window.set(None);
+                    pinned.set(BTreeSet::new());
+                    redraw.force_update();
                     msg.set("Cleared input.".to_string());
                   })
                 }}>{ "Clear" }</button>
               </div>
             </div>
 
-            <pre class="mono">{ preview_html }</pre>
+            { timeline_html }
+            <div class="btnrow hist-controls">
+              <button class="btn small" onclick={{
+                let rel_time = rel_time.clone();
+                Callback::from(move |_| rel_time.set(!*rel_time))
+              }}>
+                { if *rel_time { "Time: relative" } else { "Time: absolute" } }
+              </button>
+              if let Some((from, to)) = *time_window {
+                <span class="tag">{ format!("Window: {} → {} ({} of {})", time::format_ms(from), time::format_ms(to), view.shown.len(), view.untimed.len()) }</span>
+                <button class="btn small" onclick={{
+                  let time_window = time_window.clone();
+                  Callback::from(move |_| time_window.set(None))
+                }}>{ "Clear window" }</button>
+              } else {
+                <span class="smallnote">{ "Drag across the timeline to narrow the time window." }</span>
+              }
+            </div>
+
+            { preview_html }
 
             <div class="kv">
-              <span class="tag">{ format!("Matches: {}", *total_matches) }</span>
+              <span class="tag">{ format!("Matches: {}", total_matches) }</span>
               <span class="tag">
                 {
                   match *current_match {
@@ -1011,6 +2285,89 @@ fn app() -> Html {
             </div>
           </div>
         </div>
+
+        <div class="block">
+          <div class="block-head">
+            <div class="block-title">{ "Pinned, notes & export" }</div>
+            <div class="btnrow">
+              <button class="btn small" onclick={on_download_csv.clone()}>{ "Download CSV" }</button>
+              <button class="btn small" onclick={on_download_ndjson.clone()}>{ "Download NDJSON" }</button>
+              <button class="btn small" onclick={on_download_bundle.clone()}>{ "Save bundle" }</button>
+              <label class="btn small file-btn">
+                { "Import bundle…" }
+                <input
+                  type="file"
+                  accept=".json,application/json"
+                  onchange={{
+                    let on_import_bundle = on_import_bundle.clone();
+                    Callback::from(move |e: Event| {
+                      let input = e.target_unchecked_into::<web_sys::HtmlInputElement>();
+                      if let Some(f) = input.files().and_then(|l| l.get(0)) {
+                          on_import_bundle.emit(f);
+                      }
+                      input.set_value("");
+                    })
+                  }}
+                />
+              </label>
+            </div>
+          </div>
+
+          <div class="pins">
+            if pinned.is_empty() {
+              <div class="smallnote">{ "Pin entries with ☆ in the preview to keep them at hand." }</div>
+            }
+            { for pinned.iter().map(|&idx| {
+              let entry = all.binary_search_by_key(&idx, |e| e.idx).ok().map(|i| &all[i]);
+              let label = match entry {
+                  Some(e) => format!(
+                      "#{idx:04}  {}  {}",
+                      e.level.as_deref().unwrap_or("-"),
+                      e.raw.lines().next().unwrap_or_default()
+                  ),
+                  None => format!("#{idx:04}  (no longer loaded)"),
+              };
+              let on_jump = {
+                  let jump_to_entry = jump_to_entry.clone();
+                  Callback::from(move |_| jump_to_entry.emit(idx))
+              };
+              let on_unpin = {
+                  let pinned = pinned.clone();
+                  Callback::from(move |e: MouseEvent| {
+                      e.stop_propagation();
+                      let mut set = (*pinned).clone();
+                      set.remove(&idx);
+                      pinned.set(set);
+                  })
+              };
+              html! {
+                <div class="pin-row" onclick={on_jump} title="Scroll the preview to this entry">
+                  <span class="pin-text">{ label }</span>
+                  <button class="fold" onclick={on_unpin}>{ "✕" }</button>
+                </div>
+              }
+            }) }
+          </div>
+
+          <textarea
+            class="notes"
+            value={(*notes).clone()}
+            oninput={{
+              let notes = notes.clone();
+              Callback::from(move |e: InputEvent| {
+                let v = e.target_unchecked_into::<web_sys::HtmlTextAreaElement>().value();
+                notes.set(v);
+              })
+            }}
+            placeholder="Notes for this investigation — saved in the bundle"
+          />
+
+          <div class="kv">
+            <span class="tag">{ format!("Pinned: {}", pinned.len()) }</span>
+            <span class="tag">{ "CSV: line, timestamp, level, message + the Extract tab's fields" }</span>
+            <span class="tag">{ "A bundle restores input, parsing, filters, highlight, window, pins and notes" }</span>
+          </div>
+        </div>
       </div>
     };
 
@@ -1023,6 +2380,7 @@ fn app() -> Html {
               <div class="btnrow">
                 <button class="btn" onclick={on_extract.clone()}>{ "Extract" }</button>
                 <button class="btn" onclick={on_copy_extracted.clone()}>{ "Copy TSV" }</button>
+                <button class="btn" onclick={on_download_csv.clone()}>{ "Download CSV" }</button>
               </div>
             </div>
 
@@ -1040,7 +2398,7 @@ fn app() -> Html {
 
             <div class="kv">
               <span class="tag">{ "Supports dotted paths: user.id, request.id" }</span>
-              <span class="tag">{ "Only JSON entries produce rows" }</span>
+              <span class="tag">{ "Structured entries produce rows (JSON, logfmt, access, syslog…)" }</span>
             </div>
           </div>
 
@@ -1058,16 +2416,471 @@ fn app() -> Html {
       </div>
     };
 
+    // only grouped while the tab is open; it walks the whole filtered set
+    let correlate_view = if *tab != Tab::Correlate {
+        html! {}
+    } else {
+        let filtered_entries = view.entries(all).collect::<Vec<_>>();
+        let keys = correlate::key_fields(&corr_key);
+        let groups = correlate::group_by(&filtered_entries, &keys, |e| e.ts_ms);
+        let grouped: usize = groups.iter().map(|g| g.members.len()).sum();
+        let open = corr_open
+            .as_ref()
+            .and_then(|k| groups.iter().find(|g| &g.key == k));
+
+        let rows = groups.iter().take(200).map(|g| {
+            let is_open = open.is_some_and(|o| o.key == g.key);
+            let corr_open = corr_open.clone();
+            let key = g.key.clone();
+            html! {
+              <tr class={if is_open { "open" } else { "" }}
+                onclick={Callback::from(move |_| {
+                  corr_open.set(if is_open { None } else { Some(key.clone()) })
+                })}>
+                <td class="corr-key">{ &g.key }</td>
+                <td>{ g.members.len() }</td>
+                <td>{ g.extent.start.map(time::format_ms).unwrap_or_else(|| "-".to_string()) }</td>
+                <td>{ g.extent.duration().map(time::format_duration).unwrap_or_else(|| "-".to_string()) }</td>
+                <td><span class={format!("lv lv-{}", time::BANDS[time::band(g.extent.worst.as_deref())])}>
+                  { g.extent.worst.clone().unwrap_or_else(|| "-".to_string()) }
+                </span></td>
+              </tr>
+            }
+        });
+
+        let detail = match open {
+            None => html! {
+              <div class="smallnote corr-empty">{ "Pick a group to see its timeline and span waterfall." }</div>
+            },
+            Some(g) => {
+                let start = g.extent.start.unwrap_or(0);
+                let span = g.extent.duration().unwrap_or(0).max(1) as f64;
+                let pct = |t: i64| (t - start) as f64 / span * 100.0;
+
+                let timeline = g.members.iter().map(|&i| {
+                    let e = &filtered_entries[i];
+                    let band = time::BANDS[time::band(e.level.as_deref())];
+                    let first = e.raw.lines().next().unwrap_or("");
+                    html! {
+                      <div class="corr-row">
+                        <span class="corr-off">{ e.ts_ms.map(|t| time::format_offset(t - start)).unwrap_or_else(|| "—".to_string()) }</span>
+                        <span class="corr-track">
+                          if let Some(t) = e.ts_ms {
+                            <span class={format!("corr-dot lv-{band}")} style={format!("left:{:.2}%", pct(t))} />
+                          }
+                        </span>
+                        <span class="corr-msg" title={e.raw.clone()}>
+                          <span class={format!("lv lv-{band}")}>{ e.level.clone().unwrap_or_else(|| "-".to_string()) }</span>
+                          { format!(" #{} {}", e.idx + 1, first) }
+                        </span>
+                      </div>
+                    }
+                });
+
+                let spans = correlate::waterfall(&filtered_entries, &g.members, |e| e.ts_ms);
+                let waterfall = spans.iter().map(|s| {
+                    let band = time::BANDS[time::band(s.extent.worst.as_deref())];
+                    let (left, width) = match (s.extent.start, s.extent.end) {
+                        (Some(a), Some(b)) => (pct(a), (pct(b) - pct(a)).max(0.8)),
+                        _ => (0.0, 100.0),
+                    };
+                    let dur = s
+                        .extent
+                        .duration()
+                        .map(time::format_duration)
+                        .unwrap_or_else(|| "untimed".to_string());
+                    html! {
+                      <div class="corr-row">
+                        <span class="corr-span" style={format!("padding-left:{}px", s.depth * 14)} title={s.parent.clone().map(|p| format!("parent {p}")).unwrap_or_else(|| "root".to_string())}>
+                          { &s.id }
+                        </span>
+                        <span class="corr-track">
+                          <span class={format!("corr-bar lv-{band}")} style={format!("left:{left:.2}%;width:{width:.2}%")} />
+                        </span>
+                        <span class="corr-msg">{ format!("{dur} • {} entries", s.members.len()) }</span>
+                      </div>
+                    }
+                }).collect::<Vec<_>>();
+
+                html! {
+                  <>
+                    <div class="kv corr-head">
+                      <span class="tag">{ format!("Key: {}", g.key) }</span>
+                      <span class="tag">{ format!("Entries: {}", g.members.len()) }</span>
+                      <span class="tag">{ format!("Duration: {}", g.extent.duration().map(time::format_duration).unwrap_or_else(|| "-".to_string())) }</span>
+                      <span class="tag">{ format!("Worst: {}", g.extent.worst.clone().unwrap_or_else(|| "-".to_string())) }</span>
+                    </div>
+                    <div class="corr-section">{ "Timeline" }</div>
+                    { for timeline }
+                    if !waterfall.is_empty() {
+                      <div class="corr-section">{ "Span waterfall" }</div>
+                      { for waterfall }
+                    }
+                  </>
+                }
+            }
+        };
+
+        html! {
+          <div class="panel">
+            <div class="panel two-col">
+              <div class="block">
+                <div class="block-head">
+                  <div class="block-title">{ "Correlation key" }</div>
+                  <div class="btnrow">
+                    { for ["traceId, trace_id", "request_id", "userId"].into_iter().map(|k| {
+                      let corr_key = corr_key.clone();
+                      let corr_open = corr_open.clone();
+                      html! {
+                        <button class="btn small" onclick={Callback::from(move |_| {
+                          corr_key.set(k.to_string());
+                          corr_open.set(None);
+                        })}>{ k }</button>
+                      }
+                    }) }
+                  </div>
+                </div>
+
+                <div class="textline">
+                  <input
+                    type="text"
+                    class="mono-input"
+                    value={(*corr_key).clone()}
+                    oninput={{
+                      let corr_key = corr_key.clone();
+                      Callback::from(move |e: InputEvent| {
+                        let v = e.target_unchecked_into::<web_sys::HtmlInputElement>().value();
+                        corr_key.set(v);
+                      })
+                    }}
+                    placeholder="Field(s) to group by, e.g. traceId, trace_id"
+                  />
+                </div>
+
+                <div class="table-wrap">
+                  <table>
+                    <thead>
+                      <tr><th>{ "Key" }</th><th>{ "Entries" }</th><th>{ "Start" }</th><th>{ "Duration" }</th><th>{ "Worst" }</th></tr>
+                    </thead>
+                    <tbody>{ for rows }</tbody>
+                  </table>
+                </div>
+
+                <div class="kv">
+                  <span class="tag">{ format!("Groups: {}", groups.len()) }</span>
+                  <span class="tag">{ format!("Grouped entries: {} of {}", grouped, filtered_entries.len()) }</span>
+                  <span class="tag">{ "Alternatives are tried left to right" }</span>
+                </div>
+              </div>
+
+              <div class="block">
+                <div class="block-head">
+                  <div class="block-title">{ "Group timeline" }</div>
+                </div>
+                { detail }
+              </div>
+            </div>
+
+            <div class="smallnote">
+              { "Tip: groups use the Explore filters and time window. Spans nest via spanId / parentSpanId; try the “Demo: traces” live tail." }
+            </div>
+          </div>
+        }
+    };
+
+    // clustered over the filtered set, only while the tab is open
+    let templates_view = if *tab != Tab::Templates {
+        html! {}
+    } else {
+        let clusters = template::cluster(
+            view.entries(all)
+                .map(|e| (e.idx, e.template.as_str(), e.ts_ms, e.level.as_deref())),
+        );
+        let total = view.shown.len().max(1);
+        let seen = |ts: Option<i64>, line: usize| {
+            ts.map(time::format_ms)
+                .unwrap_or_else(|| format!("line {}", line + 1))
+        };
+
+        let rows = clusters.iter().take(500).map(|c| {
+            let on_pick = {
+                let query_text = query_text.clone();
+                let tab = tab.clone();
+                let msg = msg.clone();
+                let q = format!("template={}", query::quote(&c.template));
+                Callback::from(move |_| {
+                    query_text.set(q.clone());
+                    tab.set(Tab::Explore);
+                    msg.set("Explore filtered to one template — clear the query to widen it again.".to_string());
+                })
+            };
+            let breakdown = time::BANDS
+                .iter()
+                .zip(c.levels)
+                .filter(|(_, n)| *n > 0)
+                .map(|(band, n)| format!("{band} {n}"))
+                .collect::<Vec<_>>()
+                .join(" • ");
+            html! {
+              <tr onclick={on_pick}>
+                <td>{ c.count }</td>
+                <td>{ format!("{:.1}%", c.count as f64 * 100.0 / total as f64) }</td>
+                <td class="tpl-text">{ &c.template }</td>
+                <td>{ seen(c.first_ts, c.first_line) }</td>
+                <td>{ seen(c.last_ts, c.last_line) }</td>
+                <td>
+                  <span class="tpl-levels" title={breakdown}>
+                    { for time::BANDS.iter().zip(c.levels).filter(|(_, n)| *n > 0).map(|(band, n)| html! {
+                      <span class={format!("lv-{band}")} style={format!("flex:{n}")} />
+                    }) }
+                  </span>
+                </td>
+              </tr>
+            }
+        });
+
+        html! {
+          <div class="panel">
+            <div class="block">
+              <div class="block-head">
+                <div class="block-title">{ "Message templates" }</div>
+              </div>
+
+              <div class="table-wrap tall">
+                <table>
+                  <thead>
+                    <tr><th>{ "Count" }</th><th>{ "Share" }</th><th>{ "Template" }</th><th>{ "First seen" }</th><th>{ "Last seen" }</th><th>{ "Levels" }</th></tr>
+                  </thead>
+                  <tbody>{ for rows }</tbody>
+                </table>
+              </div>
+
+              <div class="kv">
+                <span class="tag">{ format!("Templates: {}", clusters.len()) }</span>
+                <span class="tag">{ format!("Entries: {}", view.shown.len()) }</span>
+                <span class="tag">{ "Masked: <num> <id> <hex> <uuid> <ip> <ts> <str>" }</span>
+                <span class="tag">{ "Click a row to filter Explore" }</span>
+              </div>
+            </div>
+
+            <div class="smallnote">
+              { "Tip: templates use the Explore filters and time window; the query template=\"…\" picks one out." }
+            </div>
+          </div>
+        }
+    };
+
+    // summarized over the filtered set, only while the tab is open
+    let aggregate_view = if *tab != Tab::Aggregate {
+        html! {}
+    } else {
+        let filtered_entries = view.entries(all).collect::<Vec<_>>();
+        let field = agg_field.trim().to_string();
+        let by = agg_by.trim().to_string();
+        let summaries = if field.is_empty() {
+            Vec::new()
+        } else {
+            aggregate::summarize(&filtered_entries, &field, (!by.is_empty()).then_some(by.as_str()), *agg_top)
+        };
+
+        // field names seen in the first entries, for the inputs' suggestions
+        let mut known = filtered_entries
+            .iter()
+            .take(2000)
+            .filter_map(|e| e.fields.as_object())
+            .flat_map(|o| o.keys().cloned())
+            .chain(["level", "message", "template", "format"].map(String::from))
+            .collect::<Vec<_>>();
+        known.sort();
+        known.dedup();
+
+        let text_input = |state: &UseStateHandle<String>, placeholder: &'static str| {
+            let state = state.clone();
+            html! {
+              <input
+                type="text"
+                class="mono-input"
+                list="agg-fields"
+                value={(*state).clone()}
+                oninput={Callback::from(move |e: InputEvent| {
+                  let v = e.target_unchecked_into::<web_sys::HtmlInputElement>().value();
+                  state.set(v);
+                })}
+                placeholder={placeholder}
+              />
+            }
+        };
+
+        let cards = summaries.iter().take(12).map(|sm| {
+            let group_clause = match (&sm.group, by.is_empty()) {
+                (_, true) => String::new(),
+                (Some(g), false) => format!(" AND {by}={}", query::quote(g)),
+                (None, false) => format!(" AND NOT {by}:*"),
+            };
+            let title = match (&sm.group, by.is_empty()) {
+                (_, true) => format!("{field} — all filtered entries"),
+                (Some(g), false) => format!("{by} = {g}"),
+                (None, false) => format!("no {by}"),
+            };
+
+            let top = sm.top.first().map_or(1, |(_, n)| *n).max(1);
+            let rows = sm.top.iter().map(|(value, n)| {
+                let on_pick = {
+                    let query_text = query_text.clone();
+                    let tab = tab.clone();
+                    let msg = msg.clone();
+                    let q = format!("{field}={}{group_clause}", query::quote(value));
+                    Callback::from(move |_| {
+                        query_text.set(q.clone());
+                        tab.set(Tab::Explore);
+                        msg.set("Explore filtered to one value — clear the query to widen it again.".to_string());
+                    })
+                };
+                html! {
+                  <tr onclick={on_pick}>
+                    <td class="agg-value">{ value }</td>
+                    <td>{ n }</td>
+                    <td>{ format!("{:.1}%", *n as f64 * 100.0 / sm.present.max(1) as f64) }</td>
+                    <td class="agg-bar-cell">
+                      <span class="agg-bar" style={format!("width:{:.1}%", *n as f64 * 100.0 / top as f64)} />
+                    </td>
+                  </tr>
+                }
+            });
+
+            let numeric = sm.numeric.as_ref().map(|st| {
+                const W: usize = 10;
+                const H: f64 = 48.0;
+                let tallest = st.bins.iter().copied().max().unwrap_or(1).max(1) as f64;
+                let width = (st.max - st.min) / st.bins.len() as f64;
+                let bars = st.bins.iter().enumerate().map(|(i, &c)| {
+                    let bh = if c == 0 { 0.0 } else { (c as f64 / tallest * (H - 2.0)).max(1.0) };
+                    let lo = st.min + width * i as f64;
+                    html! {
+                      <rect class="agg-bin" x={(i * W + 1).to_string()} y={format!("{:.2}", H - bh)}
+                        width={(W - 2).to_string()} height={format!("{bh:.2}")}>
+                        <title>{ format!("{} – {}: {c}", aggregate::format_number(lo), aggregate::format_number(lo + width)) }</title>
+                      </rect>
+                    }
+                });
+                html! {
+                  <>
+                    <div class="kv">
+                      { for [("min", st.min), ("p50", st.p50), ("mean", st.mean), ("p95", st.p95), ("p99", st.p99), ("max", st.max)]
+                          .into_iter()
+                          .map(|(k, v)| html! { <span class="tag">{ format!("{k} {}", aggregate::format_number(v)) }</span> }) }
+                    </div>
+                    <div class="hist">
+                      <svg viewBox={format!("0 0 {} {}", st.bins.len() * W, H)} preserveAspectRatio="none">
+                        { for bars }
+                      </svg>
+                      <div class="hist-axis">
+                        <span>{ aggregate::format_number(st.min) }</span>
+                        <span>{ format!("{} values", sm.present) }</span>
+                        <span>{ aggregate::format_number(st.max) }</span>
+                      </div>
+                    </div>
+                  </>
+                }
+            });
+
+            html! {
+              <div class="block">
+                <div class="block-head">
+                  <div class="block-title">{ title }</div>
+                </div>
+
+                { numeric.unwrap_or_default() }
+
+                <div class="table-wrap">
+                  <table>
+                    <thead>
+                      <tr><th>{ "Value" }</th><th>{ "Count" }</th><th>{ "Share" }</th><th></th></tr>
+                    </thead>
+                    <tbody>{ for rows }</tbody>
+                  </table>
+                </div>
+
+                <div class="kv">
+                  <span class="tag">{ format!("Entries: {}", sm.entries) }</span>
+                  <span class="tag">{ format!("With {field}: {}", sm.present) }</span>
+                  <span class="tag">{ format!("Distinct: {}", sm.distinct) }</span>
+                </div>
+              </div>
+            }
+        });
+
+        html! {
+          <div class="panel">
+            <div class="block">
+              <div class="block-head">
+                <div class="block-title">{ "Aggregate a field" }</div>
+                <div class="btnrow">
+                  { for ["level", "service", "status", "duration_ms"].into_iter().map(|f| {
+                    let agg_field = agg_field.clone();
+                    html! {
+                      <button class="btn small" onclick={Callback::from(move |_| agg_field.set(f.to_string()))}>{ f }</button>
+                    }
+                  }) }
+                </div>
+              </div>
+
+              <div class="textline">
+                <div class="row">
+                  { text_input(&agg_field, "Field, e.g. status or http.duration_ms") }
+                  { text_input(&agg_by, "Group by (optional), e.g. service") }
+                  <input
+                    type="number"
+                    value={agg_top.to_string()}
+                    oninput={{
+                      let agg_top = agg_top.clone();
+                      Callback::from(move |e: InputEvent| {
+                        let v = e.target_unchecked_into::<web_sys::HtmlInputElement>().value();
+                        if let Ok(n) = v.parse::<usize>() {
+                          agg_top.set(n.clamp(1, 100));
+                        }
+                      })
+                    }}
+                    title="Top values listed"
+                    placeholder="Top N"
+                  />
+                </div>
+                <datalist id="agg-fields">
+                  { for known.iter().map(|k| html! { <option value={k.clone()} /> }) }
+                </datalist>
+              </div>
+
+              <div class="kv">
+                <span class="tag">{ format!("Filtered entries: {}", filtered_entries.len()) }</span>
+                { if by.is_empty() { html! {} } else {
+                    html! { <span class="tag">{ format!("Groups: {}{}", summaries.len(), if summaries.len() > 12 { " (largest 12 shown)" } else { "" }) }</span> }
+                } }
+                <span class="tag">{ "Numbers get min / max / mean / p50 / p95 / p99" }</span>
+              </div>
+            </div>
+
+            <div class="agg-grid">{ for cards }</div>
+
+            <div class="smallnote">
+              { "Tip: uses the Explore filters and time window. Click a value to filter Explore to it; dotted paths like http.status work too." }
+            </div>
+          </div>
+        }
+    };
+
     let body = match *tab {
         Tab::Explore => explore_view,
         Tab::Extract => extract_view,
+        Tab::Correlate => correlate_view,
+        Tab::Templates => templates_view,
+        Tab::Aggregate => aggregate_view,
     };
 
     html! {
       <div class="app">
         <div class="tabs" role="tablist" aria-label="LogLens Tabs">
           {
-            for [Tab::Explore, Tab::Extract].into_iter().map(|t| {
+            for [Tab::Explore, Tab::Extract, Tab::Correlate, Tab::Templates, Tab::Aggregate].into_iter().map(|t| {
               let is_active = *tab == t;
               let cls = if is_active { "tab active" } else { "tab" };
               let set_tab = set_tab.clone();
@@ -0,0 +1,625 @@
+// src/parse.rs — line parsers for LogLens
+//
+// Every parser turns one log line into the same shape: optional timestamp,
+// level and message, plus a flat (or, for JSON, nested) map of fields that
+// `extract_field` can walk. Auto mode tries each built-in format in turn and
+// falls back to plain text, which still pulls out a leading timestamp, a level
+// word and inline `key=value` pairs.
+
+use regex::{Captures, Regex};
+use serde_json::{Map, Value};
+
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct Parsed {
+    pub format: &'static str,
+    pub timestamp: Option<String>,
+    pub level: Option<String>,
+    pub message: Option<String>,
+    pub fields: Map<String, Value>,
+}
+
+pub trait LineParser {
+    fn name(&self) -> &'static str;
+    fn parse(&self, line: &str) -> Option<Parsed>;
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Format {
+    Auto,
+    Json,
+    Logfmt,
+    Access,
+    Syslog,
+    Kubernetes,
+    Custom,
+}
+
+pub const FORMATS: [Format; 7] = [
+    Format::Auto,
+    Format::Json,
+    Format::Logfmt,
+    Format::Access,
+    Format::Syslog,
+    Format::Kubernetes,
+    Format::Custom,
+];
+
+pub fn format_label(f: Format) -> &'static str {
+    match f {
+        Format::Auto => "Format: auto-detect",
+        Format::Json => "JSON lines",
+        Format::Logfmt => "logfmt",
+        Format::Access => "Apache/Nginx access log",
+        Format::Syslog => "Syslog (RFC 3164/5424)",
+        Format::Kubernetes => "Kubernetes / CRI container",
+        Format::Custom => "Custom regex (named groups)",
+    }
+}
+
+pub fn format_from_debug(s: &str) -> Format {
+    FORMATS
+        .into_iter()
+        .find(|f| format!("{f:?}") == s)
+        .unwrap_or(Format::Auto)
+}
+
+/// `WARNING` → `WARN`, `err` → `ERROR`, `crit` → `FATAL`, …
+pub fn normalize_level(s: &str) -> Option<String> {
+    let lv = match s.trim().to_uppercase().as_str() {
+        "TRACE" => "TRACE",
+        "DEBUG" | "DBG" => "DEBUG",
+        "INFO" | "INFORMATION" | "NOTICE" => "INFO",
+        "WARN" | "WARNING" => "WARN",
+        "ERROR" | "ERR" => "ERROR",
+        "FATAL" | "CRIT" | "CRITICAL" | "ALERT" | "EMERG" | "PANIC" => "FATAL",
+        _ => return None,
+    };
+    Some(lv.to_string())
+}
+
+/// Syslog severity (PRI mod 8) as a LogLens level.
+fn syslog_level(pri: u32) -> &'static str {
+    match pri % 8 {
+        0..=2 => "FATAL",
+        3 => "ERROR",
+        4 => "WARN",
+        5 | 6 => "INFO",
+        _ => "DEBUG",
+    }
+}
+
+fn value_text(v: &Value) -> Option<String> {
+    match v {
+        Value::Null => None,
+        Value::String(s) => Some(s.clone()),
+        other => Some(other.to_string()),
+    }
+}
+
+fn first_of(obj: &Map<String, Value>, keys: &[&str]) -> Option<String> {
+    keys.iter().find_map(|k| obj.get(*k).and_then(value_text))
+}
+
+/// Named groups as string fields, skipping groups that did not take part.
+fn capture_fields(re: &Regex, caps: &Captures) -> Map<String, Value> {
+    re.capture_names()
+        .flatten()
+        .filter_map(|name| {
+            let m = caps.name(name)?;
+            Some((name.to_string(), Value::String(m.as_str().to_string())))
+        })
+        .collect()
+}
+
+/// Copy the normalized views into the field map so extraction can ask for
+/// `timestamp`, `level` or `message` whatever the source called them.
+fn with_common_fields(mut p: Parsed) -> Parsed {
+    for (key, val) in [
+        ("timestamp", &p.timestamp),
+        ("level", &p.level),
+        ("message", &p.message),
+    ] {
+        if let Some(v) = val {
+            p.fields
+                .entry(key)
+                .or_insert_with(|| Value::String(v.clone()));
+        }
+    }
+    p
+}
+
+// ---------- JSON ----------
+
+pub struct JsonParser;
+
+impl JsonParser {
+    fn from_object(obj: Map<String, Value>) -> Parsed {
+        Parsed {
+            timestamp: first_of(&obj, &["timestamp", "@timestamp", "time", "ts"]),
+            level: first_of(&obj, &["level", "severity", "lvl", "loglevel"])
+                .and_then(|l| normalize_level(&l)),
+            message: first_of(&obj, &["message", "msg", "error", "event"]),
+            fields: obj,
+            ..Parsed::default()
+        }
+    }
+}
+
+impl LineParser for JsonParser {
+    fn name(&self) -> &'static str {
+        "json"
+    }
+
+    fn parse(&self, line: &str) -> Option<Parsed> {
+        match serde_json::from_str::<Value>(line.trim()).ok()? {
+            Value::Object(obj) => Some(Self::from_object(obj)),
+            _ => None,
+        }
+    }
+}
+
+// ---------- logfmt ----------
+
+pub struct LogfmtParser;
+
+/// `key=value key2="quoted value"` pairs; `None` if any token is not a pair.
+fn logfmt_pairs(line: &str) -> Option<Vec<(String, String)>> {
+    let mut out = Vec::new();
+    let mut chars = line.trim().chars().peekable();
+    loop {
+        while chars.next_if(|c| c.is_whitespace()).is_some() {}
+        if chars.peek().is_none() {
+            break;
+        }
+        let mut key = String::new();
+        while let Some(c) = chars.next_if(|c| *c != '=' && !c.is_whitespace()) {
+            key.push(c);
+        }
+        chars.next_if_eq(&'=')?;
+        if key.is_empty() {
+            return None;
+        }
+        let mut val = String::new();
+        if chars.next_if_eq(&'"').is_some() {
+            loop {
+                match chars.next()? {
+                    '"' => break,
+                    '\\' => val.push(chars.next()?),
+                    c => val.push(c),
+                }
+            }
+        } else {
+            while let Some(c) = chars.next_if(|c| !c.is_whitespace()) {
+                val.push(c);
+            }
+        }
+        out.push((key, val));
+    }
+    Some(out)
+}
+
+impl LineParser for LogfmtParser {
+    fn name(&self) -> &'static str {
+        "logfmt"
+    }
+
+    fn parse(&self, line: &str) -> Option<Parsed> {
+        let pairs = logfmt_pairs(line)?;
+        if pairs.len() < 2 {
+            return None;
+        }
+        let obj: Map<String, Value> = pairs
+            .into_iter()
+            .map(|(k, v)| (k, Value::String(v)))
+            .collect();
+        Some(Parsed {
+            timestamp: first_of(&obj, &["ts", "time", "timestamp", "t"]),
+            level: first_of(&obj, &["level", "lvl", "severity"]).and_then(|l| normalize_level(&l)),
+            message: first_of(&obj, &["msg", "message", "error"]),
+            fields: obj,
+            ..Parsed::default()
+        })
+    }
+}
+
+// ---------- Apache / Nginx access logs ----------
+
+pub struct AccessParser {
+    re: Regex,
+}
+
+impl AccessParser {
+    pub fn new() -> Self {
+        // Common Log Format, with the combined format's referer + user agent optional.
+        let re = Regex::new(
+            r#"^(?P<remote_addr>\S+) \S+ (?P<remote_user>\S+) \[(?P<time_local>[^\]]+)\] "(?P<method>[A-Z]+) (?P<path>\S+)(?: (?P<protocol>[^"]*))?" (?P<status>\d{3}) (?P<bytes>\d+|-)(?: "(?P<referer>[^"]*)" "(?P<user_agent>[^"]*)")?"#,
+        )
+        .expect("access log regex");
+        Self { re }
+    }
+}
+
+impl LineParser for AccessParser {
+    fn name(&self) -> &'static str {
+        "access"
+    }
+
+    fn parse(&self, line: &str) -> Option<Parsed> {
+        let caps = self.re.captures(line)?;
+        let mut fields = capture_fields(&self.re, &caps);
+        let status: u16 = caps["status"].parse().ok()?;
+        fields.insert("status".into(), Value::from(status));
+        if let Ok(bytes) = caps["bytes"].parse::<u64>() {
+            fields.insert("bytes".into(), Value::from(bytes));
+        }
+        let level = match status {
+            500.. => "ERROR",
+            400..=499 => "WARN",
+            _ => "INFO",
+        };
+        Some(with_common_fields(Parsed {
+            timestamp: Some(caps["time_local"].to_string()),
+            level: Some(level.to_string()),
+            message: Some(format!("{} {} {}", &caps["method"], &caps["path"], status)),
+            fields,
+            ..Parsed::default()
+        }))
+    }
+}
+
+// ---------- syslog ----------
+
+pub struct SyslogParser {
+    rfc5424: Regex,
+    rfc3164: Regex,
+    sd_param: Regex,
+}
+
+impl SyslogParser {
+    pub fn new() -> Self {
+        Self {
+            rfc5424: Regex::new(
+                r#"^<(?P<pri>\d{1,3})>1 (?P<timestamp>\S+) (?P<hostname>\S+) (?P<app_name>\S+) (?P<procid>\S+) (?P<msgid>\S+) (?P<sd>-|(?:\[(?:[^\]"]|"(?:[^"\\]|\\.)*")*\])+) ?(?P<msg>.*)$"#,
+            )
+            .expect("rfc5424 regex"),
+            rfc3164: Regex::new(
+                r"^(?:<(?P<pri>\d{1,3})>)?(?P<timestamp>[A-Z][a-z]{2} [ \d]\d \d{2}:\d{2}:\d{2}) (?P<hostname>\S+) (?P<app_name>[^:\[\s]+)(?:\[(?P<procid>\d+)\])?: ?(?P<msg>.*)$",
+            )
+            .expect("rfc3164 regex"),
+            sd_param: Regex::new(r#"([A-Za-z_][\w.\-]*)="((?:[^"\\]|\\.)*)""#)
+                .expect("sd param regex"),
+        }
+    }
+}
+
+impl LineParser for SyslogParser {
+    fn name(&self) -> &'static str {
+        "syslog"
+    }
+
+    fn parse(&self, line: &str) -> Option<Parsed> {
+        let (re, caps) = [&self.rfc5424, &self.rfc3164]
+            .into_iter()
+            .find_map(|re| Some((re, re.captures(line)?)))?;
+        let mut fields = capture_fields(re, &caps);
+        fields.remove("sd");
+        if let Some(sd) = caps.name("sd") {
+            for kv in self.sd_param.captures_iter(sd.as_str()) {
+                fields.insert(
+                    kv[1].to_string(),
+                    Value::String(kv[2].replace("\\\"", "\"")),
+                );
+            }
+        }
+        let msg = caps.name("msg").map(|m| m.as_str().to_string());
+        let level = match caps
+            .name("pri")
+            .and_then(|p| p.as_str().parse::<u32>().ok())
+        {
+            Some(pri) => Some(syslog_level(pri).to_string()),
+            None => msg.as_deref().and_then(crate::detect_level),
+        };
+        Some(with_common_fields(Parsed {
+            timestamp: Some(caps["timestamp"].to_string()),
+            level,
+            message: msg,
+            fields,
+            ..Parsed::default()
+        }))
+    }
+}
+
+// ---------- Kubernetes / CRI container logs ----------
+
+/// CRI lines (`<time> stdout F <msg>`) and Docker's json-file
+/// (`{"log":…,"stream":…,"time":…}`). A JSON or logfmt payload is parsed too.
+pub struct KubernetesParser {
+    cri: Regex,
+}
+
+impl KubernetesParser {
+    pub fn new() -> Self {
+        Self {
+            cri: Regex::new(
+                r"^(?P<time>\d{4}-\d{2}-\d{2}T\S+) (?P<stream>stdout|stderr) (?P<tag>[FP]) (?P<log>.*)$",
+            )
+            .expect("cri regex"),
+        }
+    }
+
+    fn split(&self, line: &str) -> Option<(String, String, String)> {
+        if let Some(caps) = self.cri.captures(line) {
+            return Some((
+                caps["time"].to_string(),
+                caps["stream"].to_string(),
+                caps["log"].to_string(),
+            ));
+        }
+        let Value::Object(obj) = serde_json::from_str::<Value>(line.trim()).ok()? else {
+            return None;
+        };
+        let log = obj.get("log")?.as_str()?;
+        let stream = obj.get("stream")?.as_str()?;
+        let time = obj.get("time").and_then(value_text).unwrap_or_default();
+        Some((time, stream.to_string(), log.trim_end().to_string()))
+    }
+}
+
+impl LineParser for KubernetesParser {
+    fn name(&self) -> &'static str {
+        "kubernetes"
+    }
+
+    fn parse(&self, line: &str) -> Option<Parsed> {
+        let (time, stream, log) = self.split(line)?;
+        let inner = JsonParser.parse(&log).or_else(|| LogfmtParser.parse(&log));
+        let mut p = inner.unwrap_or_else(|| Parsed {
+            message: Some(log.clone()),
+            level: crate::detect_level(&log),
+            ..Parsed::default()
+        });
+        p.timestamp = p.timestamp.or((!time.is_empty()).then_some(time));
+        p.fields.insert("stream".into(), Value::String(stream));
+        Some(with_common_fields(p))
+    }
+}
+
+// ---------- custom regex ----------
+
+/// A user pattern with named groups. `ts`/`time`/`timestamp`, `level`/`severity`
+/// and `msg`/`message` fill the common views; every group becomes a field.
+pub struct RegexParser {
+    re: Regex,
+}
+
+impl RegexParser {
+    pub fn new(pattern: &str) -> Result<Self, String> {
+        let re = Regex::new(pattern).map_err(|e| format!("Custom format regex error: {e}"))?;
+        if re.capture_names().flatten().next().is_none() {
+            return Err("Custom format needs named groups, e.g. (?P<level>\\w+)".to_string());
+        }
+        Ok(Self { re })
+    }
+}
+
+impl LineParser for RegexParser {
+    fn name(&self) -> &'static str {
+        "custom"
+    }
+
+    fn parse(&self, line: &str) -> Option<Parsed> {
+        let caps = self.re.captures(line)?;
+        let fields = capture_fields(&self.re, &caps);
+        Some(with_common_fields(Parsed {
+            timestamp: first_of(&fields, &["ts", "time", "timestamp"]),
+            level: first_of(&fields, &["level", "severity", "lvl"])
+                .and_then(|l| normalize_level(&l)),
+            message: first_of(&fields, &["msg", "message"]),
+            fields,
+            ..Parsed::default()
+        }))
+    }
+}
+
+// ---------- plain text fallback ----------
+
+pub struct TextParser {
+    head: Regex,
+    kv: Regex,
+}
+
+impl TextParser {
+    pub fn new() -> Self {
+        Self {
+            head: Regex::new(
+                r"^(?P<ts>\d{4}-\d{2}-\d{2}[T ]\d{2}:\d{2}:\d{2}(?:[.,]\d+)?(?:Z|[+-]\d{2}:?\d{2})?)?\s*(?:\[?(?P<level>(?i:trace|debug|info|warn|warning|error|fatal))\]?\s+)?(?P<msg>.*)$",
+            )
+            .expect("text head regex"),
+            kv: Regex::new(r#"(?:^|\s)([A-Za-z_][\w.\-]*)=("(?:[^"\\]|\\.)*"|\S+)"#)
+                .expect("inline kv regex"),
+        }
+    }
+}
+
+impl LineParser for TextParser {
+    fn name(&self) -> &'static str {
+        "text"
+    }
+
+    fn parse(&self, line: &str) -> Option<Parsed> {
+        let line = line.trim();
+        let caps = self.head.captures(line)?;
+        let fields: Map<String, Value> = self
+            .kv
+            .captures_iter(line)
+            .map(|kv| {
+                let v = kv[2].trim_matches('"').to_string();
+                (kv[1].to_string(), Value::String(v))
+            })
+            .collect();
+        let level = caps
+            .name("level")
+            .and_then(|l| normalize_level(l.as_str()))
+            .or_else(|| crate::detect_level(line));
+        let p = Parsed {
+            timestamp: caps.name("ts").map(|m| m.as_str().to_string()),
+            level,
+            message: Some(caps["msg"].to_string()).filter(|m| !m.is_empty()),
+            fields,
+            ..Parsed::default()
+        };
+        // Only structured enough to extract from when something was found.
+        if p.fields.is_empty() && p.timestamp.is_none() {
+            Some(p)
+        } else {
+            Some(with_common_fields(p))
+        }
+    }
+}
+
+// ---------- parser chains ----------
+
+/// The parsers to try, in order, for the chosen format. Plain text always
+/// comes last so every line yields an entry.
+pub fn parsers(format: Format, custom: &str) -> Result<Vec<Box<dyn LineParser>>, String> {
+    let custom = custom.trim();
+    let mut out: Vec<Box<dyn LineParser>> = Vec::new();
+    match format {
+        Format::Auto => {
+            if !custom.is_empty() {
+                out.push(Box::new(RegexParser::new(custom)?));
+            }
+            out.push(Box::new(KubernetesParser::new()));
+            out.push(Box::new(JsonParser));
+            out.push(Box::new(SyslogParser::new()));
+            out.push(Box::new(AccessParser::new()));
+            out.push(Box::new(LogfmtParser));
+        }
+        Format::Json => out.push(Box::new(JsonParser)),
+        Format::Logfmt => out.push(Box::new(LogfmtParser)),
+        Format::Access => out.push(Box::new(AccessParser::new())),
+        Format::Syslog => out.push(Box::new(SyslogParser::new())),
+        Format::Kubernetes => out.push(Box::new(KubernetesParser::new())),
+        Format::Custom => {
+            if custom.is_empty() {
+                return Err("Custom format: enter a regex with named groups.".to_string());
+            }
+            out.push(Box::new(RegexParser::new(custom)?));
+        }
+    }
+    out.push(Box::new(TextParser::new()));
+    Ok(out)
+}
+
+pub fn parse_line(parsers: &[Box<dyn LineParser>], line: &str) -> Parsed {
+    parsers
+        .iter()
+        .find_map(|p| {
+            let mut parsed = p.parse(line)?;
+            parsed.format = p.name();
+            Some(parsed)
+        })
+        .unwrap_or_else(|| Parsed {
+            message: Some(line.to_string()),
+            ..Parsed::default()
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn auto(line: &str) -> Parsed {
+        parse_line(&parsers(Format::Auto, "").unwrap(), line)
+    }
+
+    #[test]
+    fn json_lines_keep_nested_fields() {
+        let p =
+            auto(r#"{"ts":"2026-02-24T20:11:00Z","level":"warning","msg":"slow","user":{"id":7}}"#);
+        assert_eq!(p.format, "json");
+        assert_eq!(p.level.as_deref(), Some("WARN"));
+        assert_eq!(p.message.as_deref(), Some("slow"));
+        assert_eq!(p.fields["user"]["id"], 7);
+    }
+
+    #[test]
+    fn logfmt_with_quoted_values() {
+        let p = auto(
+            r#"ts=2026-02-24T20:11:00Z level=error msg="db \"orders\" timeout" duration_ms=1200"#,
+        );
+        assert_eq!(p.format, "logfmt");
+        assert_eq!(p.level.as_deref(), Some("ERROR"));
+        assert_eq!(p.message.as_deref(), Some(r#"db "orders" timeout"#));
+        assert_eq!(p.fields["duration_ms"], "1200");
+    }
+
+    #[test]
+    fn combined_access_log() {
+        let p = auto(
+            r#"203.0.113.9 - alice [24/Feb/2026:20:11:05 +0000] "GET /api/v1/products?id=3 HTTP/1.1" 503 512 "-" "curl/8.4""#,
+        );
+        assert_eq!(p.format, "access");
+        assert_eq!(p.level.as_deref(), Some("ERROR"));
+        assert_eq!(p.fields["status"], 503);
+        assert_eq!(p.fields["user_agent"], "curl/8.4");
+        assert_eq!(p.timestamp.as_deref(), Some("24/Feb/2026:20:11:05 +0000"));
+    }
+
+    #[test]
+    fn syslog_both_rfcs() {
+        let old = auto("<11>Feb 24 20:11:05 web01 sshd[4242]: Failed password for root");
+        assert_eq!(old.format, "syslog");
+        assert_eq!(old.level.as_deref(), Some("ERROR"));
+        assert_eq!(old.fields["app_name"], "sshd");
+        assert_eq!(old.fields["procid"], "4242");
+
+        let new = auto(
+            r#"<165>1 2026-02-24T20:11:05.003Z mymachine evntslog - ID47 [exampleSDID@32473 iut="3" eventSource="Application"] An application event"#,
+        );
+        assert_eq!(new.level.as_deref(), Some("INFO"));
+        assert_eq!(new.fields["eventSource"], "Application");
+        assert_eq!(new.message.as_deref(), Some("An application event"));
+    }
+
+    #[test]
+    fn cri_and_docker_container_lines() {
+        let cri = auto(r#"2026-02-24T20:11:05.669794202Z stderr F {"level":"error","msg":"boom"}"#);
+        assert_eq!(cri.format, "kubernetes");
+        assert_eq!(cri.level.as_deref(), Some("ERROR"));
+        assert_eq!(cri.fields["stream"], "stderr");
+
+        let docker =
+            auto(r#"{"log":"INFO started\n","stream":"stdout","time":"2026-02-24T20:11:05Z"}"#);
+        assert_eq!(docker.format, "kubernetes");
+        assert_eq!(docker.message.as_deref(), Some("INFO started"));
+        assert_eq!(docker.timestamp.as_deref(), Some("2026-02-24T20:11:05Z"));
+    }
+
+    #[test]
+    fn custom_regex_groups_become_fields() {
+        let ps = parsers(
+            Format::Custom,
+            r"^(?P<ts>\S+) \| (?P<level>\w+) \| (?P<svc>\w+) \| (?P<msg>.*)$",
+        )
+        .unwrap();
+        let p = parse_line(&ps, "12:00:01 | Warning | billing | card declined");
+        assert_eq!(p.format, "custom");
+        assert_eq!(p.level.as_deref(), Some("WARN"));
+        assert_eq!(p.fields["svc"], "billing");
+        assert!(parsers(Format::Custom, r"\d+").is_err());
+    }
+
+    #[test]
+    fn plain_text_keeps_timestamp_level_and_inline_pairs() {
+        let p = auto("2026-02-24T20:11:02Z DEBUG Cache miss for key=session:u1002");
+        assert_eq!(p.format, "text");
+        assert_eq!(p.level.as_deref(), Some("DEBUG"));
+        assert_eq!(p.timestamp.as_deref(), Some("2026-02-24T20:11:02Z"));
+        assert_eq!(p.fields["key"], "session:u1002");
+
+        let bare = auto("java.sql.SQLException: Timeout while waiting for connection");
+        assert_eq!(bare.format, "text");
+        assert!(bare.fields.is_empty());
+    }
+}
@@ -0,0 +1,434 @@
+// src/query.rs — the LogLens filter language
+//
+//     level>=WARN AND service:orders AND duration_ms>1000 AND NOT message~"health"
+//
+// Terms:
+//   field:value    case-insensitive equality, `*` wildcards (`service:order*`, `userId:*`)
+//   field=value    exact equality          field!=value   not equal
+//   field>n  >=  <  <=                     numbers compare numerically, `level` by severity
+//   field~"re"     case-insensitive regex
+//   word / "two words"                     substring of the raw entry text
+// Combine with AND (or just a space), OR, NOT and parentheses. AND binds
+// tighter than OR.
+
+use regex::Regex;
+
+/// What a query needs from an entry.
+pub trait Record {
+    /// A field by name or dotted path.
+    fn field(&self, path: &str) -> Option<String>;
+    /// The raw entry text, for bare search words.
+    fn text(&self) -> &str;
+}
+
+impl<R: Record + ?Sized> Record for &R {
+    fn field(&self, path: &str) -> Option<String> {
+        (**self).field(path)
+    }
+
+    fn text(&self) -> &str {
+        (**self).text()
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Op {
+    Match,
+    Eq,
+    Ne,
+    Gt,
+    Ge,
+    Lt,
+    Le,
+    Regex,
+}
+
+#[derive(Debug, Clone)]
+pub enum Query {
+    And(Box<Query>, Box<Query>),
+    Or(Box<Query>, Box<Query>),
+    Not(Box<Query>),
+    /// Case-insensitive substring of the raw text (stored lowercase).
+    Text(String),
+    Compare {
+        field: String,
+        op: Op,
+        value: String,
+        /// Compiled for `~` and for `:` with wildcards.
+        re: Option<Regex>,
+    },
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct QueryError {
+    /// Byte offset into the query text.
+    pub pos: usize,
+    pub message: String,
+}
+
+impl QueryError {
+    fn at(pos: usize, message: impl Into<String>) -> Self {
+        Self {
+            pos,
+            message: message.into(),
+        }
+    }
+}
+
+// ---------- tokens ----------
+
+#[derive(Debug, Clone, PartialEq)]
+enum Tok {
+    Word(String),
+    Str(String),
+    Op(Op),
+    LParen,
+    RParen,
+}
+
+fn is_op_char(c: char) -> bool {
+    matches!(c, ':' | '=' | '!' | '<' | '>' | '~')
+}
+
+fn tokenize(src: &str) -> Result<Vec<(usize, Tok)>, QueryError> {
+    let mut out = Vec::new();
+    let mut it = src.char_indices().peekable();
+    while let Some(&(pos, c)) = it.peek() {
+        if c.is_whitespace() {
+            it.next();
+        } else if c == '(' {
+            it.next();
+            out.push((pos, Tok::LParen));
+        } else if c == ')' {
+            it.next();
+            out.push((pos, Tok::RParen));
+        } else if c == '"' {
+            it.next();
+            let mut s = String::new();
+            loop {
+                match it.next() {
+                    Some((_, '"')) => break,
+                    Some((_, '\\')) => match it.next() {
+                        Some((_, c)) => s.push(c),
+                        None => return Err(QueryError::at(pos, "unterminated string")),
+                    },
+                    Some((_, c)) => s.push(c),
+                    None => return Err(QueryError::at(pos, "unterminated string")),
+                }
+            }
+            out.push((pos, Tok::Str(s)));
+        } else if is_op_char(c) {
+            it.next();
+            let next = it.peek().map(|&(_, c)| c);
+            let op = match (c, next) {
+                ('!', Some('=')) | ('>', Some('=')) | ('<', Some('=')) => {
+                    it.next();
+                    match c {
+                        '!' => Op::Ne,
+                        '>' => Op::Ge,
+                        _ => Op::Le,
+                    }
+                }
+                (':', _) => Op::Match,
+                ('=', _) => Op::Eq,
+                ('>', _) => Op::Gt,
+                ('<', _) => Op::Lt,
+                ('~', _) => Op::Regex,
+                _ => return Err(QueryError::at(pos, format!("unexpected '{c}'"))),
+            };
+            out.push((pos, Tok::Op(op)));
+        } else {
+            let mut s = String::new();
+            while let Some(&(_, c)) = it.peek() {
+                if c.is_whitespace() || c == '(' || c == ')' || c == '"' || is_op_char(c) {
+                    break;
+                }
+                s.push(c);
+                it.next();
+            }
+            out.push((pos, Tok::Word(s)));
+        }
+    }
+    Ok(out)
+}
+
+// ---------- parser ----------
+
+fn is_keyword(w: &str) -> bool {
+    ["AND", "OR", "NOT"]
+        .iter()
+        .any(|k| w.eq_ignore_ascii_case(k))
+}
+
+struct Parser {
+    toks: Vec<(usize, Tok)>,
+    at: usize,
+    end: usize,
+}
+
+impl Parser {
+    fn peek(&self) -> Option<&Tok> {
+        self.toks.get(self.at).map(|(_, t)| t)
+    }
+
+    fn pos(&self) -> usize {
+        self.toks.get(self.at).map(|(p, _)| *p).unwrap_or(self.end)
+    }
+
+    fn keyword(&self, kw: &str) -> bool {
+        matches!(self.peek(), Some(Tok::Word(w)) if w.eq_ignore_ascii_case(kw))
+    }
+
+    fn or(&mut self) -> Result<Query, QueryError> {
+        let mut left = self.and()?;
+        while self.keyword("OR") {
+            self.at += 1;
+            let right = self.and()?;
+            left = Query::Or(Box::new(left), Box::new(right));
+        }
+        Ok(left)
+    }
+
+    fn and(&mut self) -> Result<Query, QueryError> {
+        let mut left = self.not()?;
+        loop {
+            if self.keyword("AND") {
+                self.at += 1;
+            } else if self.peek().is_none()
+                || self.keyword("OR")
+                || self.peek() == Some(&Tok::RParen)
+            {
+                return Ok(left);
+            }
+            let right = self.not()?;
+            left = Query::And(Box::new(left), Box::new(right));
+        }
+    }
+
+    fn not(&mut self) -> Result<Query, QueryError> {
+        if self.keyword("NOT") {
+            self.at += 1;
+            return Ok(Query::Not(Box::new(self.not()?)));
+        }
+        self.primary()
+    }
+
+    fn primary(&mut self) -> Result<Query, QueryError> {
+        let pos = self.pos();
+        let Some(tok) = self.toks.get(self.at).map(|(_, t)| t.clone()) else {
+            return Err(QueryError::at(pos, "expected a term"));
+        };
+        self.at += 1;
+        match tok {
+            Tok::LParen => {
+                let q = self.or()?;
+                if self.peek() != Some(&Tok::RParen) {
+                    return Err(QueryError::at(self.pos(), "expected ')'"));
+                }
+                self.at += 1;
+                Ok(q)
+            }
+            Tok::RParen => Err(QueryError::at(pos, "unexpected ')'")),
+            Tok::Op(_) => Err(QueryError::at(pos, "operator needs a field name before it")),
+            Tok::Str(s) => Ok(Query::Text(s.to_lowercase())),
+            Tok::Word(w) => match self.peek() {
+                Some(Tok::Op(op)) => {
+                    let op = *op;
+                    self.at += 1;
+                    let vpos = self.pos();
+                    let value = match self.toks.get(self.at).map(|(_, t)| t) {
+                        Some(Tok::Word(v)) if !is_keyword(v) => v.clone(),
+                        Some(Tok::Str(v)) => v.clone(),
+                        _ => {
+                            return Err(QueryError::at(vpos, format!("expected a value after {w}")))
+                        }
+                    };
+                    self.at += 1;
+                    compare(w, op, value, vpos)
+                }
+                _ => Ok(Query::Text(w.to_lowercase())),
+            },
+        }
+    }
+}
+
+fn compare(field: String, op: Op, value: String, pos: usize) -> Result<Query, QueryError> {
+    let re = match op {
+        Op::Regex => Some(
+            Regex::new(&format!("(?i:{value})"))
+                .map_err(|e| QueryError::at(pos, format!("bad regex: {e}")))?,
+        ),
+        Op::Match if value.contains('*') && value != "*" => {
+            let pat = value
+                .split('*')
+                .map(regex::escape)
+                .collect::<Vec<_>>()
+                .join(".*");
+            Some(Regex::new(&format!("(?i)^{pat}$")).expect("escaped wildcard regex"))
+        }
+        _ => None,
+    };
+    Ok(Query::Compare {
+        field,
+        op,
+        value,
+        re,
+    })
+}
+
+/// `s` as a quoted query value.
+pub fn quote(s: &str) -> String {
+    format!("\"{}\"", s.replace('\\', "\\\\").replace('"', "\\\""))
+}
+
+pub fn parse(src: &str) -> Result<Option<Query>, QueryError> {
+    let toks = tokenize(src)?;
+    if toks.is_empty() {
+        return Ok(None);
+    }
+    let mut p = Parser {
+        toks,
+        at: 0,
+        end: src.len(),
+    };
+    let q = p.or()?;
+    if p.at < p.toks.len() {
+        return Err(QueryError::at(p.pos(), "unexpected ')'"));
+    }
+    Ok(Some(q))
+}
+
+// ---------- evaluation ----------
+
+pub fn level_rank(level: &str) -> Option<u8> {
+    let rank = match level.to_uppercase().as_str() {
+        "TRACE" => 0,
+        "DEBUG" => 1,
+        "INFO" => 2,
+        "WARN" | "WARNING" => 3,
+        "ERROR" => 4,
+        "FATAL" => 5,
+        _ => return None,
+    };
+    Some(rank)
+}
+
+fn ordering(field: &str, have: &str, want: &str) -> Option<std::cmp::Ordering> {
+    if field.eq_ignore_ascii_case("level") {
+        return Some(level_rank(have)?.cmp(&level_rank(want)?));
+    }
+    match (have.trim().parse::<f64>(), want.trim().parse::<f64>()) {
+        (Ok(a), Ok(b)) => a.partial_cmp(&b),
+        (Err(_), Err(_)) => Some(have.cmp(want)),
+        _ => None,
+    }
+}
+
+impl Query {
+    pub fn matches(&self, rec: &impl Record) -> bool {
+        match self {
+            Query::And(a, b) => a.matches(rec) && b.matches(rec),
+            Query::Or(a, b) => a.matches(rec) || b.matches(rec),
+            Query::Not(q) => !q.matches(rec),
+            Query::Text(needle) => rec.text().to_lowercase().contains(needle),
+            Query::Compare {
+                field,
+                op,
+                value,
+                re,
+            } => {
+                let Some(have) = rec.field(field) else {
+                    // a missing field is "not equal" to anything
+                    return *op == Op::Ne;
+                };
+                use std::cmp::Ordering::*;
+                match op {
+                    Op::Match => match re {
+                        Some(re) => re.is_match(&have),
+                        None => value == "*" || have.eq_ignore_ascii_case(value),
+                    },
+                    Op::Eq => have == *value,
+                    Op::Ne => have != *value,
+                    Op::Regex => re.as_ref().is_some_and(|re| re.is_match(&have)),
+                    Op::Gt => ordering(field, &have, value) == Some(Greater),
+                    Op::Ge => matches!(ordering(field, &have, value), Some(Greater | Equal)),
+                    Op::Lt => ordering(field, &have, value) == Some(Less),
+                    Op::Le => matches!(ordering(field, &have, value), Some(Less | Equal)),
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashMap;
+
+    struct Rec(HashMap<&'static str, &'static str>, &'static str);
+
+    impl Record for Rec {
+        fn field(&self, path: &str) -> Option<String> {
+            self.0.get(path).map(|s| s.to_string())
+        }
+        fn text(&self) -> &str {
+            self.1
+        }
+    }
+
+    fn rec(level: &'static str, service: &'static str, ms: &'static str, msg: &'static str) -> Rec {
+        let fields = HashMap::from([
+            ("level", level),
+            ("service", service),
+            ("duration_ms", ms),
+            ("message", msg),
+        ]);
+        Rec(fields, msg)
+    }
+
+    fn hit(q: &str, r: &Rec) -> bool {
+        parse(q).unwrap().unwrap().matches(r)
+    }
+
+    #[test]
+    fn the_example_query() {
+        let q = r#"level>=WARN AND service:orders AND duration_ms>1000 AND NOT message~"health""#;
+        assert!(hit(q, &rec("ERROR", "orders", "1500", "Database timeout")));
+        assert!(!hit(q, &rec("INFO", "orders", "1500", "Database timeout")));
+        assert!(!hit(q, &rec("WARN", "orders", "900", "Database timeout")));
+        assert!(!hit(q, &rec("WARN", "Orders", "1500", "Health check slow")));
+        assert!(!hit(q, &rec("WARN", "gateway", "1500", "x")));
+    }
+
+    #[test]
+    fn or_not_parens_and_implicit_and() {
+        let r = rec("WARN", "auth-api", "12", "Password retry count high");
+        assert!(hit("service:gateway OR service:auth*", &r));
+        assert!(hit("retry count", &r));
+        assert!(hit(r#""retry count" level:warn"#, &r));
+        assert!(!hit("NOT (level:warn OR level:error)", &r));
+        assert!(hit("userId!=u1", &r));
+        assert!(!hit("userId:*", &r));
+        assert!(hit("service:*", &r));
+        assert!(hit("duration_ms<=12 and duration_ms>=12", &r));
+    }
+
+    #[test]
+    fn quoted_values_survive_the_round_trip() {
+        let msg = r#"db "orders" at C:\tmp <num>"#;
+        let r = rec("INFO", "orders", "1", msg);
+        assert!(hit(&format!("message={}", quote(msg)), &r));
+        assert!(!hit(&format!("message={}", quote("db")), &r));
+    }
+
+    #[test]
+    fn errors_point_at_the_problem() {
+        assert!(parse("  ").unwrap().is_none());
+        let e = parse("level>= AND x").unwrap_err();
+        assert_eq!(e.pos, 8);
+        assert!(parse("(level:info").unwrap_err().message.contains("')'"));
+        assert_eq!(parse(r#"msg~"(""#).unwrap_err().pos, 4);
+        assert_eq!(parse(r#"say "hi"#).unwrap_err().pos, 4);
+        assert_eq!(parse(":x").unwrap_err().pos, 0);
+    }
+}
@@ -0,0 +1,78 @@
+// src/rows.rs — prefix sums behind the windowed preview
+//
+// Every preview entry takes a whole number of fixed-height rows (lines never
+// wrap), so a scroll offset maps straight to the entries on screen and only
+// those get rendered. The same structure numbers highlight matches: "rows"
+// are then matches and `find` says which entry holds match k.
+
+use std::ops::Range;
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Rows {
+    /// `starts[i]` = first row of item i; the last element is the total.
+    starts: Vec<usize>,
+}
+
+impl Rows {
+    pub fn new(heights: impl IntoIterator<Item = usize>) -> Self {
+        let mut starts = vec![0];
+        let mut acc = 0;
+        for h in heights {
+            acc += h;
+            starts.push(acc);
+        }
+        Self { starts }
+    }
+
+    pub fn len(&self) -> usize {
+        self.starts.len() - 1
+    }
+
+    pub fn total(&self) -> usize {
+        self.starts[self.len()]
+    }
+
+    pub fn start(&self, item: usize) -> usize {
+        self.starts[item.min(self.len())]
+    }
+
+    /// The item covering `row` (the last item for rows past the end).
+    pub fn find(&self, row: usize) -> usize {
+        let items = &self.starts[..self.len()];
+        items.partition_point(|&s| s <= row).saturating_sub(1)
+    }
+
+    /// Items overlapping rows `first .. first + count`.
+    pub fn visible(&self, first: usize, count: usize) -> Range<usize> {
+        if self.len() == 0 {
+            return 0..0;
+        }
+        let items = &self.starts[..self.len()];
+        let end = items.partition_point(|&s| s < first + count);
+        self.find(first).min(end)..end
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn maps_rows_to_items() {
+        // items of 3, 1, 0 (an entry with no matches), 5 rows
+        let rows = Rows::new([3, 1, 0, 5]);
+        assert_eq!((rows.len(), rows.total()), (4, 9));
+        assert_eq!(rows.start(3), 4);
+        assert_eq!(rows.find(0), 0);
+        assert_eq!(rows.find(2), 0);
+        assert_eq!(rows.find(3), 1);
+        assert_eq!(rows.find(4), 3);
+        assert_eq!(rows.find(100), 3);
+
+        assert_eq!(rows.visible(0, 3), 0..1);
+        assert_eq!(rows.visible(2, 3), 0..4);
+        assert_eq!(rows.visible(5, 10), 3..4);
+        assert_eq!(rows.visible(50, 10), 3..4);
+        assert_eq!(Rows::new([]).visible(0, 10), 0..0);
+    }
+}
@@ -0,0 +1,238 @@
+// src/template.rs — message templates: the text with its variable parts masked
+//
+//     Order sync failed for order 5042 after 1200ms  ->  Order sync failed for order <num> after <num>
+//     GET /api/v1/orders/77 from 10.0.0.4:51234      ->  GET /api/v1/orders/<num> from <ip>
+//
+// Masked: quoted strings, UUIDs, IPs (port included), timestamps, numbers
+// (with a short unit such as `ms` or `%`), hex strings and ids that mix
+// letters with digits (`req-0a1b2c`, `u1001`). Entries that share a template
+// form one cluster.
+
+use std::collections::HashMap;
+
+use regex::Regex;
+
+use crate::time;
+
+pub struct Masker {
+    quoted: Regex,
+    uuid: Regex,
+    ip: Regex,
+    ts: Regex,
+    num: Regex,
+    hex: Regex,
+}
+
+impl Default for Masker {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Masker {
+    pub fn new() -> Self {
+        Self {
+            // a single quote opens a string only after a space, `=`, `:` or
+            // a bracket, so "can't" stays a word
+            quoted: Regex::new(r#""(?:[^"\\]|\\.)*"|(^|[\s=(\[:])'(?:[^'\\]|\\.)*'"#)
+                .expect("quoted regex"),
+            uuid: Regex::new(r"^[0-9a-fA-F]{8}(?:-[0-9a-fA-F]{4}){3}-[0-9a-fA-F]{12}$")
+                .expect("uuid regex"),
+            ip: Regex::new(r"^\d{1,3}(?:\.\d{1,3}){3}(?::\d+)?$").expect("ip regex"),
+            ts: Regex::new(
+                r"^(?:\d{4}-\d{2}-\d{2}(?:[T ]\d{2}:\d{2}(?::\d{2}(?:[.,]\d+)?)?(?:Z|[+-]\d{2}:?\d{2})?)?|\d{2}:\d{2}:\d{2}(?:[.,]\d+)?)$",
+            )
+            .expect("timestamp regex"),
+            num: Regex::new(r"^[-+]?\d+(?:[.,]\d+)*(?:[a-zA-Z%]{1,3})?$").expect("number regex"),
+            hex: Regex::new(r"^(?:0x[0-9a-fA-F]+|[0-9a-fA-F]{8,})$").expect("hex regex"),
+        }
+    }
+
+    /// The template for one message; whitespace runs collapse to one space.
+    pub fn mask(&self, msg: &str) -> String {
+        let msg = self.quoted.replace_all(msg, "${1}<str>");
+        msg.split_whitespace()
+            .map(|tok| {
+                // punctuation around a value is kept as-is
+                let core = tok.trim_start_matches(['(', '[', '{', '\'', '"']);
+                let lead = &tok[..tok.len() - core.len()];
+                let inner =
+                    core.trim_end_matches([')', ']', '}', '\'', '"', ',', ';', '.', ':', '!', '?']);
+                let trail = &core[inner.len()..];
+                format!("{lead}{}{trail}", self.value(inner))
+            })
+            .collect::<Vec<_>>()
+            .join(" ")
+    }
+
+    fn value(&self, s: &str) -> String {
+        if s.is_empty() || s == "<str>" {
+            return s.to_string();
+        }
+        if let Some((key, val)) = s.split_once('=') {
+            if !key.is_empty()
+                && key
+                    .chars()
+                    .all(|c| c.is_alphanumeric() || "_.-".contains(c))
+            {
+                return format!("{key}={}", self.value(val));
+            }
+        }
+        let has_digit = s.bytes().any(|b| b.is_ascii_digit());
+        if !has_digit {
+            return s.to_string();
+        }
+        let masked = if self.uuid.is_match(s) {
+            "<uuid>"
+        } else if self.ip.is_match(s) {
+            "<ip>"
+        } else if self.ts.is_match(s) {
+            "<ts>"
+        } else if self.num.is_match(s) {
+            "<num>"
+        } else if self.hex.is_match(s) {
+            "<hex>"
+        } else if s.contains(['/', ':']) {
+            // paths and key:value pairs: mask each segment
+            return s
+                .split_inclusive(['/', ':'])
+                .map(|seg| {
+                    let body = seg.trim_end_matches(['/', ':']);
+                    format!("{}{}", self.value(body), &seg[body.len()..])
+                })
+                .collect();
+        } else if is_id(s) {
+            "<id>"
+        } else {
+            s
+        };
+        masked.to_string()
+    }
+}
+
+/// Letters and digits together, either split by `-`/`_` (`req-0a1b2c`) or with
+/// at least two digits (`u1001`); `v1` and `utf8` stay words.
+fn is_id(s: &str) -> bool {
+    let alnum = s
+        .chars()
+        .all(|c| c.is_ascii_alphanumeric() || "-_.".contains(c));
+    let digits = s.bytes().filter(u8::is_ascii_digit).count();
+    let letters = s.bytes().any(|b| b.is_ascii_alphabetic());
+    let separated = s
+        .split(['-', '_'])
+        .skip(1)
+        .any(|part| part.bytes().any(|b| b.is_ascii_digit()));
+    alnum && letters && (separated || digits >= 2)
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Cluster {
+    pub template: String,
+    pub count: usize,
+    /// Line numbers of the first and last member.
+    pub first_line: usize,
+    pub last_line: usize,
+    pub first_ts: Option<i64>,
+    pub last_ts: Option<i64>,
+    /// Member count per `time::BANDS` level band.
+    pub levels: [usize; 5],
+}
+
+/// Group `(line, template, timestamp, level)` items (in log order); biggest
+/// clusters first.
+pub fn cluster<'a>(
+    items: impl IntoIterator<Item = (usize, &'a str, Option<i64>, Option<&'a str>)>,
+) -> Vec<Cluster> {
+    let mut out: Vec<Cluster> = Vec::new();
+    let mut at: HashMap<&'a str, usize> = HashMap::new();
+    for (line, template, ts, level) in items {
+        let c = *at.entry(template).or_insert_with(|| {
+            out.push(Cluster {
+                template: template.to_string(),
+                count: 0,
+                first_line: line,
+                last_line: line,
+                first_ts: None,
+                last_ts: None,
+                levels: [0; 5],
+            });
+            out.len() - 1
+        });
+        let c = &mut out[c];
+        c.count += 1;
+        c.last_line = line;
+        if let Some(t) = ts {
+            c.first_ts = Some(c.first_ts.map_or(t, |f| f.min(t)));
+            c.last_ts = Some(c.last_ts.map_or(t, |l| l.max(t)));
+        }
+        c.levels[time::band(level)] += 1;
+    }
+    out.sort_by(|a, b| b.count.cmp(&a.count).then(a.first_line.cmp(&b.first_line)));
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn masks_variable_tokens() {
+        let m = Masker::new();
+        for (msg, want) in [
+            (
+                "Order sync failed for order 5042 after 1200ms",
+                "Order sync failed for order <num> after <num>",
+            ),
+            (
+                "GET /api/v1/orders/77 from 10.0.0.4:51234",
+                "GET /api/v1/orders/<num> from <ip>",
+            ),
+            (
+                "user u1001 req=req-0a1b2c trace 123e4567-e89b-12d3-a456-426614174000",
+                "user <id> req=<id> trace <uuid>",
+            ),
+            (
+                r#"db "orders" timeout (attempt 3/5), retry in 2.5s"#,
+                "db <str> timeout (attempt <num>/<num>), retry in <num>",
+            ),
+            (
+                "Cache miss for key=session:u1001 at 2026-02-24T20:11:04Z",
+                "Cache miss for key=session:<id> at <ts>",
+            ),
+            (
+                "blob a3f9c2e1d4 at 0xdeadbeef via utf8 v1",
+                "blob <hex> at <hex> via utf8 v1",
+            ),
+            (
+                "can't reach 'db-1' (key='a b')",
+                "can't reach <str> (key=<str>)",
+            ),
+            ("  Server   health check OK  ", "Server health check OK"),
+        ] {
+            assert_eq!(m.mask(msg), want, "{msg}");
+        }
+    }
+
+    #[test]
+    fn clusters_by_template_with_counts_and_levels() {
+        let items = [
+            (0, "a <num>", Some(300), Some("INFO")),
+            (1, "b", None, Some("ERROR")),
+            (4, "a <num>", Some(100), Some("WARN")),
+            (5, "b", Some(50), None),
+            (9, "a <num>", None, Some("INFO")),
+        ];
+        let clusters = cluster(items);
+        assert_eq!(clusters.len(), 2);
+
+        let a = &clusters[0];
+        assert_eq!((a.template.as_str(), a.count), ("a <num>", 3));
+        assert_eq!((a.first_line, a.last_line), (0, 9));
+        assert_eq!((a.first_ts, a.last_ts), (Some(100), Some(300)));
+        assert_eq!(a.levels, [0, 1, 2, 0, 0]);
+
+        let b = &clusters[1];
+        assert_eq!((b.count, b.first_ts, b.last_ts), (2, Some(50), Some(50)));
+        assert_eq!(b.levels, [1, 0, 0, 0, 1]);
+    }
+}
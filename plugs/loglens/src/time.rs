@@ -0,0 +1,388 @@
+// src/time.rs — timestamps and the level histogram
+//
+// Timestamps become epoch milliseconds (UTC). Understood: ISO-8601 / RFC 3339
+// (`T` or space, optional fraction and offset), epoch seconds or milliseconds,
+// Apache `24/Feb/2026:20:11:05 +0000` and syslog `Feb 24 20:11:05`, which has
+// no year and borrows one from the rest of the log.
+
+const MONTHS: [&str; 12] = [
+    "Jan", "Feb", "Mar", "Apr", "May", "Jun", "Jul", "Aug", "Sep", "Oct", "Nov", "Dec",
+];
+
+/// Days since 1970-01-01 for a proleptic Gregorian date (Howard Hinnant's algorithm).
+fn days_from_civil(y: i64, m: u32, d: u32) -> i64 {
+    let y = if m <= 2 { y - 1 } else { y };
+    let era = y.div_euclid(400);
+    let yoe = y - era * 400;
+    let mp = (m as i64 + 9) % 12;
+    let doy = (153 * mp + 2) / 5 + d as i64 - 1;
+    let doe = yoe * 365 + yoe / 4 - yoe / 100 + doy;
+    era * 146097 + doe - 719468
+}
+
+fn civil_from_days(z: i64) -> (i64, u32, u32) {
+    let z = z + 719468;
+    let era = z.div_euclid(146097);
+    let doe = z - era * 146097;
+    let yoe = (doe - doe / 1460 + doe / 36524 - doe / 146096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let d = (doy - (153 * mp + 2) / 5 + 1) as u32;
+    let m = if mp < 10 { mp + 3 } else { mp - 9 } as u32;
+    (
+        if m <= 2 {
+            yoe + era * 400 + 1
+        } else {
+            yoe + era * 400
+        },
+        m,
+        d,
+    )
+}
+
+/// Small cursor over ASCII digits and separators.
+struct Scan<'a> {
+    s: &'a [u8],
+    i: usize,
+}
+
+impl Scan<'_> {
+    fn num(&mut self, min: usize, max: usize) -> Option<i64> {
+        let start = self.i;
+        while self.i < self.s.len() && self.i - start < max && self.s[self.i].is_ascii_digit() {
+            self.i += 1;
+        }
+        if self.i - start < min {
+            return None;
+        }
+        std::str::from_utf8(&self.s[start..self.i])
+            .ok()?
+            .parse()
+            .ok()
+    }
+
+    fn eat(&mut self, c: u8) -> bool {
+        if self.s.get(self.i) == Some(&c) {
+            self.i += 1;
+            true
+        } else {
+            false
+        }
+    }
+
+    fn expect(&mut self, c: u8) -> Option<()> {
+        self.eat(c).then_some(())
+    }
+
+    fn month_name(&mut self) -> Option<u32> {
+        let name = self.s.get(self.i..self.i + 3)?;
+        let m = MONTHS.iter().position(|m| m.as_bytes() == name)?;
+        self.i += 3;
+        Some(m as u32 + 1)
+    }
+
+    /// `.123` / `,123456` → milliseconds.
+    fn fraction_ms(&mut self) -> i64 {
+        if !(self.eat(b'.') || self.eat(b',')) {
+            return 0;
+        }
+        let start = self.i;
+        while self.i < self.s.len() && self.s[self.i].is_ascii_digit() {
+            self.i += 1;
+        }
+        let digits = &self.s[start..self.i];
+        let mut ms = 0;
+        for k in 0..3 {
+            ms = ms * 10 + digits.get(k).map_or(0, |d| (d - b'0') as i64);
+        }
+        ms
+    }
+
+    /// `Z`, `+hh:mm`, `+hhmm`, `-hh` or nothing (UTC); returns the offset in ms.
+    fn offset_ms(&mut self) -> Option<i64> {
+        if self.eat(b'Z') || self.eat(b'z') {
+            return Some(0);
+        }
+        let sign = if self.eat(b'+') {
+            1
+        } else if self.eat(b'-') {
+            -1
+        } else {
+            return Some(0);
+        };
+        let h = self.num(2, 2)?;
+        self.eat(b':');
+        let m = self.num(0, 2).unwrap_or(0);
+        Some(sign * (h * 60 + m) * 60_000)
+    }
+
+    fn skip_spaces(&mut self) {
+        while self.s.get(self.i) == Some(&b' ') {
+            self.i += 1;
+        }
+    }
+}
+
+fn to_ms(y: i64, mo: u32, d: u32, h: i64, mi: i64, s: i64, ms: i64) -> Option<i64> {
+    if !(1..=12).contains(&mo) || !(1..=31).contains(&d) || h > 23 || mi > 59 || s > 60 {
+        return None;
+    }
+    Some(((days_from_civil(y, mo, d) * 24 + h) * 60 + mi) * 60_000 + s * 1000 + ms)
+}
+
+fn iso(s: &str) -> Option<i64> {
+    let mut c = Scan {
+        s: s.as_bytes(),
+        i: 0,
+    };
+    let y = c.num(4, 4)?;
+    c.expect(b'-')?;
+    let mo = c.num(2, 2)? as u32;
+    c.expect(b'-')?;
+    let d = c.num(2, 2)? as u32;
+    if !(c.eat(b'T') || c.eat(b't') || c.eat(b' ')) {
+        return to_ms(y, mo, d, 0, 0, 0, 0);
+    }
+    let h = c.num(2, 2)?;
+    c.expect(b':')?;
+    let mi = c.num(2, 2)?;
+    let sec = if c.eat(b':') { c.num(2, 2)? } else { 0 };
+    let frac = c.fraction_ms();
+    c.skip_spaces();
+    let off = c.offset_ms()?;
+    Some(to_ms(y, mo, d, h, mi, sec, frac)? - off)
+}
+
+fn apache(s: &str) -> Option<i64> {
+    let mut c = Scan {
+        s: s.as_bytes(),
+        i: 0,
+    };
+    let d = c.num(1, 2)? as u32;
+    c.expect(b'/')?;
+    let mo = c.month_name()?;
+    c.expect(b'/')?;
+    let y = c.num(4, 4)?;
+    c.expect(b':')?;
+    let h = c.num(2, 2)?;
+    c.expect(b':')?;
+    let mi = c.num(2, 2)?;
+    c.expect(b':')?;
+    let sec = c.num(2, 2)?;
+    c.skip_spaces();
+    let off = c.offset_ms()?;
+    Some(to_ms(y, mo, d, h, mi, sec, 0)? - off)
+}
+
+fn syslog(s: &str, year: i64) -> Option<i64> {
+    let mut c = Scan {
+        s: s.as_bytes(),
+        i: 0,
+    };
+    let mo = c.month_name()?;
+    c.skip_spaces();
+    let d = c.num(1, 2)? as u32;
+    c.skip_spaces();
+    let h = c.num(2, 2)?;
+    c.expect(b':')?;
+    let mi = c.num(2, 2)?;
+    c.expect(b':')?;
+    let sec = c.num(2, 2)?;
+    let frac = c.fraction_ms();
+    to_ms(year, mo, d, h, mi, sec, frac)
+}
+
+/// Epoch seconds (10 digits, optional fraction) or milliseconds (13 digits).
+fn epoch(s: &str) -> Option<i64> {
+    let (int, frac) = s.split_once('.').unwrap_or((s, ""));
+    if !int.bytes().all(|b| b.is_ascii_digit()) || !frac.bytes().all(|b| b.is_ascii_digit()) {
+        return None;
+    }
+    match int.len() {
+        13 if frac.is_empty() => int.parse().ok(),
+        10 => {
+            let secs: i64 = int.parse().ok()?;
+            let ms = format!("{frac:0<3}").get(..3)?.parse::<i64>().ok()?;
+            Some(secs * 1000 + ms)
+        }
+        _ => None,
+    }
+}
+
+/// Epoch ms for a timestamp string. Syslog dates need `year`; with `None`
+/// they are not recognized.
+pub fn parse_timestamp(s: &str, year: Option<i64>) -> Option<i64> {
+    let s = s.trim().trim_start_matches('[').trim_end_matches(']');
+    iso(s)
+        .or_else(|| epoch(s))
+        .or_else(|| apache(s))
+        .or_else(|| syslog(s, year?))
+}
+
+pub fn year_of(ms: i64) -> i64 {
+    civil_from_days(ms.div_euclid(86_400_000)).0
+}
+
+/// `2026-02-24 20:11:05.123Z`
+pub fn format_ms(ms: i64) -> String {
+    let (y, mo, d) = civil_from_days(ms.div_euclid(86_400_000));
+    let t = ms.rem_euclid(86_400_000);
+    format!(
+        "{y:04}-{mo:02}-{d:02} {:02}:{:02}:{:02}.{:03}Z",
+        t / 3_600_000,
+        t / 60_000 % 60,
+        t / 1000 % 60,
+        t % 1000
+    )
+}
+
+/// `+1.250s`, `+3m 04.000s`, `+2h 00m 10.000s`
+pub fn format_offset(ms: i64) -> String {
+    let sign = if ms < 0 { "-" } else { "+" };
+    let ms = ms.abs();
+    let (h, m, s, frac) = (ms / 3_600_000, ms / 60_000 % 60, ms / 1000 % 60, ms % 1000);
+    if h > 0 {
+        format!("{sign}{h}h {m:02}m {s:02}.{frac:03}s")
+    } else if m > 0 {
+        format!("{sign}{m}m {s:02}.{frac:03}s")
+    } else {
+        format!("{sign}{s}.{frac:03}s")
+    }
+}
+
+/// `1.250s`, `3m 04.000s` — an unsigned length of time.
+pub fn format_duration(ms: i64) -> String {
+    format_offset(ms.abs())[1..].to_string()
+}
+
+// ---------- histogram ----------
+
+/// Stack order, bottom to top.
+pub const BANDS: [&str; 5] = ["err", "warn", "info", "debug", "none"];
+
+pub fn band(level: Option<&str>) -> usize {
+    match level {
+        Some("ERROR" | "FATAL") => 0,
+        Some("WARN") => 1,
+        Some("INFO") => 2,
+        Some("DEBUG" | "TRACE") => 3,
+        _ => 4,
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Histogram {
+    pub start: i64,
+    pub width: i64,
+    /// Per bucket, entry counts per band.
+    pub buckets: Vec<[usize; 5]>,
+}
+
+const STEPS: [i64; 22] = [
+    1, 2, 5, 10, 20, 50, 100, 200, 500, 1_000, 2_000, 5_000, 10_000, 15_000, 30_000, 60_000,
+    120_000, 300_000, 600_000, 1_800_000, 3_600_000, 86_400_000,
+];
+
+impl Histogram {
+    /// At most `max_buckets` buckets of a round width covering every point.
+    pub fn build(points: &[(i64, usize)], max_buckets: usize) -> Option<Self> {
+        let min = points.iter().map(|p| p.0).min()?;
+        let max = points.iter().map(|p| p.0).max()?;
+        let span = max - min + 1;
+        let width = STEPS
+            .iter()
+            .copied()
+            .find(|w| ((span + w - 1) / w) as usize <= max_buckets)
+            .unwrap_or_else(|| (span + max_buckets as i64 - 1) / max_buckets as i64);
+        let start = min - min.rem_euclid(width);
+        let n = ((max - start) / width + 1) as usize;
+        let mut buckets = vec![[0usize; 5]; n];
+        for &(t, b) in points {
+            buckets[((t - start) / width) as usize][b.min(4)] += 1;
+        }
+        Some(Self {
+            start,
+            width,
+            buckets,
+        })
+    }
+
+    /// Inclusive time range covered by buckets `a..=b`.
+    pub fn range(&self, a: usize, b: usize) -> (i64, i64) {
+        let (lo, hi) = (a.min(b) as i64, a.max(b) as i64);
+        (
+            self.start + lo * self.width,
+            self.start + (hi + 1) * self.width - 1,
+        )
+    }
+
+    pub fn tallest(&self) -> usize {
+        self.buckets
+            .iter()
+            .map(|b| b.iter().sum::<usize>())
+            .max()
+            .unwrap_or(0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const T0: i64 = 1_771_963_865_000; // 2026-02-24T20:11:05Z
+
+    #[test]
+    fn iso_variants() {
+        assert_eq!(parse_timestamp("2026-02-24T20:11:05Z", None), Some(T0));
+        assert_eq!(
+            parse_timestamp("2026-02-24 20:11:05.25", None),
+            Some(T0 + 250)
+        );
+        assert_eq!(
+            parse_timestamp("2026-02-24T22:11:05.669794202+02:00", None),
+            Some(T0 + 669)
+        );
+        assert_eq!(parse_timestamp("2026-02-24", None), Some(T0 - 72_665_000));
+        assert_eq!(parse_timestamp("2026-13-24T20:11:05Z", None), None);
+    }
+
+    #[test]
+    fn epoch_apache_and_syslog() {
+        assert_eq!(parse_timestamp("1771963865", None), Some(T0));
+        assert_eq!(parse_timestamp("1771963865.5", None), Some(T0 + 500));
+        assert_eq!(parse_timestamp("1771963865123", None), Some(T0 + 123));
+        assert_eq!(
+            parse_timestamp("[24/Feb/2026:15:11:05 -0500]", None),
+            Some(T0)
+        );
+        assert_eq!(parse_timestamp("Feb 24 20:11:05", Some(2026)), Some(T0));
+        assert_eq!(
+            parse_timestamp("Feb  4 20:11:05", Some(2026)),
+            Some(T0 - 20 * 86_400_000)
+        );
+        assert_eq!(parse_timestamp("Feb 24 20:11:05", None), None);
+        assert_eq!(parse_timestamp("12345", None), None);
+    }
+
+    #[test]
+    fn formatting_round_trips() {
+        assert_eq!(format_ms(T0 + 7), "2026-02-24 20:11:05.007Z");
+        assert_eq!(year_of(T0), 2026);
+        assert_eq!(format_offset(1_250), "+1.250s");
+        assert_eq!(format_offset(184_000), "+3m 04.000s");
+        assert_eq!(format_offset(7_210_000), "+2h 00m 10.000s");
+    }
+
+    #[test]
+    fn histogram_uses_round_buckets() {
+        let pts = [(T0, 0), (T0 + 1_000, 2), (T0 + 59_000, 2), (T0 + 59_500, 1)];
+        let h = Histogram::build(&pts, 60).unwrap();
+        assert_eq!(h.width, 1_000);
+        assert_eq!(h.buckets.len(), 60);
+        assert_eq!(h.buckets[0], [1, 0, 0, 0, 0]);
+        assert_eq!(h.buckets[59], [0, 1, 1, 0, 0]);
+        assert_eq!(h.tallest(), 2);
+        assert_eq!(h.range(3, 1), (T0 + 1_000, T0 + 3_999));
+        assert!(Histogram::build(&[], 60).is_none());
+    }
+}
@@ -1,377 +1,13 @@
-﻿use base64::Engine;
-use gloo_net::http::Request;
-use gloo_storage::{LocalStorage, Storage};
-use serde::{Deserialize, Serialize};
-use web_sys::HtmlInputElement;
+﻿use gloo_storage::{LocalStorage, Storage};
+use plug_kit::templates::{self, TemplateVars};
+use plug_kit::{plug, DeliveryMode, GitHubClient, GitHubConfig, GlooTransport};
+use web_sys::{HtmlInputElement, HtmlSelectElement};
 use yew::prelude::*;
 
-const OWNER: &str = "ekim5sg";
-const REPO: &str = "webhtml5-plug-deployer";
-const WORKFLOW_FILE: &str = "deploy-hostek-plug.yml"; // file name under .github/workflows
+const USER_AGENT: &str = "webhtml5-plug-creator";
 
-#[derive(Serialize)]
-struct PutContentBody<'a> {
-    message: &'a str,
-    content: String, // base64
-    branch: &'a str,
-
-    #[serde(skip_serializing_if = "Option::is_none")]
-    sha: Option<String>,
-}
-
-#[derive(Deserialize)]
-struct ContentResp {
-    sha: String,
-}
-
-#[derive(Serialize)]
-struct DispatchBody<'a> {
-    #[serde(rename = "ref")]
-    git_ref: &'a str,
-    inputs: DispatchInputs<'a>,
-}
-
-#[derive(Serialize)]
-struct DispatchInputs<'a> {
-    plug_name: &'a str,
-    app_dir: &'a str,
-    clean_remote: &'a str,
-}
-
-fn b64(s: &str) -> String {
-    base64::engine::general_purpose::STANDARD.encode(s.as_bytes())
-}
-
-fn make_index_html(title: &str) -> String {
-    // Use r## to avoid accidental termination when content includes `"#` (e.g., "#0b1020")
-    format!(
-        r##"<!doctype html>
-<html lang="en">
-<head>
-  <meta charset="utf-8" />
-  <meta name="viewport" content="width=device-width,initial-scale=1" />
-  <meta name="color-scheme" content="dark" />
-  <meta name="theme-color" content="#0b1020" />
-  <title>{}</title>
-  <link data-trunk rel="css" href="styles.css" />
-</head>
-<body id="top">
-  <div class="bg" aria-hidden="true"></div>
-  <div id="app"></div>
-  <link data-trunk rel="rust" />
-</body>
-</html>
-"##,
-        title
-    )
-}
-
-fn make_styles_css() -> String {
-    // Starter dark-mode styling for generated plugs (no light sections)
-    r#"/* MikeGyver Studio • hard-locked dark mode (no light sections) */
-:root{
-  --bg0:#070a12;
-  --bg1:#0b1020;
-  --card:#0f1730;
-  --card2:#111c3a;
-  --text:#e8ecff;
-  --muted:#aab3d6;
-  --line:rgba(255,255,255,.10);
-  --shadow:rgba(0,0,0,.55);
-  --accent:#7c5cff;
-  --accent2:#28d7ff;
-  --good:#39d98a;
-  --warn:#ffd166;
-  --danger:#ff5c7a;
-  --radius:18px;
-}
-
-html,body{
-  height:100%;
-  background:var(--bg0) !important;
-  color:var(--text) !important;
-  margin:0;
-}
-
-body{
-  font-family: system-ui, -apple-system, Segoe UI, Roboto, Arial, sans-serif;
-  -webkit-font-smoothing:antialiased;
-  -moz-osx-font-smoothing:grayscale;
-  overflow-x:hidden;
-}
-
-*{ box-sizing:border-box; }
-a{ color:inherit; text-decoration:none; }
-button, input, select{ font:inherit; }
-
-.bg{
-  position:fixed;
-  inset:-20%;
-  z-index:-1;
-  background:
-    radial-gradient(900px 600px at 15% 10%, rgba(124,92,255,.28), transparent 55%),
-    radial-gradient(900px 600px at 85% 15%, rgba(40,215,255,.20), transparent 55%),
-    radial-gradient(900px 700px at 40% 90%, rgba(57,217,138,.12), transparent 60%),
-    linear-gradient(180deg, var(--bg0), var(--bg1));
-  filter:saturate(115%);
-}
-
-.wrap{
-  width:min(1100px, calc(100% - 32px));
-  margin:0 auto;
-  padding:18px 0 64px;
-}
-
-.badge{
-  display:inline-flex;
-  align-items:center;
-  gap:10px;
-  padding:8px 12px;
-  border:1px solid var(--line);
-  border-radius:999px;
-  background:rgba(255,255,255,.04);
-  box-shadow: 0 18px 60px var(--shadow);
-  font-size:13px;
-  color:var(--muted);
-}
-
-.h1{
-  margin:14px 0 6px;
-  font-size:clamp(28px, 4vw, 44px);
-  line-height:1.08;
-  letter-spacing:-.02em;
-}
-
-.sub{
-  margin:0;
-  color:var(--muted);
-  font-size:15px;
-  line-height:1.5;
-  max-width:70ch;
-}
-
-.card{
-  border:1px solid var(--line);
-  background:linear-gradient(180deg, rgba(255,255,255,.04), rgba(255,255,255,.02));
-  border-radius:var(--radius);
-  box-shadow: 0 22px 80px var(--shadow);
-  overflow:hidden;
-}
-
-.card-h{ padding:16px 16px 0; }
-.card-b{ padding:0 16px 16px; }
-
-.row{
-  display:flex;
-  gap:10px;
-  flex-wrap:wrap;
-}
-
-.btn{
-  appearance:none;
-  border:none;
-  border-radius:14px;
-  padding:12px 14px;
-  font-weight:650;
-  color:var(--text);
-  background:linear-gradient(135deg, rgba(124,92,255,.95), rgba(40,215,255,.70));
-  box-shadow: 0 14px 30px rgba(124,92,255,.18);
-  cursor:pointer;
-}
-.btn:active{ transform: scale(.99); }
-
-.btn2{
-  background:rgba(255,255,255,.05);
-  border:1px solid var(--line);
-  box-shadow:none;
-}
-"#.to_string()
-}
-
-fn make_cargo_toml(plug_name: &str) -> String {
-    let pkg = plug_name.replace('-', "_");
-    format!(
-        r#"[package]
-name = "{pkg}"
-version = "0.1.0"
-edition = "2021"
-
-[dependencies]
-yew = {{ version = "0.21", features = ["csr"] }}
-wasm-bindgen = "0.2"
-"#,
-        pkg = pkg
-    )
-}
-
-fn make_main_rs(title: &str, plug_name: &str) -> String {
-    // IMPORTANT: Do NOT use format! on a template that contains lots of `{}` braces
-    // (Yew html! uses them). Use replace() with explicit tokens instead.
-    let title_lit = serde_json::to_string(title).unwrap_or_else(|_| "\"Untitled\"".to_string());
-    let url = format!("https://www.webhtml5.info/{}/", plug_name);
-    let url_lit = serde_json::to_string(&url).unwrap_or_else(|_| "\"\"".to_string());
-
-    let template = r#"use yew::prelude::*;
-
-#[function_component(App)]
-fn app() -> Html {
-    html! {
-        <main class="wrap">
-          <section class="card">
-            <div class="card-h">
-              <div class="badge">{ "webhtml5 plug scaffold" }</div>
-              <h1 class="h1">{ __TITLE__ }</h1>
-              <p class="sub">{ "Plug scaffold is live. Replace this content with your real app." }</p>
-            </div>
-            <div class="card-b">
-              <p class="sub">{ __URL__ }</p>
-            </div>
-          </section>
-        </main>
-    }
-}
-
-fn main() {
-    yew::Renderer::<App>::new().render();
-}
-"#;
-
-    template
-        .replace("__TITLE__", &title_lit)
-        .replace("__URL__", &url_lit)
-}
-
-async fn github_get_sha(token: &str, file_path: &str) -> Result<Option<String>, String> {
-    // Pin to main to avoid weirdness while commits are happening
-    let url = format!(
-        "https://api.github.com/repos/{}/{}/contents/{}?ref=main",
-        OWNER, REPO, file_path
-    );
-
-    let resp = Request::get(&url)
-        .header("Authorization", &format!("Bearer {}", token))
-        .header("Accept", "application/vnd.github+json")
-        .header("X-GitHub-Api-Version", "2022-11-28")
-        .header("User-Agent", "webhtml5-plug-creator")
-        .send()
-        .await
-        .map_err(|e| e.to_string())?;
-
-    if resp.status() == 404 {
-        return Ok(None);
-    }
-
-    if !resp.ok() {
-        let status = resp.status();
-        let text = resp.text().await.unwrap_or_default();
-        return Err(format!("GET {} failed: {} {}", file_path, status, text));
-    }
-
-    let json = resp.json::<ContentResp>().await.map_err(|e| e.to_string())?;
-    Ok(Some(json.sha))
-}
-
-async fn github_put_file(
-    token: &str,
-    file_path: &str,
-    message: &str,
-    content: &str,
-    overwrite: bool,
-) -> Result<(), String> {
-    let url = format!(
-        "https://api.github.com/repos/{}/{}/contents/{}",
-        OWNER, REPO, file_path
-    );
-
-    // We'll try twice. If the SHA changed between GET and PUT, GitHub returns 409.
-    // On 409, refetch sha and retry once.
-    for attempt in 1..=2 {
-        let sha = match github_get_sha(token, file_path).await? {
-            Some(existing_sha) => {
-                if overwrite {
-                    Some(existing_sha)
-                } else {
-                    return Err(format!(
-                        "File already exists (overwrite disabled): {}",
-                        file_path
-                    ));
-                }
-            }
-            None => None,
-        };
-
-        let body = PutContentBody {
-            message,
-            content: b64(content),
-            branch: "main",
-            sha,
-        };
-
-        let resp = Request::put(&url)
-            .header("Authorization", &format!("Bearer {}", token))
-            .header("Accept", "application/vnd.github+json")
-            .header("X-GitHub-Api-Version", "2022-11-28")
-            .header("User-Agent", "webhtml5-plug-creator")
-            .json(&body)
-            .map_err(|e| e.to_string())?
-            .send()
-            .await
-            .map_err(|e| e.to_string())?;
-
-        if resp.ok() {
-            return Ok(());
-        }
-
-        let status = resp.status();
-        let text = resp.text().await.unwrap_or_default();
-
-        // 409 means: sha mismatch / file changed since we fetched sha
-        if status == 409 && attempt == 1 {
-            // retry once after refetching sha
-            continue;
-        }
-
-        return Err(format!("PUT {} failed: {} {}", file_path, status, text));
-    }
-
-    Err(format!("PUT {} failed: sha mismatch after retry", file_path))
-}
-
-async fn github_dispatch(token: &str, plug_name: &str) -> Result<(), String> {
-    let url = format!(
-        "https://api.github.com/repos/{}/{}/actions/workflows/{}/dispatches",
-        OWNER, REPO, WORKFLOW_FILE
-    );
-
-    let app_dir = format!("plugs/{}", plug_name);
-
-    let body = DispatchBody {
-        git_ref: "main",
-        inputs: DispatchInputs {
-            plug_name,
-            app_dir: &app_dir,
-            clean_remote: "false",
-        },
-    };
-
-    let resp = Request::post(&url)
-        .header("Authorization", &format!("Bearer {}", token))
-        .header("Accept", "application/vnd.github+json")
-        .header("X-GitHub-Api-Version", "2022-11-28")
-        .header("User-Agent", "webhtml5-plug-creator")
-        .json(&body)
-        .map_err(|e| e.to_string())?
-        .send()
-        .await
-        .map_err(|e| e.to_string())?;
-
-    if resp.status() == 204 {
-        Ok(())
-    } else {
-        let status = resp.status();
-        let text = resp.text().await.unwrap_or_default();
-        Err(format!("Dispatch failed: {} {}", status, text))
-    }
+fn github(token: &str) -> GitHubClient<GlooTransport> {
+    GitHubClient::new(token, GitHubConfig::default().with_user_agent(USER_AGENT))
 }
 
 #[function_component(App)]
@@ -381,6 +17,12 @@ fn app() -> Html {
     let title = use_state(|| "Rust iPhone Compiler".to_string());
     let status = use_state(|| "".to_string());
     let busy = use_state(|| false);
+    // One Git Data API commit for the whole scaffold (vs. one Contents PUT per file)
+    let atomic = use_state(|| true);
+    let mode = use_state(|| DeliveryMode::Direct);
+    let template_id = use_state(|| templates::default_template().id);
+    // (PR number, plug) waiting for merge before the production deploy
+    let pending_pr = use_state(|| None::<(u64, String)>);
 
     let on_token = {
         let token = token.clone();
@@ -406,6 +48,30 @@ fn app() -> Html {
         })
     };
 
+    let on_atomic = {
+        let atomic = atomic.clone();
+        Callback::from(move |e: Event| {
+            let v = e.target_unchecked_into::<HtmlInputElement>().checked();
+            atomic.set(v);
+        })
+    };
+
+    let on_mode = {
+        let mode = mode.clone();
+        Callback::from(move |e: Event| {
+            let v = e.target_unchecked_into::<HtmlSelectElement>().value();
+            mode.set(DeliveryMode::from_key(&v).unwrap_or(DeliveryMode::Direct));
+        })
+    };
+
+    let on_template = {
+        let template_id = template_id.clone();
+        Callback::from(move |e: Event| {
+            let v = e.target_unchecked_into::<HtmlSelectElement>().value();
+            template_id.set(templates::find(&v).unwrap_or(templates::default_template()).id);
+        })
+    };
+
     let on_save_token = {
         let token = token.clone();
         let status = status.clone();
@@ -426,21 +92,24 @@ fn app() -> Html {
         let title = title.clone();
         let status = status.clone();
         let busy = busy.clone();
+        let atomic = atomic.clone();
+        let mode = mode.clone();
+        let template_id = template_id.clone();
+        let pending_pr = pending_pr.clone();
 
         Callback::from(move |_| {
             let token = (*token).clone();
             let plug = (*plug_name).trim().to_string();
             let title = (*title).trim().to_string();
+            let atomic = *atomic;
+            let mode = *mode;
+            let template = templates::find(*template_id).unwrap_or(templates::default_template());
 
             if token.trim().is_empty() {
                 status.set("Missing GitHub token.".into());
                 return;
             }
-            if plug.is_empty()
-                || !plug
-                    .chars()
-                    .all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '-')
-            {
+            if plug::sanitize_name(&plug).is_none() {
                 status.set("plug_name must be lowercase letters, numbers, hyphens.".into());
                 return;
             }
@@ -451,42 +120,66 @@ fn app() -> Html {
             wasm_bindgen_futures::spawn_local({
                 let status = status.clone();
                 let busy = busy.clone();
+                let pending_pr = pending_pr.clone();
                 async move {
-                    let base = format!("plugs/{}", plug);
+                    let base = plug::app_dir(&plug);
                     let msg = format!("Add plug scaffold: {}", plug);
 
                     // Carpool lane default: overwrite existing files if present
                     let overwrite = true;
 
-                    let idx = make_index_html(&title);
-                    let toml = make_cargo_toml(&plug);
-                    let mainrs = make_main_rs(&title, &plug);
-                    let css = make_styles_css();
+                    let gh = github(&token);
+                    let changes = template.files(&TemplateVars::new(&plug, &title));
+
+                    if mode != DeliveryMode::Direct {
+                        match gh.deliver_plug(&plug, &msg, &changes, mode).await {
+                            Ok(d) => {
+                                let mut lines = vec![format!("Committed to {} ✅", d.branch)];
+                                if let Some(pr) = &d.pull_request {
+                                    lines.push(format!("Pull request #{}: {}", pr.number, pr.html_url));
+                                    pending_pr.set(Some((pr.number, plug.clone())));
+                                }
+                                if let Some(folder) = &d.deployed_as {
+                                    lines.push(format!("Preview dispatched ✅ URL: {}", plug::public_url(folder)));
+                                }
+                                lines.push("Merge the PR, then tap “Deploy after merge”.".into());
+                                status.set(lines.join("\n"));
+                            }
+                            Err(e) => status.set(format!("Pull request flow error:\n{}", e)),
+                        }
+                        busy.set(false);
+                        return;
+                    }
 
-                    let r1 = github_put_file(&token, &format!("{}/index.html", base), &msg, &idx, overwrite).await;
-                    let r2 = github_put_file(&token, &format!("{}/Cargo.toml", base), &msg, &toml, overwrite).await;
-                    let r3 = github_put_file(&token, &format!("{}/src/main.rs", base), &msg, &mainrs, overwrite).await;
-                    let r4 = github_put_file(&token, &format!("{}/styles.css", base), &msg, &css, overwrite).await;
+                    let written = if atomic {
+                        // blobs → tree → commit → ref: main only moves if every step succeeds
+                        gh.commit_files(&msg, &changes)
+                            .await
+                            .map(|_| ())
+                            .map_err(|e| e.to_string())
+                    } else {
+                        let mut errs: Vec<String> = Vec::new();
+                        for c in &changes {
+                            let content = c.content.as_deref().unwrap_or_default();
+                            if let Err(e) = gh.upsert_file(&c.path, &msg, content, overwrite).await {
+                                errs.push(e.to_string());
+                            }
+                        }
+                        if errs.is_empty() { Ok(()) } else { Err(errs.join("\n")) }
+                    };
 
-                    match (r1, r2, r3, r4) {
-                        (Ok(_), Ok(_), Ok(_), Ok(_)) => {
+                    match written {
+                        Ok(()) => {
                             status.set("Files created/updated ✅ Dispatching workflow…".into());
-                            match github_dispatch(&token, &plug).await {
+                            match gh.dispatch(&plug, &base).await {
                                 Ok(_) => status.set(format!(
-                                    "Workflow dispatched ✅ URL: https://www.webhtml5.info/{}/",
-                                    plug
+                                    "Workflow dispatched ✅ URL: {}",
+                                    plug::public_url(&plug)
                                 )),
                                 Err(e) => status.set(format!("Dispatch error: {}", e)),
                             }
                         }
-                        (a, b, c, d) => {
-                            let mut errs = vec![];
-                            if let Err(e) = a { errs.push(e); }
-                            if let Err(e) = b { errs.push(e); }
-                            if let Err(e) = c { errs.push(e); }
-                            if let Err(e) = d { errs.push(e); }
-                            status.set(format!("Create file error:\n{}", errs.join("\n")));
-                        }
+                        Err(e) => status.set(format!("Create file error:\n{}", e)),
                     }
 
                     busy.set(false);
@@ -495,6 +188,46 @@ fn app() -> Html {
         })
     };
 
+    let on_deploy_merged = {
+        let token = token.clone();
+        let status = status.clone();
+        let busy = busy.clone();
+        let pending_pr = pending_pr.clone();
+
+        Callback::from(move |_| {
+            let Some((number, plug)) = (*pending_pr).clone() else {
+                return;
+            };
+            busy.set(true);
+            status.set(format!("Checking pull request #{}…", number));
+
+            wasm_bindgen_futures::spawn_local({
+                let token = (*token).clone();
+                let status = status.clone();
+                let busy = busy.clone();
+                let pending_pr = pending_pr.clone();
+                async move {
+                    match github(&token).deploy_if_merged(number, &plug).await {
+                        Ok(pr) if pr.is_merged() => {
+                            pending_pr.set(None);
+                            status.set(format!(
+                                "PR #{} merged — workflow dispatched ✅ URL: {}",
+                                number,
+                                plug::public_url(&plug)
+                            ));
+                        }
+                        Ok(pr) => status.set(format!(
+                            "PR #{} is {} and not merged yet — nothing deployed.\n{}",
+                            number, pr.state, pr.html_url
+                        )),
+                        Err(e) => status.set(format!("Deploy after merge error: {}", e)),
+                    }
+                    busy.set(false);
+                }
+            });
+        })
+    };
+
     html! {
         <>
           <div class="bg" aria-hidden="true"></div>
@@ -531,10 +264,46 @@ fn app() -> Html {
                 <input value={(*title).clone()} oninput={on_title}
                   style="width:100%; margin-top:6px; padding:12px; border-radius:14px; border:1px solid rgba(255,255,255,.10); background:rgba(0,0,0,.25); color:#e8ecff;" />
 
+                <label style="display:block; margin-top:12px; font-size:12px; color:#aab3d6;">{ "Starter template" }</label>
+                <select onchange={on_template}
+                  style="width:100%; margin-top:6px; padding:12px; border-radius:14px; border:1px solid rgba(255,255,255,.10); background:rgba(0,0,0,.25); color:#e8ecff;">
+                  { for templates::CATALOG.iter().map(|t| html! {
+                    <option value={t.id} selected={t.id == *template_id}>{ t.name }</option>
+                  }) }
+                </select>
+                <p class="sub" style="max-width:none; margin-top:6px; font-size:13px;">
+                  { templates::find(*template_id).map(|t| t.description).unwrap_or_default() }
+                </p>
+
+                <label style="display:block; margin-top:12px; font-size:12px; color:#aab3d6;">{ "Delivery" }</label>
+                <select onchange={on_mode}
+                  style="width:100%; margin-top:6px; padding:12px; border-radius:14px; border:1px solid rgba(255,255,255,.10); background:rgba(0,0,0,.25); color:#e8ecff;">
+                  { for DeliveryMode::ALL.iter().map(|m| html! {
+                    <option value={m.key()} selected={*m == *mode}>{ m.label() }</option>
+                  }) }
+                </select>
+
+                if *mode == DeliveryMode::Direct {
+                  <label style="display:flex; gap:8px; align-items:center; margin-top:12px; font-size:13px; color:#aab3d6;">
+                    <input type="checkbox" checked={*atomic} onchange={on_atomic} />
+                    { "Single atomic commit (all files or nothing)" }
+                  </label>
+                } else {
+                  <p class="sub" style="max-width:none; margin-top:10px; font-size:13px;">
+                    { format!("Commits land on {} and open a pull request into main.", plug::feature_branch((*plug_name).trim())) }
+                  </p>
+                }
+
                 <button class="btn" onclick={on_create} disabled={*busy} style="margin-top:12px;">
-                  { if *busy { "Working…" } else { "Create + Deploy" } }
+                  { if *busy { "Working…" } else if *mode == DeliveryMode::Direct { "Create + Deploy" } else { "Create + Open PR" } }
                 </button>
 
+                if let Some((number, _)) = &*pending_pr {
+                  <button class="btn btn2" onclick={on_deploy_merged} disabled={*busy} style="margin-top:12px; margin-left:8px;">
+                    { format!("Deploy after merge (PR #{})", number) }
+                  </button>
+                }
+
                 <pre style="white-space:pre-wrap; margin-top:12px; color:#aab3d6;">{ (*status).clone() }</pre>
               </div>
             </section>
@@ -1,9 +1,16 @@
-use base64::Engine;
-use gloo_net::http::Request;
 use gloo_storage::{LocalStorage, Storage};
-use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
-use web_sys::{HtmlInputElement, HtmlTextAreaElement};
+use gloo_file::{Blob, ObjectUrl};
+use plug_kit::logs::LogFile;
+use plug_kit::manifest::MANIFEST_FILE;
+use plug_kit::merge::{diff_lines, merge3, DiffKind, Merge, Resolution};
+use plug_kit::templates::{self, Template, TemplateVars};
+use plug_kit::{
+    plug, Delivery, DeliveryMode, FileChange, GitHubClient, GitHubConfig, GlooTransport,
+    Diagnostic, JobStep, Project, RunLogs, RunProgress, Severity, WorkflowJob, WorkflowRun,
+};
+use std::collections::{BTreeMap, HashMap};
+use std::rc::Rc;
+use web_sys::{HtmlInputElement, HtmlSelectElement, HtmlTextAreaElement};
 use yew::prelude::*;
 
 // clipboard + reflection fallback
@@ -11,578 +18,362 @@ use js_sys::{Function, Reflect};
 use wasm_bindgen::{JsCast, JsValue};
 use wasm_bindgen_futures::{spawn_local, JsFuture};
 
-const OWNER: &str = "ekim5sg";
-const REPO: &str = "webhtml5-plug-deployer";
-const WORKFLOW_FILE: &str = "deploy-hostek-plug.yml"; // .github/workflows/<file>
+const USER_AGENT: &str = "webhtml5-rust-iphone-compiler";
 
-#[derive(Serialize)]
-struct DispatchBody<'a> {
-    #[serde(rename = "ref")]
-    git_ref: &'a str,
-    inputs: DispatchInputs<'a>,
+fn github(token: &str) -> GitHubClient<GlooTransport> {
+    GitHubClient::new(token, GitHubConfig::default().with_user_agent(USER_AGENT))
 }
 
-#[derive(Serialize)]
-struct DispatchInputs<'a> {
-    plug_name: &'a str,
-    app_dir: &'a str,
-    clean_remote: &'a str,
-}
-
-#[derive(Deserialize, Debug, Clone)]
-struct RunsResp {
-    workflow_runs: Vec<WorkflowRun>,
-}
-
-#[derive(Deserialize, Debug, Clone)]
-struct WorkflowRun {
-    id: u64,
-    html_url: String,
-    name: Option<String>,
-    status: Option<String>,
-    conclusion: Option<String>,
-    created_at: Option<String>,
+/// Like `github`, but reading files from `branch`.
+fn github_on(token: &str, branch: &str) -> GitHubClient<GlooTransport> {
+    GitHubClient::new(
+        token,
+        GitHubConfig::default()
+            .with_user_agent(USER_AGENT)
+            .with_branch(branch),
+    )
 }
 
-#[derive(Deserialize, Debug)]
-struct ContentGetResp {
-    sha: String,
-    content: Option<String>,
-    // NOTE: removed `encoding` because we never read it and it triggers a dead_code warning.
+/// Status text after a branch/PR delivery.
+fn delivery_summary(d: &Delivery) -> String {
+    let mut lines = vec![format!("Committed to {} ✅", d.branch)];
+    if let Some(pr) = &d.pull_request {
+        lines.push(format!("Pull request #{}: {}", pr.number, pr.html_url));
+    }
+    if let Some(folder) = &d.deployed_as {
+        lines.push(format!("Deploy dispatched ✅ URL: {}", plug::public_url(folder)));
+    }
+    if d.pull_request.is_some() {
+        lines.push("Merge the PR, then tap “Deploy after merge”.".into());
+    }
+    lines.join("\n")
 }
 
-#[derive(Serialize)]
-struct PutContentBody<'a> {
-    message: &'a str,
-    content: String, // base64
-    branch: &'a str,
-    #[serde(skip_serializing_if = "Option::is_none")]
-    sha: Option<String>,
+/// Rustc errors shown for a failed run; the first few are what matter.
+const MAX_RUN_ERRORS: usize = 5;
+
+/// `.ta` is 13px at line-height 1.4; used to scroll the editor to a line.
+const EDITOR_LINE_PX: f64 = 18.2;
+
+/// State of the "Live run" card, shared by every handler that dispatches.
+#[derive(Clone, PartialEq)]
+struct RunTracker {
+    progress: UseStateHandle<Option<RunProgress>>,
+    /// Errors and warnings from the last failed run; the editor marks them too.
+    diagnostics: UseStateHandle<Vec<Diagnostic>>,
+    /// Job logs of the last finished run.
+    logs: UseStateHandle<Option<Rc<RunLogView>>>,
+    note: UseStateHandle<String>,
 }
 
-fn b64_encode(s: &str) -> String {
-    base64::engine::general_purpose::STANDARD.encode(s.as_bytes())
+/// A finished run's job logs, plus a link to the zip they came from.
+struct RunLogView {
+    run_id: u64,
+    jobs: Vec<LogFile>,
+    zip_url: ObjectUrl,
 }
 
-fn b64_decode(s: &str) -> Result<String, String> {
-    let cleaned = s.replace('\n', "");
-    let bytes = base64::engine::general_purpose::STANDARD
-        .decode(cleaned.as_bytes())
-        .map_err(|e| format!("base64 decode failed: {}", e))?;
-    String::from_utf8(bytes).map_err(|e| format!("utf8 decode failed: {}", e))
+impl RunLogView {
+    fn new(run_id: u64, logs: &RunLogs) -> Self {
+        RunLogView {
+            run_id,
+            jobs: logs.job_logs().cloned().collect(),
+            zip_url: ObjectUrl::from(Blob::new_with_options(
+                logs.zip.as_slice(),
+                Some("application/zip"),
+            )),
+        }
+    }
 }
 
-fn iso_short(s: &Option<String>) -> String {
-    s.as_deref()
-        .unwrap_or("")
-        .replace('T', " ")
-        .replace('Z', "")
+impl PartialEq for RunLogView {
+    fn eq(&self, other: &Self) -> bool {
+        self.run_id == other.run_id
+    }
 }
 
-/// Convert "My Cool App" -> "my-cool-app"
-fn slugify_app_name(s: &str) -> String {
-    let mut out = String::new();
-    let mut last_dash = false;
-
-    for ch in s.trim().chars() {
-        let c = ch.to_ascii_lowercase();
-        if c.is_ascii_alphanumeric() {
-            out.push(c);
-            last_dash = false;
-        } else if c.is_whitespace() || c == '-' || c == '_' {
-            if !out.is_empty() && !last_dash {
-                out.push('-');
-                last_dash = true;
+impl RunTracker {
+    /// Poll `run_id` until it finishes, then fetch its logs; on failure pull
+    /// rustc diagnostics from them.
+    async fn follow(self, token: String, run_id: u64) {
+        let gh = github(&token);
+        self.diagnostics.set(vec![]);
+        self.logs.set(None);
+        self.note.set(format!("Following run #{}…", run_id));
+
+        let progress = self.progress.clone();
+        let last = match gh.follow_run(run_id, |p| progress.set(Some(p.clone()))).await {
+            Ok(last) => last,
+            Err(e) => {
+                self.note.set(format!("Run tracking error: {}", e));
+                return;
             }
+        };
+
+        if !last.is_completed() {
+            self.note.set("Still running — tap Track on the run to keep following.".into());
+            return;
+        }
+        if last.failed() {
+            self.note.set(format!("Run #{} failed ❌ Downloading logs…", run_id));
         } else {
-            // drop other punctuation/symbols
+            self.note.set(format!("Run #{} succeeded ✅", run_id));
         }
-    }
 
-    while out.ends_with('-') {
-        out.pop();
+        let logs = match gh.run_logs(run_id).await {
+            Ok(logs) => logs,
+            Err(e) if last.failed() => {
+                self.note.set(format!("Run failed ❌ Log download error: {}", e));
+                return;
+            }
+            Err(e) => {
+                self.note.set(format!("Run #{} succeeded ✅ (logs unavailable: {})", run_id, e));
+                return;
+            }
+        };
+        if last.failed() {
+            let diags = logs.diagnostics();
+            let errors = diags.iter().filter(|d| d.severity == Severity::Error).count();
+            if errors == 0 {
+                self.note.set(
+                    "Run failed ❌ No rustc errors in the logs — see the job logs below.".into(),
+                );
+            } else {
+                self.note.set(format!(
+                    "Run failed ❌ {} rustc error(s) — also listed under Problems in the editor:",
+                    errors
+                ));
+            }
+            self.diagnostics.set(diags);
+        }
+        self.logs.set(Some(Rc::new(RunLogView::new(run_id, &logs))));
     }
-    out
-}
 
-fn sanitize_plug_name(s: &str) -> Option<String> {
-    let p = s.trim();
-    if p.is_empty() {
-        return None;
-    }
-    if p.chars()
-        .all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '-')
-    {
-        Some(p.to_string())
-    } else {
-        None
+    /// Dispatch a deploy of `plug` from main and return the run it started.
+    async fn dispatch(token: &str, plug: &str, status: &UseStateHandle<String>) -> Option<u64> {
+        let gh = github(token);
+        let branch = gh.config().branch.clone();
+        match gh.dispatch_tracked(&branch, plug, &plug::app_dir(plug)).await {
+            Ok(run) => {
+                status.set(format!(
+                    "Workflow dispatched ✅ Run #{} Deployed URL: {}",
+                    run.id,
+                    plug::public_url(plug)
+                ));
+                Some(run.id)
+            }
+            Err(e) => {
+                status.set(format!("Dispatch error: {}", e));
+                None
+            }
+        }
     }
 }
 
-// IMPORTANT: r##" .. "## so `content="#0b1020"` does not terminate raw strings.
-fn scaffold_index_html(title: &str) -> String {
-    format!(
-        r##"<!doctype html>
-<html lang="en">
-<head>
-  <meta charset="utf-8" />
-  <meta name="viewport" content="width=device-width,initial-scale=1" />
-  <meta name="color-scheme" content="dark" />
-  <meta name="theme-color" content="#0b1020" />
-  <title>{}</title>
-  <link data-trunk rel="css" href="styles.css" />
-</head>
-<body id="top">
-  <div id="app"></div>
-  <link data-trunk rel="rust" />
-</body>
-</html>
-"##,
-        title
-    )
-}
-
-fn scaffold_styles_css() -> String {
-    r#"/* MikeGyver Studio • hard-locked dark mode (no light sections) */
-:root{
-  --bg0:#070a12;
-  --bg1:#0b1020;
-  --card:#0f1730;
-  --card2:#111c3a;
-  --text:#e8ecff;
-  --muted:#aab3d6;
-  --line:rgba(255,255,255,.10);
-  --shadow:rgba(0,0,0,.55);
-  --accent:#7c5cff;
-  --accent2:#28d7ff;
-  --good:#39d98a;
-  --warn:#ffd166;
-  --danger:#ff5c7a;
-  --radius:18px;
-}
-
-html,body{
-  height:100%;
-  background:var(--bg0) !important;
-  color:var(--text) !important;
-  margin:0;
-}
-
-body{
-  font-family: system-ui, -apple-system, Segoe UI, Roboto, Arial, sans-serif;
-  -webkit-font-smoothing:antialiased;
-  -moz-osx-font-smoothing:grayscale;
-  overflow-x:hidden;
-}
-
-*{ box-sizing:border-box; }
-a{ color:inherit; text-decoration:none; }
-button, input, select, textarea{ font:inherit; }
-
-.bg{
-  position:fixed;
-  inset:-20%;
-  z-index:-1;
-  background:
-    radial-gradient(900px 600px at 15% 10%, rgba(124,92,255,.28), transparent 55%),
-    radial-gradient(900px 600px at 85% 15%, rgba(40,215,255,.20), transparent 55%),
-    radial-gradient(900px 700px at 40% 90%, rgba(57,217,138,.12), transparent 60%),
-    linear-gradient(180deg, var(--bg0), var(--bg1));
-  filter:saturate(115%);
-}
-
-.wrap{
-  width:min(1100px, calc(100% - 32px));
-  margin:0 auto;
-  padding:18px 0 90px;
-}
-
-.badge{
-  display:inline-flex;
-  align-items:center;
-  gap:10px;
-  padding:8px 12px;
-  border:1px solid var(--line);
-  border-radius:999px;
-  background:rgba(255,255,255,.04);
-  box-shadow: 0 18px 60px var(--shadow);
-  font-size:13px;
-  color:var(--muted);
-}
-
-.h1{
-  margin:14px 0 6px;
-  font-size:clamp(28px, 4vw, 44px);
-  line-height:1.08;
-  letter-spacing:-.02em;
-}
-
-.h2{
-  margin:0 0 6px;
-  font-size:18px;
-  letter-spacing:-.01em;
-}
-
-.sub{
-  margin:0;
-  color:var(--muted);
-  font-size:15px;
-  line-height:1.5;
-  max-width:72ch;
-}
-
-.grid{
-  display:grid;
-  gap:14px;
-  grid-template-columns: 1fr;
-  margin-top:16px;
-}
-@media (min-width: 860px){
-  .grid{ grid-template-columns: 1.1fr .9fr; }
-}
-
-.card{
-  border:1px solid var(--line);
-  background:linear-gradient(180deg, rgba(255,255,255,.04), rgba(255,255,255,.02));
-  border-radius:var(--radius);
-  box-shadow: 0 22px 80px var(--shadow);
-  overflow:hidden;
-}
-
-.card-h{ padding:16px 16px 0; }
-.card-b{ padding:0 16px 16px; }
-
-.row{
-  display:flex;
-  gap:10px;
-  flex-wrap:wrap;
-  align-items:center;
-}
-
-.btn{
-  appearance:none;
-  border:none;
-  border-radius:14px;
-  padding:12px 14px;
-  font-weight:800;
-  color:var(--text);
-  background:linear-gradient(135deg, rgba(124,92,255,.95), rgba(40,215,255,.70));
-  box-shadow: 0 14px 30px rgba(124,92,255,.18);
-  cursor:pointer;
-  transform: translateZ(0);
-}
-.btn:active{ transform: scale(.99); }
-.btn:disabled{ opacity:.65; cursor:not-allowed; }
-
-.btn2{
-  background:rgba(255,255,255,.05);
-  border:1px solid var(--line);
-  box-shadow:none;
-  font-weight:700;
-}
-
-.input, .select, .ta{
-  width:100%;
-  margin-top:6px;
-  padding:12px;
-  border-radius:14px;
-  border:1px solid rgba(255,255,255,.10);
-  background:rgba(0,0,0,.25);
-  color:var(--text);
-  outline:none;
-}
-
-.select{ appearance:none; }
-
-.ta{
-  min-height: 280px;
-  resize: vertical;
-  font-family: ui-monospace, SFMono-Regular, Menlo, Consolas, monospace;
-  font-size: 13px;
-  line-height: 1.4;
-}
-
-.kv{
-  display:grid;
-  grid-template-columns: 1fr;
-  gap:10px;
-}
-@media (min-width: 700px){
-  .kv{ grid-template-columns: 1fr 1fr; }
+/// The plug open in the Quick Edit card. A reducer rather than a `use_state`
+/// so file loads that finish late apply to the latest working copy.
+#[derive(Clone, PartialEq, Default)]
+struct Workspace(Option<Project>);
+
+enum WorkspaceAction {
+    Open(Project),
+    Loaded { path: String, content: String, sha: String },
+    Edit { path: String, text: String },
+    Focus(String),
+    Close(String),
+    Create(String),
+    Rename { from: String, to: String },
+    Delete(String),
+    /// Blob shas after a Save all, keyed by path inside the plug.
+    Saved(BTreeMap<String, String>),
+    FileSaved { path: String, sha: String },
+    /// A newer GitHub copy was merged into the buffer.
+    Rebased { path: String, remote: String, sha: String, merged: String },
 }
 
-.k{
-  padding:12px;
-  border:1px solid var(--line);
-  border-radius:16px;
-  background:rgba(255,255,255,.03);
-}
-.k .label{ color:var(--muted); font-size:12px; }
-.k .value{ margin-top:4px; font-size:14px; }
-
-.mono{ font-family: ui-monospace, SFMono-Regular, Menlo, Consolas, monospace; }
-
-.progress{
-  width:100%;
-  height:12px;
-  border-radius:999px;
-  background:rgba(255,255,255,.06);
-  border:1px solid rgba(255,255,255,.10);
-  overflow:hidden;
-  box-shadow: 0 12px 30px rgba(0,0,0,.25);
-}
-.progress > div{
-  height:100%;
-  width:0%;
-  background:linear-gradient(135deg, rgba(57,217,138,.85), rgba(40,215,255,.70));
-}
+impl Reducible for Workspace {
+    type Action = WorkspaceAction;
 
-.log{
-  white-space:pre-wrap;
-  margin-top:12px;
-  color:var(--muted);
-  font-size:13px;
+    fn reduce(self: Rc<Self>, action: WorkspaceAction) -> Rc<Self> {
+        if let WorkspaceAction::Open(p) = action {
+            return Rc::new(Workspace(Some(p)));
+        }
+        let Some(mut p) = self.0.clone() else {
+            return self;
+        };
+        match action {
+            WorkspaceAction::Open(_) => {}
+            WorkspaceAction::Loaded { path, content, sha } => p.set_loaded(&path, &content, &sha),
+            WorkspaceAction::Edit { path, text } => p.edit(&path, &text),
+            WorkspaceAction::Focus(path) => p.open(&path),
+            WorkspaceAction::Close(path) => p.close(&path),
+            WorkspaceAction::Create(path) => {
+                let _ = p.create(&path);
+            }
+            WorkspaceAction::Rename { from, to } => {
+                let _ = p.rename(&from, &to);
+            }
+            WorkspaceAction::Delete(path) => p.delete(&path),
+            WorkspaceAction::Saved(shas) => p.mark_saved(&shas),
+            WorkspaceAction::FileSaved { path, sha } => p.mark_file_saved(&path, &sha),
+            WorkspaceAction::Rebased { path, remote, sha, merged } => {
+                p.rebase(&path, &remote, &sha, &merged)
+            }
+        }
+        Rc::new(Workspace(Some(p)))
+    }
 }
 
-.warn{
-  margin-top:10px;
-  padding:10px 12px;
-  border-radius:14px;
-  border:1px solid rgba(255,92,122,.35);
-  background:rgba(255,92,122,.08);
-  color:var(--muted);
-  white-space:pre-wrap;
+fn confirm(message: &str) -> bool {
+    web_sys::window()
+        .and_then(|w| w.confirm_with_message(message).ok())
+        .unwrap_or(false)
 }
 
-.ok{
-  margin-top:10px;
-  padding:10px 12px;
-  border-radius:14px;
-  border:1px solid rgba(57,217,138,.35);
-  background:rgba(57,217,138,.08);
-  color:var(--muted);
-  white-space:pre-wrap;
+/// A save that raced someone else's push and needs conflicts settled by hand.
+#[derive(Clone, PartialEq)]
+struct PendingMerge {
+    path: String,
+    /// GitHub's copy and its sha; both become the new base once applied.
+    remote: String,
+    sha: String,
+    merge: Merge,
+    /// One per conflict, in order.
+    choices: Vec<Resolution>,
 }
 
-.runs{
-  display:grid;
-  gap:10px;
-  margin-top:10px;
-}
-.run{
-  display:block;
-  padding:12px;
-  border:1px solid var(--line);
-  border-radius:16px;
-  background:rgba(255,255,255,.03);
-}
-.run:hover{ background:rgba(255,255,255,.05); }
-.run-top{
-  display:flex;
-  justify-content:space-between;
-  gap:10px;
-  align-items:baseline;
-  flex-wrap:wrap;
-}
-.run-name{ font-weight:800; }
-.run-meta{ color:var(--muted); font-size:12px; }
-
-.footer{
-  margin-top:18px;
-  color:var(--muted);
-  font-size:13px;
-  display:flex;
-  justify-content:space-between;
-  gap:10px;
-  flex-wrap:wrap;
+impl PendingMerge {
+    fn action(&self, merged: String) -> WorkspaceAction {
+        WorkspaceAction::Rebased {
+            path: self.path.clone(),
+            remote: self.remote.clone(),
+            sha: self.sha.clone(),
+            merged,
+        }
+    }
 }
 
-.backtop{
-  position:fixed;
-  right:14px;
-  bottom:14px;
-  padding:11px 12px;
-  border-radius:999px;
-  border:1px solid var(--line);
-  background:rgba(10,14,28,.72);
-  color:var(--text);
-  backdrop-filter: blur(10px);
-  box-shadow: 0 20px 80px var(--shadow);
-}
-"#.to_string()
+/// Fetch the GitHub copy of `path` and three-way merge the buffer into it,
+/// using the version loaded as the base.
+async fn merge_with_remote(
+    gh: &GitHubClient<GlooTransport>,
+    p: &Project,
+    path: &str,
+) -> Result<PendingMerge, String> {
+    let (Some(base), Some(ours)) = (p.base_text(path), p.text(path)) else {
+        return Err(format!("{} was never loaded, so it can't be merged.", path));
+    };
+    let origin = p.origin(path).unwrap_or(path);
+    let remote = gh
+        .get_file(&p.repo_path(origin))
+        .await
+        .map_err(|e| format!("Fetch for merge failed: {}", e))?;
+    let merge = merge3(base, ours, &remote.content);
+    let choices = vec![Resolution::Ours; merge.conflicts().count()];
+    Ok(PendingMerge {
+        path: path.to_string(),
+        remote: remote.content,
+        sha: remote.sha,
+        merge,
+        choices,
+    })
 }
 
-fn scaffold_cargo_toml(plug_name: &str) -> String {
-    let pkg = plug_name.replace('-', "_");
-    format!(
-        r#"[package]
-name = "{pkg}"
-version = "0.1.0"
-edition = "2021"
-
-[dependencies]
-yew = {{ version = "0.21", features = ["csr"] }}
-"#,
-        pkg = pkg
-    )
+/// Why `merge_stale` stopped.
+enum MergeStop {
+    /// This file needs its conflicts settled by hand.
+    Conflict(PendingMerge),
+    Error(String),
 }
 
-fn scaffold_main_rs(title: &str, plug_name: &str) -> String {
-    let url = format!("https://www.webhtml5.info/{}/", plug_name);
-
-    format!(
-        r#"use yew::prelude::*;
-
-#[function_component(App)]
-fn app() -> Html {{
-    html! {{
-      <>
-        <main style="font-family:system-ui; padding:24px;">
-          <h1>{title}</h1>
-          <p style="color:#667;">{{"Plug scaffold is live. Replace this content with your real app."}}</p>
-          <p>{url}</p>
-        </main>
-      </>
-    }}
-}}
-
-fn main() {{
-    yew::Renderer::<App>::new().render();
-}}
-"#,
-        title = format!("{:?}", title),
-        url = format!("{:?}", url),
-    )
+/// Three-way merge each of `stale` into a copy of `snapshot`, against the
+/// copies `gh` reads. Clean merges go into `workspace` as they land.
+async fn merge_stale(
+    gh: &GitHubClient<GlooTransport>,
+    snapshot: &Project,
+    stale: &[String],
+    workspace: &UseReducerHandle<Workspace>,
+) -> Result<Project, MergeStop> {
+    let mut merged_copy = snapshot.clone();
+    for path in stale {
+        let m = merge_with_remote(gh, snapshot, path)
+            .await
+            .map_err(MergeStop::Error)?;
+        let Some(merged) = m.merge.text() else {
+            return Err(MergeStop::Conflict(m));
+        };
+        merged_copy.rebase(path, &m.remote, &m.sha, &merged);
+        workspace.dispatch(m.action(merged));
+    }
+    Ok(merged_copy)
 }
 
-async fn dispatch_workflow(token: &str, plug_name: &str, app_dir: &str) -> Result<(), String> {
-    let url = format!(
-        "https://api.github.com/repos/{}/{}/actions/workflows/{}/dispatches",
-        OWNER, REPO, WORKFLOW_FILE
-    );
-
-    let body = DispatchBody {
-        git_ref: "main",
-        inputs: DispatchInputs {
-            plug_name,
-            app_dir,
-            clean_remote: "false",
-        },
-    };
-
-    let resp = Request::post(&url)
-        .header("Authorization", &format!("Bearer {}", token))
-        .header("Accept", "application/vnd.github+json")
-        .header("X-GitHub-Api-Version", "2022-11-28")
-        .header("User-Agent", "webhtml5-rust-iphone-compiler")
-        .json(&body)
-        .map_err(|e| e.to_string())?
-        .send()
-        .await
-        .map_err(|e| e.to_string())?;
-
-    if resp.status() == 204 {
-        Ok(())
-    } else {
-        let status = resp.status();
-        let text = resp.text().await.unwrap_or_default();
-        Err(format!("Dispatch failed: {} {}", status, text))
+/// Select `line` (1-based) in the editor and scroll it into view.
+fn select_line(ta: &HtmlTextAreaElement, line: u32) {
+    // selection offsets are UTF-16 code units
+    let text = ta.value();
+    let mut start = 0u32;
+    let mut end = 0u32;
+    for (i, l) in text.split('\n').enumerate() {
+        let len = l.encode_utf16().count() as u32;
+        if i as u32 + 1 == line {
+            end = start + len;
+            break;
+        }
+        start += len + 1;
+        end = start;
     }
+    let _ = ta.focus();
+    let _ = ta.set_selection_range(start, end);
+    let top = (f64::from(line.saturating_sub(1)) - 3.0).max(0.0) * EDITOR_LINE_PX;
+    ta.set_scroll_top(top as i32);
 }
 
-async fn fetch_runs(token: &str, per_page: u32) -> Result<Vec<WorkflowRun>, String> {
-    let url = format!(
-        "https://api.github.com/repos/{}/{}/actions/workflows/{}/runs?per_page={}",
-        OWNER, REPO, WORKFLOW_FILE, per_page
-    );
-
-    let resp = Request::get(&url)
-        .header("Authorization", &format!("Bearer {}", token))
-        .header("Accept", "application/vnd.github+json")
-        .header("X-GitHub-Api-Version", "2022-11-28")
-        .header("User-Agent", "webhtml5-rust-iphone-compiler")
-        .send()
-        .await
-        .map_err(|e| e.to_string())?;
-
-    if !resp.ok() {
-        let status = resp.status();
-        let text = resp.text().await.unwrap_or_default();
-        return Err(format!("Fetch runs failed: {} {}", status, text));
+fn step_icon(step: &JobStep) -> &'static str {
+    match (step.status.as_str(), step.conclusion.as_deref()) {
+        ("completed", Some("success")) => "✅",
+        ("completed", Some("skipped")) => "⏭",
+        ("completed", _) => "❌",
+        ("in_progress", _) => "⏳",
+        _ => "•",
     }
+}
 
-    let json = resp.json::<RunsResp>().await.map_err(|e| e.to_string())?;
-    Ok(json.workflow_runs)
+fn iso_short(s: &Option<String>) -> String {
+    s.as_deref()
+        .unwrap_or("")
+        .replace('T', " ")
+        .replace('Z', "")
 }
 
-async fn github_get_file(token: &str, path_str: &str) -> Result<(String, String), String> {
-    let url = format!(
-        "https://api.github.com/repos/{}/{}/contents/{}",
-        OWNER, REPO, path_str
-    );
-
-    let resp = Request::get(&url)
-        .header("Authorization", &format!("Bearer {}", token))
-        .header("Accept", "application/vnd.github+json")
-        .header("X-GitHub-Api-Version", "2022-11-28")
-        .header("User-Agent", "webhtml5-rust-iphone-compiler")
-        .send()
-        .await
-        .map_err(|e| e.to_string())?;
+/// Convert "My Cool App" -> "my-cool-app"
+fn slugify_app_name(s: &str) -> String {
+    let mut out = String::new();
+    let mut last_dash = false;
 
-    if resp.status() == 404 {
-        return Err(format!("Not found: {}", path_str));
-    }
-    if !resp.ok() {
-        let st = resp.status();
-        let text = resp.text().await.unwrap_or_default();
-        return Err(format!("GET failed {}: {}", st, text));
+    for ch in s.trim().chars() {
+        let c = ch.to_ascii_lowercase();
+        if c.is_ascii_alphanumeric() {
+            out.push(c);
+            last_dash = false;
+        } else if c.is_whitespace() || c == '-' || c == '_' {
+            if !out.is_empty() && !last_dash {
+                out.push('-');
+                last_dash = true;
+            }
+        } else {
+            // drop other punctuation/symbols
+        }
     }
 
-    let json = resp.json::<ContentGetResp>().await.map_err(|e| e.to_string())?;
-    let sha = json.sha;
-    let content = json.content.unwrap_or_default();
-    let decoded = b64_decode(&content)?;
-    Ok((sha, decoded))
+    while out.ends_with('-') {
+        out.pop();
+    }
+    out
 }
 
-async fn github_put_file(
-    token: &str,
-    path_str: &str,
-    message: &str,
-    content: &str,
-    sha: Option<String>,
-) -> Result<(), String> {
-    let url = format!(
-        "https://api.github.com/repos/{}/{}/contents/{}",
-        OWNER, REPO, path_str
-    );
-
-    let body = PutContentBody {
-        message,
-        content: b64_encode(content),
-        branch: "main",
-        sha,
-    };
-
-    let resp = Request::put(&url)
-        .header("Authorization", &format!("Bearer {}", token))
-        .header("Accept", "application/vnd.github+json")
-        .header("X-GitHub-Api-Version", "2022-11-28")
-        .header("User-Agent", "webhtml5-rust-iphone-compiler")
-        .json(&body)
-        .map_err(|e| e.to_string())?
-        .send()
-        .await
-        .map_err(|e| e.to_string())?;
-
-    if resp.ok() {
-        Ok(())
-    } else {
-        let status = resp.status();
-        let text = resp.text().await.unwrap_or_default();
-        Err(format!("PUT {} failed: {} {}", path_str, status, text))
-    }
+fn starter(id: &str) -> &'static Template {
+    templates::find(id).unwrap_or(templates::default_template())
 }
 
 async fn copy_to_clipboard(text: &str) -> Result<(), String> {
@@ -655,15 +446,23 @@ fn app() -> Html {
     let status = use_state(|| "".to_string());
     let busy = use_state(|| false);
 
+    // how saves/creates reach GitHub (main vs plug/<name> branch + PR)
+    let delivery = use_state(|| DeliveryMode::Direct);
+    let pending_pr = use_state(|| None::<(u64, String)>);
+
     // create + deploy (with 4 file textareas)
     let app_name = use_state(|| "My New Plug".to_string()); // human-friendly
     let new_plug = use_state(|| "my-new-plug".to_string()); // slug folder
     let new_title = use_state(|| "My New Plug".to_string());
 
-    let new_index_html = use_state(|| scaffold_index_html("My New Plug"));
-    let new_styles_css = use_state(|| scaffold_styles_css());
-    let new_cargo_toml = use_state(|| scaffold_cargo_toml("my-new-plug"));
-    let new_main_rs = use_state(|| scaffold_main_rs("My New Plug", "my-new-plug"));
+    // starter template the 4 textareas are generated from
+    let new_template = use_state(|| templates::default_template().id);
+    let defaults = TemplateVars::new("my-new-plug", "My New Plug");
+
+    let new_index_html = use_state(|| starter(&new_template).index_html(&defaults));
+    let new_styles_css = use_state(|| starter(&new_template).styles_css(&defaults));
+    let new_cargo_toml = use_state(|| starter(&new_template).cargo_toml(&defaults));
+    let new_main_rs = use_state(|| starter(&new_template).main_rs(&defaults));
 
     // dirty flags: prevents auto-overwriting if you already edited
     let dirty_index = use_state(|| false);
@@ -675,17 +474,30 @@ fn app() -> Html {
     let create_busy = use_state(|| false);
 
     // runs
-    let runs = use_state(|| Vec::<WorkflowRun>::new());
+    let runs = use_state(Vec::<WorkflowRun>::new);
     let runs_err = use_state(|| "".to_string());
     let runs_busy = use_state(|| false);
 
-    // quick editor
+    // live run: the one the last dispatch started (or the one you tapped Track on)
+    let tracker = RunTracker {
+        progress: use_state(|| None::<RunProgress>),
+        diagnostics: use_state(Vec::<Diagnostic>::new),
+        logs: use_state(|| None::<Rc<RunLogView>>),
+        note: use_state(String::new),
+    };
+
+    // quick editor: one plug's files, open tabs and unsaved edits
     let edit_plug = use_state(|| "rust-iphone-compiler".to_string());
-    let edit_file = use_state(|| "src/main.rs".to_string());
-    let edit_text = use_state(|| "".to_string());
+    let workspace = use_reducer(Workspace::default);
+    let new_path = use_state(String::new);
+    let pending_merge = use_state(|| None::<PendingMerge>);
     let edit_status = use_state(|| "".to_string());
     let edit_busy = use_state(|| false);
-    let sha_map = use_state(|| HashMap::<String, String>::new());
+
+    // problems from the last failed run: (file, line) to jump to once it is open
+    let jump_to = use_state(|| None::<(String, u32)>);
+    let editor_ref = use_node_ref();
+    let gutter_ref = use_node_ref();
 
     let app_dir = {
         let plug = (*plug_name).clone();
@@ -718,6 +530,58 @@ fn app() -> Html {
         })
     };
 
+    let on_delivery = {
+        let delivery = delivery.clone();
+        Callback::from(move |e: Event| {
+            let v = e.target_unchecked_into::<HtmlSelectElement>().value();
+            delivery.set(DeliveryMode::from_key(&v).unwrap_or(DeliveryMode::Direct));
+        })
+    };
+
+    let on_deploy_merged = {
+        let token = token.clone();
+        let status = status.clone();
+        let busy = busy.clone();
+        let pending_pr = pending_pr.clone();
+
+        Callback::from(move |_: MouseEvent| {
+            if *busy {
+                return;
+            }
+            let Some((number, plug)) = (*pending_pr).clone() else {
+                return;
+            };
+            let token = (*token).clone();
+
+            busy.set(true);
+            status.set(format!("Checking pull request #{}…", number));
+
+            spawn_local({
+                let status = status.clone();
+                let busy = busy.clone();
+                let pending_pr = pending_pr.clone();
+                async move {
+                    match github(&token).deploy_if_merged(number, &plug).await {
+                        Ok(pr) if pr.is_merged() => {
+                            pending_pr.set(None);
+                            status.set(format!(
+                                "PR #{} merged — workflow dispatched ✅ Deployed URL: {}",
+                                number,
+                                plug::public_url(&plug)
+                            ));
+                        }
+                        Ok(pr) => status.set(format!(
+                            "PR #{} is {} and not merged yet — nothing deployed.\n{}",
+                            number, pr.state, pr.html_url
+                        )),
+                        Err(e) => status.set(format!("Deploy after merge error: {}", e)),
+                    }
+                    busy.set(false);
+                }
+            });
+        })
+    };
+
     // deploy existing
     let on_plug = {
         let plug_name = plug_name.clone();
@@ -748,6 +612,7 @@ fn app() -> Html {
         let plug_name = plug_name.clone();
         let status = status.clone();
         let busy = busy.clone();
+        let tracker = tracker.clone();
 
         Callback::from(move |_: MouseEvent| {
             if *busy {
@@ -760,28 +625,24 @@ fn app() -> Html {
                 status.set("Missing GitHub token.".into());
                 return;
             }
-            let Some(plug) = sanitize_plug_name(&plug) else {
+            let Some(plug) = plug::sanitize_name(&plug) else {
                 status.set("plug_name must be lowercase letters, numbers, hyphens.".into());
                 return;
             };
 
-            let app_dir = format!("plugs/{}", plug);
-
             busy.set(true);
             status.set("Dispatching deploy workflow…".into());
 
             spawn_local({
                 let status = status.clone();
                 let busy = busy.clone();
+                let tracker = tracker.clone();
                 async move {
-                    match dispatch_workflow(&token, &plug, &app_dir).await {
-                        Ok(_) => status.set(format!(
-                            "Workflow dispatched ✅ Deployed URL: https://www.webhtml5.info/{}/",
-                            plug
-                        )),
-                        Err(e) => status.set(format!("Dispatch error: {}", e)),
-                    }
+                    let run = RunTracker::dispatch(&token, &plug, &status).await;
                     busy.set(false);
+                    if let Some(id) = run {
+                        tracker.follow(token, id).await;
+                    }
                 }
             });
         })
@@ -809,9 +670,9 @@ fn app() -> Html {
                 let runs_err = runs_err.clone();
                 let runs_busy = runs_busy.clone();
                 async move {
-                    match fetch_runs(&token, 10).await {
+                    match github(&token).list_runs(10).await {
                         Ok(list) => runs.set(list),
-                        Err(e) => runs_err.set(e),
+                        Err(e) => runs_err.set(e.to_string()),
                     }
                     runs_busy.set(false);
                 }
@@ -819,11 +680,25 @@ fn app() -> Html {
         })
     };
 
+    let on_track = {
+        let token = token.clone();
+        let tracker = tracker.clone();
+        Callback::from(move |run_id: u64| {
+            let token = (*token).clone();
+            if token.trim().is_empty() {
+                tracker.note.set("Enter token first to follow a run.".into());
+                return;
+            }
+            spawn_local(tracker.clone().follow(token, run_id));
+        })
+    };
+
     // Create section handlers
     let on_app_name = {
         let app_name = app_name.clone();
         let new_title = new_title.clone();
         let new_plug = new_plug.clone();
+        let new_template = new_template.clone();
 
         let new_index_html = new_index_html.clone();
         let new_styles_css = new_styles_css.clone();
@@ -846,19 +721,20 @@ fn app() -> Html {
             new_title.set(v.clone());
 
             // only refresh scaffolds if you haven't typed into those textareas yet
+            let p = if slug.is_empty() { "my-new-plug".to_string() } else { slug.clone() };
+            let vars = TemplateVars::new(&p, v.trim());
+            let t = starter(&new_template);
             if !*dirty_index {
-                new_index_html.set(scaffold_index_html(v.trim()));
+                new_index_html.set(t.index_html(&vars));
             }
             if !*dirty_css {
-                new_styles_css.set(scaffold_styles_css());
+                new_styles_css.set(t.styles_css(&vars));
             }
             if !*dirty_toml {
-                let p = if slug.is_empty() { "my-new-plug".to_string() } else { slug.clone() };
-                new_cargo_toml.set(scaffold_cargo_toml(&p));
+                new_cargo_toml.set(t.cargo_toml(&vars));
             }
             if !*dirty_main {
-                let p = if slug.is_empty() { "my-new-plug".to_string() } else { slug.clone() };
-                new_main_rs.set(scaffold_main_rs(v.trim(), &p));
+                new_main_rs.set(t.main_rs(&vars));
             }
         })
     };
@@ -868,6 +744,7 @@ fn app() -> Html {
         let new_cargo_toml = new_cargo_toml.clone();
         let new_main_rs = new_main_rs.clone();
         let new_title = new_title.clone();
+        let new_template = new_template.clone();
 
         let dirty_toml = dirty_toml.clone();
         let dirty_main = dirty_main.clone();
@@ -877,15 +754,14 @@ fn app() -> Html {
             new_plug.set(v.clone());
 
             // if user hasn't edited TOML/main yet, keep them aligned with plug name
+            let p = slugify_app_name(&v);
+            let p = if p.is_empty() { v.clone() } else { p };
+            let vars = TemplateVars::new(&p, new_title.trim());
             if !*dirty_toml {
-                let p = slugify_app_name(&v);
-                let p = if p.is_empty() { v.clone() } else { p };
-                new_cargo_toml.set(scaffold_cargo_toml(&p));
+                new_cargo_toml.set(starter(&new_template).cargo_toml(&vars));
             }
             if !*dirty_main {
-                let p = slugify_app_name(&v);
-                let p = if p.is_empty() { v.clone() } else { p };
-                new_main_rs.set(scaffold_main_rs(new_title.trim(), &p));
+                new_main_rs.set(starter(&new_template).main_rs(&vars));
             }
         })
     };
@@ -895,6 +771,7 @@ fn app() -> Html {
         let new_index_html = new_index_html.clone();
         let new_main_rs = new_main_rs.clone();
         let new_plug = new_plug.clone();
+        let new_template = new_template.clone();
 
         let dirty_index = dirty_index.clone();
         let dirty_main = dirty_main.clone();
@@ -903,12 +780,12 @@ fn app() -> Html {
             let v = e.target_unchecked_into::<HtmlInputElement>().value();
             new_title.set(v.clone());
 
-            let plug = (*new_plug).clone();
+            let vars = TemplateVars::new(new_plug.trim(), v.trim());
             if !*dirty_index {
-                new_index_html.set(scaffold_index_html(v.trim()));
+                new_index_html.set(starter(&new_template).index_html(&vars));
             }
             if !*dirty_main {
-                new_main_rs.set(scaffold_main_rs(v.trim(), plug.trim()));
+                new_main_rs.set(starter(&new_template).main_rs(&vars));
             }
         })
     };
@@ -953,10 +830,47 @@ fn app() -> Html {
         })
     };
 
-    // Load defaults: repopulate the 4 textareas from scaffolds (even after edits)
+    // Switching starter regenerates every textarea you haven't edited yet
+    let on_template = {
+        let new_template = new_template.clone();
+        let new_plug = new_plug.clone();
+        let new_title = new_title.clone();
+
+        let new_index_html = new_index_html.clone();
+        let new_styles_css = new_styles_css.clone();
+        let new_cargo_toml = new_cargo_toml.clone();
+        let new_main_rs = new_main_rs.clone();
+
+        let dirty_index = dirty_index.clone();
+        let dirty_css = dirty_css.clone();
+        let dirty_toml = dirty_toml.clone();
+        let dirty_main = dirty_main.clone();
+
+        Callback::from(move |e: Event| {
+            let t = starter(&e.target_unchecked_into::<HtmlSelectElement>().value());
+            new_template.set(t.id);
+
+            let vars = TemplateVars::new(new_plug.trim(), new_title.trim());
+            if !*dirty_index {
+                new_index_html.set(t.index_html(&vars));
+            }
+            if !*dirty_css {
+                new_styles_css.set(t.styles_css(&vars));
+            }
+            if !*dirty_toml {
+                new_cargo_toml.set(t.cargo_toml(&vars));
+            }
+            if !*dirty_main {
+                new_main_rs.set(t.main_rs(&vars));
+            }
+        })
+    };
+
+    // Load defaults: repopulate the 4 textareas from the starter (even after edits)
     let on_load_defaults = {
         let new_plug = new_plug.clone();
         let new_title = new_title.clone();
+        let new_template = new_template.clone();
 
         let new_index_html = new_index_html.clone();
         let new_styles_css = new_styles_css.clone();
@@ -975,10 +889,12 @@ fn app() -> Html {
             let title = if title.trim().is_empty() { "My New Plug".to_string() } else { title };
             let plug = if plug.trim().is_empty() { "my-new-plug".to_string() } else { plug };
 
-            new_index_html.set(scaffold_index_html(title.trim()));
-            new_styles_css.set(scaffold_styles_css());
-            new_cargo_toml.set(scaffold_cargo_toml(plug.trim()));
-            new_main_rs.set(scaffold_main_rs(title.trim(), plug.trim()));
+            let vars = TemplateVars::new(plug.trim(), title.trim());
+            let t = starter(&new_template);
+            new_index_html.set(t.index_html(&vars));
+            new_styles_css.set(t.styles_css(&vars));
+            new_cargo_toml.set(t.cargo_toml(&vars));
+            new_main_rs.set(t.main_rs(&vars));
 
             dirty_index.set(false);
             dirty_css.set(false);
@@ -997,14 +913,19 @@ fn app() -> Html {
         let new_styles_css = new_styles_css.clone();
         let new_cargo_toml = new_cargo_toml.clone();
         let new_main_rs = new_main_rs.clone();
+        let new_template = new_template.clone();
 
         let create_status = create_status.clone();
         let create_busy = create_busy.clone();
+        let delivery = delivery.clone();
+        let pending_pr = pending_pr.clone();
+        let tracker = tracker.clone();
 
         Callback::from(move |_: MouseEvent| {
             if *create_busy {
                 return;
             }
+            let mode = *delivery;
 
             let token = (*token).clone();
             if token.trim().is_empty() {
@@ -1015,7 +936,7 @@ fn app() -> Html {
             let plug = (*new_plug).clone();
             let title = (*new_title).clone();
 
-            let Some(plug) = sanitize_plug_name(&plug) else {
+            let Some(plug) = plug::sanitize_name(&plug) else {
                 create_status.set("plug_name must be lowercase letters, numbers, hyphens.".into());
                 return;
             };
@@ -1038,59 +959,71 @@ fn app() -> Html {
             let css = (*new_styles_css).clone();
             let toml = (*new_cargo_toml).clone();
             let mainrs = (*new_main_rs).clone();
+            // portal listing, tagged with the starter the files came from
+            let manifest = starter(&new_template).plug_toml(&TemplateVars::new(&plug, &title));
 
             create_busy.set(true);
-            create_status.set("Writing 4 files to GitHub…".into());
+            create_status.set("Writing 5 files to GitHub…".into());
 
             spawn_local({
                 let create_status = create_status.clone();
                 let create_busy = create_busy.clone();
+                let pending_pr = pending_pr.clone();
+                let tracker = tracker.clone();
                 async move {
-                    let base = format!("plugs/{}", plug);
+                    let base = plug::app_dir(&plug);
                     let msg = format!("Create plug via rust-iphone-compiler: {}", plug);
 
-                    async fn upsert(
-                        token: &str,
-                        path: &str,
-                        msg: &str,
-                        content: &str,
-                    ) -> Result<(), String> {
-                        let sha = match github_get_file(token, path).await {
-                            Ok((sha, _old)) => Some(sha),
-                            Err(e) if e.starts_with("Not found:") => None,
-                            Err(_) => None,
-                        };
-                        github_put_file(token, path, msg, content, sha).await
+                    let gh = github(&token);
+
+                    if mode != DeliveryMode::Direct {
+                        let changes = [
+                            FileChange::write(&format!("{}/index.html", base), &idx),
+                            FileChange::write(&format!("{}/styles.css", base), &css),
+                            FileChange::write(&format!("{}/Cargo.toml", base), &toml),
+                            FileChange::write(&format!("{}/src/main.rs", base), &mainrs),
+                            FileChange::write(&format!("{}/{}", base, MANIFEST_FILE), &manifest),
+                        ];
+                        match gh.deliver_plug(&plug, &msg, &changes, mode).await {
+                            Ok(d) => {
+                                if let Some(pr) = &d.pull_request {
+                                    pending_pr.set(Some((pr.number, plug.clone())));
+                                }
+                                create_status.set(delivery_summary(&d));
+                            }
+                            Err(e) => create_status.set(format!("Create error: {}", e)),
+                        }
+                        create_busy.set(false);
+                        return;
                     }
 
-                    let r1 = upsert(&token, &format!("{}/index.html", base), &msg, &idx).await;
-                    let r2 = upsert(&token, &format!("{}/styles.css", base), &msg, &css).await;
-                    let r3 = upsert(&token, &format!("{}/Cargo.toml", base), &msg, &toml).await;
-                    let r4 = upsert(&token, &format!("{}/src/main.rs", base), &msg, &mainrs).await;
+                    let r1 = gh.upsert_file(&format!("{}/index.html", base), &msg, &idx, true).await;
+                    let r2 = gh.upsert_file(&format!("{}/styles.css", base), &msg, &css, true).await;
+                    let r3 = gh.upsert_file(&format!("{}/Cargo.toml", base), &msg, &toml, true).await;
+                    let r4 = gh.upsert_file(&format!("{}/src/main.rs", base), &msg, &mainrs, true).await;
+                    let r5 = gh.upsert_file(&format!("{}/{}", base, MANIFEST_FILE), &msg, &manifest, true).await;
 
-                    match (r1, r2, r3, r4) {
-                        (Ok(_), Ok(_), Ok(_), Ok(_)) => {
+                    let run = match (r1, r2, r3, r4, r5) {
+                        (Ok(_), Ok(_), Ok(_), Ok(_), Ok(_)) => {
                             create_status.set("Files saved ✅ Dispatching deploy workflow…".into());
-                            let app_dir = format!("plugs/{}", plug);
-                            match dispatch_workflow(&token, &plug, &app_dir).await {
-                                Ok(_) => create_status.set(format!(
-                                    "Workflow dispatched ✅ URL: https://www.webhtml5.info/{}/",
-                                    plug
-                                )),
-                                Err(e) => create_status.set(format!("Dispatch error: {}", e)),
-                            }
+                            RunTracker::dispatch(&token, &plug, &create_status).await
                         }
-                        (a, b, c, d) => {
+                        (a, b, c, d, m) => {
                             let mut errs = vec![];
                             if let Err(e) = a { errs.push(format!("index.html: {e}")); }
                             if let Err(e) = b { errs.push(format!("styles.css: {e}")); }
                             if let Err(e) = c { errs.push(format!("Cargo.toml: {e}")); }
                             if let Err(e) = d { errs.push(format!("src/main.rs: {e}")); }
+                            if let Err(e) = m { errs.push(format!("{MANIFEST_FILE}: {e}")); }
                             create_status.set(format!("Create error:\n{}", errs.join("\n")));
+                            None
                         }
-                    }
+                    };
 
                     create_busy.set(false);
+                    if let Some(id) = run {
+                        tracker.follow(token, id).await;
+                    }
                 }
             });
         })
@@ -1105,36 +1038,23 @@ fn app() -> Html {
         })
     };
 
-    let on_edit_file = {
-        let edit_file = edit_file.clone();
-        Callback::from(move |e: Event| {
-            let v = e.target_unchecked_into::<HtmlInputElement>().value();
-            edit_file.set(v);
-        })
-    };
-
-    let on_edit_text = {
-        let edit_text = edit_text.clone();
-        Callback::from(move |e: InputEvent| {
-            let v = e.target_unchecked_into::<HtmlTextAreaElement>().value();
-            edit_text.set(v);
-        })
-    };
-
-    let editor_path = {
-        let p = (*edit_plug).clone();
-        let f = (*edit_file).clone();
-        use_memo((p, f), |(plug, file)| format!("plugs/{}/{}", plug.trim(), file))
+    let project = workspace.0.clone();
+    let active = project
+        .as_ref()
+        .and_then(|p| p.active())
+        .map(str::to_string);
+    let active_text = match (&project, &active) {
+        (Some(p), Some(a)) => p.text(a).map(str::to_string),
+        _ => None,
     };
 
-    let on_load_file = {
+    // List plugs/<name>/ and start a fresh working copy
+    let on_browse = {
         let token = token.clone();
         let edit_plug = edit_plug.clone();
-        let edit_text = edit_text.clone();
+        let workspace = workspace.clone();
         let edit_status = edit_status.clone();
         let edit_busy = edit_busy.clone();
-        let sha_map = sha_map.clone();
-        let edit_file = edit_file.clone();
 
         Callback::from(move |_: MouseEvent| {
             if *edit_busy {
@@ -1145,33 +1065,32 @@ fn app() -> Html {
                 edit_status.set("Missing GitHub token.".into());
                 return;
             }
-
-            let Some(plug) = sanitize_plug_name(&(*edit_plug)) else {
+            let Some(plug) = plug::sanitize_name(&edit_plug) else {
                 edit_status.set("plug_name must be lowercase letters, numbers, hyphens.".into());
                 return;
             };
+            if workspace.0.as_ref().is_some_and(|p| p.has_changes())
+                && !confirm("Discard unsaved changes in the open plug?")
+            {
+                return;
+            }
 
-            let file = (*edit_file).clone();
-            let path = format!("plugs/{}/{}", plug, file);
-
+            let dir = plug::app_dir(&plug);
             edit_busy.set(true);
-            edit_status.set(format!("Loading {}…", path));
+            edit_status.set(format!("Listing {}…", dir));
 
             spawn_local({
-                let edit_text = edit_text.clone();
+                let workspace = workspace.clone();
                 let edit_status = edit_status.clone();
                 let edit_busy = edit_busy.clone();
-                let sha_map = sha_map.clone();
                 async move {
-                    match github_get_file(&token, &path).await {
-                        Ok((sha, txt)) => {
-                            edit_text.set(txt);
-                            let mut m = (*sha_map).clone();
-                            m.insert(path.clone(), sha);
-                            sha_map.set(m);
-                            edit_status.set(format!("Loaded ✅ {}", path));
+                    match github(&token).list_tree(&dir).await {
+                        Ok(files) => {
+                            let n = files.len();
+                            workspace.dispatch(WorkspaceAction::Open(Project::new(&plug, files)));
+                            edit_status.set(format!("Opened {} ✅ {} files — tap one to edit.", dir, n));
                         }
-                        Err(e) => edit_status.set(format!("Load error: {}", e)),
+                        Err(e) => edit_status.set(format!("Browse error: {}", e)),
                     }
                     edit_busy.set(false);
                 }
@@ -1179,14 +1098,247 @@ fn app() -> Html {
         })
     };
 
+    // Focus a file's tab, fetching it the first time
+    let on_open_file = {
+        let token = token.clone();
+        let workspace = workspace.clone();
+        let edit_status = edit_status.clone();
+
+        Callback::from(move |path: String| {
+            let Some(p) = workspace.0.as_ref() else {
+                edit_status.set("Tap “Open plug” first.".into());
+                return;
+            };
+            if !p.contains(&path) {
+                edit_status.set(format!("{} is not in {}.", path, plug::app_dir(&p.plug)));
+                return;
+            }
+            workspace.dispatch(WorkspaceAction::Focus(path.clone()));
+            if p.is_loaded(&path) {
+                return;
+            }
+
+            let token = (*token).clone();
+            let repo_path = p.repo_path(&path);
+            edit_status.set(format!("Loading {}…", repo_path));
+
+            spawn_local({
+                let workspace = workspace.clone();
+                let edit_status = edit_status.clone();
+                async move {
+                    match github(&token).get_file(&repo_path).await {
+                        Ok(file) => {
+                            workspace.dispatch(WorkspaceAction::Loaded {
+                                path,
+                                content: file.content,
+                                sha: file.sha,
+                            });
+                            edit_status.set(format!("Loaded ✅ {}", repo_path));
+                        }
+                        Err(e) => edit_status.set(format!("Load error: {}", e)),
+                    }
+                }
+            });
+        })
+    };
+
+    let on_close_tab = {
+        let workspace = workspace.clone();
+        Callback::from(move |path: String| workspace.dispatch(WorkspaceAction::Close(path)))
+    };
+
+    let on_edit_text = {
+        let workspace = workspace.clone();
+        let active = active.clone();
+        Callback::from(move |e: InputEvent| {
+            let Some(path) = active.clone() else {
+                return;
+            };
+            let text = e.target_unchecked_into::<HtmlTextAreaElement>().value();
+            workspace.dispatch(WorkspaceAction::Edit { path, text });
+        })
+    };
+
+    let on_new_path = {
+        let new_path = new_path.clone();
+        Callback::from(move |e: InputEvent| {
+            let v = e.target_unchecked_into::<HtmlInputElement>().value();
+            new_path.set(v);
+        })
+    };
+
+    let on_new_file = {
+        let workspace = workspace.clone();
+        let new_path = new_path.clone();
+        let edit_status = edit_status.clone();
+        Callback::from(move |_: MouseEvent| {
+            let Some(p) = workspace.0.as_ref() else {
+                edit_status.set("Tap “Open plug” first.".into());
+                return;
+            };
+            // dry run for the error message; the reducer applies it for real
+            match p.clone().create(&new_path) {
+                Ok(path) => {
+                    edit_status.set(format!("New file {} (unsaved)", path));
+                    workspace.dispatch(WorkspaceAction::Create(path));
+                    new_path.set(String::new());
+                }
+                Err(e) => edit_status.set(e),
+            }
+        })
+    };
+
+    let on_rename_file = {
+        let workspace = workspace.clone();
+        let new_path = new_path.clone();
+        let edit_status = edit_status.clone();
+        let active = active.clone();
+        Callback::from(move |_: MouseEvent| {
+            let (Some(p), Some(from)) = (workspace.0.as_ref(), active.clone()) else {
+                edit_status.set("Open the file you want to rename.".into());
+                return;
+            };
+            match p.clone().rename(&from, &new_path) {
+                Ok(to) => {
+                    edit_status.set(format!("Renamed {} → {} (unsaved)", from, to));
+                    workspace.dispatch(WorkspaceAction::Rename { from, to });
+                    new_path.set(String::new());
+                }
+                Err(e) => edit_status.set(e),
+            }
+        })
+    };
+
+    let on_delete_file = {
+        let workspace = workspace.clone();
+        let edit_status = edit_status.clone();
+        let active = active.clone();
+        Callback::from(move |_: MouseEvent| {
+            let Some(path) = active.clone() else {
+                edit_status.set("Open the file you want to delete.".into());
+                return;
+            };
+            if !confirm(&format!("Delete {}? It is removed on the next Save all.", path)) {
+                return;
+            }
+            edit_status.set(format!("Deleted {} (unsaved)", path));
+            workspace.dispatch(WorkspaceAction::Delete(path));
+        })
+    };
+
+    // problems for the plug in the editor, as (path inside the plug, diagnostic)
+    let problems: Vec<(String, Diagnostic)> = {
+        let plug_dir = match &project {
+            Some(p) => plug::app_dir(&p.plug),
+            None => plug::app_dir(edit_plug.trim()),
+        };
+        tracker
+            .diagnostics
+            .iter()
+            .filter_map(|d| d.path_in(&plug_dir).map(|p| (p, d.clone())))
+            .collect()
+    };
+    let gutter_marks: HashMap<u32, Severity> = {
+        let mut marks = HashMap::new();
+        for (path, d) in &problems {
+            if Some(path) != active.as_ref() || active_text.is_none() {
+                continue;
+            }
+            if let Some(line) = d.line {
+                let mark = marks.entry(line).or_insert(d.severity);
+                *mark = (*mark).min(d.severity);
+            }
+        }
+        marks
+    };
+
+    // Jump once the requested file is the one in the textarea
+    {
+        let editor_ref = editor_ref.clone();
+        let jump_to = jump_to.clone();
+        let ready = active.clone().filter(|_| active_text.is_some());
+        use_effect_with(((*jump_to).clone(), ready), move |(target, ready)| {
+            if let (Some((path, line)), Some(open)) = (target, ready) {
+                if path == open {
+                    if let Some(ta) = editor_ref.cast::<HtmlTextAreaElement>() {
+                        select_line(&ta, *line);
+                    }
+                    jump_to.set(None);
+                }
+            }
+        });
+    }
+
+    let on_editor_scroll = {
+        let editor_ref = editor_ref.clone();
+        let gutter_ref = gutter_ref.clone();
+        Callback::from(move |_: Event| {
+            if let (Some(ta), Some(g)) = (
+                editor_ref.cast::<HtmlTextAreaElement>(),
+                gutter_ref.cast::<web_sys::Element>(),
+            ) {
+                g.set_scroll_top(ta.scroll_top());
+            }
+        })
+    };
+
+    let on_problem = {
+        let on_open_file = on_open_file.clone();
+        let jump_to = jump_to.clone();
+        Callback::from(move |(path, line): (String, u32)| {
+            on_open_file.emit(path.clone());
+            jump_to.set(Some((path, line)));
+        })
+    };
+
+    // conflict view: pick a side per conflict, then write the result into the buffer
+    let on_merge_choice = {
+        let pending_merge = pending_merge.clone();
+        Callback::from(move |(n, choice): (usize, Resolution)| {
+            if let Some(mut m) = (*pending_merge).clone() {
+                if let Some(c) = m.choices.get_mut(n) {
+                    *c = choice;
+                }
+                pending_merge.set(Some(m));
+            }
+        })
+    };
+
+    let on_apply_merge = {
+        let pending_merge = pending_merge.clone();
+        let workspace = workspace.clone();
+        let edit_status = edit_status.clone();
+        Callback::from(move |_: MouseEvent| {
+            let Some(m) = (*pending_merge).clone() else {
+                return;
+            };
+            let merged = m.merge.resolve(&m.choices);
+            workspace.dispatch(m.action(merged));
+            workspace.dispatch(WorkspaceAction::Focus(m.path.clone()));
+            pending_merge.set(None);
+            edit_status.set(format!("Merged {} ✅ Review it, then Save again.", m.path));
+        })
+    };
+
+    let on_cancel_merge = {
+        let pending_merge = pending_merge.clone();
+        let edit_status = edit_status.clone();
+        Callback::from(move |_: MouseEvent| {
+            pending_merge.set(None);
+            edit_status.set("Merge cancelled — nothing was saved.".into());
+        })
+    };
+
+    // Save just the active tab
     let on_save_file = {
         let token = token.clone();
-        let edit_plug = edit_plug.clone();
-        let edit_file = edit_file.clone();
-        let edit_text = edit_text.clone();
+        let workspace = workspace.clone();
         let edit_status = edit_status.clone();
         let edit_busy = edit_busy.clone();
-        let sha_map = sha_map.clone();
+        let delivery = delivery.clone();
+        let pending_pr = pending_pr.clone();
+        let pending_merge = pending_merge.clone();
+        let active = active.clone();
 
         Callback::from(move |_: MouseEvent| {
             if *edit_busy {
@@ -1197,50 +1349,152 @@ fn app() -> Html {
                 edit_status.set("Missing GitHub token.".into());
                 return;
             }
-
-            let Some(plug) = sanitize_plug_name(&(*edit_plug)) else {
-                edit_status.set("plug_name must be lowercase letters, numbers, hyphens.".into());
+            let (Some(p), Some(file)) = (workspace.0.as_ref(), active.clone()) else {
+                edit_status.set("Open a file first.".into());
+                return;
+            };
+            let Some(text) = p.text(&file).map(str::to_string) else {
+                edit_status.set(format!("{} is still loading.", file));
                 return;
             };
+            if p.origin(&file).is_some_and(|o| o != file) {
+                edit_status.set("Renames are saved with “Save all”, which also removes the old path.".into());
+                return;
+            }
 
-            let file = (*edit_file).clone();
-            let path = format!("plugs/{}/{}", plug, file);
+            if pending_merge.is_some() {
+                edit_status.set("Apply or cancel the merge below first.".into());
+                return;
+            }
 
-            let text = (*edit_text).clone();
+            let snapshot = p.clone();
+            let plug = p.plug.clone();
+            let path = p.repo_path(&file);
+            let sha = p.sha(&file).map(str::to_string);
+            let mode = *delivery;
             let msg = format!("Edit {} via rust-iphone-compiler", path);
 
             edit_busy.set(true);
             edit_status.set(format!("Saving {}…", path));
 
             spawn_local({
+                let workspace = workspace.clone();
                 let edit_status = edit_status.clone();
                 let edit_busy = edit_busy.clone();
-                let sha_map = sha_map.clone();
+                let pending_pr = pending_pr.clone();
+                let pending_merge = pending_merge.clone();
                 async move {
-                    let sha = (*sha_map).get(&path).cloned();
-
-                    let sha = match sha {
-                        Some(s) => Some(s),
-                        None => match github_get_file(&token, &path).await {
-                            Ok((sha, _)) => Some(sha),
-                            Err(e) if e.starts_with("Not found:") => None,
-                            Err(_) => None,
-                        },
-                    };
+                    let gh = github(&token);
+
+                    if mode != DeliveryMode::Direct {
+                        // branch commits go through the Git Data API; merge against the branch first
+                        let branch = plug::feature_branch(&plug);
+                        if let Err(e) = gh.ensure_branch(&branch, &gh.config().branch).await {
+                            edit_status.set(format!("Save error: {}", e));
+                            edit_busy.set(false);
+                            return;
+                        }
+                        let on_branch = github_on(&token, &branch);
+                        let dir = plug::app_dir(&plug);
+                        let stale = match on_branch.list_tree(&dir).await {
+                            Ok(now) => snapshot.stale_paths(&now),
+                            Err(e) => {
+                                edit_status.set(format!("Save error: {}", e));
+                                edit_busy.set(false);
+                                return;
+                            }
+                        };
+                        let mut text = text;
+                        if stale.contains(&file) {
+                            edit_status.set(format!("{} changed on {} — merging…", path, branch));
+                            match merge_stale(&on_branch, &snapshot, std::slice::from_ref(&file), &workspace).await {
+                                Ok(merged) => text = merged.text(&file).unwrap_or_default().to_string(),
+                                Err(MergeStop::Conflict(m)) => {
+                                    edit_status.set(format!(
+                                        "{} changed on {} ❌ {} conflicting change(s) — pick a side for each below, then Apply merge.",
+                                        m.path,
+                                        branch,
+                                        m.choices.len()
+                                    ));
+                                    pending_merge.set(Some(m));
+                                    edit_busy.set(false);
+                                    return;
+                                }
+                                Err(MergeStop::Error(e)) => {
+                                    edit_status.set(e);
+                                    edit_busy.set(false);
+                                    return;
+                                }
+                            }
+                        }
 
-                    match github_put_file(&token, &path, &msg, &text, sha).await {
-                        Ok(_) => {
-                            match github_get_file(&token, &path).await {
-                                Ok((new_sha, _)) => {
-                                    let mut m = (*sha_map).clone();
-                                    m.insert(path.clone(), new_sha);
-                                    sha_map.set(m);
+                        let changes = [FileChange::write(&path, &text)];
+                        match gh.deliver_plug(&plug, &msg, &changes, mode).await {
+                            Ok(d) => {
+                                if let Some(pr) = &d.pull_request {
+                                    pending_pr.set(Some((pr.number, plug.clone())));
+                                }
+                                edit_status.set(delivery_summary(&d));
+                                // the branch copy is the new base for this file
+                                if let Ok(files) = on_branch.list_tree(&dir).await {
+                                    if let Some(f) = files.into_iter().find(|f| f.path == file) {
+                                        workspace.dispatch(WorkspaceAction::FileSaved { path: file, sha: f.sha });
+                                    }
                                 }
-                                Err(_) => {}
                             }
+                            Err(e) => edit_status.set(format!("Save error: {}", e)),
+                        }
+                        edit_busy.set(false);
+                        return;
+                    }
+
+                    let (text, sha) = match gh.put_file(&path, &msg, &text, sha).await {
+                        Ok(new_sha) => {
+                            workspace.dispatch(WorkspaceAction::FileSaved { path: file, sha: new_sha });
                             edit_status.set(format!("Saved ✅ {}", path));
+                            edit_busy.set(false);
+                            return;
                         }
-                        Err(e) => edit_status.set(format!("Save error: {}", e)),
+                        // someone pushed since we loaded: merge their version in
+                        Err(e) if e.is_conflict() => {
+                            edit_status.set(format!("{} changed on GitHub — merging…", path));
+                            match merge_with_remote(&gh, &snapshot, &file).await {
+                                Ok(m) => match m.merge.text() {
+                                    Some(merged) => {
+                                        workspace.dispatch(m.action(merged.clone()));
+                                        (merged, Some(m.sha))
+                                    }
+                                    None => {
+                                        edit_status.set(format!(
+                                            "{} changed on GitHub ❌ {} conflicting change(s) — pick a side for each below, then Apply merge.",
+                                            path,
+                                            m.choices.len()
+                                        ));
+                                        pending_merge.set(Some(m));
+                                        edit_busy.set(false);
+                                        return;
+                                    }
+                                },
+                                Err(e) => {
+                                    edit_status.set(e);
+                                    edit_busy.set(false);
+                                    return;
+                                }
+                            }
+                        }
+                        Err(e) => {
+                            edit_status.set(format!("Save error: {}", e));
+                            edit_busy.set(false);
+                            return;
+                        }
+                    };
+
+                    match gh.put_file(&path, &msg, &text, sha).await {
+                        Ok(new_sha) => {
+                            workspace.dispatch(WorkspaceAction::FileSaved { path: file, sha: new_sha });
+                            edit_status.set(format!("Merged with GitHub's changes and saved ✅ {}", path));
+                        }
+                        Err(e) => edit_status.set(format!("Save error after merge: {}", e)),
                     }
 
                     edit_busy.set(false);
@@ -1249,48 +1503,174 @@ fn app() -> Html {
         })
     };
 
-    let on_save_and_deploy = {
+    // Save every changed, new, renamed and deleted file as one commit (optionally deploy)
+    let on_save_all = {
         let token = token.clone();
-        let edit_plug = edit_plug.clone();
-        let status = status.clone();
-        let busy = busy.clone();
-        let on_save_file = on_save_file.clone();
-
-        Callback::from(move |_: MouseEvent| {
-            // fire save
-            on_save_file.emit(MouseEvent::new("click").unwrap());
+        let workspace = workspace.clone();
+        let edit_status = edit_status.clone();
+        let edit_busy = edit_busy.clone();
+        let delivery = delivery.clone();
+        let pending_pr = pending_pr.clone();
+        let pending_merge = pending_merge.clone();
+        let tracker = tracker.clone();
 
+        Callback::from(move |deploy: bool| {
+            if *edit_busy {
+                return;
+            }
             let token = (*token).clone();
-            let plug = (*edit_plug).clone();
-
             if token.trim().is_empty() {
-                status.set("Missing GitHub token.".into());
+                edit_status.set("Missing GitHub token.".into());
                 return;
             }
-            let Some(plug) = sanitize_plug_name(&plug) else {
-                status.set("plug_name must be lowercase letters, numbers, hyphens.".into());
+            let Some(p) = workspace.0.as_ref() else {
+                edit_status.set("Tap “Open plug” first.".into());
                 return;
             };
-            if *busy {
+            if pending_merge.is_some() {
+                edit_status.set("Apply or cancel the merge below first.".into());
+                return;
+            }
+            let changes = match p.changes() {
+                Ok(c) => c,
+                Err(e) => {
+                    edit_status.set(e);
+                    return;
+                }
+            };
+            if changes.is_empty() && !deploy {
+                edit_status.set("Nothing to save.".into());
                 return;
             }
 
-            let app_dir = format!("plugs/{}", plug);
-            busy.set(true);
-            status.set("Dispatching deploy after save…".into());
+            let snapshot = p.clone();
+            let plug = p.plug.clone();
+            let mode = *delivery;
+            let msg = format!(
+                "Edit {} via rust-iphone-compiler ({} files)",
+                plug::app_dir(&plug),
+                changes.len()
+            );
+
+            edit_busy.set(true);
+            edit_status.set(format!("Saving {} change(s) in one commit…", changes.len()));
 
             spawn_local({
-                let status = status.clone();
-                let busy = busy.clone();
+                let workspace = workspace.clone();
+                let edit_status = edit_status.clone();
+                let edit_busy = edit_busy.clone();
+                let pending_pr = pending_pr.clone();
+                let pending_merge = pending_merge.clone();
+                let tracker = tracker.clone();
                 async move {
-                    match dispatch_workflow(&token, &plug, &app_dir).await {
-                        Ok(_) => status.set(format!(
-                            "Workflow dispatched ✅ Deployed URL: https://www.webhtml5.info/{}/",
-                            plug
-                        )),
-                        Err(e) => status.set(format!("Dispatch error: {}", e)),
+                    let gh = github(&token);
+
+                    // branch modes commit to plug/<name>, so that is the copy to merge against
+                    let target = if mode == DeliveryMode::Direct {
+                        gh.config().branch.clone()
+                    } else {
+                        let branch = plug::feature_branch(&plug);
+                        if let Err(e) = gh.ensure_branch(&branch, &gh.config().branch).await {
+                            edit_status.set(format!("Save error: {}", e));
+                            edit_busy.set(false);
+                            return;
+                        }
+                        branch
+                    };
+                    let on_target = github_on(&token, &target);
+
+                    let mut changes = changes;
+                    if !changes.is_empty() {
+                        // files pushed by someone else since we loaded them get merged, not overwritten
+                        let stale = match on_target.list_tree(&plug::app_dir(&plug)).await {
+                            Ok(now) => snapshot.stale_paths(&now),
+                            Err(e) => {
+                                edit_status.set(format!("Save error: {}", e));
+                                edit_busy.set(false);
+                                return;
+                            }
+                        };
+                        if !stale.is_empty() {
+                            let merged_copy = match merge_stale(&on_target, &snapshot, &stale, &workspace).await {
+                                Ok(p) => p,
+                                Err(MergeStop::Conflict(m)) => {
+                                    edit_status.set(format!(
+                                        "{} changed on {} ❌ {} conflicting change(s) — settle them below, then Save all again.",
+                                        m.path,
+                                        target,
+                                        m.choices.len()
+                                    ));
+                                    pending_merge.set(Some(m));
+                                    edit_busy.set(false);
+                                    return;
+                                }
+                                Err(MergeStop::Error(e)) => {
+                                    edit_status.set(e);
+                                    edit_busy.set(false);
+                                    return;
+                                }
+                            };
+                            changes = match merged_copy.changes() {
+                                Ok(c) => c,
+                                Err(e) => {
+                                    edit_status.set(e);
+                                    edit_busy.set(false);
+                                    return;
+                                }
+                            };
+                            edit_status.set(format!("Merged {}'s changes into {} ✅ Committing…", target, stale.join(", ")));
+                        }
+                    }
+
+                    if mode != DeliveryMode::Direct {
+                        match gh.deliver_plug(&plug, &msg, &changes, mode).await {
+                            Ok(d) => {
+                                if let Some(pr) = &d.pull_request {
+                                    pending_pr.set(Some((pr.number, plug.clone())));
+                                }
+                                edit_status.set(delivery_summary(&d));
+                            }
+                            Err(e) => {
+                                edit_status.set(format!("Save error: {}", e));
+                                edit_busy.set(false);
+                                return;
+                            }
+                        }
+                    } else if !changes.is_empty() {
+                        match gh.commit_files(&msg, &changes).await {
+                            Ok(c) => edit_status.set(format!(
+                                "Saved {} change(s) ✅ commit {}",
+                                changes.len(),
+                                &c.commit_sha[..c.commit_sha.len().min(7)]
+                            )),
+                            Err(e) => {
+                                edit_status.set(format!("Save error: {}", e));
+                                edit_busy.set(false);
+                                return;
+                            }
+                        }
+                    }
+
+                    if !changes.is_empty() {
+                        // new blob shas so single-file saves keep working
+                        let shas: BTreeMap<String, String> = on_target
+                            .list_tree(&plug::app_dir(&plug))
+                            .await
+                            .map(|files| files.into_iter().map(|f| (f.path, f.sha)).collect())
+                            .unwrap_or_default();
+                        workspace.dispatch(WorkspaceAction::Saved(shas));
+                    }
+
+                    // branch modes deploy (or not) inside deliver_plug
+                    if mode != DeliveryMode::Direct || !deploy {
+                        edit_busy.set(false);
+                        return;
+                    }
+                    let run = RunTracker::dispatch(&token, &plug, &edit_status).await;
+                    edit_busy.set(false);
+                    if let Some(id) = run {
+                        tracker.follow(token, id).await;
                     }
-                    busy.set(false);
                 }
             });
         })
@@ -1334,6 +1714,20 @@ fn app() -> Html {
                 <button class="btn btn2" onclick={on_save_token}>{ "Save token" }</button>
                 <button class="btn btn2" onclick={on_refresh} disabled={*runs_busy}>{ if *runs_busy { "Refreshing…" } else { "Refresh runs" } }</button>
               </div>
+
+              <label class="sub" style="display:block; margin:12px 0 6px; max-width:none;">{ "delivery (create + save)" }</label>
+              <select class="select input" onchange={on_delivery}>
+                { for DeliveryMode::ALL.iter().map(|m| html! {
+                  <option value={m.key()} selected={*m == *delivery}>{ m.label() }</option>
+                }) }
+              </select>
+              if let Some((number, plug)) = &*pending_pr {
+                <div class="row" style="margin-top:10px;">
+                  <button class="btn btn2" onclick={on_deploy_merged} disabled={*busy}>
+                    { format!("Deploy after merge (PR #{} • {})", number, plug) }
+                  </button>
+                </div>
+              }
             </div>
           </section>
 
@@ -1341,46 +1735,162 @@ fn app() -> Html {
             <section class="card">
               <div class="card-h">
                 <h2 class="h2">{ "Quick Edit (iPhone) + Deploy" }</h2>
-                <p class="sub">{ "Load a plug file from GitHub, edit, save, and deploy." }</p>
+                <p class="sub">{ "Open a plug, browse its files in tabs, then save everything as one commit and deploy." }</p>
               </div>
               <div class="card-b">
                 <label class="sub" style="display:block; margin:0 0 6px; max-width:none;">{ "plug_name" }</label>
-                <input class="input" value={(*edit_plug).clone()} oninput={on_edit_plug} placeholder="my-new-plug" />
-
-                <label class="sub" style="display:block; margin:12px 0 6px; max-width:none;">{ "file" }</label>
-                <select class="select input" onchange={on_edit_file} value={(*edit_file).clone()}>
-                  <option value="src/main.rs">{ "src/main.rs" }</option>
-                  <option value="styles.css">{ "styles.css" }</option>
-                  <option value="index.html">{ "index.html" }</option>
-                  <option value="Cargo.toml">{ "Cargo.toml" }</option>
-                </select>
+                <div class="row">
+                  <input class="input" style="flex:1;" value={(*edit_plug).clone()} oninput={on_edit_plug} placeholder="my-new-plug" />
+                  <button class="btn btn2" onclick={on_browse} disabled={*edit_busy}>{ "Open plug" }</button>
+                </div>
 
-                <div class="kv" style="margin-top:10px;">
-                  <div class="k">
-                    <div class="label">{ "editor path" }</div>
-                    <div class="value mono">{ (*editor_path).clone() }</div>
+                if let Some(p) = &project {
+                  <label class="sub" style="display:block; margin:12px 0 6px; max-width:none;">{ format!("{} — tap a file", plug::app_dir(&p.plug)) }</label>
+                  <div class="tree">
+                    { for p.tree_rows().into_iter().map(|row| {
+                        let indent = format!("padding-left:{}px;", 8 + row.depth * 14);
+                        match row.file {
+                            None => html! { <div class="tree-dir mono" style={indent}>{ format!("{}/", row.name) }</div> },
+                            Some(path) => {
+                                let mut class = classes!("tree-file", "mono");
+                                if active.as_deref() == Some(path.as_str()) {
+                                    class.push("on");
+                                }
+                                let mark = if p.is_dirty(&path) { " •" } else { "" };
+                                let onclick = on_open_file.reform({
+                                    let path = path.clone();
+                                    move |_: MouseEvent| path.clone()
+                                });
+                                html! { <button class={class} style={indent} {onclick}>{ format!("{}{}", row.name, mark) }</button> }
+                            }
+                        }
+                    }) }
+                    { for p.deleted_paths().into_iter().map(|d| html! {
+                        <div class="tree-dir mono tree-gone">{ format!("{} (deleted)", d) }</div>
+                    }) }
                   </div>
-                  <div class="k">
-                    <div class="label">{ "tip" }</div>
-                    <div class="value">{ "Tap Load before editing so SHA is tracked." }</div>
+
+                  <div class="row" style="margin-top:10px;">
+                    <input class="input mono" style="flex:1;" value={(*new_path).clone()} oninput={on_new_path} placeholder="src/components/header.rs" />
+                    <button class="btn btn2" onclick={on_new_file}>{ "New" }</button>
+                    <button class="btn btn2" onclick={on_rename_file} disabled={active.is_none()}>{ "Rename" }</button>
+                    <button class="btn btn2" onclick={on_delete_file} disabled={active.is_none()}>{ "Delete" }</button>
                   </div>
-                </div>
+
+                  if !p.tabs().is_empty() {
+                    <div class="tabs">
+                      { for p.tabs().iter().map(|t| {
+                          let mut class = classes!("tab");
+                          if active.as_deref() == Some(t.as_str()) {
+                              class.push("on");
+                          }
+                          let name = t.rsplit('/').next().unwrap_or(t);
+                          let mark = if p.is_dirty(t) { " •" } else { "" };
+                          let onclick = on_open_file.reform({
+                              let t = t.clone();
+                              move |_: MouseEvent| t.clone()
+                          });
+                          let onclose = on_close_tab.reform({
+                              let t = t.clone();
+                              move |_: MouseEvent| t.clone()
+                          });
+                          html! {
+                            <span class={class}>
+                              <button class="tab-name mono" title={t.clone()} {onclick}>{ format!("{}{}", name, mark) }</button>
+                              <button class="tab-x" onclick={onclose} aria-label="Close tab">{ "×" }</button>
+                            </span>
+                          }
+                      }) }
+                    </div>
+                  }
+                }
 
                 <div class="row" style="margin-top:12px;">
-                  <button class="btn btn2" onclick={on_load_file} disabled={*edit_busy}>{ if *edit_busy { "Loading…" } else { "Load" } }</button>
-                  <button class="btn btn2" onclick={on_save_file} disabled={*edit_busy}>{ if *edit_busy { "Saving…" } else { "Save" } }</button>
-                  <button class="btn" onclick={on_save_and_deploy} disabled={*edit_busy || *busy}>{ "Save + Deploy" }</button>
+                  <button class="btn btn2" onclick={on_save_file} disabled={*edit_busy || active_text.is_none()}>{ if *edit_busy { "Saving…" } else { "Save" } }</button>
+                  <button class="btn btn2" onclick={on_save_all.reform(|_: MouseEvent| false)} disabled={*edit_busy || project.is_none()}>
+                    { format!("Save all ({})", project.as_ref().map_or(0, |p| p.dirty_paths().len() + p.deleted_paths().len())) }
+                  </button>
+                  <button class="btn" onclick={on_save_all.reform(|_: MouseEvent| true)} disabled={*edit_busy || *busy || project.is_none()}>{ "Save all + Deploy" }</button>
                 </div>
 
-                <textarea class="ta" value={(*edit_text).clone()} oninput={on_edit_text} placeholder="// edit here…"></textarea>
+                <div class="editor">
+                  <div class="gutter" ref={gutter_ref} aria-hidden="true">
+                    { for (1..=active_text.as_deref().unwrap_or_default().lines().count().max(1) as u32).map(|n| {
+                        let class = match gutter_marks.get(&n) {
+                            Some(Severity::Error) => "g-err",
+                            Some(Severity::Warning) => "g-warn",
+                            None => "",
+                        };
+                        html! { <div class={class}>{ n }</div> }
+                    }) }
+                  </div>
+                  <textarea class="ta" ref={editor_ref} wrap="off" value={active_text.clone().unwrap_or_default()} oninput={on_edit_text} onscroll={on_editor_scroll} disabled={active_text.is_none() || pending_merge.is_some()} placeholder="Open a plug, then tap a file…"></textarea>
+                </div>
                 <pre class="log">{ (*edit_status).clone() }</pre>
+
+                if let Some(m) = &*pending_merge {
+                  <label class="sub" style="display:block; margin:12px 0 6px; max-width:none;">{ format!("Merge {} — − GitHub, + mine", m.path) }</label>
+                  <div class="merge">
+                    { for m.merge.conflicts().enumerate().map(|(n, c)| {
+                        let choice = m.choices.get(n).copied().unwrap_or(Resolution::Ours);
+                        let pick = |r: Resolution, label: &'static str| {
+                            let class = if choice == r { "btn" } else { "btn btn2" };
+                            let onclick = on_merge_choice.reform(move |_: MouseEvent| (n, r));
+                            html! { <button class={class} {onclick}>{ label }</button> }
+                        };
+                        html! {
+                          <div class="conflict">
+                            <pre class="diff">
+                              { for diff_lines(&c.theirs, &c.ours).into_iter().map(|l| {
+                                  let (class, sign) = match l.kind {
+                                      DiffKind::Same => ("d-same", "  "),
+                                      DiffKind::Removed => ("d-del", "− "),
+                                      DiffKind::Added => ("d-add", "+ "),
+                                  };
+                                  html! { <div class={class}>{ format!("{}{}", sign, l.text) }</div> }
+                              }) }
+                            </pre>
+                            <div class="row">
+                              { pick(Resolution::Ours, "Keep mine") }
+                              { pick(Resolution::Theirs, "Take GitHub's") }
+                              { pick(Resolution::Both, "Keep both") }
+                            </div>
+                          </div>
+                        }
+                    }) }
+                  </div>
+                  <div class="row" style="margin-top:10px;">
+                    <button class="btn" onclick={on_apply_merge}>{ "Apply merge" }</button>
+                    <button class="btn btn2" onclick={on_cancel_merge}>{ "Cancel" }</button>
+                  </div>
+                }
+
+                if !problems.is_empty() {
+                  <label class="sub" style="display:block; margin:12px 0 0; max-width:none;">{ format!("Problems ({}) — tap to jump", problems.len()) }</label>
+                  <div class="problems">
+                    { for problems.iter().map(|(path, d)| {
+                        let line = d.line.unwrap_or(1);
+                        let onclick = on_problem.reform({
+                            let path = path.clone();
+                            move |_: MouseEvent| (path.clone(), line)
+                        });
+                        let class = if d.severity == Severity::Error { "problem p-err" } else { "problem p-warn" };
+                        html! {
+                          <button class={class} {onclick} title={d.text.clone()}>
+                            <span class="mono">{ format!("{}:{}:{}", path, line, d.column.unwrap_or(1)) }</span>
+                            { format!(" {}{}: {}", d.severity.label(), d.code.as_deref().map(|c| format!("[{}]", c)).unwrap_or_default(), d.message) }
+                          </button>
+                        }
+                    }) }
+                  </div>
+                }
               </div>
             </section>
 
             <section class="card">
               <div class="card-h">
                 <h2 class="h2">{ "Create a new plug + deploy" }</h2>
-                <p class="sub">{ "Paste your 4 files (index.html, styles.css, Cargo.toml, src/main.rs), then deploy." }</p>
+                <p class="sub">{ "Paste your 4 files (index.html, styles.css, Cargo.toml, src/main.rs), then deploy. A plug.toml for the portal is added for you." }</p>
               </div>
 
               <div class="card-b">
@@ -1393,6 +1903,14 @@ fn app() -> Html {
                 <label class="sub" style="display:block; margin:12px 0 6px; max-width:none;">{ "title (for index.html scaffold)" }</label>
                 <input class="input" value={(*new_title).clone()} oninput={on_new_title} />
 
+                <label class="sub" style="display:block; margin:12px 0 6px; max-width:none;">{ "starter template" }</label>
+                <select class="select" onchange={on_template}>
+                  { for templates::CATALOG.iter().map(|t| html! {
+                    <option value={t.id} selected={t.id == *new_template}>{ t.name }</option>
+                  }) }
+                </select>
+                <p class="sub" style="margin:6px 0 0; max-width:none;">{ starter(&new_template).description }</p>
+
                 <div class="row" style="margin-top:12px;">
                   <button class="btn btn2" onclick={on_load_defaults}>{ "Load defaults" }</button>
                 </div>
@@ -1464,6 +1982,27 @@ fn app() -> Html {
             </div>
           </section>
 
+          <section class="card" style="margin-top:14px;">
+            <div class="card-h">
+              <h2 class="h2">{ "Live run" }</h2>
+              <p class="sub">{ "Jobs, steps and logs of the last dispatched (or tracked) run. Failed builds show their rustc errors here." }</p>
+            </div>
+            <div class="card-b">
+              if let Some(p) = &*tracker.progress {
+                { run_progress_view(p) }
+              }
+              if !tracker.note.is_empty() {
+                <pre class="log">{ (*tracker.note).clone() }</pre>
+              }
+              { for tracker.diagnostics.iter().filter(|d| d.severity == Severity::Error).take(MAX_RUN_ERRORS).map(|e| html! {
+                <div class="warn mono">{ e.text.clone() }</div>
+              }) }
+              if let Some(logs) = &*tracker.logs {
+                { run_logs_view(logs, tracker.progress.as_ref()) }
+              }
+            </div>
+          </section>
+
           <section class="card" style="margin-top:14px;">
             <div class="card-h">
               <h2 class="h2">{ "Recent workflow runs" }</h2>
@@ -1479,14 +2018,19 @@ fn app() -> Html {
                     let when = iso_short(&r.created_at);
                     let st = r.status.clone().unwrap_or_default();
                     let conc = r.conclusion.clone().unwrap_or_else(|| "—".into());
+                    let id = r.id;
+                    let on_track = on_track.reform(move |_: MouseEvent| id);
                     html! {
-                      <a class="run" href={r.html_url.clone()} target="_blank">
-                        <div class="run-top">
-                          <div class="run-name">{ format!("{} #{}", name, r.id) }</div>
-                          <div class="run-meta mono">{ when }</div>
-                        </div>
-                        <div class="run-meta">{ format!("status: {} • conclusion: {}", st, conc) }</div>
-                      </a>
+                      <div class="run">
+                        <a href={r.html_url.clone()} target="_blank">
+                          <div class="run-top">
+                            <div class="run-name">{ format!("{} #{}", name, r.id) }</div>
+                            <div class="run-meta mono">{ when }</div>
+                          </div>
+                          <div class="run-meta">{ format!("status: {} • conclusion: {}", st, conc) }</div>
+                        </a>
+                        <button class="btn btn2" style="margin-top:8px;" onclick={on_track}>{ "Track" }</button>
+                      </div>
                     }
                 }) }
               </div>
@@ -1502,6 +2046,66 @@ fn app() -> Html {
     }
 }
 
+fn run_progress_view(p: &RunProgress) -> Html {
+    let (done, total) = p.step_counts();
+    let pct = (done * 100).checked_div(total).unwrap_or(0);
+    let name = p.run.name.clone().unwrap_or_else(|| "Run".into());
+    let state = p.run.conclusion.clone().or_else(|| p.run.status.clone()).unwrap_or_default();
+
+    html! {
+      <>
+        <a class="run" href={p.run.html_url.clone()} target="_blank">
+          <div class="run-top">
+            <div class="run-name">{ format!("{} #{}", name, p.run.id) }</div>
+            <div class="run-meta mono">{ format!("{} • {}/{} steps", state, done, total) }</div>
+          </div>
+        </a>
+        <div class="progress" style="margin-top:10px;">
+          <div style={format!("width:{}%;", pct)}></div>
+        </div>
+        { for p.jobs.iter().map(|j| html! {
+          <div class="k" style="margin-top:10px;">
+            <div class="label">{ job_label(j) }</div>
+            { for j.steps.iter().map(|s| html! {
+              <div class="value mono">{ format!("{} {}", step_icon(s), s.name) }</div>
+            }) }
+          </div>
+        }) }
+      </>
+    }
+}
+
+/// `job: build • in_progress • trunk build`: the step is the running or failed one.
+fn job_label(j: &WorkflowJob) -> String {
+    let state = j.conclusion.as_deref().unwrap_or(&j.status);
+    match j.current_step() {
+        Some(s) => format!("job: {} • {} • {}", j.name, state, s.name),
+        None => format!("job: {} • {}", j.name, state),
+    }
+}
+
+/// One collapsible log per job; failed jobs start open.
+fn run_logs_view(logs: &RunLogView, progress: Option<&RunProgress>) -> Html {
+    let failed = |job: &str| {
+        progress
+            .and_then(|p| p.jobs.iter().find(|j| j.name == job))
+            .is_some_and(WorkflowJob::failed)
+    };
+    html! {
+      <div style="margin-top:10px;">
+        <a class="btn btn2" href={logs.zip_url.to_string()} download={format!("run-{}-logs.zip", logs.run_id)}>
+          { "Download log zip" }
+        </a>
+        { for logs.jobs.iter().map(|f| html! {
+          <details class="job-log" open={failed(f.job_name())}>
+            <summary class="mono">{ format!("log: {}", f.job_name()) }</summary>
+            <pre class="log mono">{ f.plain_text() }</pre>
+          </details>
+        }) }
+      </div>
+    }
+}
+
 fn main() {
     yew::Renderer::<App>::new().render();
 }
@@ -0,0 +1,312 @@
+//! Scan `plugs/*/plug.toml`, check every manifest and write the portal index.
+//!
+//!     cargo run --manifest-path tools/plug-index/Cargo.toml -- [--check] [--init] [--root DIR] [--out DIR]
+//!
+//! `--check` writes nothing: it validates and fails if `<out>/plugs.json` or
+//! `<out>/thumbs/` differ from what would be generated (CI runs this on every
+//! push). `--init` writes a starter `plug.toml` for plugs that have none, from
+//! their `index.html`. Otherwise `<out>/plugs.json` and `<out>/thumbs/` are
+//! regenerated; `--out` defaults to `app`, the portal.
+
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::ExitCode;
+
+use plug_kit::manifest::{IssueSeverity, PlugManifest, PortalEntry, PortalIndex, MANIFEST_FILE};
+use plug_kit::plug;
+
+struct Args {
+    root: PathBuf,
+    out: PathBuf,
+    check: bool,
+    init: bool,
+}
+
+fn parse_args() -> Result<Args, String> {
+    let mut args = Args {
+        root: PathBuf::from("."),
+        out: PathBuf::new(),
+        check: false,
+        init: false,
+    };
+    let mut out = None;
+    let mut it = std::env::args().skip(1);
+    while let Some(arg) = it.next() {
+        match arg.as_str() {
+            "--check" => args.check = true,
+            "--init" => args.init = true,
+            "--root" => args.root = it.next().ok_or("--root needs a directory")?.into(),
+            "--out" => out = Some(PathBuf::from(it.next().ok_or("--out needs a directory")?)),
+            "-h" | "--help" => {
+                return Err("usage: plug-index [--check] [--init] [--root DIR] [--out DIR]".into())
+            }
+            other => return Err(format!("unknown argument {}", other)),
+        }
+    }
+    args.out = out.unwrap_or_else(|| args.root.join("app"));
+    Ok(args)
+}
+
+/// Plug folders: every `plugs/<name>/` with a `Cargo.toml`, sorted by name.
+fn plug_dirs(root: &Path) -> Result<Vec<(String, PathBuf)>, String> {
+    let plugs = root.join("plugs");
+    let entries = fs::read_dir(&plugs).map_err(|e| format!("{}: {}", plugs.display(), e))?;
+    let mut out: Vec<(String, PathBuf)> = entries
+        .filter_map(Result::ok)
+        .map(|e| e.path())
+        .filter(|p| p.join("Cargo.toml").is_file())
+        .filter_map(|p| Some((p.file_name()?.to_str()?.to_string(), p)))
+        .collect();
+    out.sort();
+    Ok(out)
+}
+
+/// `<title>` text without the `| Studio` / `• tagline` suffix.
+fn html_title(html: &str) -> Option<String> {
+    let start = html.find("<title>")? + "<title>".len();
+    let end = start + html[start..].find("</title>")?;
+    let title = html[start..end]
+        .split(" | ")
+        .flat_map(|part| part.split(" • "))
+        .next()
+        .unwrap_or_default()
+        .trim();
+    (!title.is_empty()).then(|| title.to_string())
+}
+
+fn html_description(html: &str) -> Option<String> {
+    let tag_at = html.find(r#"name="description""#)?;
+    let rest = &html[tag_at..];
+    let start = rest.find(r#"content=""#)? + r#"content=""#.len();
+    let end = start + rest[start..].find('"')?;
+    Some(rest[start..end].trim().to_string())
+}
+
+/// `hot-mic-detector` → `Hot Mic Detector`.
+fn humanize(name: &str) -> String {
+    name.split('-')
+        .filter(|w| !w.is_empty())
+        .map(|w| {
+            let mut c = w.chars();
+            c.next()
+                .map(|f| f.to_uppercase().chain(c).collect::<String>())
+                .unwrap_or_default()
+        })
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// A starter manifest from what `index.html` already says.
+fn starter_manifest(name: &str, dir: &Path) -> PlugManifest {
+    let html = fs::read_to_string(dir.join("index.html")).unwrap_or_default();
+    let title = html_title(&html)
+        .filter(|t| t != name)
+        .unwrap_or_else(|| humanize(name));
+    let description = html_description(&html).unwrap_or_default();
+    PlugManifest::starter(&title, &description)
+}
+
+fn load(dir: &Path) -> Result<PlugManifest, String> {
+    let path = dir.join(MANIFEST_FILE);
+    let text = fs::read_to_string(&path)
+        .map_err(|_| format!("missing {} (run with --init to create one)", MANIFEST_FILE))?;
+    toml::from_str(&text).map_err(|e| e.to_string().trim().replace('\n', " "))
+}
+
+/// Portal path of a plug's thumbnail: `thumbs/<name>.<ext>`.
+fn thumbnail_path(name: &str, thumb: &str) -> String {
+    let ext = Path::new(thumb)
+        .extension()
+        .and_then(|e| e.to_str())
+        .unwrap_or("png")
+        .to_ascii_lowercase();
+    format!("thumbs/{}.{}", name, ext)
+}
+
+/// The `plugs.json` text for `manifests`.
+fn index_json(manifests: &[(String, PathBuf, PlugManifest)]) -> Result<String, String> {
+    let mut index = PortalIndex::default();
+    for (name, _, m) in manifests {
+        let thumbnail = m.thumbnail.as_deref().map(|t| thumbnail_path(name, t));
+        index.plugs.push(PortalEntry::new(name, m, thumbnail));
+    }
+    let json = serde_json::to_string_pretty(&index).map_err(|e| e.to_string())?;
+    Ok(json + "\n")
+}
+
+/// What in `out` differs from a fresh generation, as paths relative to it.
+fn stale_outputs(
+    manifests: &[(String, PathBuf, PlugManifest)],
+    out: &Path,
+    json: &str,
+) -> Vec<String> {
+    let mut stale = Vec::new();
+    if fs::read_to_string(out.join("plugs.json")).ok().as_deref() != Some(json) {
+        stale.push("plugs.json".to_string());
+    }
+    let mut thumbs = vec![".gitkeep".to_string()];
+    for (name, dir, m) in manifests {
+        let Some(t) = &m.thumbnail else { continue };
+        let rel = thumbnail_path(name, t);
+        if fs::read(dir.join(t)).ok() != fs::read(out.join(&rel)).ok() {
+            stale.push(rel.clone());
+        }
+        thumbs.push(rel.trim_start_matches("thumbs/").to_string());
+    }
+    let extra = fs::read_dir(out.join("thumbs"))
+        .into_iter()
+        .flatten()
+        .flatten()
+        .filter_map(|e| e.file_name().to_str().map(str::to_string))
+        .filter(|f| !thumbs.contains(f));
+    stale.extend(extra.map(|f| format!("thumbs/{}", f)));
+    stale
+}
+
+/// Empty `<out>/thumbs/`, keeping `.gitkeep` so Trunk's copy-dir always has a folder.
+fn reset_thumbs(out: &Path) -> Result<(), String> {
+    let thumbs = out.join("thumbs");
+    fs::create_dir_all(&thumbs).map_err(|e| format!("{}: {}", thumbs.display(), e))?;
+    for entry in fs::read_dir(&thumbs).map_err(|e| e.to_string())?.flatten() {
+        if entry.file_name() != ".gitkeep" {
+            fs::remove_file(entry.path()).map_err(|e| e.to_string())?;
+        }
+    }
+    fs::write(thumbs.join(".gitkeep"), "").map_err(|e| e.to_string())
+}
+
+fn run(args: &Args) -> Result<bool, String> {
+    let mut errors = 0;
+    let mut warnings = 0;
+    let mut manifests = Vec::new();
+
+    for (name, dir) in plug_dirs(&args.root)? {
+        let where_ = format!("{}/{}", plug::app_dir(&name), MANIFEST_FILE);
+
+        if args.init && !dir.join(MANIFEST_FILE).exists() {
+            let m = starter_manifest(&name, &dir);
+            fs::write(dir.join(MANIFEST_FILE), m.to_toml())
+                .map_err(|e| format!("{}: {}", where_, e))?;
+            println!("{}: created", where_);
+        }
+
+        let manifest = match load(&dir) {
+            Ok(m) => m,
+            Err(e) => {
+                eprintln!("{}: error: {}", where_, e);
+                errors += 1;
+                continue;
+            }
+        };
+        for issue in manifest.validate(&name, |p| dir.join(p).is_file()) {
+            eprintln!("{}: {}: {}", where_, issue.severity.label(), issue.message);
+            match issue.severity {
+                IssueSeverity::Error => errors += 1,
+                IssueSeverity::Warning => warnings += 1,
+            }
+        }
+        manifests.push((name, dir, manifest));
+    }
+
+    println!(
+        "{} plugs, {} error(s), {} warning(s)",
+        manifests.len(),
+        errors,
+        warnings
+    );
+    if errors > 0 {
+        return Ok(false);
+    }
+
+    let json = index_json(&manifests)?;
+    if args.check {
+        let stale = stale_outputs(&manifests, &args.out, &json);
+        for rel in &stale {
+            eprintln!(
+                "{}: out of date (run plug-index without --check and commit the result)",
+                args.out.join(rel).display()
+            );
+        }
+        return Ok(stale.is_empty());
+    }
+
+    reset_thumbs(&args.out)?;
+    for (name, dir, m) in &manifests {
+        if let Some(t) = &m.thumbnail {
+            let rel = thumbnail_path(name, t);
+            fs::copy(dir.join(t), args.out.join(&rel)).map_err(|e| format!("{}: {}", rel, e))?;
+        }
+    }
+    let path = args.out.join("plugs.json");
+    fs::write(&path, json).map_err(|e| format!("{}: {}", path.display(), e))?;
+    println!("wrote {}", path.display());
+    Ok(true)
+}
+
+fn main() -> ExitCode {
+    let result = parse_args().and_then(|args| run(&args));
+    match result {
+        Ok(true) => ExitCode::SUCCESS,
+        Ok(false) => ExitCode::FAILURE,
+        Err(e) => {
+            eprintln!("plug-index: {}", e);
+            ExitCode::FAILURE
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn title_drops_site_suffix() {
+        let html = "<head><title>Canon Devotional Builder | MikeGyver Studio</title></head>";
+        assert_eq!(
+            html_title(html).as_deref(),
+            Some("Canon Devotional Builder")
+        );
+        assert_eq!(
+            html_title("<title>DevPocket • Rust Yew Dev Toolbox</title>").as_deref(),
+            Some("DevPocket")
+        );
+        assert_eq!(html_title("<title> </title>"), None);
+    }
+
+    #[test]
+    fn description_from_meta_tag() {
+        let html = r#"<meta name="description" content="Daily random prompt." />"#;
+        assert_eq!(
+            html_description(html).as_deref(),
+            Some("Daily random prompt.")
+        );
+        assert_eq!(html_description("<title>x</title>"), None);
+    }
+
+    #[test]
+    fn check_spots_stale_outputs() {
+        let out = std::env::temp_dir().join(format!("plug-index-check-{}", std::process::id()));
+        fs::create_dir_all(out.join("thumbs")).unwrap();
+        let m = PlugManifest::starter("Demo", "A demo plug.");
+        let manifests = vec![("demo".to_string(), out.clone(), m)];
+        let json = index_json(&manifests).unwrap();
+
+        fs::write(out.join("plugs.json"), &json).unwrap();
+        fs::write(out.join("thumbs/.gitkeep"), "").unwrap();
+        assert!(stale_outputs(&manifests, &out, &json).is_empty());
+
+        fs::write(out.join("thumbs/gone.png"), "x").unwrap();
+        fs::write(out.join("plugs.json"), "{}").unwrap();
+        assert_eq!(
+            stale_outputs(&manifests, &out, &json),
+            ["plugs.json", "thumbs/gone.png"]
+        );
+        fs::remove_dir_all(&out).unwrap();
+    }
+
+    #[test]
+    fn humanize_plug_names() {
+        assert_eq!(humanize("hot-mic-detector"), "Hot Mic Detector");
+        assert_eq!(humanize("x-59"), "X 59");
+    }
+}
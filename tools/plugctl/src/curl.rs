@@ -0,0 +1,100 @@
+//! `curl`-backed transport so the shared GitHub client runs natively.
+//!
+//! curl is already needed for FTP uploads, and shelling out keeps this tool
+//! free of an HTTP/TLS stack. Requests run synchronously inside `send`, so
+//! the client's futures are driven with `futures::executor::block_on`.
+
+use std::io::Write;
+use std::process::{Command, Stdio};
+
+use plug_kit::{HttpRequest, HttpResponse, Transport};
+
+#[derive(Debug, Clone, Copy, Default)]
+pub struct CurlTransport;
+
+impl Transport for CurlTransport {
+    async fn send(&self, req: HttpRequest) -> Result<HttpResponse, String> {
+        let mut cmd = Command::new("curl");
+        cmd.args(["-sS", "-L", "-i", "-X", req.method.as_str()]);
+        for (k, v) in &req.headers {
+            cmd.arg("-H").arg(format!("{}: {}", k, v));
+        }
+        if req.body.is_some() {
+            cmd.args(["--data-binary", "@-"]);
+        }
+        cmd.arg(&req.url)
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped());
+
+        let mut child = cmd.spawn().map_err(|e| format!("curl: {}", e))?;
+        if let (Some(body), Some(mut stdin)) = (&req.body, child.stdin.take()) {
+            stdin
+                .write_all(body.as_bytes())
+                .map_err(|e| format!("curl: {}", e))?;
+        }
+        let out = child
+            .wait_with_output()
+            .map_err(|e| format!("curl: {}", e))?;
+        if !out.status.success() {
+            return Err(String::from_utf8_lossy(&out.stderr).trim().to_string());
+        }
+        parse_response(&out.stdout)
+    }
+
+    async fn sleep(&self, secs: u64) {
+        std::thread::sleep(std::time::Duration::from_secs(secs));
+    }
+}
+
+/// Split `curl -i` output into the last response's status, headers and body.
+/// Redirects and `100 Continue` each print their own header block first.
+fn parse_response(raw: &[u8]) -> Result<HttpResponse, String> {
+    let mut rest = raw;
+    loop {
+        let end = rest
+            .windows(4)
+            .position(|w| w == b"\r\n\r\n")
+            .ok_or("curl: no HTTP headers in response")?;
+        let head = String::from_utf8_lossy(&rest[..end]).into_owned();
+        rest = &rest[end + 4..];
+        if rest.starts_with(b"HTTP/") {
+            continue;
+        }
+
+        let mut lines = head.lines();
+        let status = lines
+            .next()
+            .and_then(|l| l.split_whitespace().nth(1))
+            .and_then(|s| s.parse().ok())
+            .ok_or("curl: bad status line")?;
+        let headers = lines
+            .filter_map(|l| l.split_once(':'))
+            .map(|(k, v)| (k.trim().to_ascii_lowercase(), v.trim().to_string()))
+            .collect();
+        return Ok(HttpResponse {
+            status,
+            headers,
+            body: rest.to_vec(),
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn keeps_the_final_response_after_redirects() {
+        let raw = b"HTTP/2 302\r\nlocation: https://x\r\n\r\nHTTP/2 200\r\nContent-Type: text/plain\r\n\r\nlog line\r\n\r\nmore";
+        let resp = parse_response(raw).unwrap();
+        assert_eq!(resp.status, 200);
+        assert_eq!(resp.header("content-type"), Some("text/plain"));
+        assert_eq!(resp.text(), "log line\r\n\r\nmore");
+    }
+
+    #[test]
+    fn rejects_output_without_headers() {
+        assert!(parse_response(b"oops").is_err());
+    }
+}
@@ -0,0 +1,372 @@
+//! Scaffold, build and publish plugs from a terminal.
+//!
+//!     cargo run --manifest-path tools/plugctl/Cargo.toml -- <command> [options]
+//!
+//! The native counterpart of plug-creator and the deploy workflow: `new` writes
+//! the same starter templates, `build` runs the same preflight and Trunk build
+//! with the plug's `public_url`, and `deploy` publishes `dist/` to a local
+//! directory or an FTP/SFTP server. Run it from the repo root or pass `--root`.
+
+mod curl;
+mod publish;
+
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::{Command, ExitCode};
+
+use futures::executor::block_on;
+use plug_kit::manifest::{last_deploys, PlugManifest, MANIFEST_FILE};
+use plug_kit::templates::{self, TemplateVars, CATALOG};
+use plug_kit::{plug, GitHubClient, GitHubConfig};
+
+use curl::CurlTransport;
+use publish::{Options, Target};
+
+const USAGE: &str = "usage: plugctl [--root DIR] <command>
+
+  new <name> [--title T] [--subtitle S] [--template ID] [--force] [--commit-and-push]
+                  write a starter plug into plugs/<name>/; the subtitle becomes
+                  its plug.toml description
+  templates       list the starter templates
+  build <name> [--preview]
+                  trunk build with the Hostek public_url, plus web.config
+  deploy <name> --to <DIR|ftp://..|sftp://..> [--preview] [--skip-build] [--clean] [--dry-run]
+                  build, then publish dist/ into <target>/<folder>/
+  list            every plug with its plug.toml title and tags
+  status [name] [--limit N]
+                  newest deploy run per plug (GITHUB_TOKEN optional)
+
+FTP/SFTP credentials come from HOSTEK_FTP_USER and HOSTEK_FTP_PASS.";
+
+const USER_AGENT: &str = "plugctl";
+
+#[derive(Default)]
+struct Args {
+    root: PathBuf,
+    command: String,
+    name: Option<String>,
+    title: Option<String>,
+    subtitle: Option<String>,
+    template: Option<String>,
+    to: Option<String>,
+    limit: Option<u32>,
+    force: bool,
+    commit_and_push: bool,
+    preview: bool,
+    skip_build: bool,
+    clean: bool,
+    dry_run: bool,
+}
+
+fn parse_args() -> Result<Args, String> {
+    let mut args = Args {
+        root: PathBuf::from("."),
+        ..Args::default()
+    };
+    let mut positional = Vec::new();
+    let mut it = std::env::args().skip(1);
+    while let Some(arg) = it.next() {
+        let mut value = |flag: &str| it.next().ok_or(format!("{} needs a value", flag));
+        match arg.as_str() {
+            "--root" => args.root = value("--root")?.into(),
+            "--title" => args.title = Some(value("--title")?),
+            "--subtitle" => args.subtitle = Some(value("--subtitle")?),
+            "--template" => args.template = Some(value("--template")?),
+            "--to" => args.to = Some(value("--to")?),
+            "--limit" => {
+                let n = value("--limit")?;
+                args.limit = Some(n.parse().map_err(|_| format!("bad --limit {}", n))?);
+            }
+            "--force" => args.force = true,
+            "--commit-and-push" => args.commit_and_push = true,
+            "--preview" => args.preview = true,
+            "--skip-build" => args.skip_build = true,
+            "--clean" => args.clean = true,
+            "--dry-run" => args.dry_run = true,
+            "-h" | "--help" => return Err(USAGE.into()),
+            flag if flag.starts_with("--") => return Err(format!("unknown option {}", flag)),
+            _ => positional.push(arg),
+        }
+    }
+    let mut positional = positional.into_iter();
+    args.command = positional.next().ok_or(USAGE)?;
+    args.name = positional.next();
+    if let Some(extra) = positional.next() {
+        return Err(format!("unexpected argument {}", extra));
+    }
+    Ok(args)
+}
+
+impl Args {
+    /// The plug name argument, checked like plug-creator checks it.
+    fn plug_name(&self) -> Result<String, String> {
+        let name = self
+            .name
+            .as_deref()
+            .ok_or(format!("{} needs a plug name", self.command))?;
+        plug::sanitize_name(name)
+            .ok_or_else(|| format!("{}: use lowercase letters, numbers, hyphens", name))
+    }
+
+    fn plug_dir(&self, name: &str) -> PathBuf {
+        self.root.join(plug::app_dir(name))
+    }
+
+    fn folder(&self, name: &str) -> String {
+        plug::deploy_folder(name, self.preview)
+    }
+}
+
+fn cmd_new(args: &Args) -> Result<(), String> {
+    let name = args.plug_name()?;
+    let template = match &args.template {
+        Some(id) => templates::find(id).ok_or_else(|| {
+            let ids: Vec<_> = CATALOG.iter().map(|t| t.id).collect();
+            format!("unknown template {} (one of: {})", id, ids.join(", "))
+        })?,
+        None => templates::default_template(),
+    };
+    let dir = args.plug_dir(&name);
+    if dir.exists() && !args.force {
+        return Err(format!(
+            "{} already exists (--force overwrites)",
+            dir.display()
+        ));
+    }
+
+    let title = args.title.clone().unwrap_or_else(|| name.clone());
+    for file in template.files(&TemplateVars::new(&name, &title)) {
+        let path = args.root.join(&file.path);
+        let mut content = file.content.unwrap_or_default();
+        if let (Some(subtitle), true) = (&args.subtitle, file.path.ends_with(MANIFEST_FILE)) {
+            let mut m: PlugManifest = toml::from_str(&content).map_err(|e| e.to_string())?;
+            m.description = subtitle.trim().to_string();
+            content = m.to_toml();
+        }
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent).map_err(|e| format!("{}: {}", parent.display(), e))?;
+        }
+        fs::write(&path, content).map_err(|e| format!("{}: {}", path.display(), e))?;
+        println!("wrote {}", file.path);
+    }
+    println!(
+        "{} from the {} template. Next: plugctl build {}",
+        name, template.id, name
+    );
+
+    if args.commit_and_push {
+        // only the new folder, whatever else is staged
+        let dir = plug::app_dir(&name);
+        let message = format!("Add plug scaffold: {}", name);
+        git(&args.root, &["add", "--", &dir])?;
+        git(&args.root, &["commit", "-m", &message, "--", &dir])?;
+        git(&args.root, &["push"])?;
+    }
+    Ok(())
+}
+
+fn git(root: &Path, git_args: &[&str]) -> Result<(), String> {
+    println!("git {}", git_args.join(" "));
+    let status = Command::new("git")
+        .arg("-C")
+        .arg(root)
+        .args(git_args)
+        .status()
+        .map_err(|e| format!("git: {}", e))?;
+    if status.success() {
+        Ok(())
+    } else {
+        Err(format!("git {} failed", git_args[0]))
+    }
+}
+
+fn cmd_templates() {
+    for t in CATALOG {
+        println!("{:<12} {:<16} {}", t.id, t.name, t.description);
+    }
+}
+
+/// The deploy workflow's preflight: Trunk must find `styles.css` to bundle it.
+fn preflight(dir: &Path) -> Result<(), String> {
+    for file in ["index.html", "Cargo.toml", "styles.css"] {
+        if !dir.join(file).is_file() {
+            return Err(format!("{} is missing {}", dir.display(), file));
+        }
+    }
+    let html = fs::read_to_string(dir.join("index.html")).map_err(|e| e.to_string())?;
+    if !html.contains(r#"data-trunk rel="css""#) || !html.contains(r#"href="styles.css""#) {
+        return Err(r#"index.html needs <link data-trunk rel="css" href="styles.css" />"#.into());
+    }
+    Ok(())
+}
+
+/// `dist/` must hold a stylesheet that `dist/index.html` links to.
+fn verify_dist(dist: &Path) -> Result<(), String> {
+    let has_css = publish::files(dist)?.iter().any(|f| f.ends_with(".css"));
+    let html = fs::read_to_string(dist.join("index.html")).unwrap_or_default();
+    if !has_css || !html.contains(".css") {
+        return Err(format!("{} has no bundled CSS", dist.display()));
+    }
+    Ok(())
+}
+
+fn cmd_build(args: &Args) -> Result<PathBuf, String> {
+    let name = args.plug_name()?;
+    let dir = args.plug_dir(&name);
+    preflight(&dir)?;
+
+    let public_url = format!("/{}/", args.folder(&name));
+    println!("trunk build --release --public-url {}", public_url);
+    let status = Command::new("trunk")
+        .args(["build", "--release", "--public-url", &public_url])
+        .current_dir(&dir)
+        .status()
+        .map_err(|e| format!("trunk: {} (install with: cargo install --locked trunk)", e))?;
+    if !status.success() {
+        return Err(format!("trunk build failed for {}", name));
+    }
+
+    let dist = dir.join("dist");
+    verify_dist(&dist)?;
+    let web_config = args.root.join("templates/web.config");
+    fs::copy(&web_config, dist.join("web.config"))
+        .map_err(|e| format!("{}: {}", web_config.display(), e))?;
+    println!("built {}", dist.display());
+    Ok(dist)
+}
+
+fn cmd_deploy(args: &Args) -> Result<(), String> {
+    let name = args.plug_name()?;
+    let target = Target::parse(args.to.as_deref().ok_or("deploy needs --to")?);
+    let dist = if args.skip_build || args.dry_run {
+        args.plug_dir(&name).join("dist")
+    } else {
+        cmd_build(args)?
+    };
+
+    let folder = args.folder(&name);
+    let opts = Options {
+        dry_run: args.dry_run,
+        clean: args.clean,
+    };
+    for line in publish::publish(&dist, &target, &folder, &opts)? {
+        println!("{}", line);
+    }
+    if args.dry_run {
+        println!("dry run: nothing was written");
+    } else {
+        println!("deployed {} -> {}", name, plug::public_url(&folder));
+    }
+    Ok(())
+}
+
+/// Plug folders: every `plugs/<name>/` with a `Cargo.toml`, sorted by name.
+fn plug_names(root: &Path) -> Result<Vec<String>, String> {
+    let plugs = root.join("plugs");
+    let entries = fs::read_dir(&plugs).map_err(|e| format!("{}: {}", plugs.display(), e))?;
+    let mut out: Vec<String> = entries
+        .filter_map(Result::ok)
+        .filter(|e| e.path().join("Cargo.toml").is_file())
+        .filter_map(|e| e.file_name().to_str().map(str::to_string))
+        .collect();
+    out.sort();
+    Ok(out)
+}
+
+fn cmd_list(args: &Args) -> Result<(), String> {
+    for name in plug_names(&args.root)? {
+        let path = args.plug_dir(&name).join(MANIFEST_FILE);
+        let manifest = fs::read_to_string(&path)
+            .ok()
+            .and_then(|text| toml::from_str::<PlugManifest>(&text).ok());
+        match manifest {
+            Some(m) => println!("{:<32} {:<36} {}", name, m.title, m.tags.join(", ")),
+            None => println!("{:<32} (no valid {})", name, MANIFEST_FILE),
+        }
+    }
+    Ok(())
+}
+
+fn cmd_status(args: &Args) -> Result<(), String> {
+    let only = args.name.as_deref();
+    let token = std::env::var("GITHUB_TOKEN").unwrap_or_default();
+    let gh = GitHubClient::with_transport(
+        &token,
+        GitHubConfig::default().with_user_agent(USER_AGENT),
+        CurlTransport,
+    );
+    let branch = gh.config().branch.clone();
+    let runs = block_on(gh.list_dispatch_runs(&branch, args.limit.unwrap_or(100)))
+        .map_err(|e| e.to_string())?;
+
+    let deploys = last_deploys(&runs);
+    let mut shown = 0;
+    for (name, run) in deploys.iter().filter(|(n, _)| only.is_none_or(|o| o == *n)) {
+        let state = match (run.status.as_deref(), run.conclusion.as_deref()) {
+            (Some("completed"), Some(c)) => c,
+            (Some(s), _) => s,
+            _ => "unknown",
+        };
+        let day = run.created_at.as_deref().and_then(|d| d.get(..10));
+        println!(
+            "{:<32} {:<10} {:<10} {}",
+            name,
+            state,
+            day.unwrap_or_default(),
+            run.html_url
+        );
+        shown += 1;
+    }
+    if shown == 0 {
+        println!("no deploys found in the last {} runs", runs.len());
+    }
+    Ok(())
+}
+
+fn run(args: &Args) -> Result<(), String> {
+    match args.command.as_str() {
+        "new" => cmd_new(args),
+        "templates" => {
+            cmd_templates();
+            Ok(())
+        }
+        "build" => cmd_build(args).map(|_| ()),
+        "deploy" => cmd_deploy(args),
+        "list" => cmd_list(args),
+        "status" => cmd_status(args),
+        other => Err(format!("unknown command {}\n\n{}", other, USAGE)),
+    }
+}
+
+fn main() -> ExitCode {
+    match parse_args().and_then(|args| run(&args)) {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(e) => {
+            eprintln!("plugctl: {}", e);
+            ExitCode::FAILURE
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn preflight_wants_the_trunk_css_link() {
+        let dir = std::env::temp_dir().join(format!("plugctl-preflight-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        fs::write(dir.join("Cargo.toml"), "").unwrap();
+        fs::write(dir.join("styles.css"), "").unwrap();
+        fs::write(dir.join("index.html"), "<html></html>").unwrap();
+        assert!(preflight(&dir).unwrap_err().contains("styles.css"));
+
+        fs::write(
+            dir.join("index.html"),
+            r#"<link data-trunk rel="css" href="styles.css" />"#,
+        )
+        .unwrap();
+        assert_eq!(preflight(&dir), Ok(()));
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -0,0 +1,226 @@
+//! Copy a built `dist/` to where Hostek serves it.
+//!
+//! A target is either a local directory (a mounted share, or a stand-in for
+//! the server while testing) or an `ftp://`, `ftps://` or `sftp://` URL that
+//! curl uploads to. Either way the files land in `<target>/<folder>/`, the
+//! same layout the deploy workflow's FTP step produces.
+
+use std::fs;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Target {
+    Dir(PathBuf),
+    /// Server URL without a trailing slash, e.g. `ftp://ftp.example.com/site`.
+    Remote(String),
+}
+
+impl Target {
+    pub fn parse(s: &str) -> Target {
+        let remote = ["ftp://", "ftps://", "sftp://"]
+            .iter()
+            .any(|scheme| s.starts_with(scheme));
+        if remote {
+            Target::Remote(s.trim_end_matches('/').to_string())
+        } else {
+            Target::Dir(PathBuf::from(s))
+        }
+    }
+
+    /// Where `rel` (a `/`-separated path inside dist) ends up.
+    pub fn dest(&self, folder: &str, rel: &str) -> String {
+        match self {
+            Target::Dir(dir) => dir.join(folder).join(rel).display().to_string(),
+            Target::Remote(url) => format!("{}/{}/{}", url, folder, rel),
+        }
+    }
+}
+
+/// Every file under `dir`, as sorted `/`-separated paths relative to it.
+pub fn files(dir: &Path) -> Result<Vec<String>, String> {
+    fn walk(dir: &Path, prefix: &str, out: &mut Vec<String>) -> Result<(), String> {
+        let entries = fs::read_dir(dir).map_err(|e| format!("{}: {}", dir.display(), e))?;
+        for entry in entries.flatten() {
+            let name = entry.file_name().to_string_lossy().into_owned();
+            let rel = format!("{}{}", prefix, name);
+            if entry.path().is_dir() {
+                walk(&entry.path(), &format!("{}/", rel), out)?;
+            } else {
+                out.push(rel);
+            }
+        }
+        Ok(())
+    }
+    let mut out = Vec::new();
+    walk(dir, "", &mut out)?;
+    out.sort();
+    Ok(out)
+}
+
+pub struct Options {
+    pub dry_run: bool,
+    /// Empty the target folder first (directory targets only).
+    pub clean: bool,
+}
+
+/// Publish `dist` into `<target>/<folder>/`; returns one line per file.
+pub fn publish(
+    dist: &Path,
+    target: &Target,
+    folder: &str,
+    opts: &Options,
+) -> Result<Vec<String>, String> {
+    let files = files(dist)?;
+    if files.is_empty() {
+        return Err(format!("{} is empty; build first", dist.display()));
+    }
+    let mut lines = Vec::new();
+
+    match target {
+        Target::Dir(dir) => {
+            let dest = dir.join(folder);
+            if opts.clean && dest.exists() {
+                lines.push(format!("clean {}", dest.display()));
+                if !opts.dry_run {
+                    fs::remove_dir_all(&dest).map_err(|e| format!("{}: {}", dest.display(), e))?;
+                }
+            }
+            for rel in &files {
+                let to = dest.join(rel);
+                lines.push(format!("copy {} -> {}", rel, to.display()));
+                if opts.dry_run {
+                    continue;
+                }
+                if let Some(parent) = to.parent() {
+                    fs::create_dir_all(parent)
+                        .map_err(|e| format!("{}: {}", parent.display(), e))?;
+                }
+                fs::copy(dist.join(rel), &to).map_err(|e| format!("{}: {}", to.display(), e))?;
+            }
+        }
+        Target::Remote(_) => {
+            if opts.clean {
+                return Err("--clean only works with directory targets".into());
+            }
+            let config = ftp_config();
+            for rel in &files {
+                let url = target.dest(folder, rel);
+                lines.push(format!("upload {} -> {}", rel, url));
+                if !opts.dry_run {
+                    upload(&dist.join(rel), &url, config.as_deref())?;
+                }
+            }
+        }
+    }
+    Ok(lines)
+}
+
+/// A curl config with the credentials, from the same names the deploy
+/// workflow's secrets use. It goes to curl on stdin (`-K -`) so the password
+/// never shows up in the process list.
+fn ftp_config() -> Option<String> {
+    let user = std::env::var("HOSTEK_FTP_USER").ok()?;
+    let pass = std::env::var("HOSTEK_FTP_PASS").unwrap_or_default();
+    Some(user_config(&user, &pass))
+}
+
+/// `user = "user:pass"`, quoted the way curl config files read it.
+fn user_config(user: &str, pass: &str) -> String {
+    let value = format!("{}:{}", user, pass)
+        .replace('\\', "\\\\")
+        .replace('"', "\\\"");
+    format!("user = \"{}\"\n", value)
+}
+
+fn upload(file: &Path, url: &str, config: Option<&str>) -> Result<(), String> {
+    let mut cmd = Command::new("curl");
+    cmd.args(["-sS", "--ftp-create-dirs", "-T"]).arg(file);
+    if config.is_some() {
+        cmd.args(["-K", "-"]);
+    }
+    cmd.arg(url)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped());
+
+    let mut child = cmd.spawn().map_err(|e| format!("curl: {}", e))?;
+    // dropping stdin closes it, which ends the config
+    if let (Some(config), Some(mut stdin)) = (config, child.stdin.take()) {
+        stdin
+            .write_all(config.as_bytes())
+            .map_err(|e| format!("curl: {}", e))?;
+    }
+    let out = child
+        .wait_with_output()
+        .map_err(|e| format!("curl: {}", e))?;
+    if out.status.success() {
+        Ok(())
+    } else {
+        Err(format!(
+            "{}: {}",
+            url,
+            String::from_utf8_lossy(&out.stderr).trim()
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn urls_are_remote_everything_else_is_a_directory() {
+        assert_eq!(
+            Target::parse("ftp://host/site/"),
+            Target::Remote("ftp://host/site".into())
+        );
+        assert_eq!(
+            Target::parse("sftp://host").dest("demo", "pkg/app.wasm"),
+            "sftp://host/demo/pkg/app.wasm"
+        );
+        assert_eq!(
+            Target::parse("/srv/www"),
+            Target::Dir(PathBuf::from("/srv/www"))
+        );
+    }
+
+    #[test]
+    fn credentials_are_quoted_for_curl_config() {
+        assert_eq!(user_config("web", "s3cret"), "user = \"web:s3cret\"\n");
+        assert_eq!(
+            user_config("web", r#"a"b\c"#),
+            "user = \"web:a\\\"b\\\\c\"\n"
+        );
+    }
+
+    #[test]
+    fn copies_dist_into_the_plug_folder() {
+        let root = std::env::temp_dir().join(format!("plugctl-test-{}", std::process::id()));
+        let dist = root.join("dist");
+        fs::create_dir_all(dist.join("assets")).unwrap();
+        fs::write(dist.join("index.html"), "<html>").unwrap();
+        fs::write(dist.join("assets/app.css"), "body{}").unwrap();
+        let target = Target::Dir(root.join("www"));
+
+        let dry = Options {
+            dry_run: true,
+            clean: false,
+        };
+        let plan = publish(&dist, &target, "demo", &dry).unwrap();
+        assert_eq!(plan.len(), 2);
+        assert!(!root.join("www").exists());
+
+        let real = Options {
+            dry_run: false,
+            clean: true,
+        };
+        publish(&dist, &target, "demo", &real).unwrap();
+        assert_eq!(
+            files(&root.join("www/demo")).unwrap(),
+            ["assets/app.css", "index.html"]
+        );
+        fs::remove_dir_all(&root).unwrap();
+    }
+}